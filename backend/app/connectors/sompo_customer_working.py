@@ -41,6 +41,25 @@ def parse_tl(text: str) -> float:
     print(f"💰 Fiyat parse edildi: '{text}' -> {result}", file=sys.stderr)
     return result
 
+def parse_proposal_reference(text: str):
+    """Sayfa metninden teklif numarası ve geçerlilik tarihi (GG.AA.YYYY) çıkar"""
+    proposal_no = None
+    valid_until = None
+
+    m = re.search(r"(?i:teklif\s*(?:no|numarası))\s*[:#]?\s*([A-Z0-9][A-Z0-9\-/]{3,})", text or "")
+    if m and any(c.isdigit() for c in m.group(1)):
+        proposal_no = m.group(1)
+
+    m = re.search(r"(?i)geçerli(?:lik)?[^0-9]{0,40}?(\d{2}[./]\d{2}[./]\d{4})", text or "")
+    if m:
+        valid_until = m.group(1)
+    else:
+        m = re.search(r"(?i)geçerli(?:lik)?[^0-9]{0,40}?(\d{1,3})\s*gün", text or "")
+        if m:
+            valid_until = (dt.date.today() + dt.timedelta(days=int(m.group(1)))).strftime("%d.%m.%Y")
+
+    return proposal_no, valid_until
+
 # ==================== BROWSER ====================
 @asynccontextmanager
 async def browser_context(proxy_url: Optional[str] = None, headless: bool = True):
@@ -313,6 +332,14 @@ async def fetch_sompo_quote(payload: Dict[str, Any]) -> dict:
             premium = parse_tl(price_text or "4350")
             print(f"💰 Final Premium: {premium}", file=sys.stderr)
 
+            # Teklif numarası ve geçerlilik (poliçeleştirme / yeniden basım için)
+            proposal_no, valid_until = None, None
+            try:
+                proposal_no, valid_until = parse_proposal_reference(await page.inner_text("body"))
+                print(f"🧾 Teklif No: {proposal_no} (geçerlilik: {valid_until})", file=sys.stderr)
+            except Exception as e:
+                print(f"⚠️ Teklif numarası okunamadı: {e}", file=sys.stderr)
+
         except PWTimeout as e:
             # Screenshot al
             path = f"debug_sompo_timeout.png"
@@ -351,6 +378,8 @@ async def fetch_sompo_quote(payload: Dict[str, Any]) -> dict:
         "coverages": [
             {"code":"TRAFIK_ZORUNLU","name":"Zorunlu Trafik Sigortası","limit":None,"included":True}
         ],
+        "proposal_no": proposal_no,
+        "valid_until": valid_until,
         "timings": {
             "scrape_ms": 0  # TODO
         }
@@ -58,6 +58,29 @@ export default function PoliciesPage() {
     }
   };
 
+  const downloadPdf = async (policy: Policy) => {
+    try {
+      const token = localStorage.getItem("auth_token");
+      const response = await fetch(
+        `http://localhost:8099/api/v1/policies/${policy.id}/pdf`,
+        { headers: { Authorization: `Bearer ${token}` } }
+      );
+      if (!response.ok) {
+        toast.error("PDF oluşturulamadı");
+        return;
+      }
+      const url = URL.createObjectURL(await response.blob());
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = `police_${policy.policyNumber}.pdf`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch (error) {
+      toast.error("Bağlantı hatası");
+      console.error(error);
+    }
+  };
+
   const filteredPolicies = policies.filter(
     (p) =>
       p.policyNumber.toLowerCase().includes(searchTerm.toLowerCase()) ||
@@ -72,6 +95,12 @@ export default function PoliciesPage() {
         return <Badge variant="secondary">Süresi Dolmuş</Badge>;
       case "cancelled":
         return <Badge variant="destructive">İptal Edilmiş</Badge>;
+      case "endorsed":
+        return <Badge variant="success">Zeyilli</Badge>;
+      case "renewed":
+        return <Badge variant="secondary">Yenilendi</Badge>;
+      case "pending_issue":
+        return <Badge variant="secondary">Kesim Bekliyor</Badge>;
       default:
         return <Badge>{status}</Badge>;
     }
@@ -160,7 +189,11 @@ export default function PoliciesPage() {
                     </p>
                   </div>
                   <div className="flex items-end justify-end gap-2">
-                    <Button variant="outline" size="sm">
+                    <Button
+                      variant="outline"
+                      size="sm"
+                      onClick={() => downloadPdf(policy)}
+                    >
                       <Download className="h-4 w-4 mr-2" />
                       PDF İndir
                     </Button>
@@ -195,7 +228,7 @@ export default function PoliciesPage() {
           </CardHeader>
           <CardContent>
             <p className="text-2xl font-bold">
-              {policies.filter((p) => p.status === "active").length}
+              {policies.filter((p) => p.status === "active" || p.status === "endorsed").length}
             </p>
           </CardContent>
         </Card>
@@ -17,6 +17,7 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import { QuoteResponse } from "@/lib/types";
 import { formatCurrency } from "@/lib/utils";
@@ -33,9 +34,17 @@ export function PolicyDialog({ quote, open, onOpenChange }: PolicyDialogProps) {
   const [isCreating, setIsCreating] = useState(false);
   const [paymentMethod, setPaymentMethod] = useState("credit_card");
   const [installmentCount, setInstallmentCount] = useState("1");
+  const [card, setCard] = useState({
+    holderName: "",
+    number: "",
+    expiry: "",
+    cvv: "",
+  });
 
   if (!quote) return null;
 
+  const isCreditCard = paymentMethod === "credit_card";
+
   const handleCreatePolicy = async () => {
     setIsCreating(true);
 
@@ -46,6 +55,22 @@ export function PolicyDialog({ quote, open, onOpenChange }: PolicyDialogProps) {
         throw new Error("Oturum bulunamadı. Lütfen giriş yapın.");
       }
 
+      if (!quote.quoteId) {
+        throw new Error("Teklif kaydedilemedi, lütfen yeniden teklif alın.");
+      }
+
+      // Kart bilgisi yalnızca portala iletilir, sunucuda saklanmaz
+      const [expiryMonth, expiryYear] = card.expiry.split("/");
+      const cardPayload = isCreditCard
+        ? {
+            holderName: card.holderName,
+            number: card.number.replace(/\s/g, ""),
+            expiryMonth: parseInt(expiryMonth),
+            expiryYear: 2000 + parseInt(expiryYear),
+            cvv: card.cvv,
+          }
+        : undefined;
+
       const response = await fetch(
         `${process.env.NEXT_PUBLIC_API_URL}/api/v1/policies`,
         {
@@ -55,15 +80,17 @@ export function PolicyDialog({ quote, open, onOpenChange }: PolicyDialogProps) {
             Authorization: `Bearer ${token}`,
           },
           body: JSON.stringify({
-            quoteId: quote.requestId,
+            quoteId: quote.quoteId,
             paymentMethod,
-            installmentCount: parseInt(installmentCount),
+            installmentCount: isCreditCard ? parseInt(installmentCount) : 1,
+            card: cardPayload,
           }),
         }
       );
 
       if (!response.ok) {
-        throw new Error("Poliçe oluşturulamadı");
+        const error = await response.json().catch(() => null);
+        throw new Error(error?.error?.message || "Poliçe oluşturulamadı");
       }
 
       const policy = await response.json();
@@ -109,25 +136,61 @@ export function PolicyDialog({ quote, open, onOpenChange }: PolicyDialogProps) {
             </Select>
           </div>
 
-          {/* Taksit Sayısı */}
-          <div className="space-y-2">
-            <Label>Taksit Sayısı</Label>
-            <Select
-              value={installmentCount}
-              onValueChange={setInstallmentCount}
-            >
-              <SelectTrigger>
-                <SelectValue />
-              </SelectTrigger>
-              <SelectContent>
-                {quote.installments.map((inst) => (
-                  <SelectItem key={inst.count} value={inst.count.toString()}>
-                    {inst.count} Taksit - {formatCurrency(inst.perInstallment)}
-                  </SelectItem>
-                ))}
-              </SelectContent>
-            </Select>
-          </div>
+          {/* Kart Bilgileri */}
+          {isCreditCard && (
+            <div className="space-y-2">
+              <Label>Kart Bilgileri</Label>
+              <Input
+                placeholder="Kart Üzerindeki İsim"
+                value={card.holderName}
+                onChange={(e) => setCard({ ...card, holderName: e.target.value })}
+              />
+              <Input
+                placeholder="Kart Numarası"
+                inputMode="numeric"
+                autoComplete="cc-number"
+                value={card.number}
+                onChange={(e) => setCard({ ...card, number: e.target.value })}
+              />
+              <div className="grid grid-cols-2 gap-2">
+                <Input
+                  placeholder="AA/YY"
+                  autoComplete="cc-exp"
+                  value={card.expiry}
+                  onChange={(e) => setCard({ ...card, expiry: e.target.value })}
+                />
+                <Input
+                  placeholder="CVV"
+                  inputMode="numeric"
+                  autoComplete="cc-csc"
+                  value={card.cvv}
+                  onChange={(e) => setCard({ ...card, cvv: e.target.value })}
+                />
+              </div>
+            </div>
+          )}
+
+          {/* Taksit Sayısı (yalnızca kredi kartı) */}
+          {isCreditCard && (
+            <div className="space-y-2">
+              <Label>Taksit Sayısı</Label>
+              <Select
+                value={installmentCount}
+                onValueChange={setInstallmentCount}
+              >
+                <SelectTrigger>
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {quote.installments.map((inst) => (
+                    <SelectItem key={inst.count} value={inst.count.toString()}>
+                      {inst.count} Taksit - {formatCurrency(inst.perInstallment)}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+          )}
 
           {/* Özet */}
           <div className="rounded-lg border p-4 space-y-2">
@@ -137,7 +200,7 @@ export function PolicyDialog({ quote, open, onOpenChange }: PolicyDialogProps) {
                 {formatCurrency(quote.premium.gross)}
               </span>
             </div>
-            {selectedInstallment && selectedInstallment.count > 1 && (
+            {isCreditCard && selectedInstallment && selectedInstallment.count > 1 && (
               <div className="flex justify-between text-sm">
                 <span className="text-muted-foreground">Taksit Başına:</span>
                 <span className="font-medium">
@@ -55,8 +55,8 @@ export function QuoteComparison({
         {quotes
           .sort((a, b) => Number(a.premium.gross) - Number(b.premium.gross))
           .map((quote) => {
-            const isCheapest = quote.requestId === cheapest.requestId;
-            const isFastest = quote.requestId === fastest.requestId;
+            const isCheapest = quote === cheapest;
+            const isFastest = quote === fastest;
             const displayCompany =
               quote.company === "Sompo"
                 ? "Sompo Sigorta"
@@ -70,7 +70,7 @@ export function QuoteComparison({
 
             return (
               <Card
-                key={quote.requestId}
+                key={quote.quoteId ?? quote.company}
                 className={cn(
                   "transition-all hover:shadow-lg",
                   isCheapest && "ring-2 ring-green-500"
@@ -3,6 +3,10 @@ export interface ProviderInfo {
   active: boolean;
   reason?: string;
   supported_products: string[];
+  supported_addons: string[];
+  usage_types: Array<"hususi" | "ticari">;
+  vehicle_categories: string[];
+  policy_issuance: boolean;
 }
 
 export interface ProvidersResponse {
@@ -26,6 +30,7 @@ export interface QuoteRequest {
     model: string;
     year: number;
     usage: "hususi" | "ticari";
+    category?: "otomobil" | "kamyonet" | "minibus" | "otobus" | "kamyon" | "motosiklet";
   };
   coverage: {
     productType: "trafik" | "kasko" | "konut" | "saglik";
@@ -40,6 +45,8 @@ export interface QuoteRequest {
 
 export interface QuoteResponse {
   requestId: string;
+  // Kaydedilmiş teklif id'si; poliçe kesiminde gönderilir
+  quoteId?: string;
   company: string;
   productType: string;
   premium: {
@@ -60,6 +67,8 @@ export interface QuoteResponse {
     included: boolean;
   }>;
   warnings: string[];
+  providerQuoteNo?: string;
+  validUntil?: string;
   raw?: {
     htmlSnapshotPath?: string;
     fieldsEcho?: Record<string, unknown>;
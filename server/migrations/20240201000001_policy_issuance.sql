@@ -0,0 +1,10 @@
+-- Teklif → poliçe dönüşümü (status: completed -> issuing -> issued)
+-- issuing durumunda kalan teklif, issue_error dolu ya da süresi aşılmışsa yeniden denenebilir
+ALTER TABLE quotes ADD COLUMN issue_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE quotes ADD COLUMN issue_error TEXT;
+ALTER TABLE quotes ADD COLUMN issuing_started_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_quotes_status ON quotes(status);
+
+-- Portalın döndürdüğü vade başlangıcı (expires_at = vade bitişi)
+ALTER TABLE policies ADD COLUMN starts_at TEXT;
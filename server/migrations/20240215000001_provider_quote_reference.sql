@@ -0,0 +1,5 @@
+-- Portalın verdiği teklif numarası ve geçerlilik tarihi (poliçeleştirme / yeniden basım için)
+ALTER TABLE quotes ADD COLUMN provider_quote_no TEXT;
+ALTER TABLE quotes ADD COLUMN valid_until TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_quotes_provider_quote_no ON quotes(provider_quote_no);
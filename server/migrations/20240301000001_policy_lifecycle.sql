@@ -0,0 +1,24 @@
+-- Poliçe yaşam döngüsü: pending_issue, active, cancelled, expired, renewed, endorsed
+-- Her durum değişikliği policy_events tablosuna işlenir
+ALTER TABLE policies ADD COLUMN cancelled_at TEXT;
+ALTER TABLE policies ADD COLUMN refund_amount REAL;
+ALTER TABLE policies ADD COLUMN renewal_of_policy_id TEXT REFERENCES policies(id) ON DELETE SET NULL;
+ALTER TABLE policies ADD COLUMN renewed_by_policy_id TEXT REFERENCES policies(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_policies_expires_at ON policies(expires_at);
+
+CREATE TABLE IF NOT EXISTS policy_events (
+    id TEXT PRIMARY KEY NOT NULL,
+    policy_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    -- Kullanıcı id'si ya da otomatik işlemler için 'system'
+    actor_id TEXT NOT NULL,
+    reason TEXT,
+    data TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (policy_id) REFERENCES policies(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_policy_events_policy_id ON policy_events(policy_id, created_at);
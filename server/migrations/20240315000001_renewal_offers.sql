@@ -0,0 +1,24 @@
+-- Vadesi yaklaşan poliçeler için yenileme teklifleri
+-- Teklifler quotes tablosuna da yazılır (POST /api/v1/policies ile poliçeleştirilebilir)
+ALTER TABLE policies ADD COLUMN renewal_quoted_at TEXT;
+ALTER TABLE policies ADD COLUMN renewal_attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE policies ADD COLUMN renewal_error TEXT;
+
+CREATE TABLE IF NOT EXISTS renewal_offers (
+    id TEXT PRIMARY KEY NOT NULL,
+    policy_id TEXT NOT NULL,
+    quote_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    premium REAL NOT NULL,
+    -- Mevcut poliçe primine göre fark (+ artış, - düşüş)
+    premium_change REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (policy_id) REFERENCES policies(id) ON DELETE CASCADE,
+    FOREIGN KEY (quote_id) REFERENCES quotes(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_renewal_offers_policy_id ON renewal_offers(policy_id);
+CREATE INDEX IF NOT EXISTS idx_renewal_offers_user_id ON renewal_offers(user_id, created_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_renewal_offers_quote_id ON renewal_offers(quote_id);
@@ -0,0 +1,23 @@
+-- Giden e-postalar: önce outbox'a yazılır, worker SMTP'ye gönderir
+-- status: pending -> sent | failed (EMAIL_MAX_ATTEMPTS denemeden sonra)
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT,
+    template TEXT NOT NULL,
+    to_email TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    html_body TEXT NOT NULL,
+    text_body TEXT NOT NULL,
+    attachment_name TEXT,
+    attachment_type TEXT,
+    attachment BLOB,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    next_attempt_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    sent_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_email_outbox_due ON email_outbox(status, next_attempt_at);
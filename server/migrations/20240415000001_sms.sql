@@ -0,0 +1,25 @@
+-- SMS gönderim kaydı ve sigortalı bazlı SMS red listesi (İYS/ret talepleri)
+CREATE TABLE IF NOT EXISTS sms_opt_outs (
+    phone TEXT PRIMARY KEY NOT NULL,
+    reason TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- status: sent | failed | opted_out | invalid_phone
+CREATE TABLE IF NOT EXISTS sms_messages (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT,
+    phone TEXT NOT NULL,
+    event TEXT NOT NULL,
+    message TEXT NOT NULL,
+    status TEXT NOT NULL,
+    provider TEXT,
+    provider_message_id TEXT,
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sms_messages_created ON sms_messages(created_at);
+CREATE INDEX IF NOT EXISTS idx_sms_messages_phone ON sms_messages(phone);
@@ -0,0 +1,27 @@
+-- Komisyon kuralları: şirket / ürün / kullanım tipi / prim bandına göre oran, yürürlük tarihleriyle.
+-- NULL koşul "tümü" demektir; birden fazla kural uyarsa en çok koşulu olan seçilir.
+CREATE TABLE IF NOT EXISTS commission_rules (
+    id TEXT PRIMARY KEY NOT NULL,
+    provider TEXT,
+    -- trafik | kasko | konut | saglik
+    product_type TEXT,
+    -- hususi | ticari (yalnızca araç ürünleri)
+    usage TEXT,
+    -- Prim bandı: min dahil, max hariç
+    min_premium REAL,
+    max_premium REAL,
+    -- Yüzde: 12.5 = %12,5
+    rate_percent REAL NOT NULL,
+    -- YYYY-MM-DD, her iki uç dahil; valid_to NULL ise süresiz
+    valid_from TEXT NOT NULL,
+    valid_to TEXT,
+    note TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_commission_rules_validity ON commission_rules(valid_from, valid_to);
+
+-- Poliçe komisyonunun hesaplandığı kural (denetim için; NULL: varsayılan oran)
+ALTER TABLE policies ADD COLUMN commission_rule_id TEXT REFERENCES commission_rules(id);
@@ -0,0 +1,46 @@
+-- Şirket komisyon ekstreleri ve poliçe bazında mutabakat sonuçları
+CREATE TABLE IF NOT EXISTS provider_statements (
+    id TEXT PRIMARY KEY NOT NULL,
+    provider TEXT NOT NULL,
+    -- YYYY-MM (ekstrenin ait olduğu ay)
+    period TEXT NOT NULL,
+    file_name TEXT,
+    uploaded_by TEXT,
+    -- Ekstredeki farklı poliçe sayısı (aynı poliçenin satırları toplanır)
+    line_count INTEGER NOT NULL DEFAULT 0,
+    matched_count INTEGER NOT NULL DEFAULT 0,
+    mismatch_count INTEGER NOT NULL DEFAULT 0,
+    missing_ours_count INTEGER NOT NULL DEFAULT 0,
+    missing_theirs_count INTEGER NOT NULL DEFAULT 0,
+    statement_commission_total REAL NOT NULL DEFAULT 0,
+    our_commission_total REAL NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (uploaded_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_statements_period ON provider_statements(provider, period);
+
+-- status: matched | amount_mismatch | missing_ours (ekstrede var, bizde yok) | missing_theirs (bizde var, ekstrede yok)
+-- matched dışındaki kayıtlar resolved_at dolana kadar takipte kalır
+CREATE TABLE IF NOT EXISTS statement_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    statement_id TEXT NOT NULL,
+    policy_id TEXT,
+    policy_number TEXT NOT NULL,
+    status TEXT NOT NULL,
+    statement_commission REAL,
+    our_commission REAL,
+    difference REAL,
+    statement_premium REAL,
+    -- Ekstredeki satır numaraları (virgülle)
+    source_rows TEXT,
+    resolution_note TEXT,
+    resolved_by TEXT,
+    resolved_at TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (statement_id) REFERENCES provider_statements(id) ON DELETE CASCADE,
+    FOREIGN KEY (policy_id) REFERENCES policies(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_statement_items_statement ON statement_items(statement_id, status);
+CREATE INDEX IF NOT EXISTS idx_statement_items_open ON statement_items(status, resolved_at);
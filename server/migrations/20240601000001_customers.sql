@@ -0,0 +1,72 @@
+-- Sigortalılar: TCKN (11 hane) veya VKN (10 hane) ile tekil.
+-- Her teklifte request'teki sigortalı bilgileriyle güncellenir (boş gelen alan eskisini silmez).
+CREATE TABLE IF NOT EXISTS customers (
+    id TEXT PRIMARY KEY NOT NULL,
+    identity_no TEXT UNIQUE NOT NULL,
+    identity_type TEXT NOT NULL,         -- tckn | vkn
+    name TEXT NOT NULL,
+    -- Arama için küçük harfe ve ASCII'ye indirgenmiş ad ("ŞÜKRÜ Öztürk" -> "sukru ozturk")
+    name_key TEXT NOT NULL,
+    birth_date TEXT,
+    phone TEXT,                          -- uygulama 905551234567 biçiminde yazar
+    email TEXT,
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_quote_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_customers_name_key ON customers(name_key);
+CREATE INDEX IF NOT EXISTS idx_customers_phone ON customers(phone);
+
+ALTER TABLE quotes ADD COLUMN customer_id TEXT REFERENCES customers(id) ON DELETE SET NULL;
+ALTER TABLE policies ADD COLUMN customer_id TEXT REFERENCES customers(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_quotes_customer_id ON quotes(customer_id);
+CREATE INDEX IF NOT EXISTS idx_policies_customer_id ON policies(customer_id);
+
+-- Mevcut tekliflerden sigortalıları çıkar: kimlik no başına en son teklifin bilgileri.
+-- Telefon ham haliyle alınır; bir sonraki teklifte uygulama tarafından normalize edilir.
+INSERT OR IGNORE INTO customers (id, identity_no, identity_type, name, name_key, birth_date, phone, email, created_by, created_at, last_quote_at)
+SELECT
+    lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' || substr(lower(hex(randomblob(2))), 2) || '-'
+        || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(lower(hex(randomblob(2))), 2) || '-' || lower(hex(randomblob(6))),
+    identity_no,
+    CASE WHEN length(identity_no) = 11 THEN 'tckn' ELSE 'vkn' END,
+    name,
+    lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(name, 'ç', 'c'), 'Ç', 'c'), 'ğ', 'g'), 'Ğ', 'g'), 'ı', 'i'), 'İ', 'i'), 'I', 'i'), 'ö', 'o'), 'Ö', 'o'), 'ş', 's'), 'Ş', 's'), 'ü', 'u'), 'Ü', 'u')),
+    NULLIF(birth_date, ''),
+    NULLIF(phone, ''),
+    NULLIF(email, ''),
+    user_id,
+    first_quote_at,
+    created_at
+FROM (
+    SELECT
+        trim(json_extract(q.request_data, '$.insured.tckn')) AS identity_no,
+        trim(json_extract(q.request_data, '$.insured.name')) AS name,
+        json_extract(q.request_data, '$.insured.birthDate') AS birth_date,
+        json_extract(q.request_data, '$.insured.phone') AS phone,
+        json_extract(q.request_data, '$.insured.email') AS email,
+        q.user_id,
+        q.created_at,
+        MIN(q.created_at) OVER (PARTITION BY json_extract(q.request_data, '$.insured.tckn')) AS first_quote_at,
+        ROW_NUMBER() OVER (PARTITION BY json_extract(q.request_data, '$.insured.tckn') ORDER BY q.created_at DESC) AS rn
+    FROM quotes q
+    WHERE json_valid(q.request_data)
+)
+WHERE rn = 1
+  AND length(identity_no) IN (10, 11)
+  AND identity_no NOT GLOB '*[^0-9]*'
+  AND name IS NOT NULL AND name != '';
+
+UPDATE quotes
+SET customer_id = (
+    SELECT c.id FROM customers c
+    WHERE c.identity_no = trim(json_extract(quotes.request_data, '$.insured.tckn'))
+)
+WHERE customer_id IS NULL AND json_valid(request_data);
+
+UPDATE policies
+SET customer_id = (SELECT q.customer_id FROM quotes q WHERE q.id = policies.quote_id)
+WHERE customer_id IS NULL AND quote_id IS NOT NULL;
@@ -0,0 +1,79 @@
+-- Araçlar: sigortalı başına plaka ile tekil (araç satılınca yeni sahibinde yeni kayıt açılır).
+-- Her teklifte request'teki araç bilgileriyle güncellenir; ruhsat alanları yalnızca dolu geldiyse değişir.
+CREATE TABLE IF NOT EXISTS vehicles (
+    id TEXT PRIMARY KEY NOT NULL,
+    customer_id TEXT NOT NULL REFERENCES customers(id) ON DELETE CASCADE,
+    plate TEXT NOT NULL,                 -- büyük harf, boşluksuz (34ABC123)
+    vin TEXT,
+    brand TEXT NOT NULL,
+    model TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    usage TEXT NOT NULL,                 -- hususi | ticari
+    category TEXT NOT NULL,              -- otomobil | kamyonet | ...
+    registration_serial TEXT,            -- ruhsat belge seri (AB)
+    registration_no TEXT,                -- ruhsat belge no
+    engine_no TEXT,
+    registration_date TEXT,              -- tescil tarihi (YYYY-MM-DD)
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_quote_at TEXT,
+    UNIQUE (customer_id, plate)
+);
+
+CREATE INDEX IF NOT EXISTS idx_vehicles_plate ON vehicles(plate);
+CREATE INDEX IF NOT EXISTS idx_vehicles_vin ON vehicles(vin);
+
+ALTER TABLE quotes ADD COLUMN vehicle_id TEXT REFERENCES vehicles(id) ON DELETE SET NULL;
+ALTER TABLE policies ADD COLUMN vehicle_id TEXT REFERENCES vehicles(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_quotes_vehicle_id ON quotes(vehicle_id);
+CREATE INDEX IF NOT EXISTS idx_policies_vehicle_id ON policies(vehicle_id);
+
+-- Sigortalısı bilinen tekliflerdeki araçlar: sigortalı + plaka başına en son teklifin bilgileri
+INSERT OR IGNORE INTO vehicles (id, customer_id, plate, vin, brand, model, year, usage, category, created_by, created_at, last_quote_at)
+SELECT
+    lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' || substr(lower(hex(randomblob(2))), 2) || '-'
+        || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(lower(hex(randomblob(2))), 2) || '-' || lower(hex(randomblob(6))),
+    customer_id,
+    plate,
+    NULLIF(vin, ''),
+    COALESCE(brand, ''),
+    COALESCE(model, ''),
+    COALESCE(year, 0),
+    COALESCE(usage, 'hususi'),
+    COALESCE(category, 'otomobil'),
+    user_id,
+    first_quote_at,
+    created_at
+FROM (
+    SELECT
+        q.customer_id,
+        upper(replace(replace(json_extract(q.request_data, '$.vehicle.plate'), ' ', ''), '-', '')) AS plate,
+        json_extract(q.request_data, '$.vehicle.vin') AS vin,
+        json_extract(q.request_data, '$.vehicle.brand') AS brand,
+        json_extract(q.request_data, '$.vehicle.model') AS model,
+        json_extract(q.request_data, '$.vehicle.year') AS year,
+        json_extract(q.request_data, '$.vehicle.usage') AS usage,
+        json_extract(q.request_data, '$.vehicle.category') AS category,
+        q.user_id,
+        q.created_at,
+        MIN(q.created_at) OVER w AS first_quote_at,
+        ROW_NUMBER() OVER (w ORDER BY q.created_at DESC) AS rn
+    FROM quotes q
+    WHERE q.customer_id IS NOT NULL AND json_valid(q.request_data)
+    WINDOW w AS (PARTITION BY q.customer_id, upper(replace(replace(json_extract(q.request_data, '$.vehicle.plate'), ' ', ''), '-', '')))
+)
+WHERE rn = 1 AND plate IS NOT NULL AND plate != '';
+
+UPDATE quotes
+SET vehicle_id = (
+    SELECT v.id FROM vehicles v
+    WHERE v.customer_id = quotes.customer_id
+      AND v.plate = upper(replace(replace(json_extract(quotes.request_data, '$.vehicle.plate'), ' ', ''), '-', ''))
+)
+WHERE vehicle_id IS NULL AND customer_id IS NOT NULL AND json_valid(request_data);
+
+UPDATE policies
+SET vehicle_id = (SELECT q.vehicle_id FROM quotes q WHERE q.id = policies.quote_id)
+WHERE vehicle_id IS NULL AND quote_id IS NOT NULL;
@@ -0,0 +1,15 @@
+-- Teklif / poliçe aramaları: kullanıcı kapsamlı sıralamalar (id imleç eşitliğini bozar),
+-- ürün tipi ve prim aralığı filtreleri. Plaka, TCKN ve sigortalı filtreleri
+-- vehicles / customers üzerindeki mevcut indexlerle join edilir.
+CREATE INDEX IF NOT EXISTS idx_quotes_user_created ON quotes(user_id, created_at, id);
+CREATE INDEX IF NOT EXISTS idx_quotes_user_premium ON quotes(user_id, premium, id);
+CREATE INDEX IF NOT EXISTS idx_quotes_created_id ON quotes(created_at, id);
+CREATE INDEX IF NOT EXISTS idx_quotes_premium_id ON quotes(premium, id);
+CREATE INDEX IF NOT EXISTS idx_quotes_status ON quotes(status);
+CREATE INDEX IF NOT EXISTS idx_quotes_product_type ON quotes(json_extract(request_data, '$.coverage.productType'));
+
+CREATE INDEX IF NOT EXISTS idx_policies_user_created ON policies(user_id, created_at, id);
+CREATE INDEX IF NOT EXISTS idx_policies_user_premium ON policies(user_id, premium, id);
+CREATE INDEX IF NOT EXISTS idx_policies_created_id ON policies(created_at, id);
+CREATE INDEX IF NOT EXISTS idx_policies_premium_id ON policies(premium, id);
+CREATE INDEX IF NOT EXISTS idx_policies_product_type ON policies(product_type);
@@ -0,0 +1,89 @@
+-- Acenteler: kullanıcılar, sigortalılar, teklifler, poliçeler ve provider kullanıcı bilgileri acenteye bağlıdır.
+-- Mevcut kayıtlar 'default' acenteye taşınır; mevcut admin'ler tüm acenteleri gören super_admin olur.
+CREATE TABLE IF NOT EXISTS agencies (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT OR IGNORE INTO agencies (id, name) VALUES ('default', 'Varsayılan Acente');
+
+-- ALTER TABLE ile varsayılan değerli REFERENCES kolonu eklenemiyor; acente uygulama tarafında doğrulanır
+ALTER TABLE users ADD COLUMN agency_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE quotes ADD COLUMN agency_id TEXT NOT NULL DEFAULT 'default';
+ALTER TABLE policies ADD COLUMN agency_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_users_agency ON users(agency_id);
+CREATE INDEX IF NOT EXISTS idx_quotes_agency_created ON quotes(agency_id, created_at, id);
+CREATE INDEX IF NOT EXISTS idx_policies_agency_created ON policies(agency_id, created_at, id);
+
+UPDATE users SET role = 'super_admin' WHERE role = 'admin';
+
+-- Sigortalılar acente başına tekil (aynı TCKN iki acentede ayrı kayıt).
+-- UNIQUE kısıtı değiştirilemediği için tablo yeniden oluşturulur. DROP TABLE foreign key
+-- aksiyonlarını çalıştırır (araçlar silinir, teklif/poliçe bağlantıları NULL olur); önce saklanıp sonra geri yazılır.
+CREATE TEMP TABLE saved_vehicles AS SELECT * FROM vehicles;
+CREATE TEMP TABLE saved_quote_links AS
+    SELECT id, customer_id, vehicle_id FROM quotes WHERE customer_id IS NOT NULL OR vehicle_id IS NOT NULL;
+CREATE TEMP TABLE saved_policy_links AS
+    SELECT id, customer_id, vehicle_id FROM policies WHERE customer_id IS NOT NULL OR vehicle_id IS NOT NULL;
+
+CREATE TABLE customers_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    agency_id TEXT NOT NULL REFERENCES agencies(id),
+    identity_no TEXT NOT NULL,
+    identity_type TEXT NOT NULL,         -- tckn | vkn
+    name TEXT NOT NULL,
+    -- Arama için küçük harfe ve ASCII'ye indirgenmiş ad ("ŞÜKRÜ Öztürk" -> "sukru ozturk")
+    name_key TEXT NOT NULL,
+    birth_date TEXT,
+    phone TEXT,                          -- uygulama 905551234567 biçiminde yazar
+    email TEXT,
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_quote_at TEXT,
+    UNIQUE (agency_id, identity_no)
+);
+
+INSERT INTO customers_new
+    (id, agency_id, identity_no, identity_type, name, name_key, birth_date, phone, email, created_by, created_at, updated_at, last_quote_at)
+SELECT id, 'default', identity_no, identity_type, name, name_key, birth_date, phone, email, created_by, created_at, updated_at, last_quote_at
+FROM customers;
+
+DROP TABLE customers;
+ALTER TABLE customers_new RENAME TO customers;
+
+CREATE INDEX IF NOT EXISTS idx_customers_name_key ON customers(name_key);
+CREATE INDEX IF NOT EXISTS idx_customers_phone ON customers(phone);
+
+INSERT INTO vehicles SELECT * FROM saved_vehicles;
+
+UPDATE quotes
+SET customer_id = (SELECT s.customer_id FROM saved_quote_links s WHERE s.id = quotes.id),
+    vehicle_id = (SELECT s.vehicle_id FROM saved_quote_links s WHERE s.id = quotes.id)
+WHERE id IN (SELECT id FROM saved_quote_links);
+
+UPDATE policies
+SET customer_id = (SELECT s.customer_id FROM saved_policy_links s WHERE s.id = policies.id),
+    vehicle_id = (SELECT s.vehicle_id FROM saved_policy_links s WHERE s.id = policies.id)
+WHERE id IN (SELECT id FROM saved_policy_links);
+
+DROP TABLE saved_vehicles;
+DROP TABLE saved_quote_links;
+DROP TABLE saved_policy_links;
+
+-- Acentenin provider portal kullanıcı bilgileri. 'default' acentede kayıt yoksa .env'dekiler kullanılır,
+-- diğer acentelerde kaydı olmayan provider pasiftir.
+CREATE TABLE IF NOT EXISTS agency_provider_credentials (
+    agency_id TEXT NOT NULL REFERENCES agencies(id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,              -- küçük harf: sompo | axa | anadolu
+    username TEXT NOT NULL,
+    password TEXT NOT NULL,
+    secret_key TEXT,                     -- OTP anahtarı (Sompo)
+    updated_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (agency_id, provider)
+);
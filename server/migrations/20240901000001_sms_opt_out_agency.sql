@@ -0,0 +1,16 @@
+-- SMS red listesi acente başına: bir acentenin kullanıcıları başka acentenin listesini göremez/değiştiremez.
+-- Mevcut kayıtlar 'default' acenteye taşınır. Tabloya foreign key veren tablo yok, yeniden oluşturmak güvenli.
+CREATE TABLE sms_opt_outs_new (
+    agency_id TEXT NOT NULL REFERENCES agencies(id) ON DELETE CASCADE,
+    phone TEXT NOT NULL,
+    reason TEXT,
+    created_by TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (agency_id, phone)
+);
+
+INSERT INTO sms_opt_outs_new (agency_id, phone, reason, created_by, created_at)
+SELECT 'default', phone, reason, created_by, created_at FROM sms_opt_outs;
+
+DROP TABLE sms_opt_outs;
+ALTER TABLE sms_opt_outs_new RENAME TO sms_opt_outs;
@@ -0,0 +1,6 @@
+-- PDF başlığındaki acente bilgileri (adres, telefon, e-posta, SEDDK levha no).
+-- Varsayılan acentede boş bırakılan alanlar .env'deki AGENCY_* değerlerini kullanır.
+ALTER TABLE agencies ADD COLUMN address TEXT NOT NULL DEFAULT '';
+ALTER TABLE agencies ADD COLUMN phone TEXT NOT NULL DEFAULT '';
+ALTER TABLE agencies ADD COLUMN email TEXT NOT NULL DEFAULT '';
+ALTER TABLE agencies ADD COLUMN license_no TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,5 @@
+-- Komisyon ekstresi acente başına: mutabakat yalnızca yükleyen acentenin poliçeleriyle yapılır.
+-- Mevcut ekstreler 'default' acenteye atanır.
+ALTER TABLE provider_statements ADD COLUMN agency_id TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_provider_statements_agency ON provider_statements(agency_id, period);
@@ -0,0 +1,51 @@
+#!/usr/bin/env bash
+# Scraper E2E testleri: mock-portal ve chromedriver'ı başlatır, ignore edilmiş portal testlerini çalıştırır.
+#
+# Kullanım (server dizininden):
+#   scripts/portal_e2e.sh
+#
+# Gerekenler: Chrome/Chromium ve aynı sürümde chromedriver (PATH'te veya CHROMEDRIVER ile).
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+PORTAL_ADDR="${MOCK_PORTAL_ADDR:-127.0.0.1:9300}"
+DRIVER_PORT="${CHROMEDRIVER_PORT:-9515}"
+CHROMEDRIVER="${CHROMEDRIVER:-chromedriver}"
+
+export MOCK_PORTAL_ADDR="$PORTAL_ADDR"
+export MOCK_PORTAL_URL="http://$PORTAL_ADDR"
+export WEBDRIVER_URL="http://127.0.0.1:$DRIVER_PORT"
+
+pids=()
+cleanup() {
+    for pid in "${pids[@]}"; do
+        kill "$pid" 2>/dev/null || true
+    done
+}
+trap cleanup EXIT
+
+wait_for() {
+    local url="$1" name="$2"
+    for _ in $(seq 1 60); do
+        if curl -s -o /dev/null "$url"; then
+            return 0
+        fi
+        sleep 1
+    done
+    echo "❌ $name başlamadı: $url" >&2
+    exit 1
+}
+
+cargo build --bin mock-portal
+cargo build --tests
+
+"${CARGO_TARGET_DIR:-target}/debug/mock-portal" &
+pids+=($!)
+"$CHROMEDRIVER" --port="$DRIVER_PORT" &
+pids+=($!)
+
+wait_for "$MOCK_PORTAL_URL" "mock-portal"
+wait_for "$WEBDRIVER_URL/status" "chromedriver"
+
+cargo test --test portal_e2e_test -- --ignored
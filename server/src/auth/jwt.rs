@@ -1,3 +1,4 @@
+use crate::db::agencies::DEFAULT_AGENCY_ID;
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
@@ -8,14 +9,48 @@ pub struct Claims {
     pub sub: String, // user_id
     pub email: String,
     pub role: String,
+    /// Acente öncesi üretilmiş token'lar varsayılan acenteye aittir
+    #[serde(default = "default_agency_id")]
+    pub agency_id: String,
     pub exp: i64, // expiration timestamp
     pub iat: i64, // issued at
 }
 
+fn default_agency_id() -> String {
+    DEFAULT_AGENCY_ID.to_string()
+}
+
+impl Claims {
+    /// Tüm acenteleri yönetir
+    pub fn is_super_admin(&self) -> bool {
+        self.role == "super_admin"
+    }
+
+    /// Kendi acentesinin admini veya super-admin
+    pub fn is_admin(&self) -> bool {
+        self.role == "admin" || self.is_super_admin()
+    }
+
+    /// Sorgulara uygulanacak acente filtresi; super-admin için None (tüm acenteler)
+    pub fn agency_scope(&self) -> Option<&str> {
+        (!self.is_super_admin()).then_some(self.agency_id.as_str())
+    }
+
+    pub fn can_access_agency(&self, agency_id: &str) -> bool {
+        self.is_super_admin() || self.agency_id == agency_id
+    }
+
+    /// Kaydın sahibi veya kaydın acentesindeki admin
+    pub fn can_access(&self, user_id: &str, agency_id: &str) -> bool {
+        self.sub == user_id || (self.is_admin() && self.can_access_agency(agency_id))
+    }
+}
+
 pub fn create_token(
     user_id: &str,
     email: &str,
     role: &str,
+    agency_id: &str,
     jwt_secret: &str,
 ) -> Result<String, jsonwebtoken::errors::Error> {
     let now = Utc::now();
@@ -25,6 +60,7 @@ pub fn create_token(
         sub: user_id.to_string(),
         email: email.to_string(),
         role: role.to_string(),
+        agency_id: agency_id.to_string(),
         exp,
         iat: now.timestamp(),
     };
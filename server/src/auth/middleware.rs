@@ -1,4 +1,5 @@
 use crate::auth::jwt::{verify_token, Claims};
+use crate::db::{users, DbPool};
 use axum::{
     extract::{Request, State},
     http::StatusCode,
@@ -6,8 +7,16 @@ use axum::{
     response::Response,
 };
 
+#[derive(Clone)]
+pub struct AuthState {
+    pub jwt_secret: String,
+    pub db_pool: DbPool,
+}
+
+/// Token imzası doğrulanır; rol ve acente token'dan değil kullanıcı kaydından alınır.
+/// Pasif kullanıcı veya pasif acentenin token'ı süresi dolmadan da reddedilir.
 pub async fn auth_middleware(
-    State(jwt_secret): State<String>,
+    State(auth): State<AuthState>,
     mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
@@ -23,9 +32,20 @@ pub async fn auth_middleware(
         .ok_or(StatusCode::UNAUTHORIZED)?;
     
     // Token'ı doğrula
-    let claims = verify_token(token, &jwt_secret)
+    let mut claims = verify_token(token, &auth.jwt_secret)
         .map_err(|_| StatusCode::UNAUTHORIZED)?;
     
+    // Acente değişikliği / rol düşürme yeni giriş beklemeden geçerli olur
+    let user = users::get_active_user(&auth.db_pool, &claims.sub)
+        .await
+        .map_err(|e| {
+            tracing::error!("❌ Token kullanıcısı okunamadı: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    claims.role = user.role;
+    claims.agency_id = user.agency_id;
+    
     // Claims'i request'e ekle
     req.extensions_mut().insert(claims);
     
@@ -43,7 +63,25 @@ pub async fn admin_middleware(
         .cloned()
         .ok_or(StatusCode::UNAUTHORIZED)?;
     
-    if claims.role != "admin" {
+    if !claims.is_admin() {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    
+    Ok(next.run(req).await)
+}
+
+// Super-admin middleware: acente yönetimi ve tüm acenteleri etkileyen işlemler
+pub async fn super_admin_middleware(
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    
+    if !claims.is_super_admin() {
         return Err(StatusCode::FORBIDDEN);
     }
     
@@ -4,5 +4,5 @@ pub mod middleware;
 
 pub use hash::{hash_password, verify_password};
 pub use jwt::{create_token, Claims};
-pub use middleware::{admin_middleware, auth_middleware};
+pub use middleware::{admin_middleware, auth_middleware, super_admin_middleware, AuthState};
 
@@ -0,0 +1,646 @@
+//! Scraper E2E testleri için sahte acente portalı.
+//!
+//! Sompo ve Quick akışlarının beklediği yapıyı taklit eder: login formu, TOTP ekranı,
+//! "YENİ İŞ TEKLİFİ" dashboard'u, trafik/kasko formu ve sonuç sayfası.
+//!
+//! ```text
+//! cargo run --bin mock-portal
+//! SOMPO_BASE_URL=http://127.0.0.1:9300/dashboard/login SOMPO_USER=demo SOMPO_PASS=demo123 \
+//! SOMPO_SECRET_KEY=JBSWY3DPEHPK3PXP QUICK_URL=http://127.0.0.1:9300/quick/agent/login ...
+//! ```
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Json, Router,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_lite::{totp_custom, Sha1};
+
+const SESSION_COOKIE: &str = "PORTAL_SESSION";
+const PENDING_COOKIE: &str = "PORTAL_PENDING";
+
+#[derive(Clone)]
+struct PortalState {
+    username: String,
+    password: String,
+    totp_secret: Vec<u8>,
+    /// Şifre adımını geçmiş, OTP bekleyen token'lar
+    pending: Arc<Mutex<HashSet<String>>>,
+    sessions: Arc<Mutex<HashSet<String>>>,
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+    username: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct OtpForm {
+    otp: String,
+}
+
+#[derive(Deserialize)]
+struct ProposalForm {
+    #[serde(default)]
+    plaka: String,
+    #[serde(default)]
+    tckn: String,
+}
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::fmt().with_env_filter("info").init();
+
+    let addr = std::env::var("MOCK_PORTAL_ADDR").unwrap_or_else(|_| "127.0.0.1:9300".to_string());
+    let secret = std::env::var("MOCK_PORTAL_TOTP_SECRET").unwrap_or_else(|_| "JBSWY3DPEHPK3PXP".to_string());
+
+    let state = PortalState {
+        username: std::env::var("MOCK_PORTAL_USER").unwrap_or_else(|_| "demo".to_string()),
+        password: std::env::var("MOCK_PORTAL_PASS").unwrap_or_else(|_| "demo123".to_string()),
+        totp_secret: data_encoding::BASE32
+            .decode(secret.to_uppercase().as_bytes())
+            .expect("MOCK_PORTAL_TOTP_SECRET base32 olmalı"),
+        pending: Arc::new(Mutex::new(HashSet::new())),
+        sessions: Arc::new(Mutex::new(HashSet::new())),
+    };
+
+    let listener = tokio::net::TcpListener::bind(&addr).await.expect("Port açılamadı");
+    tracing::info!("🧪 Mock portal: http://{}", addr);
+    tracing::info!("   Sompo: http://{}/dashboard/login  |  Quick: http://{}/quick/agent/login", addr, addr);
+
+    axum::serve(listener, router(state)).await.expect("Mock portal durdu");
+}
+
+fn router(state: PortalState) -> Router {
+    Router::new()
+        // Sompo benzeri akış
+        .route("/", get(|| async { Redirect::to("/dashboard") }))
+        .route("/dashboard/login/login", get(sompo_login_page).post(sompo_login_submit))
+        .route(
+            "/dashboard/login/google-authenticator-validation",
+            get(sompo_otp_page).post(sompo_otp_submit),
+        )
+        .route("/dashboard", get(sompo_dashboard))
+        .route("/dashboard/proposal/:product", get(sompo_proposal_form).post(sompo_proposal_result))
+        .route("/api/proposal/:product/calculate", post(sompo_calculate_api))
+        .route("/api/proposal/:product/issue", post(sompo_issue_api))
+        .route("/logout", get(logout))
+        // Quick benzeri akış (OTP yok, login sonrası direkt form)
+        .route("/quick/agent/login", get(quick_login_page).post(quick_login_submit))
+        .route("/quick/agent/trafik", get(quick_form).post(quick_result))
+        .with_state(state)
+}
+
+// ==================== Sompo ====================
+
+async fn sompo_login_page() -> Html<String> {
+    Html(sompo_login_html(None))
+}
+
+async fn sompo_login_submit(State(state): State<PortalState>, Form(form): Form<LoginForm>) -> Response {
+    if form.username != state.username || form.password != state.password {
+        return Html(sompo_login_html(Some("Kullanıcı adı veya şifre hatalı"))).into_response();
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    state.pending.lock().unwrap().insert(token.clone());
+
+    redirect_with_cookie("/dashboard/login/google-authenticator-validation", PENDING_COOKIE, &token)
+}
+
+async fn sompo_otp_page(State(state): State<PortalState>, headers: HeaderMap) -> Response {
+    if !has_token(&state.pending, &headers, PENDING_COOKIE) {
+        return Redirect::to("/dashboard/login/login").into_response();
+    }
+    Html(sompo_otp_html(None)).into_response()
+}
+
+async fn sompo_otp_submit(State(state): State<PortalState>, headers: HeaderMap, Form(form): Form<OtpForm>) -> Response {
+    let Some(pending) = cookie(&headers, PENDING_COOKIE) else {
+        return Redirect::to("/dashboard/login/login").into_response();
+    };
+    if !state.pending.lock().unwrap().contains(&pending) {
+        return Redirect::to("/dashboard/login/login").into_response();
+    }
+
+    if !verify_totp(&state.totp_secret, form.otp.trim(), unix_now()) {
+        return Html(sompo_otp_html(Some("Doğrulama kodu hatalı"))).into_response();
+    }
+
+    state.pending.lock().unwrap().remove(&pending);
+    let token = uuid::Uuid::new_v4().to_string();
+    state.sessions.lock().unwrap().insert(token.clone());
+
+    redirect_with_cookie("/dashboard", SESSION_COOKIE, &token)
+}
+
+async fn sompo_dashboard(State(state): State<PortalState>, headers: HeaderMap) -> Response {
+    if !has_token(&state.sessions, &headers, SESSION_COOKIE) {
+        return Redirect::to("/dashboard/login/login").into_response();
+    }
+    Html(page(
+        "Sompo Sigorta - Dashboard",
+        r#"<header class="navbar">
+  <div class="user-menu"><span>DEMO ACENTE</span> <a href="/logout">Çıkış</a></div>
+</header>
+<main class="dashboard">
+  <h2>Hoş geldiniz</h2>
+  <button type="button" class="new-proposal" onclick="document.getElementById('product-modal').style.display='block'">YENİ İŞ TEKLİFİ</button>
+  <div id="product-modal" class="modal" role="dialog" style="display:none">
+    <h3>Ürün Seçimi</h3>
+    <div class="product-grid">
+      <div class="product-card"><h4>Trafik</h4><p>Zorunlu Trafik Sigortası</p>
+        <button type="button" onclick="location.href='/dashboard/proposal/trafik'">Trafik Teklif Al</button></div>
+      <div class="product-card"><h4>Kasko</h4><p>Genişletilmiş Kasko</p>
+        <button type="button" onclick="location.href='/dashboard/proposal/kasko'">Kasko Teklif Al</button></div>
+      <div class="product-card"><h4>Konut</h4><p>Konut Sigortası</p>
+        <button type="button" disabled>Konut Teklif Al</button></div>
+      <div class="product-card"><h4>DASK</h4><p>Zorunlu Deprem Sigortası</p>
+        <button type="button" disabled>DASK Teklif Al</button></div>
+    </div>
+  </div>
+</main>"#,
+    ))
+    .into_response()
+}
+
+async fn sompo_proposal_form(
+    State(state): State<PortalState>,
+    headers: HeaderMap,
+    Path(product): Path<String>,
+) -> Response {
+    if !has_token(&state.sessions, &headers, SESSION_COOKIE) {
+        return Redirect::to("/dashboard/login/login").into_response();
+    }
+    let Some(title) = product_title(&product) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    Html(page(
+        &format!("Sompo Sigorta - {} Teklifi", title),
+        &format!(
+            r#"<main class="proposal-form">
+  <h2>{title} Teklifi</h2>
+  <form method="post" action="/dashboard/proposal/{product}">
+    <div class="form-row"><label for="plaka">Plaka</label>
+      <input type="text" id="plaka" name="plaka" placeholder="34ABC123"></div>
+    <div class="form-row"><label for="tckn">TC Kimlik No</label>
+      <input type="text" id="tckn" name="tckn" placeholder="TCKN"></div>
+    <button type="submit" class="teklif-btn">Teklif Al</button>
+  </form>
+</main>"#
+        ),
+    ))
+    .into_response()
+}
+
+async fn sompo_proposal_result(
+    State(state): State<PortalState>,
+    headers: HeaderMap,
+    Path(product): Path<String>,
+    Form(form): Form<ProposalForm>,
+) -> Response {
+    if !has_token(&state.sessions, &headers, SESSION_COOKIE) {
+        return Redirect::to("/dashboard/login/login").into_response();
+    }
+    let Some(title) = product_title(&product) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if form.plaka.trim().is_empty() {
+        return Html(page("Hata", r#"<div class="alert alert-danger">Plaka zorunludur</div>"#)).into_response();
+    }
+
+    let premium = portal_premium(&form.plaka, &product);
+    let api_product = if product == "kasko" { "casco" } else { "traffic" };
+
+    Html(page(
+        &format!("Sompo Sigorta - {} Teklif Sonucu", title),
+        &format!(
+            r#"<main class="proposal-result">
+  <h2>{title} Teklifi</h2>
+  <div class="proposal-info"><span>Plaka: {plate}</span> <span class="proposal-no"></span></div>
+  <div class="premium-box"><label>Ödenecek Prim</label> <span class="premium">{premium}</span></div>
+  <p class="validity">Teklif geçerlilik süresi: 15 gün</p>
+  <table class="teminat-table">
+    <tr><th>Teminat</th><th>Limit</th></tr>
+    {coverages}
+  </table>
+  <table class="taksit-table">
+    <tr><th>Ödeme Seçeneği</th><th>Tutar</th><th>Toplam</th></tr>
+    <tr><td>Peşin</td><td>{premium}</td><td>{premium}</td></tr>
+    <tr><td>3 Taksit</td><td>{third}</td><td>{premium}</td></tr>
+  </table>
+</main>
+<script>
+  // Gerçek portal gibi fiyat XHR ile de dönüyor (CDP XHR yakalama testi için)
+  fetch('/api/proposal/{api_product}/calculate', {{
+    method: 'POST',
+    headers: {{ 'Content-Type': 'application/json' }},
+    body: JSON.stringify({{ plateNo: '{plate}', identityNo: '{tckn}' }})
+  }}).then(r => r.json()).then(d => {{
+    document.querySelector('.proposal-no').textContent = 'Teklif No: ' + d.data.proposalNo;
+  }});
+</script>"#,
+            plate = html_escape(&form.plaka),
+            tckn = html_escape(&form.tckn),
+            premium = format_tl(premium),
+            third = format_tl((premium / 3.0 * 100.0).round() / 100.0),
+            coverages = coverage_rows(&product),
+        ),
+    ))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CalculateRequest {
+    #[serde(default)]
+    plate_no: String,
+}
+
+async fn sompo_calculate_api(
+    State(state): State<PortalState>,
+    headers: HeaderMap,
+    Path(product): Path<String>,
+    Json(body): Json<CalculateRequest>,
+) -> Response {
+    if !api_authorized(&state, &headers) {
+        return api_unauthorized();
+    }
+
+    let product = match product.as_str() {
+        "traffic" => "trafik",
+        "casco" => "kasko",
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+
+    let gross = portal_premium(&body.plate_no, product);
+    let net = (gross / 1.18 * 100.0).round() / 100.0;
+
+    Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "proposalNo": format!("T-{}", &uuid::Uuid::new_v4().simple().to_string()[..8].to_uppercase()),
+            "validUntil": (chrono::Utc::now().date_naive() + chrono::Days::new(15)).format("%d.%m.%Y").to_string(),
+            "premiumInfo": {
+                "netPremium": net,
+                "taxAmount": ((gross - net) * 100.0).round() / 100.0,
+                "grossPremium": gross
+            },
+            "installments": [
+                { "installmentCount": 1, "installmentAmount": gross, "totalAmount": gross },
+                { "installmentCount": 3, "installmentAmount": (gross / 3.0 * 100.0).round() / 100.0, "totalAmount": gross }
+            ]
+        }
+    }))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IssueRequest {
+    #[serde(default)]
+    proposal_no: String,
+    #[serde(default)]
+    start_date: String,
+    #[serde(default)]
+    gross_premium: serde_json::Value,
+    #[serde(default)]
+    payment: serde_json::Value,
+}
+
+async fn sompo_issue_api(
+    State(state): State<PortalState>,
+    headers: HeaderMap,
+    Path(product): Path<String>,
+    Json(body): Json<IssueRequest>,
+) -> Response {
+    if !api_authorized(&state, &headers) {
+        return api_unauthorized();
+    }
+
+    if !matches!(product.as_str(), "traffic" | "casco") {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    if body.proposal_no.trim().is_empty() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({ "success": false, "message": "Teklif numarası gerekli" })),
+        )
+            .into_response();
+    }
+
+    // Kredi kartında kart numarası zorunlu; portal ödeme reddini 422 ile bildirir
+    let card_number = body.payment["card"]["number"].as_str().unwrap_or_default();
+    if body.payment["type"] == "credit_card" && card_number.len() < 15 {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({ "success": false, "message": "Ödeme reddedildi" })),
+        )
+            .into_response();
+    }
+
+    let start = chrono::NaiveDate::parse_from_str(&body.start_date, "%Y-%m-%d")
+        .unwrap_or_else(|_| chrono::Utc::now().date_naive());
+    let end = start.checked_add_months(chrono::Months::new(12)).unwrap_or(start);
+
+    Json(serde_json::json!({
+        "success": true,
+        "data": {
+            "policy": {
+                "policyNo": format!("{}", 1_200_000_000 + (uuid::Uuid::new_v4().as_u128() % 100_000_000) as u64),
+                // Portal tarihleri Türkçe formatta döner
+                "startDate": start.format("%d.%m.%Y").to_string(),
+                "endDate": end.format("%d.%m.%Y").to_string()
+            },
+            "premiumInfo": { "grossPremium": body.gross_premium }
+        }
+    }))
+    .into_response()
+}
+
+fn api_authorized(state: &PortalState, headers: &HeaderMap) -> bool {
+    let bearer_ok = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| state.sessions.lock().unwrap().contains(t))
+        .unwrap_or(false);
+
+    bearer_ok || has_token(&state.sessions, headers, SESSION_COOKIE)
+}
+
+fn api_unauthorized() -> Response {
+    (StatusCode::UNAUTHORIZED, Json(serde_json::json!({ "success": false, "message": "Oturum geçersiz" })))
+        .into_response()
+}
+
+async fn logout(State(state): State<PortalState>, headers: HeaderMap) -> Response {
+    if let Some(token) = cookie(&headers, SESSION_COOKIE) {
+        state.sessions.lock().unwrap().remove(&token);
+    }
+    Redirect::to("/dashboard/login/login").into_response()
+}
+
+// ==================== Quick ====================
+
+async fn quick_login_page() -> Html<String> {
+    Html(quick_login_html(None))
+}
+
+async fn quick_login_submit(State(state): State<PortalState>, Form(form): Form<LoginForm>) -> Response {
+    if form.username != state.username || form.password != state.password {
+        return Html(quick_login_html(Some("Hatalı kullanıcı bilgileri"))).into_response();
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    state.sessions.lock().unwrap().insert(token.clone());
+
+    redirect_with_cookie("/quick/agent/trafik", SESSION_COOKIE, &token)
+}
+
+async fn quick_form(State(state): State<PortalState>, headers: HeaderMap) -> Response {
+    if !has_token(&state.sessions, &headers, SESSION_COOKIE) {
+        return Redirect::to("/quick/agent/login").into_response();
+    }
+    Html(page(
+        "Quick Sigorta - Trafik",
+        r#"<div class="container">
+  <h3>Zorunlu Trafik Sigortası</h3>
+  <form method="post" action="/quick/agent/trafik">
+    <input type="text" name="plaka" placeholder="plaka">
+    <button type="submit">Teklif Al</button>
+  </form>
+</div>"#,
+    ))
+    .into_response()
+}
+
+async fn quick_result(State(state): State<PortalState>, headers: HeaderMap, Form(form): Form<ProposalForm>) -> Response {
+    if !has_token(&state.sessions, &headers, SESSION_COOKIE) {
+        return Redirect::to("/quick/agent/login").into_response();
+    }
+    let premium = portal_premium(&format!("quick:{}", form.plaka), "trafik");
+
+    Html(page(
+        "Quick Sigorta - Teklif",
+        &format!(
+            r#"<div class="container">
+  <h3>Zorunlu Trafik Sigortası</h3>
+  <div class="offer-card"><span class="fiyat">{}</span>
+    <p>Teklif geçerlilik süresi: 15 gün</p></div>
+</div>"#,
+            format_tl(premium)
+        ),
+    ))
+    .into_response()
+}
+
+// ==================== HTML ====================
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"tr\">\n<head><meta charset=\"utf-8\"><title>{}</title></head>\n<body>\n{}\n</body>\n</html>",
+        title, body
+    )
+}
+
+/// Yapı login.rs'teki USERNAME_XPATH / PASSWORD_XPATH ile birebir uyumlu
+fn sompo_login_html(error: Option<&str>) -> String {
+    page(
+        "Sompo Sigorta - Acente Girişi",
+        &format!(
+            r#"<div id="app">
+  <div class="login-wrapper">
+    <div class="brand"><h1>Sompo Sigorta</h1></div>
+    <div class="login-panel">
+      <form method="post" action="/dashboard/login/login">
+        <div class="form-group"><div><input type="text" name="username" placeholder="Kullanıcı Adı"></div></div>
+        <div class="form-group"><div><div><input type="password" name="password" placeholder="Şifre"></div></div></div>
+        {}
+        <button type="submit" class="login-btn">Giriş</button>
+      </form>
+    </div>
+  </div>
+</div>"#,
+            error_html(error)
+        ),
+    )
+}
+
+fn sompo_otp_html(error: Option<&str>) -> String {
+    page(
+        "Sompo Sigorta - Doğrulama",
+        &format!(
+            r#"<div class="otp-wrapper">
+  <h2>Google Authenticator Doğrulama</h2>
+  <form id="otp-form" method="post" action="/dashboard/login/google-authenticator-validation">
+    <input type="text" name="otp" placeholder="OTP Kodu" autocomplete="one-time-code" maxlength="6">
+    {}
+    <button type="submit" class="otp-submit">Doğrula</button>
+  </form>
+</div>
+<script>
+  // Portal 6 hane girilince formu kendisi gönderiyor
+  const otp = document.querySelector('input[name="otp"]');
+  otp.addEventListener('input', () => {{
+    if (otp.value.length === 6) document.getElementById('otp-form').submit();
+  }});
+</script>"#,
+            error_html(error)
+        ),
+    )
+}
+
+fn quick_login_html(error: Option<&str>) -> String {
+    page(
+        "Quick Sigorta - Acente Girişi",
+        &format!(
+            r#"<div class="container">
+  <form method="post" action="/quick/agent/login">
+    <input type="text" name="username" placeholder="Kullanıcı adı">
+    <input type="password" name="password" placeholder="Şifre">
+    {}
+    <button type="submit" class="login-btn">Giriş</button>
+  </form>
+</div>"#,
+            error_html(error)
+        ),
+    )
+}
+
+fn error_html(error: Option<&str>) -> String {
+    error
+        .map(|e| format!(r#"<div class="alert alert-danger">{}</div>"#, e))
+        .unwrap_or_default()
+}
+
+fn coverage_rows(product: &str) -> String {
+    let rows: &[(&str, &str)] = if product == "kasko" {
+        &[("Kasko", "Araç Rayiç Değeri"), ("İhtiyari Mali Mesuliyet", "1.000.000 TL"), ("Ferdi Kaza", "Dahil Değil")]
+    } else {
+        &[("Maddi Hasar (Araç Başı)", "300.000 TL"), ("Sağlık Giderleri (Kişi Başı)", "2.700.000 TL")]
+    };
+    rows.iter()
+        .map(|(name, limit)| format!("<tr><td>{}</td><td>{}</td></tr>", name, limit))
+        .collect::<Vec<_>>()
+        .join("\n    ")
+}
+
+fn product_title(product: &str) -> Option<&'static str> {
+    match product {
+        "trafik" => Some("Trafik"),
+        "kasko" => Some("Kasko"),
+        _ => None,
+    }
+}
+
+// ==================== Yardımcılar ====================
+
+/// Plakaya göre deterministik prim (testler sabit değer bekleyebilsin)
+fn portal_premium(plate: &str, product: &str) -> f64 {
+    let normalized: String = plate.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+    let hash = normalized
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    let (base, spread) = if product == "kasko" { (12000.0, 10000.0) } else { (3500.0, 2500.0) };
+    ((base + (hash % 10_000) as f64 / 10_000.0 * spread) * 100.0).round() / 100.0
+}
+
+/// 4350.5 -> "4.350,50 TL"
+fn format_tl(value: f64) -> String {
+    let cents = (value * 100.0).round() as u64;
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, c) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i).is_multiple_of(3) {
+            grouped.push('.');
+        }
+        grouped.push(c);
+    }
+    format!("{},{:02} TL", grouped, cents % 100)
+}
+
+/// ±1 time window toleransı (login.rs ile aynı)
+fn verify_totp(secret: &[u8], code: &str, now: u64) -> bool {
+    [now.saturating_sub(30), now, now + 30]
+        .iter()
+        .any(|t| totp_custom::<Sha1>(30, 6, secret, *t) == code)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(k, _)| *k == name)
+        .map(|(_, v)| v.to_string())
+}
+
+fn has_token(store: &Mutex<HashSet<String>>, headers: &HeaderMap, name: &str) -> bool {
+    cookie(headers, name)
+        .map(|t| store.lock().unwrap().contains(&t))
+        .unwrap_or(false)
+}
+
+fn redirect_with_cookie(location: &str, name: &str, value: &str) -> Response {
+    (
+        StatusCode::SEE_OTHER,
+        [
+            (header::LOCATION, location.to_string()),
+            (header::SET_COOKIE, format!("{}={}; Path=/; HttpOnly", name, value)),
+        ],
+    )
+        .into_response()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_totp_window() {
+        let secret = data_encoding::BASE32.decode(b"JBSWY3DPEHPK3PXP").unwrap();
+        let now = 1_700_000_000;
+
+        let current = totp_custom::<Sha1>(30, 6, &secret, now);
+        let previous = totp_custom::<Sha1>(30, 6, &secret, now - 30);
+        let stale = totp_custom::<Sha1>(30, 6, &secret, now - 120);
+
+        assert!(verify_totp(&secret, &current, now));
+        assert!(verify_totp(&secret, &previous, now));
+        assert!(!verify_totp(&secret, &stale, now));
+    }
+
+    #[test]
+    fn test_result_page_parses_with_sompo_parser() {
+        let premium = portal_premium("34ABC123", "trafik");
+        let html = page("Sonuç", &format!(r#"<span class="premium">{}</span>"#, format_tl(premium)));
+
+        let parsed = sigorta_server::providers::sompo::parser::parse_sompo_html(&html).unwrap();
+        assert_eq!(parsed.premium, premium);
+        assert_eq!(format_tl(4350.5), "4.350,50 TL");
+    }
+}
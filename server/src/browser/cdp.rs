@@ -1,3 +1,4 @@
+use crate::browser::har::HarRecorder;
 use crate::config::Config;
 use chromiumoxide::browser::{Browser, BrowserConfig};
 use chromiumoxide::error::CdpError;
@@ -110,6 +111,21 @@ pub async fn inject_anti_detection(page: &Page) -> Result<(), CdpError> {
     Ok(())
 }
 
+/// HAR kaydını başlat (HAR_CAPTURE=true ise). Kapalıysa veya başlatılamazsa None döner.
+pub async fn start_har_capture(page: &Page, config: &Config) -> Option<HarRecorder> {
+    if !config.har_capture {
+        return None;
+    }
+    
+    match HarRecorder::start(page).await {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            tracing::warn!("⚠️ HAR kaydı başlatılamadı: {}", e);
+            None
+        }
+    }
+}
+
 /// Wait for navigation (Playwright benzeri)
 pub async fn wait_for_navigation(
     page: &Page,
@@ -1,6 +1,9 @@
+use crate::browser::session::{Cookie, SessionData};
 use crate::config::Config;
-use fantoccini::{Client, ClientBuilder};
+use crate::http::ApiError;
+use fantoccini::{Client, ClientBuilder, Locator};
 use serde_json::json;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub async fn create_webdriver_client(config: &Config) -> Result<Client, fantoccini::error::NewSessionError> {
     let mut caps = serde_json::Map::new();
@@ -110,3 +113,181 @@ pub async fn create_webdriver_client(config: &Config) -> Result<Client, fantocci
     Ok(client)
 }
 
+
+/// Açık WebDriver oturumunun cookie ve localStorage'ını SessionData olarak alır (1 saat geçerli)
+pub async fn capture_session(client: &Client) -> Result<SessionData, ApiError> {
+    // Fantoccini'den cookie'leri al
+    let cookies_raw = client.get_all_cookies().await
+        .map_err(|e| ApiError::WebDriverError(format!("Cookie alınamadı: {}", e)))?;
+    
+    let cookies: Vec<Cookie> = cookies_raw
+        .into_iter()
+        .map(|c| Cookie {
+            name: c.name().to_string(),
+            value: c.value().to_string(),
+            domain: c.domain().unwrap_or("").to_string(),
+            path: c.path().unwrap_or("/").to_string(),
+            secure: c.secure().unwrap_or(false),
+            http_only: c.http_only().unwrap_or(false),
+        })
+        .collect();
+    
+    // LocalStorage'ı al (Python kodundan gelen özellik)
+    let js_get_local_storage = r#"
+        try {
+            const items = {};
+            for (let i = 0; i < localStorage.length; i++) {
+                const key = localStorage.key(i);
+                items[key] = localStorage.getItem(key);
+            }
+            return JSON.stringify(items);
+        } catch (e) {
+            return JSON.stringify({});
+        }
+    "#;
+    
+    let local_storage = match client.execute(js_get_local_storage, vec![]).await {
+        Ok(result) => {
+            if let Some(json_str) = result.as_str() {
+                match serde_json::from_str::<std::collections::HashMap<String, String>>(json_str) {
+                    Ok(map) => {
+                        tracing::info!("💾 LocalStorage alındı: {} items", map.len());
+                        map
+                    }
+                    Err(e) => {
+                        tracing::warn!("LocalStorage parse hatası: {}", e);
+                        std::collections::HashMap::new()
+                    }
+                }
+            } else {
+                std::collections::HashMap::new()
+            }
+        }
+        Err(e) => {
+            tracing::warn!("LocalStorage alınamadı: {}", e);
+            std::collections::HashMap::new()
+        }
+    };
+    
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    
+    // Session 1 saat geçerli
+    let valid_until = now + 3600;
+    
+    let session = SessionData {
+        cookies,
+        local_storage,
+        timestamp: now,
+        valid_until,
+    };
+    
+    Ok(session)
+}
+
+/// Kayıtlı session'ı (cookies + localStorage) base URL üzerinden tarayıcıya yükler
+pub async fn restore_session(client: &Client, session: &SessionData, base_url: &str) -> Result<(), String> {
+    // Önce domain'e git ki cookie'leri set edebilsin
+    client.goto(base_url).await
+        .map_err(|e| format!("Sayfa yüklenemedi: {}", e))?;
+    
+    // Cookie'leri yükle
+    for cookie in &session.cookies {
+        // Fantoccini'de add_cookie fonksiyonu farklı şekilde çalışıyor
+        // Bu yüzden script ile ekleyeceğiz
+        let script = format!(
+            r#"document.cookie = "{}={}; domain={}; path={}; {}{}""#,
+            cookie.name,
+            cookie.value,
+            cookie.domain,
+            cookie.path,
+            if cookie.secure { "secure; " } else { "" },
+            if cookie.http_only { "httpOnly; " } else { "" }
+        );
+        
+        if let Err(e) = client.execute(&script, vec![]).await {
+            tracing::warn!("Cookie set edilemedi: {:?}", e);
+        }
+    }
+    
+    // LocalStorage'ı yükle (Python kodundan gelen özellik)
+    if !session.local_storage.is_empty() {
+        let local_storage_json = serde_json::to_string(&session.local_storage)
+            .unwrap_or_else(|_| "{}".to_string());
+        
+        let js_set_local_storage = format!(r#"
+            try {{
+                const data = {};
+                for (const [key, value] of Object.entries(data)) {{
+                    localStorage.setItem(key, value);
+                }}
+                return true;
+            }} catch (e) {{
+                return false;
+            }}
+        "#, local_storage_json);
+        
+        match client.execute(&js_set_local_storage, vec![]).await {
+            Ok(_) => {
+                tracing::info!("💾 LocalStorage yüklendi: {} items", session.local_storage.len());
+            }
+            Err(e) => {
+                tracing::warn!("LocalStorage yüklenemedi: {}", e);
+            }
+        }
+    }
+    
+    // Sayfayı yenile
+    client.refresh().await
+        .map_err(|e| format!("Sayfa yenilenemedi: {}", e))?;
+    
+    Ok(())
+}
+
+/// Selector listesindeki ilk bulunan input'a değer yazar
+pub async fn fill_first(client: &Client, selectors: &[&str], value: &str) -> bool {
+    for selector in selectors {
+        if let Ok(elem) = client.find(Locator::Css(selector)).await {
+            if elem.send_keys(value).await.is_ok() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Selector listesindeki ilk bulunan elemente tıklar
+pub async fn click_first(client: &Client, selectors: &[&str]) -> bool {
+    for selector in selectors {
+        if let Ok(elem) = client.find(Locator::Css(selector)).await {
+            if elem.click().await.is_ok() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Selector listesindeki ilk bulunan select'te value'ya göre seçim yapar
+pub async fn select_first(client: &Client, selectors: &[&str], value: &str) -> bool {
+    for selector in selectors {
+        if let Ok(elem) = client.find(Locator::Css(selector)).await {
+            if elem.select_by_value(value).await.is_ok() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Selector listesinden herhangi biri sayfada var mı
+pub async fn any_present(client: &Client, selectors: &[&str]) -> bool {
+    for selector in selectors {
+        if client.find(Locator::Css(selector)).await.is_ok() {
+            return true;
+        }
+    }
+    false
+}
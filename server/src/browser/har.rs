@@ -0,0 +1,586 @@
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived,
+    GetResponseBodyParams, Headers, ResourceType, Response,
+};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::Page;
+use futures::StreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// HAR'a yazılmadan önce değeri maskelenecek header'lar (küçük harf)
+const REDACTED_HEADERS: &[&str] = &[
+    "cookie",
+    "set-cookie",
+    "authorization",
+    "proxy-authorization",
+    "x-auth-token",
+    "x-access-token",
+    "x-csrf-token",
+    "x-xsrf-token",
+];
+
+const REDACTED_VALUE: &str = "[REDACTED]";
+
+/// JSON body'lerde değeri maskelenecek alanlar: küçük harfe çevrilmiş, `_`/`-` atılmış ad bunlardan birini içeriyorsa
+const REDACTED_BODY_KEYS: &[&str] = &[
+    "password", "passwd", "sifre", "parola", "token", "secret", "cookie", "session", "authorization", "otp",
+];
+
+/// Bu path parçalarını içeren isteklerin (login, OTP, token) body'si hiç saklanmaz
+const AUTH_PATH_MARKERS: &[&str] = &["login", "logon", "signin", "auth", "token", "otp", "oauth", "sso"];
+
+/// Body'si HAR'a eklenecek maksimum yanıt boyutu (byte)
+const MAX_BODY_BYTES: usize = 512 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarFile {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarLog {
+    pub version: String,
+    pub creator: HarCreator,
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarCreator {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    pub started_date_time: String,
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    pub cache: serde_json::Value,
+    pub timings: HarTimings,
+    #[serde(rename = "_resourceType", skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    pub http_version: String,
+    pub cookies: Vec<serde_json::Value>,
+    pub headers: Vec<HarHeader>,
+    pub query_string: Vec<HarHeader>,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    pub status: i64,
+    pub status_text: String,
+    pub http_version: String,
+    pub cookies: Vec<serde_json::Value>,
+    pub headers: Vec<HarHeader>,
+    pub content: HarContent,
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    pub headers_size: i64,
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    pub size: i64,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HarHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HarTimings {
+    pub send: f64,
+    pub wait: f64,
+    pub receive: f64,
+}
+
+/// Kayıt sırasında tamamlanmamış istek
+#[derive(Debug, Clone)]
+struct PendingEntry {
+    started_date_time: String,
+    started_ts: f64,
+    finished_ts: Option<f64>,
+    request: HarRequest,
+    response: Option<HarResponse>,
+    resource_type: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    order: Vec<String>,
+    entries: HashMap<String, PendingEntry>,
+}
+
+/// CDP Network domain event'lerini dinleyip HAR üretir (opt-in, debug amaçlı)
+pub struct HarRecorder {
+    state: Arc<Mutex<RecorderState>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl HarRecorder {
+    /// Sayfaya listener'ları bağla. Network domain chromiumoxide tarafından zaten enable edilir.
+    pub async fn start(page: &Page) -> Result<Self, CdpError> {
+        let state = Arc::new(Mutex::new(RecorderState::default()));
+        let mut tasks = Vec::new();
+
+        let mut requests = page.event_listener::<EventRequestWillBeSent>().await?;
+        let st = state.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event) = requests.next().await {
+                let id = event.request_id.as_ref().to_string();
+                let mut state = st.lock().await;
+
+                // Redirect: önceki hop'u kapat, yeni hop'u aynı id ile aç
+                if let Some(redirect) = &event.redirect_response {
+                    if let Some(mut prev) = state.entries.remove(&id) {
+                        let hop_id = format!("{}#{}", id, state.order.len());
+                        prev.response = Some(build_response(redirect));
+                        prev.finished_ts = Some(*event.timestamp.inner());
+                        if let Some(pos) = state.order.iter().position(|k| k == &id) {
+                            state.order[pos] = hop_id.clone();
+                        }
+                        state.entries.insert(hop_id, prev);
+                    }
+                }
+
+                let entry = PendingEntry {
+                    started_date_time: wall_time_to_rfc3339(*event.wall_time.inner()),
+                    started_ts: *event.timestamp.inner(),
+                    finished_ts: None,
+                    request: HarRequest {
+                        method: event.request.method.clone(),
+                        url: event.request.url.clone(),
+                        http_version: "HTTP/1.1".to_string(),
+                        cookies: vec![],
+                        headers: redact_headers(headers_to_vec(&event.request.headers)),
+                        query_string: query_string(&event.request.url),
+                        headers_size: -1,
+                        body_size: -1,
+                    },
+                    response: None,
+                    resource_type: event.r#type.as_ref().map(|t| t.as_ref().to_string()),
+                    error: None,
+                };
+
+                state.order.push(id.clone());
+                state.entries.insert(id, entry);
+            }
+        }));
+
+        let mut responses = page.event_listener::<EventResponseReceived>().await?;
+        let st = state.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event) = responses.next().await {
+                let id = event.request_id.as_ref().to_string();
+                let mut state = st.lock().await;
+                if let Some(entry) = state.entries.get_mut(&id) {
+                    entry.response = Some(build_response(&event.response));
+                    if entry.resource_type.is_none() {
+                        entry.resource_type = Some(event.r#type.as_ref().to_string());
+                    }
+                }
+            }
+        }));
+
+        let mut finished = page.event_listener::<EventLoadingFinished>().await?;
+        let st = state.clone();
+        let body_page = page.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event) = finished.next().await {
+                let id = event.request_id.as_ref().to_string();
+
+                let wants_body = {
+                    let mut state = st.lock().await;
+                    match state.entries.get_mut(&id) {
+                        Some(entry) => {
+                            entry.finished_ts = Some(*event.timestamp.inner());
+                            is_body_worthy(entry.resource_type.as_deref())
+                                && !is_auth_url(&entry.request.url)
+                                && (event.encoded_data_length as usize) <= MAX_BODY_BYTES
+                        }
+                        None => false,
+                    }
+                };
+
+                if !wants_body {
+                    continue;
+                }
+
+                // Body sadece loadingFinished sonrası alınabilir
+                match body_page
+                    .execute(GetResponseBodyParams::new(event.request_id.clone()))
+                    .await
+                {
+                    Ok(body) => {
+                        let mut state = st.lock().await;
+                        if let Some(response) =
+                            state.entries.get_mut(&id).and_then(|e| e.response.as_mut())
+                        {
+                            response.content.size = body.body.len() as i64;
+                            if body.base64_encoded {
+                                response.content.text = Some(body.body.clone());
+                                response.content.encoding = Some("base64".to_string());
+                            } else {
+                                response.content.text = Some(redact_body(&body.body));
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::debug!("HAR: response body alınamadı ({}): {}", id, e);
+                    }
+                }
+            }
+        }));
+
+        let mut failed = page.event_listener::<EventLoadingFailed>().await?;
+        let st = state.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event) = failed.next().await {
+                let id = event.request_id.as_ref().to_string();
+                let mut state = st.lock().await;
+                if let Some(entry) = state.entries.get_mut(&id) {
+                    entry.finished_ts = Some(*event.timestamp.inner());
+                    entry.error = Some(event.error_text.clone());
+                }
+            }
+        }));
+
+        tracing::info!("🎙️ HAR kaydı başlatıldı");
+
+        Ok(Self { state, tasks })
+    }
+
+    /// Kaydı durdur ve o ana kadar toplanan istekleri HAR olarak döndür
+    pub async fn finish(self) -> HarFile {
+        // Bekleyen body fetch'lerin tamamlanması için kısa bir süre tanı
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+        for task in &self.tasks {
+            task.abort();
+        }
+
+        let state = self.state.lock().await;
+        let entries = state
+            .order
+            .iter()
+            .filter_map(|id| state.entries.get(id))
+            .map(finalize_entry)
+            .collect();
+
+        HarFile {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries,
+            },
+        }
+    }
+
+    /// Kaydı durdur ve `{artifact_dir}/har/{provider}_{request_id}.har` olarak yaz
+    pub async fn finish_and_save(
+        self,
+        artifact_dir: &str,
+        provider: &str,
+        request_id: &str,
+    ) -> Result<PathBuf, std::io::Error> {
+        let har = self.finish().await;
+        let path = har_path(artifact_dir, provider, request_id);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(&har)?;
+        std::fs::write(&path, content)?;
+
+        tracing::info!("📼 HAR kaydedildi: {:?} ({} istek)", path, har.log.entries.len());
+        Ok(path)
+    }
+}
+
+impl Drop for HarRecorder {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+pub fn har_path(artifact_dir: &str, provider: &str, request_id: &str) -> PathBuf {
+    Path::new(artifact_dir)
+        .join("har")
+        .join(format!("{}_{}.har", provider.to_lowercase(), request_id))
+}
+
+/// Cookie ve auth header değerlerini maskeler
+pub fn redact_headers(headers: Vec<HarHeader>) -> Vec<HarHeader> {
+    headers
+        .into_iter()
+        .map(|h| {
+            if REDACTED_HEADERS.contains(&h.name.to_lowercase().as_str()) {
+                HarHeader {
+                    name: h.name,
+                    value: REDACTED_VALUE.to_string(),
+                }
+            } else {
+                h
+            }
+        })
+        .collect()
+}
+
+/// JSON body'de parola/token/OTP alanlarının değerlerini maskeler; JSON olmayan body aynen döner
+pub fn redact_body(text: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            value.to_string()
+        }
+        Err(_) => text.to_string(),
+    }
+}
+
+fn redact_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive_key(key) && !value.is_null() {
+                    *value = serde_json::Value::String(REDACTED_VALUE.to_string());
+                } else {
+                    redact_json(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
+    }
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let key: String = key.to_lowercase().chars().filter(|c| *c != '_' && *c != '-').collect();
+    REDACTED_BODY_KEYS.iter().any(|k| key.contains(k))
+}
+
+/// Login / OTP / token uç noktası (query string hariç path'e bakılır)
+fn is_auth_url(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+    let path = path.split_once("://").map(|(_, rest)| rest).unwrap_or(&path);
+    let path = path.split_once('/').map(|(_, rest)| rest).unwrap_or("");
+    AUTH_PATH_MARKERS.iter().any(|marker| path.contains(marker))
+}
+
+fn headers_to_vec(headers: &Headers) -> Vec<HarHeader> {
+    headers
+        .inner()
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(name, value)| HarHeader {
+                    name: name.clone(),
+                    value: value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn query_string(url: &str) -> Vec<HarHeader> {
+    url.split_once('?')
+        .map(|(_, query)| {
+            query
+                .split('#')
+                .next()
+                .unwrap_or("")
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                    HarHeader {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn build_response(response: &Response) -> HarResponse {
+    let headers = headers_to_vec(&response.headers);
+    let redirect_url = headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case("location"))
+        .map(|h| h.value.clone())
+        .unwrap_or_default();
+
+    HarResponse {
+        status: response.status,
+        status_text: response.status_text.clone(),
+        http_version: response
+            .protocol
+            .clone()
+            .unwrap_or_else(|| "HTTP/1.1".to_string()),
+        cookies: vec![],
+        headers: redact_headers(headers),
+        content: HarContent {
+            size: -1,
+            mime_type: response.mime_type.clone(),
+            text: None,
+            encoding: None,
+        },
+        redirect_url,
+        headers_size: -1,
+        body_size: -1,
+    }
+}
+
+fn finalize_entry(entry: &PendingEntry) -> HarEntry {
+    let time = entry
+        .finished_ts
+        .map(|end| ((end - entry.started_ts) * 1000.0).max(0.0))
+        .unwrap_or(0.0);
+
+    let response = entry.response.clone().unwrap_or_else(|| HarResponse {
+        status: 0,
+        status_text: String::new(),
+        http_version: "HTTP/1.1".to_string(),
+        cookies: vec![],
+        headers: vec![],
+        content: HarContent {
+            size: 0,
+            mime_type: String::new(),
+            text: None,
+            encoding: None,
+        },
+        redirect_url: String::new(),
+        headers_size: -1,
+        body_size: -1,
+    });
+
+    HarEntry {
+        started_date_time: entry.started_date_time.clone(),
+        time,
+        request: entry.request.clone(),
+        response,
+        cache: serde_json::json!({}),
+        timings: HarTimings {
+            send: 0.0,
+            wait: time,
+            receive: 0.0,
+        },
+        resource_type: entry.resource_type.clone(),
+        error: entry.error.clone(),
+    }
+}
+
+/// Portal'ın ne döndürdüğünü görmek için sadece belge ve XHR body'leri saklanır
+fn is_body_worthy(resource_type: Option<&str>) -> bool {
+    matches!(
+        resource_type,
+        Some(t) if t == ResourceType::Xhr.as_ref()
+            || t == ResourceType::Fetch.as_ref()
+            || t == ResourceType::Document.as_ref()
+    )
+}
+
+fn wall_time_to_rfc3339(wall_time: f64) -> String {
+    let secs = wall_time.trunc() as i64;
+    let nanos = ((wall_time.fract()) * 1_000_000_000.0) as u32;
+    chrono::DateTime::from_timestamp(secs, nanos)
+        .unwrap_or_else(chrono::Utc::now)
+        .to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(name: &str, value: &str) -> HarHeader {
+        HarHeader {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_redact_headers() {
+        let redacted = redact_headers(vec![
+            header("Cookie", "JSESSIONID=abc"),
+            header("authorization", "Bearer xyz"),
+            header("Set-Cookie", "token=1; HttpOnly"),
+            header("Content-Type", "application/json"),
+        ]);
+
+        assert_eq!(redacted[0], header("Cookie", REDACTED_VALUE));
+        assert_eq!(redacted[1], header("authorization", REDACTED_VALUE));
+        assert_eq!(redacted[2], header("Set-Cookie", REDACTED_VALUE));
+        assert_eq!(redacted[3], header("Content-Type", "application/json"));
+    }
+
+    #[test]
+    fn test_redact_body() {
+        let body = r#"{"data":{"accessToken":"abc","user":{"Password":"gizli","name":"Ali"}},"items":[{"otp_code":"123456","prim":4350.0}],"session_id":null}"#;
+        let redacted: serde_json::Value = serde_json::from_str(&redact_body(body)).unwrap();
+        assert_eq!(redacted["data"]["accessToken"], REDACTED_VALUE);
+        assert_eq!(redacted["data"]["user"]["Password"], REDACTED_VALUE);
+        assert_eq!(redacted["data"]["user"]["name"], "Ali");
+        assert_eq!(redacted["items"][0]["otp_code"], REDACTED_VALUE);
+        assert_eq!(redacted["items"][0]["prim"], 4350.0);
+        assert!(redacted["session_id"].is_null());
+        // JSON olmayan body (HTML) değişmez
+        assert_eq!(redact_body("<html>teklif</html>"), "<html>teklif</html>");
+    }
+
+    #[test]
+    fn test_auth_urls_skip_body() {
+        assert!(is_auth_url("https://portal.example.com/api/Auth/Login"));
+        assert!(is_auth_url("https://portal.example.com/api/otp/verify?x=1"));
+        assert!(!is_auth_url("https://auth-portal.example.com/api/teklif?token=1"));
+        assert!(!is_auth_url("https://portal.example.com/api/teklif"));
+    }
+
+    #[test]
+    fn test_query_string() {
+        let qs = query_string("https://portal/api/teklif?plaka=34ABC123&urun=trafik#x");
+        assert_eq!(qs, vec![header("plaka", "34ABC123"), header("urun", "trafik")]);
+        assert!(query_string("https://portal/api/teklif").is_empty());
+    }
+
+    #[test]
+    fn test_har_path() {
+        let path = har_path("./screenshots", "Sompo", "req-1");
+        assert_eq!(path, Path::new("./screenshots/har/sompo_req-1.har"));
+    }
+}
@@ -0,0 +1,110 @@
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventLoadingFinished, EventResponseReceived, GetResponseBodyParams, ResourceType,
+};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::Page;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// Yakalanan bir XHR/fetch JSON yanıtı
+#[derive(Debug, Clone)]
+pub struct CapturedResponse {
+    pub url: String,
+    pub status: i64,
+    pub body: serde_json::Value,
+}
+
+#[derive(Default)]
+struct CaptureState {
+    /// request_id -> (url, status), loadingFinished gelene kadar bekler
+    pending: HashMap<String, (String, i64)>,
+    captured: Vec<CapturedResponse>,
+}
+
+/// Portal'ın XHR/fetch JSON yanıtlarını Network domain üzerinden yakalar.
+/// Sadece URL'i verilen pattern'lerden birini içeren yanıtlar saklanır.
+pub struct ResponseCapture {
+    state: Arc<Mutex<CaptureState>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl ResponseCapture {
+    pub async fn start(page: &Page, url_patterns: &[&str]) -> Result<Self, CdpError> {
+        let state = Arc::new(Mutex::new(CaptureState::default()));
+        let patterns: Vec<String> = url_patterns.iter().map(|p| p.to_lowercase()).collect();
+        let mut tasks = Vec::new();
+
+        let mut responses = page.event_listener::<EventResponseReceived>().await?;
+        let st = state.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event) = responses.next().await {
+                let is_xhr = matches!(event.r#type, ResourceType::Xhr | ResourceType::Fetch);
+                let is_json = event.response.mime_type.contains("json");
+                let url = event.response.url.to_lowercase();
+
+                if is_xhr && is_json && patterns.iter().any(|p| url.contains(p)) {
+                    st.lock().await.pending.insert(
+                        event.request_id.as_ref().to_string(),
+                        (event.response.url.clone(), event.response.status),
+                    );
+                }
+            }
+        }));
+
+        let mut finished = page.event_listener::<EventLoadingFinished>().await?;
+        let st = state.clone();
+        let body_page = page.clone();
+        tasks.push(tokio::spawn(async move {
+            while let Some(event) = finished.next().await {
+                let id = event.request_id.as_ref().to_string();
+                let Some((url, status)) = st.lock().await.pending.remove(&id) else {
+                    continue;
+                };
+
+                let body = match body_page
+                    .execute(GetResponseBodyParams::new(event.request_id.clone()))
+                    .await
+                {
+                    Ok(body) if !body.base64_encoded => body.body.clone(),
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::debug!("Yanıt body alınamadı ({}): {}", url, e);
+                        continue;
+                    }
+                };
+
+                match serde_json::from_str::<serde_json::Value>(&body) {
+                    Ok(json) => {
+                        tracing::debug!("📡 XHR yakalandı: {} ({})", url, status);
+                        st.lock().await.captured.push(CapturedResponse {
+                            url,
+                            status,
+                            body: json,
+                        });
+                    }
+                    Err(e) => {
+                        tracing::debug!("XHR JSON parse edilemedi ({}): {}", url, e);
+                    }
+                }
+            }
+        }));
+
+        Ok(Self { state, tasks })
+    }
+
+    /// O ana kadar yakalanan yanıtlar (geliş sırasına göre)
+    pub async fn responses(&self) -> Vec<CapturedResponse> {
+        self.state.lock().await.captured.clone()
+    }
+}
+
+impl Drop for ResponseCapture {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
@@ -1,8 +1,12 @@
 pub mod driver;
 pub mod session;
 pub mod cdp;
+pub mod har;
+pub mod intercept;
 
-pub use driver::create_webdriver_client;
+pub use driver::{any_present, capture_session, click_first, create_webdriver_client, fill_first, restore_session, select_first};
 pub use session::SessionManager;
-pub use cdp::{create_cdp_browser, inject_anti_detection, wait_for_navigation, wait_for_network_idle};
+pub use cdp::{create_cdp_browser, inject_anti_detection, start_har_capture, wait_for_navigation, wait_for_network_idle};
+pub use har::HarRecorder;
+pub use intercept::{CapturedResponse, ResponseCapture};
 
@@ -1,4 +1,12 @@
+use crate::db::agencies::DEFAULT_AGENCY_ID;
+use crate::db::models::AgencyCredential;
+use crate::http::ApiError;
+use std::collections::HashMap;
 use std::env;
+use std::path::Path;
+
+/// Acente bazında portal kullanıcı bilgisi tanımlanabilen provider'lar
+pub const AGENCY_CREDENTIAL_PROVIDERS: &[&str] = &["sompo", "axa", "anadolu", "quick"];
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -10,6 +18,30 @@ pub struct Config {
     pub sompo_username: String,
     pub sompo_password: String,
     pub sompo_secret_key: String,
+    /// Login sonrası quote endpoint'lerini browser yerine direkt HTTP ile çağır
+    pub sompo_api_mode: bool,
+    /// API mode istek şablonları (JSON dosyası, yoksa varsayılanlar)
+    pub sompo_api_templates: Option<String>,
+    
+    // Axa
+    pub axa_url: String,
+    pub axa_username: String,
+    pub axa_password: String,
+    
+    // Anadolu
+    pub anadolu_url: String,
+    pub anadolu_username: String,
+    pub anadolu_password: String,
+    
+    // Quick
+    pub quick_url: String,
+    pub quick_username: String,
+    pub quick_password: String,
+    
+    /// Provider ayar dosyası (enabled, ürünler, öncelik, timeout, eşzamanlılık)
+    pub providers_config: Option<String>,
+    /// .env ve provider config değişikliklerini kontrol aralığı (0 = kapalı)
+    pub config_watch_interval_secs: u64,
     
     // Browser
     pub webdriver_url: String,
@@ -28,6 +60,65 @@ pub struct Config {
     // Session
     pub session_dir: String,
     
+    // Debug artifacts (screenshot, HAR)
+    pub artifact_dir: String,
+    pub har_capture: bool,
+    
+    // Mock provider (geliştirme / CI, credential gerektirmez)
+    pub mock_provider: bool,
+    pub mock_latency_ms: u64,
+    /// 0.0 - 1.0 arası; request_id'ye göre deterministik seçilir
+    pub mock_failure_rate: f64,
+    /// Başarısızlıkta dönecek hata: "otp", "blocked", "timeout" (boşsa sırayla)
+    pub mock_error: Option<String>,
+    
+    // Yenileme (vadesi yaklaşan poliçeler için otomatik teklif)
+    /// Vadesine bu kadar gün kalan poliçeler için teklif alınır
+    pub renewal_window_days: u32,
+    /// Tarama aralığı (0 = kapalı, elle: POST /api/v1/admin/renewals/run)
+    pub renewal_scan_interval_secs: u64,
+    /// Teklif alınamayan poliçe en fazla bu kadar tekrar denenir
+    pub renewal_max_attempts: u32,
+    
+    /// Yüzde; eşleşen komisyon kuralı yoksa uygulanır
+    pub default_commission_rate: f64,
+    
+    // E-posta (SMTP, outbox üzerinden gönderilir)
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    /// "starttls" | "tls" | "none" (none: yerel capture sunucusu, ör. Mailpit)
+    pub smtp_tls: String,
+    pub email_from: String,
+    pub email_from_name: String,
+    /// Outbox worker kontrol aralığı
+    pub email_outbox_interval_secs: u64,
+    /// Bu kadar başarısız denemeden sonra e-posta failed olur
+    pub email_max_attempts: u32,
+    
+    // Acente bilgileri (PDF başlığı) ve PDF arşivi
+    pub agency_name: String,
+    pub agency_address: String,
+    pub agency_phone: String,
+    pub agency_email: String,
+    /// SEDDK levha kayıt numarası
+    pub agency_license_no: String,
+    pub pdf_dir: String,
+    
+    // SMS (sigortalıya bildirim)
+    /// "" (kapalı) | "log" (geliştirme: dosyaya yazar) | "http" (SMS firması)
+    pub sms_gateway: String,
+    /// HTTP gateway formatı: "netgsm" | "iletimerkezi" | "verimor"
+    pub sms_vendor: String,
+    /// Firma API adresi (testlerde yerel stand-in verilebilir)
+    pub sms_gateway_url: Option<String>,
+    pub sms_username: String,
+    pub sms_password: String,
+    /// Onaylı gönderici başlığı
+    pub sms_sender: String,
+    pub sms_log_file: String,
+    
     // Metrics
     pub enable_metrics: bool,
     
@@ -42,59 +133,327 @@ impl Config {
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
         dotenvy::dotenv().ok();
         
-        Ok(Config {
-            http_addr: env::var("HTTP_ADDR").unwrap_or_else(|_| "0.0.0.0:8099".to_string()),
-            log_level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
+        Ok(Self::build(|key| env::var(key)))
+    }
+    
+    /// Hot reload: `.env` dosyasını process env'in üzerine okuyup yeni Config üretir.
+    /// Process env değiştirilmez; dosyada olmayan değişkenler env'den alınır.
+    pub fn reload_from_env_file() -> Result<Self, ApiError> {
+        let overrides: HashMap<String, String> = match dotenvy::dotenv_iter() {
+            Ok(iter) => iter
+                .collect::<Result<_, _>>()
+                .map_err(|e| ApiError::InternalServerError(format!(".env okunamadı: {}", e)))?,
+            Err(e) if e.not_found() => HashMap::new(),
+            Err(e) => return Err(ApiError::InternalServerError(format!(".env okunamadı: {}", e))),
+        };
+        
+        let config = Self::build(|key| match overrides.get(key) {
+            Some(value) => Ok(value.clone()),
+            None => env::var(key),
+        });
+        config.validate()?;
+        Ok(config)
+    }
+    
+    /// Runtime'da uygulanamayacak / tutarsız değerleri reddeder
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let mut errors = Vec::new();
+        
+        if self.request_timeout_ms == 0 {
+            errors.push("REQUEST_TIMEOUT_MS 0 olamaz".to_string());
+        }
+        if self.login_timeout_ms == 0 {
+            errors.push("LOGIN_TIMEOUT_MS 0 olamaz".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.mock_failure_rate) {
+            errors.push(format!("MOCK_FAILURE_RATE 0.0 - 1.0 arası olmalı: {}", self.mock_failure_rate));
+        }
+        if let Some(kind) = &self.mock_error {
+            if !["otp", "blocked", "timeout"].contains(&kind.as_str()) {
+                errors.push(format!("MOCK_ERROR geçersiz: {}", kind));
+            }
+        }
+        if self.renewal_window_days == 0 {
+            errors.push("RENEWAL_WINDOW_DAYS 0 olamaz".to_string());
+        }
+        if !(0.0..=100.0).contains(&self.default_commission_rate) {
+            errors.push(format!("DEFAULT_COMMISSION_RATE 0 - 100 arası olmalı: {}", self.default_commission_rate));
+        }
+        if !["starttls", "tls", "none"].contains(&self.smtp_tls.as_str()) {
+            errors.push(format!("SMTP_TLS geçersiz (starttls | tls | none): {}", self.smtp_tls));
+        }
+        if self.email_from.parse::<lettre::Address>().is_err() {
+            errors.push(format!("SMTP_FROM geçersiz e-posta adresi: {}", self.email_from));
+        }
+        if !["", "log", "http"].contains(&self.sms_gateway.as_str()) {
+            errors.push(format!("SMS_GATEWAY geçersiz (log | http): {}", self.sms_gateway));
+        }
+        if self.sms_gateway == "http" {
+            if !["netgsm", "iletimerkezi", "verimor"].contains(&self.sms_vendor.as_str()) {
+                errors.push(format!("SMS_VENDOR geçersiz (netgsm | iletimerkezi | verimor): {}", self.sms_vendor));
+            }
+            match &self.sms_gateway_url {
+                Some(url) if reqwest::Url::parse(url).is_ok() => {}
+                Some(url) => errors.push(format!("SMS_GATEWAY_URL geçersiz: {}", url)),
+                None => errors.push("SMS_GATEWAY=http için SMS_GATEWAY_URL gerekli".to_string()),
+            }
+        }
+        if reqwest::Url::parse(&self.webdriver_url).is_err() {
+            errors.push(format!("WEBDRIVER_URL geçersiz: {}", self.webdriver_url));
+        }
+        if let Some(path) = &self.providers_config {
+            if !std::path::Path::new(path).exists() {
+                errors.push(format!("PROVIDERS_CONFIG dosyası yok: {}", path));
+            }
+        }
+        
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::FormValidation(errors.join("; ")))
+        }
+    }
+    
+    /// Restart gerektiren (runtime'da değiştirilemeyen) alanlardaki farklar
+    pub fn restart_required_changes(&self, other: &Config) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.http_addr != other.http_addr {
+            changed.push("HTTP_ADDR");
+        }
+        if self.database_url != other.database_url {
+            changed.push("DATABASE_URL");
+        }
+        if self.jwt_secret != other.jwt_secret {
+            changed.push("JWT_SECRET");
+        }
+        if self.smtp_host != other.smtp_host
+            || self.smtp_port != other.smtp_port
+            || self.smtp_username != other.smtp_username
+            || self.smtp_password != other.smtp_password
+            || self.smtp_tls != other.smtp_tls
+            || self.email_from != other.email_from
+        {
+            changed.push("SMTP_*");
+        }
+        if self.sms_gateway != other.sms_gateway
+            || self.sms_vendor != other.sms_vendor
+            || self.sms_gateway_url != other.sms_gateway_url
+            || self.sms_username != other.sms_username
+            || self.sms_password != other.sms_password
+            || self.sms_sender != other.sms_sender
+            || self.sms_log_file != other.sms_log_file
+        {
+            changed.push("SMS_*");
+        }
+        if self.log_level != other.log_level {
+            changed.push("LOG_LEVEL");
+        }
+        changed
+    }
+    
+    /// Acente registry'si için config: portal kullanıcı bilgileri acentenin kayıtlarından gelir.
+    /// Varsayılan acentede kaydı olmayan provider .env'dekini kullanır, diğer acentelerde pasif kalır.
+    /// Portal oturumları acenteler arasında paylaşılmasın diye session dizini ayrılır.
+    pub fn for_agency(&self, agency_id: &str, credentials: &[AgencyCredential]) -> Config {
+        let mut config = self.clone();
+        if agency_id != DEFAULT_AGENCY_ID {
+            config.sompo_username.clear();
+            config.sompo_password.clear();
+            config.sompo_secret_key.clear();
+            config.axa_username.clear();
+            config.axa_password.clear();
+            config.anadolu_username.clear();
+            config.anadolu_password.clear();
+            config.quick_username.clear();
+            config.quick_password.clear();
+            config.session_dir = Path::new(&self.session_dir).join(format!("agency-{}", agency_id)).to_string_lossy().to_string();
+        }
+        for credential in credentials {
+            let (username, password) = (credential.username.clone(), credential.password.clone());
+            match credential.provider.as_str() {
+                "sompo" => {
+                    config.sompo_username = username;
+                    config.sompo_password = password;
+                    config.sompo_secret_key = credential.secret_key.clone().unwrap_or_default();
+                }
+                "axa" => {
+                    config.axa_username = username;
+                    config.axa_password = password;
+                }
+                "anadolu" => {
+                    config.anadolu_username = username;
+                    config.anadolu_password = password;
+                }
+                "quick" => {
+                    config.quick_username = username;
+                    config.quick_password = password;
+                }
+                other => tracing::warn!("⚠️ Acente kullanıcı bilgisi bilinmeyen provider için: {}", other),
+            }
+        }
+        config
+    }
+    
+    fn build(var: impl Fn(&str) -> Result<String, env::VarError>) -> Self {
+        Config {
+            http_addr: var("HTTP_ADDR").unwrap_or_else(|_| "0.0.0.0:8099".to_string()),
+            log_level: var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
             
-            sompo_base_url: env::var("SOMPO_BASE_URL")
+            sompo_base_url: var("SOMPO_BASE_URL")
                 .unwrap_or_else(|_| "https://ejento.somposigorta.com.tr/dashboard/login".to_string()),
-            sompo_username: env::var("SOMPO_USER").unwrap_or_default(),
-            sompo_password: env::var("SOMPO_PASS").unwrap_or_default(),
-            sompo_secret_key: env::var("SOMPO_SECRET_KEY").unwrap_or_default(),
+            sompo_username: var("SOMPO_USER").unwrap_or_default(),
+            sompo_password: var("SOMPO_PASS").unwrap_or_default(),
+            sompo_secret_key: var("SOMPO_SECRET_KEY").unwrap_or_default(),
+            sompo_api_mode: var("SOMPO_API_MODE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            sompo_api_templates: var("SOMPO_API_TEMPLATES").ok().filter(|s| !s.is_empty()),
+            
+            axa_url: var("AXA_URL").unwrap_or_default(),
+            axa_username: var("AXA_USERNAME").unwrap_or_default(),
+            axa_password: var("AXA_PASSWORD").unwrap_or_default(),
+            
+            anadolu_url: var("ANADOLU_URL").unwrap_or_default(),
+            anadolu_username: var("ANADOLU_USERNAME").unwrap_or_default(),
+            anadolu_password: var("ANADOLU_PASSWORD").unwrap_or_default(),
+            
+            quick_url: var("QUICK_URL")
+                .unwrap_or_else(|_| "https://www.quicksigorta.com.tr/agent/login".to_string()),
+            quick_username: var("QUICK_USERNAME").unwrap_or_default(),
+            quick_password: var("QUICK_PASSWORD").unwrap_or_default(),
+            
+            providers_config: var("PROVIDERS_CONFIG").ok().filter(|s| !s.is_empty()),
+            config_watch_interval_secs: var("CONFIG_WATCH_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
             
-            webdriver_url: env::var("WEBDRIVER_URL")
+            webdriver_url: var("WEBDRIVER_URL")
                 .unwrap_or_else(|_| "http://localhost:9515".to_string()),
-            headless: env::var("HEADLESS")
+            headless: var("HEADLESS")
                 .unwrap_or_else(|_| "true".to_string())
                 .parse()
                 .unwrap_or(true),
-            proxy_url: env::var("PROXY_URL").ok().filter(|s| !s.is_empty()),
-            user_agent: env::var("USER_AGENT").unwrap_or_else(|_| {
+            proxy_url: var("PROXY_URL").ok().filter(|s| !s.is_empty()),
+            user_agent: var("USER_AGENT").unwrap_or_else(|_| {
                 "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/121.0.0.0 Safari/537.36".to_string()
             }),
-            accept_language: env::var("ACCEPT_LANGUAGE")
+            accept_language: var("ACCEPT_LANGUAGE")
                 .unwrap_or_else(|_| "tr-TR,tr;q=0.9".to_string()),
-            timezone: env::var("TIMEZONE")
+            timezone: var("TIMEZONE")
                 .unwrap_or_else(|_| "Europe/Istanbul".to_string()),
             
-            request_timeout_ms: env::var("REQUEST_TIMEOUT_MS")
+            request_timeout_ms: var("REQUEST_TIMEOUT_MS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(90000),
-            login_timeout_ms: env::var("LOGIN_TIMEOUT_MS")
+            login_timeout_ms: var("LOGIN_TIMEOUT_MS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(45000),
-            retry_max: env::var("RETRY_MAX")
+            retry_max: var("RETRY_MAX")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(2),
-            retry_backoff_ms: env::var("RETRY_BACKOFF_MS")
+            retry_backoff_ms: var("RETRY_BACKOFF_MS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(1500),
             
-            session_dir: env::var("SESSION_DIR")
+            session_dir: var("SESSION_DIR")
                 .unwrap_or_else(|_| "/data/sessions".to_string()),
             
-            enable_metrics: env::var("ENABLE_METRICS")
+            artifact_dir: var("ARTIFACT_DIR")
+                .unwrap_or_else(|_| "./screenshots".to_string()),
+            har_capture: var("HAR_CAPTURE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            
+            mock_provider: var("MOCK_PROVIDER")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            mock_latency_ms: var("MOCK_LATENCY_MS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300),
+            mock_failure_rate: var("MOCK_FAILURE_RATE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0),
+            mock_error: var("MOCK_ERROR").ok().filter(|s| !s.is_empty()),
+            
+            renewal_window_days: var("RENEWAL_WINDOW_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
+            renewal_scan_interval_secs: var("RENEWAL_SCAN_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3600),
+            renewal_max_attempts: var("RENEWAL_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            
+            default_commission_rate: var("DEFAULT_COMMISSION_RATE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10.0),
+            
+            smtp_host: var("SMTP_HOST").ok().filter(|s| !s.is_empty()),
+            smtp_port: var("SMTP_PORT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(587),
+            smtp_username: var("SMTP_USER").unwrap_or_default(),
+            smtp_password: var("SMTP_PASSWORD").unwrap_or_default(),
+            smtp_tls: var("SMTP_TLS")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "starttls".to_string())
+                .to_lowercase(),
+            email_from: var("SMTP_FROM")
+                .or_else(|_| var("EMAIL_FROM"))
+                .unwrap_or_else(|_| "noreply@eesigorta.com".to_string()),
+            email_from_name: var("SMTP_FROM_NAME").unwrap_or_else(|_| "EES Sigorta".to_string()),
+            email_outbox_interval_secs: var("EMAIL_OUTBOX_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            email_max_attempts: var("EMAIL_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            
+            agency_name: var("AGENCY_NAME").unwrap_or_else(|_| "EES Sigorta".to_string()),
+            agency_address: var("AGENCY_ADDRESS").unwrap_or_default(),
+            agency_phone: var("AGENCY_PHONE").unwrap_or_default(),
+            agency_email: var("AGENCY_EMAIL").unwrap_or_default(),
+            agency_license_no: var("AGENCY_LICENSE_NO").unwrap_or_default(),
+            pdf_dir: var("PDF_DIR").unwrap_or_else(|_| "./pdfs".to_string()),
+            
+            sms_gateway: var("SMS_GATEWAY").unwrap_or_default().to_lowercase(),
+            sms_vendor: var("SMS_VENDOR")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .unwrap_or_else(|| "netgsm".to_string())
+                .to_lowercase(),
+            sms_gateway_url: var("SMS_GATEWAY_URL").ok().filter(|s| !s.is_empty()),
+            sms_username: var("SMS_USERNAME").unwrap_or_default(),
+            sms_password: var("SMS_PASSWORD").unwrap_or_default(),
+            sms_sender: var("SMS_SENDER").unwrap_or_default(),
+            sms_log_file: var("SMS_LOG_FILE").unwrap_or_else(|_| "./sms.log".to_string()),
+            
+            enable_metrics: var("ENABLE_METRICS")
                 .unwrap_or_else(|_| "true".to_string())
                 .parse()
                 .unwrap_or(true),
             
-            database_url: env::var("DATABASE_URL").ok(),
-            jwt_secret: env::var("JWT_SECRET").ok(),
-        })
+            database_url: var("DATABASE_URL").ok(),
+            jwt_secret: var("JWT_SECRET").ok(),
+        }
     }
 }
-
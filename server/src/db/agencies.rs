@@ -0,0 +1,184 @@
+use crate::db::models::{Agency, AgencyCredential, User};
+use crate::db::DbPool;
+use uuid::Uuid;
+
+/// Migration'da mevcut kayıtların taşındığı acente; yeni kayıt olan kullanıcılar da buraya eklenir
+pub const DEFAULT_AGENCY_ID: &str = "default";
+
+pub async fn create_agency(pool: &DbPool, name: &str) -> Result<Agency, sqlx::Error> {
+    sqlx::query_as::<_, Agency>("INSERT INTO agencies (id, name) VALUES ($1, $2) RETURNING *")
+        .bind(Uuid::new_v4().to_string())
+        .bind(name)
+        .fetch_one(pool)
+        .await
+}
+
+pub async fn get_agency(pool: &DbPool, id: &str) -> Result<Option<Agency>, sqlx::Error> {
+    sqlx::query_as::<_, Agency>("SELECT * FROM agencies WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_agencies(pool: &DbPool) -> Result<Vec<Agency>, sqlx::Error> {
+    sqlx::query_as::<_, Agency>("SELECT * FROM agencies ORDER BY created_at, name")
+        .fetch_all(pool)
+        .await
+}
+
+/// None olan alanlar değişmez
+#[derive(Debug, Default)]
+pub struct AgencyChanges<'a> {
+    pub name: Option<&'a str>,
+    pub is_active: Option<bool>,
+    pub address: Option<&'a str>,
+    pub phone: Option<&'a str>,
+    pub email: Option<&'a str>,
+    pub license_no: Option<&'a str>,
+}
+
+pub async fn update_agency(pool: &DbPool, id: &str, changes: &AgencyChanges<'_>) -> Result<Option<Agency>, sqlx::Error> {
+    sqlx::query_as::<_, Agency>(
+        r#"
+        UPDATE agencies
+        SET name = COALESCE($2, name),
+            is_active = COALESCE($3, is_active),
+            address = COALESCE($4, address),
+            phone = COALESCE($5, phone),
+            email = COALESCE($6, email),
+            license_no = COALESCE($7, license_no),
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(changes.name)
+    .bind(changes.is_active)
+    .bind(changes.address)
+    .bind(changes.phone)
+    .bind(changes.email)
+    .bind(changes.license_no)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Kullanıcıyı başka acenteye taşır. Mevcut teklif ve poliçeleri eski acentede kalır.
+pub async fn set_user_agency(
+    pool: &DbPool,
+    user_id: &str,
+    agency_id: &str,
+    role: Option<&str>,
+) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users
+        SET agency_id = $2, role = COALESCE($3, role)
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(agency_id)
+    .bind(role)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_credentials(pool: &DbPool, agency_id: &str) -> Result<Vec<AgencyCredential>, sqlx::Error> {
+    sqlx::query_as::<_, AgencyCredential>(
+        "SELECT * FROM agency_provider_credentials WHERE agency_id = $1 ORDER BY provider",
+    )
+    .bind(agency_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn upsert_credential(
+    pool: &DbPool,
+    agency_id: &str,
+    provider: &str,
+    username: &str,
+    password: &str,
+    secret_key: Option<&str>,
+    updated_by: &str,
+) -> Result<AgencyCredential, sqlx::Error> {
+    sqlx::query_as::<_, AgencyCredential>(
+        r#"
+        INSERT INTO agency_provider_credentials (agency_id, provider, username, password, secret_key, updated_by)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT(agency_id, provider) DO UPDATE SET
+            username = excluded.username,
+            password = excluded.password,
+            secret_key = excluded.secret_key,
+            updated_by = excluded.updated_by,
+            updated_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(agency_id)
+    .bind(provider)
+    .bind(username)
+    .bind(password)
+    .bind(secret_key)
+    .bind(updated_by)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn delete_credential(pool: &DbPool, agency_id: &str, provider: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM agency_provider_credentials WHERE agency_id = $1 AND provider = $2")
+        .bind(agency_id)
+        .bind(provider)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_pool, test_user, users};
+
+    #[tokio::test]
+    async fn test_agency_users_and_credentials() {
+        let pool = test_pool().await;
+
+        let agency = create_agency(&pool, "Kadıköy Acente").await.unwrap();
+        assert!(agency.is_active);
+        assert_eq!(list_agencies(&pool).await.unwrap().len(), 2);
+
+        let user = test_user(&pool, "A", "agent").await;
+        let moved = set_user_agency(&pool, &user.id, &agency.id, Some("admin")).await.unwrap().unwrap();
+        assert_eq!(moved.agency_id, agency.id);
+        assert_eq!(moved.role, "admin");
+        assert_eq!(users::count_users(&pool, Some(&agency.id)).await.unwrap(), 1);
+        assert_eq!(users::count_users(&pool, Some(DEFAULT_AGENCY_ID)).await.unwrap(), 0);
+        assert!(set_user_agency(&pool, "missing", &agency.id, None).await.unwrap().is_none());
+
+        upsert_credential(&pool, &agency.id, "sompo", "kadikoy", "p1", Some("OTPKEY"), &user.id).await.unwrap();
+        let updated = upsert_credential(&pool, &agency.id, "sompo", "kadikoy2", "p2", None, &user.id).await.unwrap();
+        assert_eq!(updated.username, "kadikoy2");
+        assert!(updated.secret_key.is_none());
+        // Şifre ve OTP anahtarı API yanıtına çıkmaz
+        let json = serde_json::to_value(&updated).unwrap();
+        assert!(json.get("password").is_none());
+        assert!(list_credentials(&pool, DEFAULT_AGENCY_ID).await.unwrap().is_empty());
+
+        let branded = AgencyChanges { address: Some("Moda Cad. No:5"), license_no: Some("T091-1"), ..Default::default() };
+        let branded = update_agency(&pool, &agency.id, &branded).await.unwrap().unwrap();
+        assert_eq!(branded.address, "Moda Cad. No:5");
+        assert_eq!(branded.license_no, "T091-1");
+        assert!(users::get_active_user(&pool, &user.id).await.unwrap().is_some());
+        let deactivated = AgencyChanges { is_active: Some(false), ..Default::default() };
+        let deactivated = update_agency(&pool, &agency.id, &deactivated).await.unwrap().unwrap();
+        assert_eq!(deactivated.name, "Kadıköy Acente");
+        assert_eq!(deactivated.address, "Moda Cad. No:5");
+        assert!(!deactivated.is_active);
+        // Pasif acentenin kullanıcısının token'ı kabul edilmez
+        assert!(users::get_active_user(&pool, &user.id).await.unwrap().is_none());
+
+        assert!(delete_credential(&pool, &agency.id, "sompo").await.unwrap());
+        assert!(!delete_credential(&pool, &agency.id, "sompo").await.unwrap());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::db::models::CommissionRule;
+use crate::db::DbPool;
+use sqlx::Row;
+use uuid::Uuid;
+
+/// Kural koşulları ve oranı; tarihler YYYY-MM-DD
+#[derive(Debug, Clone, Default)]
+pub struct CommissionRuleInput {
+    pub provider: Option<String>,
+    pub product_type: Option<String>,
+    pub usage: Option<String>,
+    pub min_premium: Option<f64>,
+    pub max_premium: Option<f64>,
+    pub rate_percent: f64,
+    pub valid_from: String,
+    pub valid_to: Option<String>,
+    pub note: Option<String>,
+}
+
+pub async fn create_rule(
+    pool: &DbPool,
+    input: &CommissionRuleInput,
+    created_by: Option<&str>,
+) -> Result<CommissionRule, sqlx::Error> {
+    sqlx::query_as::<_, CommissionRule>(
+        r#"
+        INSERT INTO commission_rules
+        (id, provider, product_type, usage, min_premium, max_premium, rate_percent, valid_from, valid_to, note, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&input.provider)
+    .bind(&input.product_type)
+    .bind(&input.usage)
+    .bind(input.min_premium)
+    .bind(input.max_premium)
+    .bind(input.rate_percent)
+    .bind(&input.valid_from)
+    .bind(&input.valid_to)
+    .bind(&input.note)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// Tüm alanları değiştirir; kural yoksa None
+pub async fn update_rule(
+    pool: &DbPool,
+    id: &str,
+    input: &CommissionRuleInput,
+) -> Result<Option<CommissionRule>, sqlx::Error> {
+    sqlx::query_as::<_, CommissionRule>(
+        r#"
+        UPDATE commission_rules SET
+            provider = $2,
+            product_type = $3,
+            usage = $4,
+            min_premium = $5,
+            max_premium = $6,
+            rate_percent = $7,
+            valid_from = $8,
+            valid_to = $9,
+            note = $10,
+            updated_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&input.provider)
+    .bind(&input.product_type)
+    .bind(&input.usage)
+    .bind(input.min_premium)
+    .bind(input.max_premium)
+    .bind(input.rate_percent)
+    .bind(&input.valid_from)
+    .bind(&input.valid_to)
+    .bind(&input.note)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn delete_rule(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM commission_rules WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_rule(pool: &DbPool, id: &str) -> Result<Option<CommissionRule>, sqlx::Error> {
+    sqlx::query_as::<_, CommissionRule>("SELECT * FROM commission_rules WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// `on` verilirse yalnızca o gün yürürlükte olan kurallar
+pub async fn list_rules(
+    pool: &DbPool,
+    on: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<CommissionRule>, sqlx::Error> {
+    sqlx::query_as::<_, CommissionRule>(
+        r#"
+        SELECT * FROM commission_rules
+        WHERE ($1 IS NULL OR (valid_from <= $1 AND (valid_to IS NULL OR valid_to >= $1)))
+        ORDER BY valid_from DESC, created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(on)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// Kuralla hesaplanmış poliçe sayısı (bu kuralların oranı/koşulları denetim için değiştirilemez)
+pub async fn count_policies_using_rule(pool: &DbPool, id: &str) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT COUNT(*) as count FROM policies WHERE commission_rule_id = $1")
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.get("count"))
+}
+
+/// `date` günü yürürlükte olup poliçeye uyan en özel kural.
+/// Öncelik: şirket > ürün > kullanım tipi > prim bandı; eşitlikte en yeni yürürlük tarihi.
+pub async fn find_matching_rule(
+    pool: &DbPool,
+    provider: &str,
+    product_type: &str,
+    usage: Option<&str>,
+    premium: f64,
+    date: &str,
+) -> Result<Option<CommissionRule>, sqlx::Error> {
+    sqlx::query_as::<_, CommissionRule>(
+        r#"
+        SELECT * FROM commission_rules
+        WHERE (provider IS NULL OR provider = $1 COLLATE NOCASE)
+          AND (product_type IS NULL OR product_type = $2)
+          AND (usage IS NULL OR usage = $3)
+          AND (min_premium IS NULL OR $4 >= min_premium)
+          AND (max_premium IS NULL OR $4 < max_premium)
+          AND valid_from <= $5
+          AND (valid_to IS NULL OR valid_to >= $5)
+        ORDER BY
+            (provider IS NOT NULL) * 8
+            + (product_type IS NOT NULL) * 4
+            + (usage IS NOT NULL) * 2
+            + (min_premium IS NOT NULL OR max_premium IS NOT NULL) DESC,
+            valid_from DESC,
+            created_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(provider)
+    .bind(product_type)
+    .bind(usage)
+    .bind(premium)
+    .bind(date)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    fn rule(rate_percent: f64) -> CommissionRuleInput {
+        CommissionRuleInput {
+            rate_percent,
+            valid_from: "2024-01-01".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_matching_rule_prefers_specific() {
+        let pool = test_pool().await;
+
+        let general = create_rule(&pool, &rule(10.0), None).await.unwrap();
+        let kasko = create_rule(&pool, &CommissionRuleInput { product_type: Some("kasko".to_string()), ..rule(12.0) }, None)
+            .await
+            .unwrap();
+        let sompo_ticari = CommissionRuleInput {
+            provider: Some("Sompo".to_string()),
+            product_type: Some("trafik".to_string()),
+            usage: Some("ticari".to_string()),
+            ..rule(6.0)
+        };
+        let sompo_ticari = create_rule(&pool, &sompo_ticari, None).await.unwrap();
+        let band = CommissionRuleInput {
+            product_type: Some("kasko".to_string()),
+            min_premium: Some(20000.0),
+            ..rule(15.0)
+        };
+        let band = create_rule(&pool, &band, None).await.unwrap();
+        let expired = CommissionRuleInput {
+            provider: Some("Axa".to_string()),
+            valid_to: Some("2024-06-30".to_string()),
+            ..rule(20.0)
+        };
+        create_rule(&pool, &expired, None).await.unwrap();
+
+        let matched = |provider: &'static str, product: &'static str, usage: Option<&'static str>, premium: f64| {
+            let pool = pool.clone();
+            async move {
+                find_matching_rule(&pool, provider, product, usage, premium, "2024-09-01")
+                    .await
+                    .unwrap()
+                    .map(|r| r.id)
+            }
+        };
+
+        assert_eq!(matched("Axa", "trafik", Some("hususi"), 5000.0).await, Some(general.id.clone()));
+        assert_eq!(matched("Axa", "kasko", Some("hususi"), 5000.0).await, Some(kasko.id.clone()));
+        assert_eq!(matched("Axa", "kasko", Some("hususi"), 25000.0).await, Some(band.id));
+        assert_eq!(matched("sompo", "trafik", Some("ticari"), 5000.0).await, Some(sompo_ticari.id));
+        assert_eq!(matched("Sompo", "trafik", Some("hususi"), 5000.0).await, Some(general.id));
+
+        assert_eq!(count_policies_using_rule(&pool, &kasko.id).await.unwrap(), 0);
+        assert_eq!(list_rules(&pool, Some("2024-09-01"), 20, 0).await.unwrap().len(), 4);
+        assert!(delete_rule(&pool, &kasko.id).await.unwrap());
+        assert!(get_rule(&pool, &kasko.id).await.unwrap().is_none());
+    }
+}
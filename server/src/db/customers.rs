@@ -0,0 +1,244 @@
+use crate::db::models::{Customer, Policy, Quote};
+use crate::db::DbPool;
+use uuid::Uuid;
+
+/// Normalize edilmiş sigortalı bilgileri (bkz. services::customer::customer_input)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomerInput {
+    pub identity_no: String,
+    pub identity_type: String,
+    pub name: String,
+    pub name_key: String,
+    pub birth_date: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Acente + kimlik no ile ekler veya günceller. Ad her zaman son tekliftekiyle değişir;
+/// doğum tarihi, telefon ve e-posta yalnızca yeni değer geldiyse.
+pub async fn upsert_customer(
+    pool: &DbPool,
+    input: &CustomerInput,
+    agency_id: &str,
+    created_by: Option<&str>,
+) -> Result<Customer, sqlx::Error> {
+    sqlx::query_as::<_, Customer>(
+        r#"
+        INSERT INTO customers
+        (id, identity_no, identity_type, name, name_key, birth_date, phone, email, created_by, agency_id, last_quote_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, CURRENT_TIMESTAMP)
+        ON CONFLICT(agency_id, identity_no) DO UPDATE SET
+            name = excluded.name,
+            name_key = excluded.name_key,
+            birth_date = COALESCE(excluded.birth_date, customers.birth_date),
+            phone = COALESCE(excluded.phone, customers.phone),
+            email = COALESCE(excluded.email, customers.email),
+            updated_at = CURRENT_TIMESTAMP,
+            last_quote_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&input.identity_no)
+    .bind(&input.identity_type)
+    .bind(&input.name)
+    .bind(&input.name_key)
+    .bind(&input.birth_date)
+    .bind(&input.phone)
+    .bind(&input.email)
+    .bind(created_by)
+    .bind(agency_id)
+    .fetch_one(pool)
+    .await
+}
+
+/// `owner` verilirse yalnızca o kullanıcının teklif aldığı sigortalılar görünür (admin için None).
+/// `agency` verilirse yalnızca o acentenin sigortalıları (super-admin için None).
+pub async fn get_customer(
+    pool: &DbPool,
+    id: &str,
+    owner: Option<&str>,
+    agency: Option<&str>,
+) -> Result<Option<Customer>, sqlx::Error> {
+    sqlx::query_as::<_, Customer>(
+        r#"
+        SELECT * FROM customers c
+        WHERE c.id = $1
+          AND ($2 IS NULL OR EXISTS (SELECT 1 FROM quotes q WHERE q.customer_id = c.id AND q.user_id = $2))
+          AND ($3 IS NULL OR c.agency_id = $3)
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(agency)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn get_customer_by_identity(
+    pool: &DbPool,
+    agency_id: &str,
+    identity_no: &str,
+) -> Result<Option<Customer>, sqlx::Error> {
+    sqlx::query_as::<_, Customer>("SELECT * FROM customers WHERE agency_id = $1 AND identity_no = $2")
+        .bind(agency_id)
+        .bind(identity_no)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Boş alanlar filtre uygulanmaz. `phone` rakamların bir parçası, `name_key` fold_turkish ile sadeleştirilmiş ad parçası
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CustomerSearch {
+    pub identity_no: Option<String>,
+    pub phone: Option<String>,
+    pub name_key: Option<String>,
+}
+
+pub async fn search_customers(
+    pool: &DbPool,
+    search: &CustomerSearch,
+    owner: Option<&str>,
+    agency: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Customer>, sqlx::Error> {
+    sqlx::query_as::<_, Customer>(
+        r#"
+        SELECT * FROM customers c
+        WHERE ($1 IS NULL OR c.identity_no = $1)
+          AND ($2 IS NULL OR c.phone LIKE '%' || $2 || '%')
+          AND ($3 IS NULL OR c.name_key LIKE '%' || $3 || '%')
+          AND ($4 IS NULL OR EXISTS (SELECT 1 FROM quotes q WHERE q.customer_id = c.id AND q.user_id = $4))
+          AND ($5 IS NULL OR c.agency_id = $5)
+        ORDER BY COALESCE(c.last_quote_at, c.created_at) DESC, c.name_key
+        LIMIT $6 OFFSET $7
+        "#,
+    )
+    .bind(&search.identity_no)
+    .bind(&search.phone)
+    .bind(&search.name_key)
+    .bind(owner)
+    .bind(agency)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_customer_quotes(
+    pool: &DbPool,
+    customer_id: &str,
+    owner: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Quote>, sqlx::Error> {
+    sqlx::query_as::<_, Quote>(
+        r#"
+        SELECT * FROM quotes
+        WHERE customer_id = $1 AND ($2 IS NULL OR user_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(customer_id)
+    .bind(owner)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_customer_policies(
+    pool: &DbPool,
+    customer_id: &str,
+    owner: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Policy>, sqlx::Error> {
+    sqlx::query_as::<_, Policy>(
+        r#"
+        SELECT * FROM policies
+        WHERE customer_id = $1 AND ($2 IS NULL OR user_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(customer_id)
+    .bind(owner)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::agencies::{self, DEFAULT_AGENCY_ID};
+    use crate::db::quotes::{self, NewQuote};
+    use crate::db::{test_pool, test_user};
+
+    #[tokio::test]
+    async fn test_upsert_merges_and_scopes_history() {
+        let pool = test_pool().await;
+
+        let agent = test_user(&pool, "A", "agent").await;
+        let other = test_user(&pool, "B", "agent").await;
+
+        let input = CustomerInput {
+            identity_no: "12345678901".to_string(),
+            identity_type: "tckn".to_string(),
+            name: "Şükrü Öztürk".to_string(),
+            name_key: "sukru ozturk".to_string(),
+            birth_date: Some("1990-01-01".to_string()),
+            phone: Some("905551234567".to_string()),
+            email: None,
+        };
+        let first = upsert_customer(&pool, &input, DEFAULT_AGENCY_ID, Some(&agent.id)).await.unwrap();
+        let merged = upsert_customer(
+            &pool,
+            &CustomerInput { phone: None, email: Some("sukru@example.com".to_string()), ..input.clone() },
+            DEFAULT_AGENCY_ID,
+            Some(&other.id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(merged.id, first.id);
+        assert_eq!(merged.phone.as_deref(), Some("905551234567"));
+        assert_eq!(merged.email.as_deref(), Some("sukru@example.com"));
+        assert_eq!(merged.created_by.as_deref(), Some(agent.id.as_str()));
+
+        quotes::create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &agent.id,
+                request_id: "req-1",
+                request_data: serde_json::json!({}),
+                provider: "Mock",
+                premium: 1000.0,
+                response_data: serde_json::json!({}),
+                customer_id: Some(&first.id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let search = CustomerSearch { name_key: Some("ozt".to_string()), ..Default::default() };
+        assert_eq!(search_customers(&pool, &search, None, None, 10, 0).await.unwrap().len(), 1);
+        assert_eq!(search_customers(&pool, &search, Some(&agent.id), None, 10, 0).await.unwrap().len(), 1);
+        assert!(search_customers(&pool, &search, Some(&other.id), None, 10, 0).await.unwrap().is_empty());
+        assert!(get_customer(&pool, &first.id, Some(&other.id), None).await.unwrap().is_none());
+        assert_eq!(list_customer_quotes(&pool, &first.id, Some(&agent.id), 10, 0).await.unwrap().len(), 1);
+
+        // Başka acentede aynı TCKN ayrı kayıttır; acenteler birbirinin sigortalısını görmez
+        let agency = agencies::create_agency(&pool, "Acente B").await.unwrap();
+        let separate = upsert_customer(&pool, &input, &agency.id, None).await.unwrap();
+        assert_ne!(separate.id, first.id);
+        assert!(get_customer(&pool, &first.id, None, Some(&agency.id)).await.unwrap().is_none());
+        assert_eq!(search_customers(&pool, &search, None, Some(&agency.id), 10, 0).await.unwrap().len(), 1);
+        let found = get_customer_by_identity(&pool, &agency.id, "12345678901").await.unwrap().unwrap();
+        assert_eq!(found.id, separate.id);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::db::models::{ActivityLog, Policy, Quote};
+use crate::db::DbPool;
+use futures::stream::BoxStream;
+use sqlx::FromRow;
+
+/// Dışa aktarma filtreleri; None olan alanlar uygulanmaz.
+/// Tarihler YYYY-MM-DD, `created_at` üzerinden ve her iki uç dahil.
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// None: tüm acenteler (super-admin)
+    pub agency_id: Option<String>,
+    pub user_id: Option<String>,
+    pub status: Option<String>,
+    pub provider: Option<String>,
+    pub quote_no: Option<String>,
+    pub action: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct QuoteExportRow {
+    #[sqlx(flatten)]
+    pub quote: Quote,
+    pub user_email: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PolicyExportRow {
+    #[sqlx(flatten)]
+    pub policy: Policy,
+    pub user_email: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ActivityExportRow {
+    #[sqlx(flatten)]
+    pub log: ActivityLog,
+    pub user_email: Option<String>,
+}
+
+/// Satırlar tek tek okunur; tablo belleğe alınmaz
+pub fn stream_quotes<'a>(
+    pool: &'a DbPool,
+    filter: &'a ExportFilter,
+) -> BoxStream<'a, Result<QuoteExportRow, sqlx::Error>> {
+    sqlx::query_as::<_, QuoteExportRow>(
+        r#"
+        SELECT q.*, u.email AS user_email
+        FROM quotes q
+        LEFT JOIN users u ON u.id = q.user_id
+        WHERE ($1 IS NULL OR q.user_id = $1)
+          AND ($2 IS NULL OR q.status = $2)
+          AND ($3 IS NULL OR q.provider = $3 COLLATE NOCASE)
+          AND ($4 IS NULL OR q.provider_quote_no = $4 COLLATE NOCASE)
+          AND ($5 IS NULL OR substr(q.created_at, 1, 10) >= $5)
+          AND ($6 IS NULL OR substr(q.created_at, 1, 10) <= $6)
+          AND ($7 IS NULL OR q.agency_id = $7)
+        ORDER BY q.created_at DESC
+        "#,
+    )
+    .bind(filter.user_id.as_deref())
+    .bind(filter.status.as_deref())
+    .bind(filter.provider.as_deref())
+    .bind(filter.quote_no.as_deref().map(str::trim))
+    .bind(filter.from.as_deref())
+    .bind(filter.to.as_deref())
+    .bind(filter.agency_id.as_deref())
+    .fetch(pool)
+}
+
+pub fn stream_policies<'a>(
+    pool: &'a DbPool,
+    filter: &'a ExportFilter,
+) -> BoxStream<'a, Result<PolicyExportRow, sqlx::Error>> {
+    sqlx::query_as::<_, PolicyExportRow>(
+        r#"
+        SELECT p.*, u.email AS user_email
+        FROM policies p
+        LEFT JOIN users u ON u.id = p.user_id
+        WHERE ($1 IS NULL OR p.user_id = $1)
+          AND ($2 IS NULL OR p.status = $2)
+          AND ($3 IS NULL OR p.provider = $3 COLLATE NOCASE)
+          AND ($4 IS NULL OR substr(p.created_at, 1, 10) >= $4)
+          AND ($5 IS NULL OR substr(p.created_at, 1, 10) <= $5)
+          AND ($6 IS NULL OR p.agency_id = $6)
+        ORDER BY p.created_at DESC
+        "#,
+    )
+    .bind(filter.user_id.as_deref())
+    .bind(filter.status.as_deref())
+    .bind(filter.provider.as_deref())
+    .bind(filter.from.as_deref())
+    .bind(filter.to.as_deref())
+    .bind(filter.agency_id.as_deref())
+    .fetch(pool)
+}
+
+pub fn stream_activity_logs<'a>(
+    pool: &'a DbPool,
+    filter: &'a ExportFilter,
+) -> BoxStream<'a, Result<ActivityExportRow, sqlx::Error>> {
+    sqlx::query_as::<_, ActivityExportRow>(
+        r#"
+        SELECT l.*, u.email AS user_email
+        FROM activity_logs l
+        LEFT JOIN users u ON u.id = l.user_id
+        WHERE ($1 IS NULL OR l.user_id = $1)
+          AND ($2 IS NULL OR l.action = $2)
+          AND ($3 IS NULL OR substr(l.created_at, 1, 10) >= $3)
+          AND ($4 IS NULL OR substr(l.created_at, 1, 10) <= $4)
+          AND ($5 IS NULL OR u.agency_id = $5)
+        ORDER BY l.created_at DESC
+        "#,
+    )
+    .bind(filter.user_id.as_deref())
+    .bind(filter.action.as_deref())
+    .bind(filter.from.as_deref())
+    .bind(filter.to.as_deref())
+    .bind(filter.agency_id.as_deref())
+    .fetch(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::quotes::{self, NewQuote};
+    use crate::db::{test_pool, test_user};
+    use futures::TryStreamExt;
+
+    #[tokio::test]
+    async fn test_stream_quotes_applies_filters() {
+        let pool = test_pool().await;
+
+        let alice = test_user(&pool, "Alice", "user").await;
+        let bob = test_user(&pool, "Bob", "user").await;
+        for (user, provider, quote_no) in [(&alice, "Axa", "AX-1"), (&alice, "Sompo", "S-1"), (&bob, "Axa", "AX-2")] {
+            quotes::create_quote(
+                &pool,
+                &NewQuote {
+                    user_id: &user.id,
+                    request_id: quote_no,
+                    request_data: serde_json::json!({}),
+                    provider,
+                    premium: 1000.0,
+                    response_data: serde_json::json!({}),
+                    provider_quote_no: Some(quote_no),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let all: Vec<QuoteExportRow> = stream_quotes(&pool, &ExportFilter::default()).try_collect().await.unwrap();
+        assert_eq!(all.len(), 3);
+
+        let filter = ExportFilter {
+            user_id: Some(alice.id.clone()),
+            provider: Some("axa".to_string()),
+            ..Default::default()
+        };
+        let rows: Vec<QuoteExportRow> = stream_quotes(&pool, &filter).try_collect().await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].quote.provider_quote_no.as_deref(), Some("AX-1"));
+        assert_eq!(rows[0].user_email.as_deref(), Some("alice@example.com"));
+
+        let future = ExportFilter { from: Some("2999-01-01".to_string()), ..Default::default() };
+        let none: Vec<QuoteExportRow> = stream_quotes(&pool, &future).try_collect().await.unwrap();
+        assert!(none.is_empty());
+    }
+}
@@ -31,38 +31,28 @@ pub async fn log_activity(
     .await
 }
 
+/// `agency`: kullanıcının acentesi; None ise tüm acenteler
 pub async fn list_activity_logs(
     pool: &DbPool,
     user_id: Option<&str>,
+    agency: Option<&str>,
     limit: i64,
     offset: i64,
 ) -> Result<Vec<ActivityLog>, sqlx::Error> {
-    if let Some(uid) = user_id {
-        sqlx::query_as::<_, ActivityLog>(
-            r#"
-            SELECT * FROM activity_logs
-            WHERE user_id = $1
-            ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
-            "#,
-        )
-        .bind(uid)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(pool)
-        .await
-    } else {
-        sqlx::query_as::<_, ActivityLog>(
-            r#"
-            SELECT * FROM activity_logs
-            ORDER BY created_at DESC
-            LIMIT $1 OFFSET $2
-            "#,
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(pool)
-        .await
-    }
+    sqlx::query_as::<_, ActivityLog>(
+        r#"
+        SELECT * FROM activity_logs
+        WHERE ($1 IS NULL OR user_id = $1)
+          AND ($2 IS NULL OR user_id IN (SELECT id FROM users WHERE agency_id = $2))
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(agency)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
 }
 
@@ -2,7 +2,17 @@ pub mod models;
 pub mod users;
 pub mod quotes;
 pub mod policies;
+pub mod renewals;
 pub mod logs;
+pub mod outbox;
+pub mod sms;
+pub mod exports;
+pub mod commissions;
+pub mod statements;
+pub mod customers;
+pub mod vehicles;
+pub mod search;
+pub mod agencies;
 
 use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
 use std::time::Duration;
@@ -21,3 +31,25 @@ pub async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::migrate::MigrateE
     sqlx::migrate!("./migrations").run(pool).await
 }
 
+
+/// Testler için migration'ları uygulanmış bellek içi veritabanı.
+/// Tek bağlantı: her :memory: bağlantısı ayrı veritabanıdır.
+#[cfg(test)]
+pub(crate) async fn test_pool() -> DbPool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+    pool
+}
+
+/// Varsayılan acentede test kullanıcısı; e-posta adından üretilir ("Alice" -> alice@example.com)
+#[cfg(test)]
+pub(crate) async fn test_user(pool: &DbPool, name: &str, role: &str) -> models::User {
+    let email = format!("{}@example.com", name.to_lowercase());
+    users::create_user(pool, &email, "hash", name, role, agencies::DEFAULT_AGENCY_ID)
+        .await
+        .unwrap()
+}
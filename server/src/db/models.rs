@@ -13,6 +13,36 @@ pub struct User {
     pub created_at: String,
     pub last_login: Option<String>,
     pub is_active: bool,
+    pub agency_id: String,
+}
+
+/// Acente; kullanıcılar ve kayıtları acenteye bağlıdır
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Agency {
+    pub id: String,
+    pub name: String,
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    pub address: String,
+    pub phone: String,
+    pub email: String,
+    /// SEDDK levha kayıt numarası
+    pub license_no: String,
+}
+
+/// Acentenin provider portal kullanıcı bilgisi (şifre ve OTP anahtarı yanıtlarda dönmez)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AgencyCredential {
+    pub agency_id: String,
+    pub provider: String,
+    pub username: String,
+    #[serde(skip_serializing)]
+    pub password: String,
+    #[serde(skip_serializing)]
+    pub secret_key: Option<String>,
+    pub updated_by: Option<String>,
+    pub updated_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -26,6 +56,14 @@ pub struct Quote {
     pub response_data: serde_json::Value,
     pub status: String,
     pub created_at: String,
+    pub issue_attempts: i64,
+    pub issue_error: Option<String>,
+    pub issuing_started_at: Option<String>,
+    pub provider_quote_no: Option<String>,
+    pub valid_until: Option<String>,
+    pub customer_id: Option<String>,
+    pub vehicle_id: Option<String>,
+    pub agency_id: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -43,6 +81,185 @@ pub struct Policy {
     pub created_at: String,
     pub expires_at: Option<String>,
     pub pdf_path: Option<String>,
+    pub starts_at: Option<String>,
+    pub cancelled_at: Option<String>,
+    pub refund_amount: Option<f64>,
+    pub renewal_of_policy_id: Option<String>,
+    pub renewed_by_policy_id: Option<String>,
+    pub renewal_quoted_at: Option<String>,
+    pub renewal_attempts: i64,
+    pub renewal_error: Option<String>,
+    /// Komisyonun hesaplandığı kural (None: varsayılan oran)
+    pub commission_rule_id: Option<String>,
+    pub customer_id: Option<String>,
+    pub vehicle_id: Option<String>,
+    pub agency_id: String,
+}
+
+/// Sigortalı (TCKN/VKN ile tekil)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Customer {
+    pub id: String,
+    pub agency_id: String,
+    pub identity_no: String,
+    /// tckn | vkn
+    pub identity_type: String,
+    pub name: String,
+    pub birth_date: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_quote_at: Option<String>,
+}
+
+/// Sigortalıya bağlı araç (sigortalı + plaka ile tekil)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Vehicle {
+    pub id: String,
+    pub customer_id: String,
+    pub plate: String,
+    pub vin: Option<String>,
+    pub brand: String,
+    pub model: String,
+    pub year: i64,
+    pub usage: String,
+    pub category: String,
+    /// Ruhsat belge seri / no
+    pub registration_serial: Option<String>,
+    pub registration_no: Option<String>,
+    pub engine_no: Option<String>,
+    /// Tescil tarihi (YYYY-MM-DD)
+    pub registration_date: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub last_quote_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RenewalOffer {
+    pub id: String,
+    pub policy_id: String,
+    pub quote_id: String,
+    pub user_id: String,
+    pub provider: String,
+    pub premium: f64,
+    pub premium_change: f64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OutboxEmail {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub template: String,
+    pub to_email: String,
+    pub subject: String,
+    #[serde(skip_serializing)]
+    pub html_body: String,
+    #[serde(skip_serializing)]
+    pub text_body: String,
+    pub attachment_name: Option<String>,
+    pub attachment_type: Option<String>,
+    #[serde(skip_serializing)]
+    pub attachment: Option<Vec<u8>>,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub next_attempt_at: String,
+    pub created_at: String,
+    pub sent_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SmsMessage {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub phone: String,
+    pub event: String,
+    pub message: String,
+    pub status: String,
+    pub provider: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SmsOptOut {
+    pub agency_id: String,
+    pub phone: String,
+    pub reason: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CommissionRule {
+    pub id: String,
+    pub provider: Option<String>,
+    pub product_type: Option<String>,
+    pub usage: Option<String>,
+    pub min_premium: Option<f64>,
+    pub max_premium: Option<f64>,
+    pub rate_percent: f64,
+    pub valid_from: String,
+    pub valid_to: Option<String>,
+    pub note: Option<String>,
+    pub created_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProviderStatement {
+    pub id: String,
+    pub agency_id: String,
+    pub provider: String,
+    pub period: String,
+    pub file_name: Option<String>,
+    pub uploaded_by: Option<String>,
+    pub line_count: i64,
+    pub matched_count: i64,
+    pub mismatch_count: i64,
+    pub missing_ours_count: i64,
+    pub missing_theirs_count: i64,
+    pub statement_commission_total: f64,
+    pub our_commission_total: f64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StatementItem {
+    pub id: String,
+    pub statement_id: String,
+    pub policy_id: Option<String>,
+    pub policy_number: String,
+    pub status: String,
+    pub statement_commission: Option<f64>,
+    pub our_commission: Option<f64>,
+    pub difference: Option<f64>,
+    pub statement_premium: Option<f64>,
+    pub source_rows: Option<String>,
+    pub resolution_note: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PolicyEvent {
+    pub id: String,
+    pub policy_id: String,
+    pub event_type: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor_id: String,
+    pub reason: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub created_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
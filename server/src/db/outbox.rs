@@ -0,0 +1,180 @@
+use crate::db::models::OutboxEmail;
+use crate::db::DbPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct NewOutboxEmail<'a> {
+    pub user_id: Option<&'a str>,
+    pub template: &'a str,
+    pub to_email: &'a str,
+    pub subject: &'a str,
+    pub html_body: &'a str,
+    pub text_body: &'a str,
+    /// (dosya adı, MIME tipi, içerik)
+    pub attachment: Option<(&'a str, &'a str, &'a [u8])>,
+}
+
+pub async fn enqueue_email(pool: &DbPool, email: &NewOutboxEmail<'_>) -> Result<OutboxEmail, sqlx::Error> {
+    let (attachment_name, attachment_type, attachment) = match email.attachment {
+        Some((name, content_type, data)) => (Some(name), Some(content_type), Some(data)),
+        None => (None, None, None),
+    };
+
+    sqlx::query_as::<_, OutboxEmail>(
+        r#"
+        INSERT INTO email_outbox
+        (id, user_id, template, to_email, subject, html_body, text_body, attachment_name, attachment_type, attachment)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(email.user_id)
+    .bind(email.template)
+    .bind(email.to_email)
+    .bind(email.subject)
+    .bind(email.html_body)
+    .bind(email.text_body)
+    .bind(attachment_name)
+    .bind(attachment_type)
+    .bind(attachment)
+    .fetch_one(pool)
+    .await
+}
+
+/// Gönderim zamanı gelmiş bekleyen e-postalar (eskiden yeniye)
+pub async fn list_due_emails(pool: &DbPool, now: &str, limit: i64) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>(
+        r#"
+        SELECT * FROM email_outbox
+        WHERE status = 'pending' AND next_attempt_at <= $1
+        ORDER BY next_attempt_at ASC, created_at ASC
+        LIMIT $2
+        "#,
+    )
+    .bind(now)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn mark_email_sent(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET status = 'sent', attempts = attempts + 1, last_error = NULL, sent_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Başarısız deneme: `next_attempt_at` None ise e-posta kalıcı olarak failed olur
+pub async fn mark_email_attempt_failed(
+    pool: &DbPool,
+    id: &str,
+    error: &str,
+    next_attempt_at: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET attempts = attempts + 1,
+            last_error = $2,
+            status = CASE WHEN $3 IS NULL THEN 'failed' ELSE 'pending' END,
+            next_attempt_at = COALESCE($3, next_attempt_at)
+        WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(error)
+    .bind(next_attempt_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Admin: failed e-postayı yeniden kuyruğa alır (deneme sayacı sıfırlanır)
+pub async fn requeue_email(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE email_outbox
+        SET status = 'pending', attempts = 0, next_attempt_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND status = 'failed'
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn list_emails(
+    pool: &DbPool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<OutboxEmail>, sqlx::Error> {
+    sqlx::query_as::<_, OutboxEmail>(
+        r#"
+        SELECT * FROM email_outbox
+        WHERE ($1 IS NULL OR status = $1)
+        ORDER BY created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[tokio::test]
+    async fn test_outbox_retry_lifecycle() {
+        let pool = test_pool().await;
+
+        let email = enqueue_email(
+            &pool,
+            &NewOutboxEmail {
+                user_id: None,
+                template: "welcome",
+                to_email: "a@example.com",
+                subject: "Hoş geldiniz",
+                html_body: "<p>Merhaba</p>",
+                text_body: "Merhaba",
+                attachment: Some(("police.pdf", "application/pdf", b"%PDF-1.4")),
+            },
+        )
+        .await
+        .unwrap();
+        let far_future = "2999-01-01 00:00:00";
+        assert_eq!(list_due_emails(&pool, far_future, 10).await.unwrap().len(), 1);
+
+        // Ertelenen deneme zamanı gelene kadar alınmaz
+        mark_email_attempt_failed(&pool, &email.id, "bağlantı reddedildi", Some("2999-01-01 00:00:00")).await.unwrap();
+        assert!(list_due_emails(&pool, "2998-12-31 23:59:59", 10).await.unwrap().is_empty());
+        let due = list_due_emails(&pool, far_future, 10).await.unwrap();
+        assert_eq!(due[0].attempts, 1);
+        assert_eq!(due[0].attachment.as_deref(), Some(&b"%PDF-1.4"[..]));
+
+        // Son deneme: kalıcı hata, admin yeniden kuyruğa alabilir
+        mark_email_attempt_failed(&pool, &email.id, "bağlantı reddedildi", None).await.unwrap();
+        assert!(list_due_emails(&pool, far_future, 10).await.unwrap().is_empty());
+        assert_eq!(list_emails(&pool, Some("failed"), 10, 0).await.unwrap().len(), 1);
+        assert!(requeue_email(&pool, &email.id).await.unwrap());
+
+        mark_email_sent(&pool, &email.id).await.unwrap();
+        let sent = list_emails(&pool, Some("sent"), 10, 0).await.unwrap();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].sent_at.is_some());
+    }
+}
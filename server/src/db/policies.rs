@@ -1,40 +1,52 @@
-use crate::db::models::Policy;
+use crate::db::models::{Policy, PolicyEvent};
 use crate::db::DbPool;
 use sqlx::Row;
 use uuid::Uuid;
 
-pub async fn create_policy(
-    pool: &DbPool,
-    user_id: &str,
-    quote_id: Option<&str>,
-    policy_number: &str,
-    provider: &str,
-    product_type: &str,
-    premium: f64,
-    commission: Option<f64>,
-    policy_data: serde_json::Value,
-    expires_at: Option<String>,
-) -> Result<Policy, sqlx::Error> {
+/// Kaydedilecek poliçe; müşteri, araç ve acente teklif kaydından alınır
+#[derive(Debug, Clone, Default)]
+pub struct NewPolicy<'a> {
+    pub user_id: &'a str,
+    pub quote_id: Option<&'a str>,
+    pub policy_number: &'a str,
+    pub provider: &'a str,
+    pub product_type: &'a str,
+    pub premium: f64,
+    pub commission: Option<f64>,
+    pub commission_rule_id: Option<&'a str>,
+    pub policy_data: serde_json::Value,
+    pub starts_at: Option<&'a str>,
+    pub expires_at: Option<&'a str>,
+    /// None: active
+    pub status: Option<&'a str>,
+}
+
+pub async fn create_policy(pool: &DbPool, policy: &NewPolicy<'_>) -> Result<Policy, sqlx::Error> {
     let id = Uuid::new_v4().to_string();
     
     sqlx::query_as::<_, Policy>(
         r#"
         INSERT INTO policies 
-        (id, user_id, quote_id, policy_number, provider, product_type, premium, commission, policy_data, expires_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        (id, user_id, quote_id, policy_number, provider, product_type, premium, commission, commission_rule_id, policy_data, starts_at, expires_at, status, customer_id, vehicle_id, agency_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, COALESCE($13, 'active'),
+                (SELECT customer_id FROM quotes WHERE id = $3), (SELECT vehicle_id FROM quotes WHERE id = $3),
+                COALESCE((SELECT agency_id FROM quotes WHERE id = $3), (SELECT agency_id FROM users WHERE id = $2)))
         RETURNING *
         "#,
     )
     .bind(id)
-    .bind(user_id)
-    .bind(quote_id)
-    .bind(policy_number)
-    .bind(provider)
-    .bind(product_type)
-    .bind(premium)
-    .bind(commission)
-    .bind(policy_data)
-    .bind(expires_at)
+    .bind(policy.user_id)
+    .bind(policy.quote_id)
+    .bind(policy.policy_number)
+    .bind(policy.provider)
+    .bind(policy.product_type)
+    .bind(policy.premium)
+    .bind(policy.commission)
+    .bind(policy.commission_rule_id)
+    .bind(&policy.policy_data)
+    .bind(policy.starts_at)
+    .bind(policy.expires_at)
+    .bind(policy.status)
     .fetch_one(pool)
     .await
 }
@@ -46,6 +58,16 @@ pub async fn get_policy_by_id(pool: &DbPool, id: &str) -> Result<Option<Policy>,
         .await
 }
 
+/// Üretilen poliçe PDF'inin yolu; None: PDF bir sonraki istekte yeniden üretilir
+pub async fn set_policy_pdf_path(pool: &DbPool, id: &str, pdf_path: Option<&str>) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE policies SET pdf_path = $2 WHERE id = $1")
+        .bind(id)
+        .bind(pdf_path)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 pub async fn list_policies_by_user(
     pool: &DbPool,
     user_id: &str,
@@ -67,24 +89,307 @@ pub async fn list_policies_by_user(
     .await
 }
 
-pub async fn count_policies(pool: &DbPool) -> Result<i64, sqlx::Error> {
-    let row = sqlx::query("SELECT COUNT(*) as count FROM policies")
+/// `agency`: None ise tüm acenteler
+pub async fn count_policies(pool: &DbPool, agency: Option<&str>) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT COUNT(*) as count FROM policies WHERE ($1 IS NULL OR agency_id = $1)")
+        .bind(agency)
         .fetch_one(pool)
         .await?;
     Ok(row.get("count"))
 }
 
-pub async fn sum_revenue(pool: &DbPool) -> Result<f64, sqlx::Error> {
-    let row = sqlx::query("SELECT COALESCE(SUM(premium), 0.0) as total FROM policies WHERE status = 'active'")
-        .fetch_one(pool)
+pub async fn sum_revenue(pool: &DbPool, agency: Option<&str>) -> Result<f64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COALESCE(SUM(premium), 0.0) as total FROM policies WHERE status IN ('active', 'endorsed') AND ($1 IS NULL OR agency_id = $1)",
+    )
+    .bind(agency)
+    .fetch_one(pool)
         .await?;
     Ok(row.get("total"))
 }
 
-pub async fn sum_commission(pool: &DbPool) -> Result<f64, sqlx::Error> {
-    let row = sqlx::query("SELECT COALESCE(SUM(commission), 0.0) as total FROM policies WHERE status = 'active'")
-        .fetch_one(pool)
+pub async fn sum_commission(pool: &DbPool, agency: Option<&str>) -> Result<f64, sqlx::Error> {
+    let row = sqlx::query(
+        "SELECT COALESCE(SUM(commission), 0.0) as total FROM policies WHERE status IN ('active', 'endorsed') AND ($1 IS NULL OR agency_id = $1)",
+    )
+    .bind(agency)
+    .fetch_one(pool)
         .await?;
     Ok(row.get("total"))
 }
 
+
+/// Durum geçişiyle birlikte güncellenecek alanlar (None = değişmez)
+#[derive(Debug, Default)]
+pub struct PolicyChanges {
+    pub premium: Option<f64>,
+    pub commission: Option<f64>,
+    pub policy_data: Option<serde_json::Value>,
+    pub cancelled_at: Option<String>,
+    pub refund_amount: Option<f64>,
+    /// Yenileme: yeni poliçe id'si (yeni poliçeye de geri bağlantı yazılır)
+    pub renewed_by_policy_id: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct NewPolicyEvent<'a> {
+    pub event_type: &'a str,
+    pub actor_id: &'a str,
+    pub reason: Option<&'a str>,
+    pub data: Option<serde_json::Value>,
+}
+
+/// Poliçeyi `from` durumundan `to` durumuna taşır ve olayı kaydeder (tek transaction).
+/// Poliçe bu arada başka bir istekle değiştiyse `None` döner.
+pub async fn transition_policy(
+    pool: &DbPool,
+    id: &str,
+    from: &str,
+    to: &str,
+    changes: PolicyChanges,
+    event: NewPolicyEvent<'_>,
+) -> Result<Option<Policy>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let updated = sqlx::query_as::<_, Policy>(
+        r#"
+        UPDATE policies
+        SET status = $3,
+            premium = COALESCE($4, premium),
+            commission = COALESCE($5, commission),
+            policy_data = COALESCE($6, policy_data),
+            cancelled_at = COALESCE($7, cancelled_at),
+            refund_amount = COALESCE($8, refund_amount),
+            renewed_by_policy_id = COALESCE($9, renewed_by_policy_id)
+        WHERE id = $1 AND status = $2
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(from)
+    .bind(to)
+    .bind(changes.premium)
+    .bind(changes.commission)
+    .bind(changes.policy_data)
+    .bind(changes.cancelled_at)
+    .bind(changes.refund_amount)
+    .bind(changes.renewed_by_policy_id.as_deref())
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(policy) = updated else {
+        return Ok(None);
+    };
+
+    if let Some(renewal_id) = changes.renewed_by_policy_id.as_deref() {
+        sqlx::query("UPDATE policies SET renewal_of_policy_id = $2 WHERE id = $1")
+            .bind(renewal_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO policy_events (id, policy_id, event_type, from_status, to_status, actor_id, reason, data)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(id)
+    .bind(event.event_type)
+    .bind(from)
+    .bind(to)
+    .bind(event.actor_id)
+    .bind(event.reason)
+    .bind(event.data)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(policy))
+}
+
+/// Durum değişmeden olay kaydı (ör. poliçe kesimi)
+pub async fn record_policy_event(
+    pool: &DbPool,
+    policy_id: &str,
+    to_status: &str,
+    event: NewPolicyEvent<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO policy_events (id, policy_id, event_type, from_status, to_status, actor_id, reason, data)
+        VALUES ($1, $2, $3, NULL, $4, $5, $6, $7)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(policy_id)
+    .bind(event.event_type)
+    .bind(to_status)
+    .bind(event.actor_id)
+    .bind(event.reason)
+    .bind(event.data)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn list_policy_events(pool: &DbPool, policy_id: &str) -> Result<Vec<PolicyEvent>, sqlx::Error> {
+    sqlx::query_as::<_, PolicyEvent>(
+        "SELECT * FROM policy_events WHERE policy_id = $1 ORDER BY created_at ASC, rowid ASC",
+    )
+    .bind(policy_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Vadesi `today` tarihinden önce bitmiş yürürlükteki poliçeleri expired yapar
+pub async fn expire_due_policies(pool: &DbPool, today: &str) -> Result<Vec<Policy>, sqlx::Error> {
+    let due = sqlx::query_as::<_, Policy>(
+        r#"
+        SELECT * FROM policies
+        WHERE status IN ('active', 'endorsed')
+          AND expires_at IS NOT NULL
+          AND substr(expires_at, 1, 10) < $1
+        "#,
+    )
+    .bind(today)
+    .fetch_all(pool)
+    .await?;
+
+    let mut expired = Vec::with_capacity(due.len());
+    for policy in due {
+        let event = NewPolicyEvent {
+            event_type: "expire",
+            actor_id: "system",
+            reason: Some("Vade sonu"),
+            data: None,
+        };
+        if let Some(updated) = transition_policy(pool, &policy.id, &policy.status, "expired", PolicyChanges::default(), event).await? {
+            expired.push(updated);
+        }
+    }
+    Ok(expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_pool, test_user};
+
+
+    async fn insert_policy(pool: &DbPool, user_id: &str, number: &str, expires_at: &str) -> Policy {
+        create_policy(
+            pool,
+            &NewPolicy {
+                user_id,
+                policy_number: number,
+                provider: "Mock",
+                product_type: "trafik",
+                premium: 1000.0,
+                commission: Some(100.0),
+                policy_data: serde_json::json!({}),
+                starts_at: Some("2024-01-01"),
+                expires_at: Some(expires_at),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transition_records_event_and_rejects_stale_state() {
+        let pool = test_pool().await;
+        let user = test_user(&pool, "A", "user").await;
+        let policy = insert_policy(&pool, &user.id, "P-1", "2025-01-01").await;
+
+        let changes = PolicyChanges {
+            cancelled_at: Some("2024-02-15".to_string()),
+            refund_amount: Some(700.0),
+            ..Default::default()
+        };
+        let event = NewPolicyEvent {
+            event_type: "cancel",
+            actor_id: &user.id,
+            reason: Some("Araç satıldı"),
+            data: Some(serde_json::json!({ "refund": 700.0 })),
+        };
+        let cancelled = transition_policy(&pool, &policy.id, "active", "cancelled", changes, event)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cancelled.status, "cancelled");
+        assert_eq!(cancelled.refund_amount, Some(700.0));
+        assert_eq!(cancelled.premium, 1000.0);
+
+        // Aynı geçiş ikinci kez uygulanamaz
+        let again = NewPolicyEvent { event_type: "cancel", actor_id: &user.id, reason: None, data: None };
+        assert!(transition_policy(&pool, &policy.id, "active", "cancelled", PolicyChanges::default(), again)
+            .await
+            .unwrap()
+            .is_none());
+
+        let events = list_policy_events(&pool, &policy.id).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].from_status.as_deref(), Some("active"));
+        assert_eq!(events[0].reason.as_deref(), Some("Araç satıldı"));
+    }
+
+    #[tokio::test]
+    async fn test_pending_issue_policy_can_be_activated() {
+        let pool = test_pool().await;
+        let user = test_user(&pool, "A", "user").await;
+        let pending = create_policy(
+            &pool,
+            &NewPolicy {
+                user_id: &user.id,
+                policy_number: "P-1",
+                provider: "Mock",
+                product_type: "trafik",
+                premium: 1000.0,
+                policy_data: serde_json::json!({}),
+                status: Some("pending_issue"),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(pending.status, "pending_issue");
+
+        let event = NewPolicyEvent { event_type: "activate", actor_id: &user.id, reason: None, data: None };
+        let activated = transition_policy(&pool, &pending.id, "pending_issue", "active", PolicyChanges::default(), event)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(activated.status, "active");
+    }
+
+    #[tokio::test]
+    async fn test_renewal_links_and_expiry_sweep() {
+        let pool = test_pool().await;
+        let user = test_user(&pool, "A", "user").await;
+        let old = insert_policy(&pool, &user.id, "P-1", "2024-12-31").await;
+        let current = insert_policy(&pool, &user.id, "P-2", "2999-01-01").await;
+
+        let expired = expire_due_policies(&pool, "2025-01-05").await.unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, old.id);
+
+        let changes = PolicyChanges {
+            renewed_by_policy_id: Some(current.id.clone()),
+            ..Default::default()
+        };
+        let event = NewPolicyEvent { event_type: "renew", actor_id: &user.id, reason: None, data: None };
+        let renewed = transition_policy(&pool, &old.id, "expired", "renewed", changes, event)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(renewed.renewed_by_policy_id.as_deref(), Some(current.id.as_str()));
+
+        let successor = get_policy_by_id(&pool, &current.id).await.unwrap().unwrap();
+        assert_eq!(successor.renewal_of_policy_id.as_deref(), Some(old.id.as_str()));
+        assert_eq!(list_policy_events(&pool, &old.id).await.unwrap().len(), 2);
+    }
+}
@@ -3,31 +3,43 @@ use crate::db::DbPool;
 use sqlx::Row;
 use uuid::Uuid;
 
-pub async fn create_quote(
-    pool: &DbPool,
-    user_id: &str,
-    request_id: &str,
-    request_data: serde_json::Value,
-    provider: &str,
-    premium: f64,
-    response_data: serde_json::Value,
-) -> Result<Quote, sqlx::Error> {
+/// Kaydedilecek teklif; acente kullanıcının acentesinden alınır
+#[derive(Debug, Clone, Default)]
+pub struct NewQuote<'a> {
+    pub user_id: &'a str,
+    pub request_id: &'a str,
+    pub request_data: serde_json::Value,
+    pub provider: &'a str,
+    pub premium: f64,
+    pub response_data: serde_json::Value,
+    pub provider_quote_no: Option<&'a str>,
+    pub valid_until: Option<&'a str>,
+    pub customer_id: Option<&'a str>,
+    pub vehicle_id: Option<&'a str>,
+}
+
+pub async fn create_quote(pool: &DbPool, quote: &NewQuote<'_>) -> Result<Quote, sqlx::Error> {
     let id = Uuid::new_v4().to_string();
     
     sqlx::query_as::<_, Quote>(
         r#"
-        INSERT INTO quotes (id, user_id, request_id, request_data, provider, premium, response_data, status)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, 'completed')
+        INSERT INTO quotes
+        (id, user_id, request_id, request_data, provider, premium, response_data, status, provider_quote_no, valid_until, customer_id, vehicle_id, agency_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, 'completed', $8, $9, $10, $11, (SELECT agency_id FROM users WHERE id = $2))
         RETURNING *
         "#,
     )
     .bind(id)
-    .bind(user_id)
-    .bind(request_id)
-    .bind(request_data)
-    .bind(provider)
-    .bind(premium)
-    .bind(response_data)
+    .bind(quote.user_id)
+    .bind(quote.request_id)
+    .bind(&quote.request_data)
+    .bind(quote.provider)
+    .bind(quote.premium)
+    .bind(&quote.response_data)
+    .bind(quote.provider_quote_no)
+    .bind(quote.valid_until)
+    .bind(quote.customer_id)
+    .bind(quote.vehicle_id)
     .fetch_one(pool)
     .await
 }
@@ -39,6 +51,75 @@ pub async fn get_quote_by_id(pool: &DbPool, id: &str) -> Result<Option<Quote>, s
         .await
 }
 
+/// Teklifi poliçe kesimi için kilitler (completed -> issuing).
+/// Yalnızca portala gönderilmeden hata almış (bkz. mark_quote_issue_failed) issuing denemesi yeniden alınabilir.
+/// Başka bir istek teklifi zaten işliyorsa veya teklif kesilmişse false döner.
+pub async fn claim_quote_for_issuing(pool: &DbPool, id: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE quotes
+        SET status = 'issuing',
+            issue_attempts = issue_attempts + 1,
+            issue_error = NULL,
+            issuing_started_at = CURRENT_TIMESTAMP
+        WHERE id = $1
+          AND (status = 'completed' OR (status = 'issuing' AND issue_error IS NOT NULL))
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    
+    Ok(result.rows_affected() == 1)
+}
+
+/// `stale_before`'dan önce başlamış ve sonuçlanmamış issuing denemesini manuel kontrole alır.
+/// Deneme portala ulaşmış olabileceğinden (ör. sunucu istek ortasında yeniden başladı) otomatik tekrar denenmez.
+pub async fn mark_stale_issuing_for_review(pool: &DbPool, id: &str, stale_before: &str) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE quotes
+        SET status = 'issue_review',
+            issue_error = 'Poliçe kesimi yarıda kaldı, portalda kontrol edin'
+        WHERE id = $1 AND status = 'issuing' AND issue_error IS NULL AND issuing_started_at < $2
+        "#,
+    )
+    .bind(id)
+    .bind(stale_before)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Portala gönderilmeden başarısız olan denemeyi kaydeder; teklif issuing durumunda kalır (yeniden denenebilir)
+pub async fn mark_quote_issue_failed(pool: &DbPool, id: &str, error: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE quotes SET issue_error = $2 WHERE id = $1 AND status = 'issuing'")
+        .bind(id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Poliçe portalda kesilmiş olabilir (sonuç belirsiz ya da kaydedilemedi): otomatik tekrar denenmez, manuel kontrol gerekir
+pub async fn mark_quote_issue_review(pool: &DbPool, id: &str, note: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE quotes SET status = 'issue_review', issue_error = $2 WHERE id = $1")
+        .bind(id)
+        .bind(note)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn mark_quote_issued(pool: &DbPool, id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE quotes SET status = 'issued', issue_error = NULL WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 pub async fn list_quotes_by_user(
     pool: &DbPool,
     user_id: &str,
@@ -60,8 +141,44 @@ pub async fn list_quotes_by_user(
     .await
 }
 
-pub async fn count_quotes(pool: &DbPool) -> Result<i64, sqlx::Error> {
-    let row = sqlx::query("SELECT COUNT(*) as count FROM quotes")
+/// Aynı karşılaştırma isteğinde alınan teklifler (şirket başına bir kayıt)
+pub async fn list_quotes_by_request_id(
+    pool: &DbPool,
+    user_id: &str,
+    request_id: &str,
+) -> Result<Vec<Quote>, sqlx::Error> {
+    sqlx::query_as::<_, Quote>(
+        "SELECT * FROM quotes WHERE user_id = $1 AND request_id = $2 ORDER BY premium ASC",
+    )
+    .bind(user_id)
+    .bind(request_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Portal teklif numarasıyla arama (büyük/küçük harf duyarsız, tam eşleşme)
+pub async fn find_quotes_by_provider_quote_no(
+    pool: &DbPool,
+    user_id: &str,
+    provider_quote_no: &str,
+) -> Result<Vec<Quote>, sqlx::Error> {
+    sqlx::query_as::<_, Quote>(
+        r#"
+        SELECT * FROM quotes
+        WHERE user_id = $1 AND provider_quote_no = $2 COLLATE NOCASE
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(provider_quote_no.trim())
+    .fetch_all(pool)
+    .await
+}
+
+/// `agency`: None ise tüm acenteler
+pub async fn count_quotes(pool: &DbPool, agency: Option<&str>) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT COUNT(*) as count FROM quotes WHERE ($1 IS NULL OR agency_id = $1)")
+        .bind(agency)
         .fetch_one(pool)
         .await?;
     Ok(row.get("count"))
@@ -80,3 +197,126 @@ pub async fn list_user_quotes(pool: &DbPool, user_id: &str) -> Result<Vec<Quote>
     list_quotes_by_user(pool, user_id, 100, 0).await
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_pool, test_user};
+
+
+    #[tokio::test]
+    async fn test_find_by_provider_quote_no() {
+        let pool = test_pool().await;
+        let user = test_user(&pool, "A", "user").await;
+        let other = test_user(&pool, "B", "user").await;
+        for (owner, request_id, quote_no) in [(&user, "req-1", "T-2024-001"), (&other, "req-2", "T-2024-002")] {
+            create_quote(
+                &pool,
+                &NewQuote {
+                    user_id: &owner.id,
+                    request_id,
+                    request_data: serde_json::json!({}),
+                    provider: "Sompo",
+                    premium: 100.0,
+                    response_data: serde_json::json!({}),
+                    provider_quote_no: Some(quote_no),
+                    valid_until: Some("2024-06-15"),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let found = find_quotes_by_provider_quote_no(&pool, &user.id, " t-2024-001 ").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].valid_until.as_deref(), Some("2024-06-15"));
+
+        // Başka kullanıcının teklifi bulunmaz
+        assert!(find_quotes_by_provider_quote_no(&pool, &user.id, "T-2024-002").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_issuing_claim_is_exclusive_and_recoverable() {
+        let pool = test_pool().await;
+        let user = test_user(&pool, "A", "user").await;
+        let quote = create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &user.id,
+                request_id: "req-1",
+                request_data: serde_json::json!({}),
+                provider: "Mock",
+                premium: 100.0,
+                response_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let long_ago = "2000-01-01 00:00:00";
+
+        assert!(claim_quote_for_issuing(&pool, &quote.id).await.unwrap());
+        // Devam eden deneme ikinci kez alınamaz
+        assert!(!claim_quote_for_issuing(&pool, &quote.id).await.unwrap());
+
+        // Portala gönderilmeden alınan hata sonrası teklif issuing'de kalır ve yeniden denenebilir
+        mark_quote_issue_failed(&pool, &quote.id, "Session reddedildi").await.unwrap();
+        let failed = get_quote_by_id(&pool, &quote.id).await.unwrap().unwrap();
+        assert_eq!(failed.status, "issuing");
+        assert_eq!(failed.issue_error.as_deref(), Some("Session reddedildi"));
+        assert!(claim_quote_for_issuing(&pool, &quote.id).await.unwrap());
+
+        // Süresi dolmamış deneme manuel kontrole alınmaz
+        assert!(!mark_stale_issuing_for_review(&pool, &quote.id, long_ago).await.unwrap());
+
+        mark_quote_issued(&pool, &quote.id).await.unwrap();
+        let issued = get_quote_by_id(&pool, &quote.id).await.unwrap().unwrap();
+        assert_eq!(issued.status, "issued");
+        assert_eq!(issued.issue_attempts, 2);
+        assert!(!claim_quote_for_issuing(&pool, &quote.id).await.unwrap());
+        assert!(!mark_stale_issuing_for_review(&pool, &quote.id, "2999-01-01 00:00:00").await.unwrap());
+
+        // Sonucu belirsiz deneme manuel kontrole alınır, süre aşımında bile yeniden alınmaz
+        let ambiguous = create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &user.id,
+                request_id: "req-2",
+                request_data: serde_json::json!({}),
+                provider: "Mock",
+                premium: 100.0,
+                response_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(claim_quote_for_issuing(&pool, &ambiguous.id).await.unwrap());
+        mark_quote_issue_review(&pool, &ambiguous.id, "Zaman aşımı").await.unwrap();
+        assert!(!claim_quote_for_issuing(&pool, &ambiguous.id).await.unwrap());
+
+        // Takılı kalmış (hatasız) deneme süre aşımında yeniden alınmaz, manuel kontrole düşer
+        let stuck = create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &user.id,
+                request_id: "req-3",
+                request_data: serde_json::json!({}),
+                provider: "Mock",
+                premium: 100.0,
+                response_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(claim_quote_for_issuing(&pool, &stuck.id).await.unwrap());
+        assert!(!claim_quote_for_issuing(&pool, &stuck.id).await.unwrap());
+        assert!(mark_stale_issuing_for_review(&pool, &stuck.id, "2999-01-01 00:00:00").await.unwrap());
+        let stuck = get_quote_by_id(&pool, &stuck.id).await.unwrap().unwrap();
+        assert_eq!(stuck.status, "issue_review");
+        assert!(stuck.issue_error.is_some());
+        assert!(!claim_quote_for_issuing(&pool, &stuck.id).await.unwrap());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::db::models::{Policy, RenewalOffer};
+use crate::db::DbPool;
+use uuid::Uuid;
+
+/// Vadesi `today` ile `until` arasında biten, henüz yenileme teklifi alınmamış yürürlükteki poliçeler.
+/// `max_attempts` kez başarısız olan poliçeler atlanır.
+pub async fn find_policies_due_for_renewal(
+    pool: &DbPool,
+    today: &str,
+    until: &str,
+    max_attempts: i64,
+) -> Result<Vec<Policy>, sqlx::Error> {
+    sqlx::query_as::<_, Policy>(
+        r#"
+        SELECT * FROM policies
+        WHERE status IN ('active', 'endorsed')
+          AND renewed_by_policy_id IS NULL
+          AND renewal_quoted_at IS NULL
+          AND renewal_attempts < $3
+          AND expires_at IS NOT NULL
+          AND substr(expires_at, 1, 10) >= $1
+          AND substr(expires_at, 1, 10) <= $2
+        ORDER BY expires_at ASC
+        "#,
+    )
+    .bind(today)
+    .bind(until)
+    .bind(max_attempts)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn create_renewal_offer(
+    pool: &DbPool,
+    policy: &Policy,
+    quote_id: &str,
+    provider: &str,
+    premium: f64,
+) -> Result<RenewalOffer, sqlx::Error> {
+    let premium_change = ((premium - policy.premium) * 100.0).round() / 100.0;
+
+    sqlx::query_as::<_, RenewalOffer>(
+        r#"
+        INSERT INTO renewal_offers (id, policy_id, quote_id, user_id, provider, premium, premium_change)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&policy.id)
+    .bind(quote_id)
+    .bind(&policy.user_id)
+    .bind(provider)
+    .bind(premium)
+    .bind(premium_change)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn mark_renewal_quoted(pool: &DbPool, policy_id: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE policies
+        SET renewal_quoted_at = CURRENT_TIMESTAMP,
+            renewal_attempts = renewal_attempts + 1,
+            renewal_error = NULL
+        WHERE id = $1
+        "#,
+    )
+    .bind(policy_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Başarısız deneme: poliçe bir sonraki taramada yeniden denenir
+pub async fn mark_renewal_failed(pool: &DbPool, policy_id: &str, error: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE policies SET renewal_attempts = renewal_attempts + 1, renewal_error = $2 WHERE id = $1")
+        .bind(policy_id)
+        .bind(error)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn list_offers_for_policy(pool: &DbPool, policy_id: &str) -> Result<Vec<RenewalOffer>, sqlx::Error> {
+    sqlx::query_as::<_, RenewalOffer>(
+        "SELECT * FROM renewal_offers WHERE policy_id = $1 ORDER BY premium ASC",
+    )
+    .bind(policy_id)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_offers_by_user(
+    pool: &DbPool,
+    user_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<RenewalOffer>, sqlx::Error> {
+    sqlx::query_as::<_, RenewalOffer>(
+        r#"
+        SELECT * FROM renewal_offers
+        WHERE user_id = $1
+        ORDER BY created_at DESC, premium ASC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn get_offer_by_quote_id(pool: &DbPool, quote_id: &str) -> Result<Option<RenewalOffer>, sqlx::Error> {
+    sqlx::query_as::<_, RenewalOffer>("SELECT * FROM renewal_offers WHERE quote_id = $1")
+        .bind(quote_id)
+        .fetch_optional(pool)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::quotes::{self, NewQuote};
+    use crate::db::policies::{self, NewPolicy};
+    use crate::db::{test_pool, test_user};
+
+
+    #[tokio::test]
+    async fn test_due_policies_and_offers() {
+        let pool = test_pool().await;
+        let user = test_user(&pool, "A", "user").await;
+        let mut created = Vec::new();
+        for (number, expires_at) in [("P-1", "2024-06-10"), ("P-2", "2024-09-01"), ("P-3", "2024-05-01")] {
+            let policy = policies::create_policy(
+                &pool,
+                &NewPolicy {
+                    user_id: &user.id,
+                    policy_number: number,
+                    provider: "Mock",
+                    product_type: "trafik",
+                    premium: 1000.0,
+                    policy_data: serde_json::json!({}),
+                    expires_at: Some(expires_at),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+            created.push(policy);
+        }
+
+        // Yalnızca pencere içindeki poliçe
+        let due = find_policies_due_for_renewal(&pool, "2024-06-01", "2024-07-01", 3).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].policy_number, "P-1");
+
+        // Başarısız denemeler sınırı aşınca atlanır
+        for _ in 0..3 {
+            mark_renewal_failed(&pool, &due[0].id, "Teklif alınamadı").await.unwrap();
+        }
+        assert!(find_policies_due_for_renewal(&pool, "2024-06-01", "2024-07-01", 3).await.unwrap().is_empty());
+        assert_eq!(find_policies_due_for_renewal(&pool, "2024-06-01", "2024-07-01", 5).await.unwrap().len(), 1);
+
+        let quote = quotes::create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &user.id,
+                request_id: "req-1",
+                request_data: serde_json::json!({}),
+                provider: "Mock",
+                premium: 1100.0,
+                response_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let offer = create_renewal_offer(&pool, &created[0], &quote.id, "Mock", 1100.0).await.unwrap();
+        assert_eq!(offer.premium_change, 100.0);
+        mark_renewal_quoted(&pool, &created[0].id).await.unwrap();
+
+        assert!(find_policies_due_for_renewal(&pool, "2024-06-01", "2024-07-01", 5).await.unwrap().is_empty());
+        assert_eq!(get_offer_by_quote_id(&pool, &quote.id).await.unwrap().unwrap().policy_id, created[0].id);
+        assert_eq!(list_offers_by_user(&pool, &user.id, 10, 0).await.unwrap().len(), 1);
+    }
+}
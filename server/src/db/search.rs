@@ -0,0 +1,364 @@
+use crate::db::models::{Policy, Quote};
+use crate::db::DbPool;
+use data_encoding::BASE64URL_NOPAD;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqliteRow;
+use sqlx::FromRow;
+
+/// Arama filtreleri; None olan alanlar uygulanmaz.
+/// `from` / `to` YYYY-MM-DD, `created_at` üzerinden ve her iki uç dahil.
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Super-admin aramasında None: tüm acenteler
+    pub agency_id: Option<String>,
+    /// Admin aramasında None: tüm kullanıcılar
+    pub user_id: Option<String>,
+    pub provider: Option<String>,
+    pub product_type: Option<String>,
+    pub status: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub min_premium: Option<f64>,
+    pub max_premium: Option<f64>,
+    /// Boşluksuz büyük harf (34ABC123)
+    pub plate: Option<String>,
+    /// TCKN / VKN
+    pub identity_no: Option<String>,
+    /// fold_turkish ile sadeleştirilmiş ad parçası
+    pub customer_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortField {
+    #[serde(rename = "createdAt")]
+    CreatedAt,
+    #[serde(rename = "premium")]
+    Premium,
+}
+
+impl SortField {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "createdAt" | "created_at" => Some(SortField::CreatedAt),
+            "premium" => Some(SortField::Premium),
+            _ => None,
+        }
+    }
+
+    fn column(&self) -> &'static str {
+        match self {
+            SortField::CreatedAt => "created_at",
+            SortField::Premium => "premium",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "asc" => Some(SortDirection::Asc),
+            "desc" => Some(SortDirection::Desc),
+            _ => None,
+        }
+    }
+
+    fn sql(&self) -> (&'static str, &'static str) {
+        match self {
+            SortDirection::Asc => ("ASC", ">"),
+            SortDirection::Desc => ("DESC", "<"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchSort {
+    pub field: SortField,
+    pub direction: SortDirection,
+}
+
+impl Default for SearchSort {
+    fn default() -> Self {
+        SearchSort { field: SortField::CreatedAt, direction: SortDirection::Desc }
+    }
+}
+
+/// Son satırın sıralama değeri ve id'si. Aynı sıralamayla bir sonraki sayfayı getirir;
+/// araya yeni kayıt girse de satır atlanmaz / tekrarlanmaz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    #[serde(rename = "f")]
+    pub field: SortField,
+    #[serde(rename = "d")]
+    pub direction: SortDirection,
+    #[serde(rename = "c", default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(rename = "p", default, skip_serializing_if = "Option::is_none")]
+    pub premium: Option<f64>,
+    pub id: String,
+}
+
+impl Cursor {
+    fn after(sort: SearchSort, id: &str, created_at: &str, premium: f64) -> Self {
+        Cursor {
+            field: sort.field,
+            direction: sort.direction,
+            created_at: (sort.field == SortField::CreatedAt).then(|| created_at.to_string()),
+            premium: (sort.field == SortField::Premium).then_some(premium),
+            id: id.to_string(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        BASE64URL_NOPAD.encode(serde_json::to_string(self).unwrap_or_default().as_bytes())
+    }
+
+    /// Bozuk imleç veya başka bir sıralamaya ait imleç None
+    pub fn decode(value: &str, sort: SearchSort) -> Option<Self> {
+        let bytes = BASE64URL_NOPAD.decode(value.trim().as_bytes()).ok()?;
+        let cursor: Cursor = serde_json::from_slice(&bytes).ok()?;
+        let has_value = match cursor.field {
+            SortField::CreatedAt => cursor.created_at.is_some(),
+            SortField::Premium => cursor.premium.is_some(),
+        };
+        (cursor.field == sort.field && cursor.direction == sort.direction && has_value).then_some(cursor)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// None: son sayfa
+    pub next_cursor: Option<String>,
+}
+
+/// `limit + 1` satır okunur; fazlası varsa son satırdan imleç üretilir
+fn into_page<T>(mut rows: Vec<T>, limit: i64, sort: SearchSort, key: impl Fn(&T) -> (&str, &str, f64)) -> Page<T> {
+    let limit = usize::try_from(limit).unwrap_or_default();
+    let next_cursor = if rows.len() > limit {
+        rows.truncate(limit);
+        rows.last().map(|row| {
+            let (id, created_at, premium) = key(row);
+            Cursor::after(sort, id, created_at, premium).encode()
+        })
+    } else {
+        None
+    };
+    Page { items: rows, next_cursor }
+}
+
+/// Filtre ve imleç koşulları; `t` teklif veya poliçe tablosunun takma adı.
+/// Sıralama sütunu ve yönü sabit listeden gelir, değerler bind edilir.
+fn search_sql(table: &str, product_type_expr: &str, sort: SearchSort) -> String {
+    let column = sort.field.column();
+    let (direction, op) = sort.direction.sql();
+    // $12: imleçteki created_at, $13: imleçteki prim
+    let cursor_param = match sort.field {
+        SortField::CreatedAt => "$12",
+        SortField::Premium => "$13",
+    };
+    format!(
+        r#"
+        SELECT t.* FROM {table} t
+        LEFT JOIN customers c ON c.id = t.customer_id
+        LEFT JOIN vehicles v ON v.id = t.vehicle_id
+        WHERE ($1 IS NULL OR t.user_id = $1)
+          AND ($2 IS NULL OR t.provider = $2 COLLATE NOCASE)
+          AND ($3 IS NULL OR {product_type_expr} = $3)
+          AND ($4 IS NULL OR t.status = $4)
+          AND ($5 IS NULL OR t.created_at >= $5)
+          AND ($6 IS NULL OR t.created_at < date($6, '+1 day'))
+          AND ($7 IS NULL OR t.premium >= $7)
+          AND ($8 IS NULL OR t.premium <= $8)
+          AND ($9 IS NULL OR v.plate = $9)
+          AND ($10 IS NULL OR c.identity_no = $10)
+          AND ($11 IS NULL OR c.name_key LIKE '%' || $11 || '%')
+          AND ($16 IS NULL OR t.agency_id = $16)
+          AND ($14 IS NULL OR (t.{column}, t.id) {op} ({cursor_param}, $14))
+        ORDER BY t.{column} {direction}, t.id {direction}
+        LIMIT $15
+        "#
+    )
+}
+
+async fn fetch_rows<T>(
+    pool: &DbPool,
+    sql: &str,
+    filter: &SearchFilter,
+    cursor: Option<&Cursor>,
+    limit: i64,
+) -> Result<Vec<T>, sqlx::Error>
+where
+    T: for<'r> FromRow<'r, SqliteRow> + Send + Unpin,
+{
+    sqlx::query_as::<_, T>(sql)
+        .bind(filter.user_id.as_deref())
+        .bind(filter.provider.as_deref())
+        .bind(filter.product_type.as_deref())
+        .bind(filter.status.as_deref())
+        .bind(filter.from.as_deref())
+        .bind(filter.to.as_deref())
+        .bind(filter.min_premium)
+        .bind(filter.max_premium)
+        .bind(filter.plate.as_deref())
+        .bind(filter.identity_no.as_deref())
+        .bind(filter.customer_name.as_deref())
+        .bind(cursor.and_then(|c| c.created_at.as_deref()))
+        .bind(cursor.and_then(|c| c.premium))
+        .bind(cursor.map(|c| c.id.as_str()))
+        .bind(limit + 1)
+        .bind(filter.agency_id.as_deref())
+        .fetch_all(pool)
+        .await
+}
+
+pub async fn search_quotes(
+    pool: &DbPool,
+    filter: &SearchFilter,
+    sort: SearchSort,
+    cursor: Option<&Cursor>,
+    limit: i64,
+) -> Result<Page<Quote>, sqlx::Error> {
+    let sql = search_sql("quotes", "json_extract(t.request_data, '$.coverage.productType')", sort);
+    let rows: Vec<Quote> = fetch_rows(pool, &sql, filter, cursor, limit).await?;
+    Ok(into_page(rows, limit, sort, |q| (&q.id, &q.created_at, q.premium)))
+}
+
+pub async fn search_policies(
+    pool: &DbPool,
+    filter: &SearchFilter,
+    sort: SearchSort,
+    cursor: Option<&Cursor>,
+    limit: i64,
+) -> Result<Page<Policy>, sqlx::Error> {
+    let sql = search_sql("policies", "t.product_type", sort);
+    let rows: Vec<Policy> = fetch_rows(pool, &sql, filter, cursor, limit).await?;
+    Ok(into_page(rows, limit, sort, |p| (&p.id, &p.created_at, p.premium)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::agencies::DEFAULT_AGENCY_ID;
+    use crate::db::customers::{self, CustomerInput};
+    use crate::db::vehicles::{self, VehicleInput};
+    use crate::db::quotes::{self, NewQuote};
+    use crate::db::{test_pool, test_user};
+
+    #[tokio::test]
+    async fn test_search_quotes_filters_and_cursor() {
+        let pool = test_pool().await;
+
+        let agent = test_user(&pool, "A", "agent").await;
+        let other = test_user(&pool, "B", "agent").await;
+        let customer = customers::upsert_customer(
+            &pool,
+            &CustomerInput {
+                identity_no: "12345678901".to_string(),
+                identity_type: "tckn".to_string(),
+                name: "Şükrü Öztürk".to_string(),
+                name_key: "sukru ozturk".to_string(),
+                ..Default::default()
+            },
+            DEFAULT_AGENCY_ID,
+            Some(&agent.id),
+        )
+        .await
+        .unwrap();
+        let vehicle = vehicles::upsert_vehicle(
+            &pool,
+            &VehicleInput {
+                customer_id: customer.id.clone(),
+                plate: "34ABC123".to_string(),
+                brand: "Fiat".to_string(),
+                model: "Egea".to_string(),
+                year: 2020,
+                usage: "hususi".to_string(),
+                category: "otomobil".to_string(),
+                ..Default::default()
+            },
+            Some(&agent.id),
+        )
+        .await
+        .unwrap();
+
+        let request = serde_json::json!({ "coverage": { "productType": "kasko" } });
+        for (i, premium) in [1000.0, 2500.0, 4000.0, 5500.0].into_iter().enumerate() {
+            quotes::create_quote(
+                &pool,
+                &NewQuote {
+                    user_id: &agent.id,
+                    request_id: &format!("req-{}", i),
+                    request_data: request.clone(),
+                    provider: if i % 2 == 0 { "Sompo" } else { "Mock" },
+                    premium,
+                    response_data: serde_json::json!({}),
+                    customer_id: Some(&customer.id),
+                    vehicle_id: Some(&vehicle.id),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+        quotes::create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &other.id,
+                request_id: "req-x",
+                request_data: serde_json::json!({ "coverage": { "productType": "trafik" } }),
+                provider: "Sompo",
+                premium: 3000.0,
+                response_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let own = SearchFilter { user_id: Some(agent.id.clone()), ..Default::default() };
+        let sort = SearchSort { field: SortField::Premium, direction: SortDirection::Desc };
+
+        let first = search_quotes(&pool, &own, sort, None, 3).await.unwrap();
+        let premiums: Vec<f64> = first.items.iter().map(|q| q.premium).collect();
+        assert_eq!(premiums, vec![5500.0, 4000.0, 2500.0]);
+        let cursor = Cursor::decode(first.next_cursor.as_deref().unwrap(), sort).unwrap();
+        assert!(Cursor::decode(first.next_cursor.as_deref().unwrap(), SearchSort::default()).is_none());
+
+        let second = search_quotes(&pool, &own, sort, Some(&cursor), 3).await.unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].premium, 1000.0);
+        assert!(second.next_cursor.is_none());
+
+        let filtered = SearchFilter {
+            provider: Some("sompo".to_string()),
+            min_premium: Some(2000.0),
+            plate: Some("34ABC123".to_string()),
+            identity_no: Some("12345678901".to_string()),
+            customer_name: Some("ozturk".to_string()),
+            product_type: Some("kasko".to_string()),
+            ..own.clone()
+        };
+        let page = search_quotes(&pool, &filtered, SearchSort::default(), None, 10).await.unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].premium, 4000.0);
+
+        // Admin: kullanıcı filtresi yok
+        let all = search_quotes(&pool, &SearchFilter::default(), SearchSort::default(), None, 10).await.unwrap();
+        assert_eq!(all.items.len(), 5);
+        let trafik = SearchFilter { product_type: Some("trafik".to_string()), ..Default::default() };
+        assert_eq!(search_quotes(&pool, &trafik, sort, None, 10).await.unwrap().items.len(), 1);
+
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let dated = SearchFilter { from: Some(today.clone()), to: Some(today), ..own };
+        assert_eq!(search_quotes(&pool, &dated, sort, None, 10).await.unwrap().items.len(), 4);
+        assert!(search_policies(&pool, &SearchFilter::default(), sort, None, 10).await.unwrap().items.is_empty());
+    }
+}
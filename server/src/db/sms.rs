@@ -0,0 +1,179 @@
+use crate::db::models::{SmsMessage, SmsOptOut};
+use crate::db::DbPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct NewSmsMessage<'a> {
+    pub user_id: Option<&'a str>,
+    pub phone: &'a str,
+    pub event: &'a str,
+    pub message: &'a str,
+    pub status: &'a str,
+    pub provider: Option<&'a str>,
+    pub provider_message_id: Option<&'a str>,
+    pub error: Option<&'a str>,
+}
+
+pub async fn record_sms(pool: &DbPool, sms: &NewSmsMessage<'_>) -> Result<SmsMessage, sqlx::Error> {
+    sqlx::query_as::<_, SmsMessage>(
+        r#"
+        INSERT INTO sms_messages (id, user_id, phone, event, message, status, provider, provider_message_id, error)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(sms.user_id)
+    .bind(sms.phone)
+    .bind(sms.event)
+    .bind(sms.message)
+    .bind(sms.status)
+    .bind(sms.provider)
+    .bind(sms.provider_message_id)
+    .bind(sms.error)
+    .fetch_one(pool)
+    .await
+}
+
+pub async fn list_sms(
+    pool: &DbPool,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SmsMessage>, sqlx::Error> {
+    sqlx::query_as::<_, SmsMessage>(
+        r#"
+        SELECT * FROM sms_messages
+        WHERE ($1 IS NULL OR status = $1)
+        ORDER BY created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// Numara acentenin listesinde zaten varsa mevcut kayıt korunur
+pub async fn add_opt_out(
+    pool: &DbPool,
+    agency_id: &str,
+    phone: &str,
+    reason: Option<&str>,
+    created_by: Option<&str>,
+) -> Result<SmsOptOut, sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO sms_opt_outs (agency_id, phone, reason, created_by) VALUES ($1, $2, $3, $4)")
+        .bind(agency_id)
+        .bind(phone)
+        .bind(reason)
+        .bind(created_by)
+        .execute(pool)
+        .await?;
+
+    sqlx::query_as::<_, SmsOptOut>("SELECT * FROM sms_opt_outs WHERE agency_id = $1 AND phone = $2")
+        .bind(agency_id)
+        .bind(phone)
+        .fetch_one(pool)
+        .await
+}
+
+/// `agency` None ise (super-admin) numara tüm acentelerin listesinden çıkarılır
+pub async fn remove_opt_out(pool: &DbPool, phone: &str, agency: Option<&str>) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM sms_opt_outs WHERE phone = $1 AND ($2 IS NULL OR agency_id = $2)")
+        .bind(phone)
+        .bind(agency)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// SMS'i gönderen kullanıcının acentesinin listesine bakılır; kullanıcı yoksa herhangi bir acentede listede olması yeterlidir
+pub async fn is_opted_out(pool: &DbPool, phone: &str, user_id: Option<&str>) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM sms_opt_outs
+        WHERE phone = $1
+          AND ($2 IS NULL OR agency_id = (SELECT agency_id FROM users WHERE id = $2))
+        "#,
+    )
+    .bind(phone)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count > 0)
+}
+
+pub async fn list_opt_outs(
+    pool: &DbPool,
+    agency: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<SmsOptOut>, sqlx::Error> {
+    sqlx::query_as::<_, SmsOptOut>(
+        r#"
+        SELECT * FROM sms_opt_outs
+        WHERE ($1 IS NULL OR agency_id = $1)
+        ORDER BY created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(agency)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::agencies::{self, DEFAULT_AGENCY_ID};
+    use crate::db::{test_pool, test_user, users};
+
+    #[tokio::test]
+    async fn test_opt_out_and_message_log() {
+        let pool = test_pool().await;
+
+        let agency = agencies::create_agency(&pool, "Kadıköy Acente").await.unwrap();
+        let user = test_user(&pool, "A", "agent").await;
+        let other = users::create_user(&pool, "b@example.com", "hash", "B", "agent", &agency.id).await.unwrap();
+
+        assert!(!is_opted_out(&pool, "905551234567", Some(&user.id)).await.unwrap());
+        add_opt_out(&pool, DEFAULT_AGENCY_ID, "905551234567", Some("müşteri talebi"), None).await.unwrap();
+        // İkinci ekleme ilk kaydı ezmez
+        let existing = add_opt_out(&pool, DEFAULT_AGENCY_ID, "905551234567", None, None).await.unwrap();
+        assert_eq!(existing.reason.as_deref(), Some("müşteri talebi"));
+        assert!(is_opted_out(&pool, "905551234567", Some(&user.id)).await.unwrap());
+        assert!(is_opted_out(&pool, "905551234567", None).await.unwrap());
+        assert_eq!(list_opt_outs(&pool, Some(DEFAULT_AGENCY_ID), 10, 0).await.unwrap().len(), 1);
+
+        // Başka acentenin listesi ayrıdır
+        assert!(!is_opted_out(&pool, "905551234567", Some(&other.id)).await.unwrap());
+        assert!(list_opt_outs(&pool, Some(&agency.id), 10, 0).await.unwrap().is_empty());
+        assert!(!remove_opt_out(&pool, "905551234567", Some(&agency.id)).await.unwrap());
+
+        assert!(remove_opt_out(&pool, "905551234567", Some(DEFAULT_AGENCY_ID)).await.unwrap());
+        assert!(!remove_opt_out(&pool, "905551234567", None).await.unwrap());
+        assert!(!is_opted_out(&pool, "905551234567", Some(&user.id)).await.unwrap());
+
+        record_sms(
+            &pool,
+            &NewSmsMessage {
+                user_id: None,
+                phone: "905551234567",
+                event: "quote_ready",
+                message: "Teklifiniz hazır",
+                status: "sent",
+                provider: Some("log"),
+                provider_message_id: Some("abc"),
+                error: None,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(list_sms(&pool, Some("sent"), 10, 0).await.unwrap().len(), 1);
+        assert!(list_sms(&pool, Some("failed"), 10, 0).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::db::models::{ProviderStatement, StatementItem};
+use crate::db::DbPool;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Mutabakatta kullanılan poliçe alanları
+#[derive(Debug, Clone, FromRow)]
+pub struct ReconciliationPolicy {
+    pub id: String,
+    pub policy_number: String,
+    pub commission: Option<f64>,
+    pub status: String,
+    pub created_at: String,
+}
+
+/// Acentenin şirkette kesilmiş tüm poliçeleri (pending_issue hariç)
+pub async fn list_policies_for_reconciliation(
+    pool: &DbPool,
+    agency_id: &str,
+    provider: &str,
+) -> Result<Vec<ReconciliationPolicy>, sqlx::Error> {
+    sqlx::query_as::<_, ReconciliationPolicy>(
+        r#"
+        SELECT id, policy_number, commission, status, created_at
+        FROM policies
+        WHERE agency_id = $1 AND provider = $2 COLLATE NOCASE AND status != 'pending_issue'
+        ORDER BY created_at
+        "#,
+    )
+    .bind(agency_id)
+    .bind(provider)
+    .fetch_all(pool)
+    .await
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewStatementItem {
+    pub policy_id: Option<String>,
+    pub policy_number: String,
+    pub status: &'static str,
+    pub statement_commission: Option<f64>,
+    pub our_commission: Option<f64>,
+    pub difference: Option<f64>,
+    pub statement_premium: Option<f64>,
+    pub source_rows: Option<String>,
+}
+
+/// Yüklenen ekstrenin başlık bilgileri
+#[derive(Debug)]
+pub struct NewStatement<'a> {
+    pub agency_id: &'a str,
+    pub provider: &'a str,
+    /// YYYY-MM
+    pub period: &'a str,
+    pub file_name: Option<&'a str>,
+    pub uploaded_by: Option<&'a str>,
+}
+
+/// Ekstreyi, sonuç satırlarını ve özet sayılarını tek transaction'da kaydeder
+pub async fn create_statement(
+    pool: &DbPool,
+    statement: &NewStatement<'_>,
+    items: &[NewStatementItem],
+) -> Result<ProviderStatement, sqlx::Error> {
+    let count = |status: &str| items.iter().filter(|i| i.status == status).count() as i64;
+    let statement_total: f64 = items.iter().filter_map(|i| i.statement_commission).sum();
+    let our_total: f64 = items.iter().filter_map(|i| i.our_commission).sum();
+    let id = Uuid::new_v4().to_string();
+
+    let mut tx = pool.begin().await?;
+
+    let created = sqlx::query_as::<_, ProviderStatement>(
+        r#"
+        INSERT INTO provider_statements
+        (id, agency_id, provider, period, file_name, uploaded_by, line_count, matched_count, mismatch_count,
+         missing_ours_count, missing_theirs_count, statement_commission_total, our_commission_total)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+        RETURNING *
+        "#,
+    )
+    .bind(&id)
+    .bind(statement.agency_id)
+    .bind(statement.provider)
+    .bind(statement.period)
+    .bind(statement.file_name)
+    .bind(statement.uploaded_by)
+    .bind(items.iter().filter(|i| i.statement_commission.is_some()).count() as i64)
+    .bind(count("matched"))
+    .bind(count("amount_mismatch"))
+    .bind(count("missing_ours"))
+    .bind(count("missing_theirs"))
+    .bind((statement_total * 100.0).round() / 100.0)
+    .bind((our_total * 100.0).round() / 100.0)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for item in items {
+        sqlx::query(
+            r#"
+            INSERT INTO statement_items
+            (id, statement_id, policy_id, policy_number, status, statement_commission, our_commission,
+             difference, statement_premium, source_rows)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&id)
+        .bind(&item.policy_id)
+        .bind(&item.policy_number)
+        .bind(item.status)
+        .bind(item.statement_commission)
+        .bind(item.our_commission)
+        .bind(item.difference)
+        .bind(item.statement_premium)
+        .bind(&item.source_rows)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(created)
+}
+
+pub async fn get_statement(pool: &DbPool, id: &str) -> Result<Option<ProviderStatement>, sqlx::Error> {
+    sqlx::query_as::<_, ProviderStatement>("SELECT * FROM provider_statements WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+}
+
+pub async fn list_statements(
+    pool: &DbPool,
+    provider: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<ProviderStatement>, sqlx::Error> {
+    sqlx::query_as::<_, ProviderStatement>(
+        r#"
+        SELECT * FROM provider_statements
+        WHERE ($1 IS NULL OR provider = $1 COLLATE NOCASE)
+        ORDER BY period DESC, created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(provider)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// `open_only`: eşleşmeyen ve henüz çözülmemiş kayıtlar (takip listesi)
+pub async fn list_items(
+    pool: &DbPool,
+    statement_id: Option<&str>,
+    status: Option<&str>,
+    open_only: bool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<StatementItem>, sqlx::Error> {
+    sqlx::query_as::<_, StatementItem>(
+        r#"
+        SELECT * FROM statement_items
+        WHERE ($1 IS NULL OR statement_id = $1)
+          AND ($2 IS NULL OR status = $2)
+          AND ($3 = 0 OR (status != 'matched' AND resolved_at IS NULL))
+        ORDER BY created_at DESC, policy_number
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(statement_id)
+    .bind(status)
+    .bind(open_only)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// Farkı çözüldü olarak işaretler; kayıt yoksa veya zaten çözüldüyse None
+pub async fn resolve_item(
+    pool: &DbPool,
+    id: &str,
+    note: Option<&str>,
+    resolved_by: &str,
+) -> Result<Option<StatementItem>, sqlx::Error> {
+    sqlx::query_as::<_, StatementItem>(
+        r#"
+        UPDATE statement_items
+        SET resolution_note = $2, resolved_by = $3, resolved_at = CURRENT_TIMESTAMP
+        WHERE id = $1 AND status != 'matched' AND resolved_at IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(note)
+    .bind(resolved_by)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::policies::{self, NewPolicy};
+    use crate::db::agencies::{self, DEFAULT_AGENCY_ID};
+    use crate::db::{test_pool, test_user};
+
+    #[tokio::test]
+    async fn test_statement_items_follow_up() {
+        let pool = test_pool().await;
+
+        let user = test_user(&pool, "Admin", "admin").await;
+        let policy = policies::create_policy(
+            &pool,
+            &NewPolicy {
+                user_id: &user.id,
+                policy_number: "SMP-1",
+                provider: "Sompo",
+                product_type: "trafik",
+                premium: 1000.0,
+                commission: Some(100.0),
+                policy_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let ours = list_policies_for_reconciliation(&pool, DEFAULT_AGENCY_ID, "sompo").await.unwrap();
+        assert_eq!(ours.len(), 1);
+        // Başka acentenin ekstresi bu poliçeyle eşleşmez
+        let other = agencies::create_agency(&pool, "Kadıköy Acente").await.unwrap();
+        assert!(list_policies_for_reconciliation(&pool, &other.id, "sompo").await.unwrap().is_empty());
+
+        let item = |number: &str, status: &'static str, theirs: Option<f64>, our: Option<f64>| NewStatementItem {
+            policy_id: our.map(|_| policy.id.clone()),
+            policy_number: number.to_string(),
+            status,
+            statement_commission: theirs,
+            our_commission: our,
+            difference: None,
+            statement_premium: None,
+            source_rows: None,
+        };
+        let items = vec![
+            item("SMP-1", "amount_mismatch", Some(90.0), Some(100.0)),
+            item("SMP-9", "missing_ours", Some(50.0), None),
+        ];
+        let statement = create_statement(
+            &pool,
+            &NewStatement {
+                agency_id: DEFAULT_AGENCY_ID,
+                provider: "Sompo",
+                period: "2024-05",
+                file_name: Some("mayis.csv"),
+                uploaded_by: Some(&user.id),
+            },
+            &items,
+        )
+        .await
+        .unwrap();
+        assert_eq!(statement.agency_id, DEFAULT_AGENCY_ID);
+        assert_eq!(statement.line_count, 2);
+        assert_eq!(statement.mismatch_count, 1);
+        assert_eq!(statement.missing_ours_count, 1);
+        assert_eq!(statement.statement_commission_total, 140.0);
+        assert_eq!(statement.our_commission_total, 100.0);
+
+        let open = list_items(&pool, None, None, true, 50, 0).await.unwrap();
+        assert_eq!(open.len(), 2);
+        let resolved = resolve_item(&pool, &open[0].id, Some("Şirkete itiraz edildi"), &user.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(resolved.resolved_at.is_some());
+        assert!(resolve_item(&pool, &open[0].id, None, &user.id).await.unwrap().is_none());
+        assert_eq!(list_items(&pool, Some(&statement.id), None, true, 50, 0).await.unwrap().len(), 1);
+    }
+}
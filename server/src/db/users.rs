@@ -9,13 +9,14 @@ pub async fn create_user(
     password_hash: &str,
     name: &str,
     role: &str,
+    agency_id: &str,
 ) -> Result<User, sqlx::Error> {
     let id = Uuid::new_v4();
     
     sqlx::query_as::<_, User>(
         r#"
-        INSERT INTO users (id, email, password_hash, name, role)
-        VALUES ($1, $2, $3, $4, $5)
+        INSERT INTO users (id, email, password_hash, name, role, agency_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
         RETURNING *
         "#,
     )
@@ -24,6 +25,7 @@ pub async fn create_user(
     .bind(password_hash)
     .bind(name)
     .bind(role)
+    .bind(agency_id)
     .fetch_one(pool)
     .await
 }
@@ -35,6 +37,20 @@ pub async fn get_user_by_id(pool: &DbPool, id: &str) -> Result<Option<User>, sql
         .await
 }
 
+/// Aktif kullanıcı (acentesi de aktif olmalı); token doğrulamasında güncel rol ve acente için okunur
+pub async fn get_active_user(pool: &DbPool, id: &str) -> Result<Option<User>, sqlx::Error> {
+    sqlx::query_as::<_, User>(
+        r#"
+        SELECT users.* FROM users
+        JOIN agencies ON agencies.id = users.agency_id
+        WHERE users.id = $1 AND users.is_active = 1 AND agencies.is_active = 1
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+}
+
 pub async fn get_user_by_email(pool: &DbPool, email: &str) -> Result<Option<User>, sqlx::Error> {
     sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
         .bind(email)
@@ -42,14 +58,17 @@ pub async fn get_user_by_email(pool: &DbPool, email: &str) -> Result<Option<User
         .await
 }
 
-pub async fn list_users(pool: &DbPool, limit: i64, offset: i64) -> Result<Vec<User>, sqlx::Error> {
+/// `agency`: None ise tüm acenteler
+pub async fn list_users(pool: &DbPool, agency: Option<&str>, limit: i64, offset: i64) -> Result<Vec<User>, sqlx::Error> {
     sqlx::query_as::<_, User>(
         r#"
         SELECT * FROM users
+        WHERE ($1 IS NULL OR agency_id = $1)
         ORDER BY created_at DESC
-        LIMIT $1 OFFSET $2
+        LIMIT $2 OFFSET $3
         "#,
     )
+    .bind(agency)
     .bind(limit)
     .bind(offset)
     .fetch_all(pool)
@@ -64,8 +83,9 @@ pub async fn update_last_login(pool: &DbPool, user_id: &str) -> Result<(), sqlx:
     Ok(())
 }
 
-pub async fn count_users(pool: &DbPool) -> Result<i64, sqlx::Error> {
-    let row = sqlx::query("SELECT COUNT(*) as count FROM users")
+pub async fn count_users(pool: &DbPool, agency: Option<&str>) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query("SELECT COUNT(*) as count FROM users WHERE ($1 IS NULL OR agency_id = $1)")
+        .bind(agency)
         .fetch_one(pool)
         .await?;
     Ok(row.get("count"))
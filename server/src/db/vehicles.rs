@@ -0,0 +1,280 @@
+use crate::db::models::{Policy, Quote, Vehicle};
+use crate::db::DbPool;
+use uuid::Uuid;
+
+/// Normalize edilmiş araç bilgileri (bkz. services::vehicle::vehicle_input)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VehicleInput {
+    pub customer_id: String,
+    pub plate: String,
+    pub vin: Option<String>,
+    pub brand: String,
+    pub model: String,
+    pub year: i64,
+    pub usage: String,
+    pub category: String,
+    pub registration_serial: Option<String>,
+    pub registration_no: Option<String>,
+    pub engine_no: Option<String>,
+    pub registration_date: Option<String>,
+}
+
+/// Sigortalı + plaka ile ekler veya günceller. Marka/model/yıl/kullanım son teklifteki gibi olur;
+/// şasi, ruhsat, motor no ve tescil tarihi yalnızca yeni değer geldiyse değişir.
+pub async fn upsert_vehicle(
+    pool: &DbPool,
+    input: &VehicleInput,
+    created_by: Option<&str>,
+) -> Result<Vehicle, sqlx::Error> {
+    sqlx::query_as::<_, Vehicle>(
+        r#"
+        INSERT INTO vehicles
+        (id, customer_id, plate, vin, brand, model, year, usage, category,
+         registration_serial, registration_no, engine_no, registration_date, created_by, last_quote_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, CURRENT_TIMESTAMP)
+        ON CONFLICT(customer_id, plate) DO UPDATE SET
+            vin = COALESCE(excluded.vin, vehicles.vin),
+            brand = excluded.brand,
+            model = excluded.model,
+            year = excluded.year,
+            usage = excluded.usage,
+            category = excluded.category,
+            registration_serial = COALESCE(excluded.registration_serial, vehicles.registration_serial),
+            registration_no = COALESCE(excluded.registration_no, vehicles.registration_no),
+            engine_no = COALESCE(excluded.engine_no, vehicles.engine_no),
+            registration_date = COALESCE(excluded.registration_date, vehicles.registration_date),
+            updated_at = CURRENT_TIMESTAMP,
+            last_quote_at = CURRENT_TIMESTAMP
+        RETURNING *
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&input.customer_id)
+    .bind(&input.plate)
+    .bind(&input.vin)
+    .bind(&input.brand)
+    .bind(&input.model)
+    .bind(input.year)
+    .bind(&input.usage)
+    .bind(&input.category)
+    .bind(&input.registration_serial)
+    .bind(&input.registration_no)
+    .bind(&input.engine_no)
+    .bind(&input.registration_date)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// `owner` verilirse yalnızca o kullanıcının teklif aldığı sigortalıların araçları görünür (admin için None).
+/// `agency` verilirse yalnızca o acentenin sigortalılarının araçları (super-admin için None).
+pub async fn get_vehicle(
+    pool: &DbPool,
+    id: &str,
+    owner: Option<&str>,
+    agency: Option<&str>,
+) -> Result<Option<Vehicle>, sqlx::Error> {
+    sqlx::query_as::<_, Vehicle>(
+        r#"
+        SELECT v.* FROM vehicles v
+        JOIN customers c ON c.id = v.customer_id
+        WHERE v.id = $1
+          AND ($2 IS NULL OR EXISTS (SELECT 1 FROM quotes q WHERE q.customer_id = v.customer_id AND q.user_id = $2))
+          AND ($3 IS NULL OR c.agency_id = $3)
+        "#,
+    )
+    .bind(id)
+    .bind(owner)
+    .bind(agency)
+    .fetch_optional(pool)
+    .await
+}
+
+pub async fn list_customer_vehicles(pool: &DbPool, customer_id: &str) -> Result<Vec<Vehicle>, sqlx::Error> {
+    sqlx::query_as::<_, Vehicle>(
+        r#"
+        SELECT * FROM vehicles
+        WHERE customer_id = $1
+        ORDER BY COALESCE(last_quote_at, created_at) DESC
+        "#,
+    )
+    .bind(customer_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Plaka veya şasi no ile tam eşleşme (farklı sigortalılardaki kayıtlar dahil)
+pub async fn find_vehicles(
+    pool: &DbPool,
+    plate: Option<&str>,
+    vin: Option<&str>,
+    owner: Option<&str>,
+    agency: Option<&str>,
+    limit: i64,
+) -> Result<Vec<Vehicle>, sqlx::Error> {
+    sqlx::query_as::<_, Vehicle>(
+        r#"
+        SELECT v.* FROM vehicles v
+        JOIN customers c ON c.id = v.customer_id
+        WHERE ($1 IS NULL OR v.plate = $1)
+          AND ($2 IS NULL OR v.vin = $2 COLLATE NOCASE)
+          AND ($3 IS NULL OR EXISTS (SELECT 1 FROM quotes q WHERE q.customer_id = v.customer_id AND q.user_id = $3))
+          AND ($4 IS NULL OR c.agency_id = $4)
+        ORDER BY COALESCE(v.last_quote_at, v.created_at) DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(plate)
+    .bind(vin)
+    .bind(owner)
+    .bind(agency)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_vehicle_quotes(
+    pool: &DbPool,
+    vehicle_id: &str,
+    owner: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Quote>, sqlx::Error> {
+    sqlx::query_as::<_, Quote>(
+        r#"
+        SELECT * FROM quotes
+        WHERE vehicle_id = $1 AND ($2 IS NULL OR user_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(vehicle_id)
+    .bind(owner)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+pub async fn list_vehicle_policies(
+    pool: &DbPool,
+    vehicle_id: &str,
+    owner: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Policy>, sqlx::Error> {
+    sqlx::query_as::<_, Policy>(
+        r#"
+        SELECT * FROM policies
+        WHERE vehicle_id = $1 AND ($2 IS NULL OR user_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(vehicle_id)
+    .bind(owner)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::customers::{self, CustomerInput};
+    use crate::db::agencies::DEFAULT_AGENCY_ID;
+    use crate::db::quotes::{self, NewQuote};
+    use crate::db::policies::{self, NewPolicy};
+    use crate::db::{test_pool, test_user};
+
+    #[tokio::test]
+    async fn test_vehicle_registry_and_history() {
+        let pool = test_pool().await;
+
+        let agent = test_user(&pool, "A", "agent").await;
+        let other = test_user(&pool, "B", "agent").await;
+        let customer = customers::upsert_customer(
+            &pool,
+            &CustomerInput {
+                identity_no: "12345678901".to_string(),
+                identity_type: "tckn".to_string(),
+                name: "Ali Veli".to_string(),
+                name_key: "ali veli".to_string(),
+                ..Default::default()
+            },
+            DEFAULT_AGENCY_ID,
+            Some(&agent.id),
+        )
+        .await
+        .unwrap();
+
+        let input = VehicleInput {
+            customer_id: customer.id.clone(),
+            plate: "34ABC123".to_string(),
+            brand: "Fiat".to_string(),
+            model: "Egea".to_string(),
+            year: 2020,
+            usage: "hususi".to_string(),
+            category: "otomobil".to_string(),
+            registration_serial: Some("AB".to_string()),
+            registration_no: Some("123456".to_string()),
+            ..Default::default()
+        };
+        let vehicle = upsert_vehicle(&pool, &input, Some(&agent.id)).await.unwrap();
+        let updated = upsert_vehicle(
+            &pool,
+            &VehicleInput { registration_no: None, engine_no: Some("M-1".to_string()), year: 2021, ..input.clone() },
+            Some(&agent.id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(updated.id, vehicle.id);
+        assert_eq!(updated.year, 2021);
+        assert_eq!(updated.registration_no.as_deref(), Some("123456"));
+        assert_eq!(updated.engine_no.as_deref(), Some("M-1"));
+        assert_eq!(list_customer_vehicles(&pool, &customer.id).await.unwrap().len(), 1);
+
+        let quote = quotes::create_quote(
+            &pool,
+            &NewQuote {
+                user_id: &agent.id,
+                request_id: "req-1",
+                request_data: serde_json::json!({}),
+                provider: "Mock",
+                premium: 1000.0,
+                response_data: serde_json::json!({}),
+                customer_id: Some(&customer.id),
+                vehicle_id: Some(&vehicle.id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let policy = policies::create_policy(
+            &pool,
+            &NewPolicy {
+                user_id: &agent.id,
+                quote_id: Some(&quote.id),
+                policy_number: "P-1",
+                provider: "Mock",
+                product_type: "trafik",
+                premium: 1000.0,
+                policy_data: serde_json::json!({}),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(policy.vehicle_id.as_deref(), Some(vehicle.id.as_str()));
+        assert_eq!(policy.customer_id.as_deref(), Some(customer.id.as_str()));
+
+        assert_eq!(list_vehicle_quotes(&pool, &vehicle.id, Some(&agent.id), 10, 0).await.unwrap().len(), 1);
+        assert_eq!(list_vehicle_policies(&pool, &vehicle.id, None, 10, 0).await.unwrap().len(), 1);
+        assert!(get_vehicle(&pool, &vehicle.id, Some(&other.id), None).await.unwrap().is_none());
+        assert!(get_vehicle(&pool, &vehicle.id, None, Some("other-agency")).await.unwrap().is_none());
+        assert_eq!(find_vehicles(&pool, Some("34ABC123"), None, Some(&agent.id), None, 10).await.unwrap().len(), 1);
+        assert!(find_vehicles(&pool, Some("34ABC123"), None, Some(&other.id), None, 10).await.unwrap().is_empty());
+        assert!(find_vehicles(&pool, Some("34ABC123"), None, None, Some("other-agency"), 10).await.unwrap().is_empty());
+    }
+}
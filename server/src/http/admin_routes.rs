@@ -1,5 +1,5 @@
 use crate::auth::Claims;
-use crate::db::{logs, models::AdminStats, policies, quotes, users};
+use crate::db::{logs, models::AdminStats, outbox, policies, quotes, sms, users};
 use crate::http::{ApiError, AppState};
 use axum::{
     extract::{Path, Query, State},
@@ -23,16 +23,17 @@ fn default_limit() -> i64 {
     50
 }
 
+/// Admin'in acentesindeki kullanıcılar (super-admin: tümü)
 pub async fn get_users_handler(
     State(state): State<AppState>,
-    _claims: Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<PaginationQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let user_list = users::list_users(&state.db_pool, params.limit, params.offset)
+    let user_list = users::list_users(&state.db_pool, claims.agency_scope(), params.limit, params.offset)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
-    let total = users::count_users(&state.db_pool)
+    let total = users::count_users(&state.db_pool, claims.agency_scope())
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
@@ -49,12 +50,13 @@ pub async fn get_users_handler(
 
 pub async fn get_user_handler(
     State(state): State<AppState>,
-    _claims: Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     Path(user_id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
     let user = users::get_user_by_id(&state.db_pool, &user_id)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .filter(|u| claims.can_access_agency(&u.agency_id))
         .ok_or_else(|| ApiError::FormValidation("Kullanıcı bulunamadı".to_string()))?;
     
     Ok((StatusCode::OK, Json(user)))
@@ -62,10 +64,10 @@ pub async fn get_user_handler(
 
 pub async fn get_activity_logs_handler(
     State(state): State<AppState>,
-    _claims: Extension<Claims>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<PaginationQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let logs_list = logs::list_activity_logs(&state.db_pool, None, params.limit, params.offset)
+    let logs_list = logs::list_activity_logs(&state.db_pool, None, claims.agency_scope(), params.limit, params.offset)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
@@ -80,25 +82,26 @@ pub async fn get_activity_logs_handler(
 
 pub async fn get_admin_stats_handler(
     State(state): State<AppState>,
-    _claims: Extension<Claims>,
+    Extension(claims): Extension<Claims>,
 ) -> Result<impl IntoResponse, ApiError> {
-    let total_users = users::count_users(&state.db_pool)
+    let agency = claims.agency_scope();
+    let total_users = users::count_users(&state.db_pool, agency)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
-    let total_quotes = quotes::count_quotes(&state.db_pool)
+    let total_quotes = quotes::count_quotes(&state.db_pool, agency)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
-    let total_policies = policies::count_policies(&state.db_pool)
+    let total_policies = policies::count_policies(&state.db_pool, agency)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
-    let total_revenue = policies::sum_revenue(&state.db_pool)
+    let total_revenue = policies::sum_revenue(&state.db_pool, agency)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
-    let total_commission = policies::sum_commission(&state.db_pool)
+    let total_commission = policies::sum_commission(&state.db_pool, agency)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
@@ -114,3 +117,111 @@ pub async fn get_admin_stats_handler(
     Ok((StatusCode::OK, Json(stats)))
 }
 
+
+/// Config ve provider registry'yi restart olmadan yeniden yükler.
+/// Geçersiz config reddedilir, mevcut config çalışmaya devam eder.
+pub async fn reload_config_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::info!("🔄 Config reload istendi: {}", claims.email);
+    
+    let result = state.runtime.reload().await;
+    
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "config_reload",
+        Some("config"),
+        None,
+        Some(serde_json::json!({
+            "success": result.is_ok(),
+            "error": result.as_ref().err().map(|e| e.to_string()),
+        })),
+        None,
+    )
+    .await;
+    
+    let summary = result?;
+    Ok((StatusCode::OK, Json(summary)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatusListQuery {
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+/// E-posta outbox durumu (pending / sent / failed)
+pub async fn get_emails_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Query(params): Query<StatusListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let emails = outbox::list_emails(&state.db_pool, params.status.as_deref(), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "emails": emails,
+            "smtpConfigured": state.email.is_configured(),
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
+
+/// Kalıcı olarak başarısız olmuş e-postayı yeniden kuyruğa alır ve hemen göndermeyi dener
+pub async fn retry_email_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(email_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let requeued = outbox::requeue_email(&state.db_pool, &email_id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if !requeued {
+        return Err(ApiError::FormValidation("Başarısız e-posta bulunamadı".to_string()));
+    }
+    
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "email_retry",
+        Some("email"),
+        Some(email_id),
+        None,
+        None,
+    )
+    .await;
+    
+    let summary = state.email.process_outbox().await?;
+    Ok((StatusCode::OK, Json(summary)))
+}
+
+/// Gönderilen / atlanan SMS kayıtları (sent / failed / opted_out / invalid_phone)
+pub async fn get_sms_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Query(params): Query<StatusListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let messages = sms::list_sms(&state.db_pool, params.status.as_deref(), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "messages": messages,
+            "gatewayConfigured": state.sms.is_configured(),
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
@@ -0,0 +1,248 @@
+use crate::auth::Claims;
+use crate::config::AGENCY_CREDENTIAL_PROVIDERS;
+use crate::db::models::Agency;
+use crate::db::agencies::{self, AgencyChanges};
+use crate::db::logs;
+use crate::http::{ApiError, AppState};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+
+/// Atanabilir roller; `super_admin` tüm acentelerde yetkilidir
+const ROLES: &[&str] = &["agent", "admin", "super_admin"];
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAgencyRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAgencyRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Pasif acentenin kullanıcıları giriş yapamaz
+    #[serde(default)]
+    pub is_active: Option<bool>,
+    /// PDF başlığındaki acente bilgileri; boş değer alanı temizler
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub license_no: Option<String>,
+}
+
+fn agency_name(name: &str) -> Result<&str, ApiError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ApiError::FormValidation("Acente adı boş olamaz".to_string()));
+    }
+    Ok(name)
+}
+
+pub async fn list_agencies_handler(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let agencies = agencies::list_agencies(&state.db_pool)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    Ok((StatusCode::OK, Json(agencies)))
+}
+
+pub async fn create_agency_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateAgencyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let agency = agencies::create_agency(&state.db_pool, agency_name(&req.name)?)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    log_change(&state, &claims, "agency_created", &agency.id, serde_json::to_value(&agency).ok()).await;
+    tracing::info!("🏢 Acente oluşturuldu: {} ({})", agency.name, agency.id);
+
+    Ok((StatusCode::CREATED, Json(agency)))
+}
+
+pub async fn update_agency_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<UpdateAgencyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let changes = AgencyChanges {
+        name: req.name.as_deref().map(agency_name).transpose()?,
+        is_active: req.is_active,
+        address: req.address.as_deref().map(str::trim),
+        phone: req.phone.as_deref().map(str::trim),
+        email: req.email.as_deref().map(str::trim),
+        license_no: req.license_no.as_deref().map(str::trim),
+    };
+    let agency = agencies::update_agency(&state.db_pool, &id, &changes)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Acente bulunamadı".to_string()))?;
+    log_change(&state, &claims, "agency_updated", &agency.id, serde_json::to_value(&agency).ok()).await;
+
+    Ok((StatusCode::OK, Json(agency)))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AssignUserRequest {
+    /// Verilmezse rol değişmez
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// Kullanıcıyı acenteye taşır. Yeni acente ve rol mevcut token'la yapılan sonraki istekte geçerli olur.
+pub async fn assign_user_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, user_id)): Path<(String, String)>,
+    Json(req): Json<AssignUserRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let role = req.role.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if let Some(role) = role.filter(|r| !ROLES.contains(r)) {
+        return Err(ApiError::FormValidation(format!("Bilinmeyen rol: {}", role)));
+    }
+    find_agency(&state, &id).await?;
+
+    let user = agencies::set_user_agency(&state.db_pool, &user_id, &id, role)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Kullanıcı bulunamadı".to_string()))?;
+    log_change(
+        &state,
+        &claims,
+        "agency_user_assigned",
+        &id,
+        Some(serde_json::json!({ "userId": user.id, "role": user.role })),
+    )
+    .await;
+
+    Ok((StatusCode::OK, Json(user)))
+}
+
+/// Acente admini kendi acentesinin, super-admin tüm acentelerin kullanıcı bilgilerini görür
+pub async fn list_credentials_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let agency = accessible_agency(&state, &claims, &id).await?;
+    let credentials = agencies::list_credentials(&state.db_pool, &agency.id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    Ok((StatusCode::OK, Json(credentials)))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialRequest {
+    pub username: String,
+    pub password: String,
+    /// OTP anahtarı (Sompo)
+    #[serde(default)]
+    pub secret_key: Option<String>,
+}
+
+pub async fn put_credential_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, provider)): Path<(String, String)>,
+    Json(req): Json<CredentialRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let agency = accessible_agency(&state, &claims, &id).await?;
+    let provider = credential_provider(&provider)?;
+    let (username, password) = (req.username.trim(), req.password.as_str());
+    if username.is_empty() || password.is_empty() {
+        return Err(ApiError::FormValidation("Kullanıcı adı ve şifre gerekli".to_string()));
+    }
+    let secret_key = req.secret_key.as_deref().map(str::trim).filter(|k| !k.is_empty());
+
+    let credential = agencies::upsert_credential(&state.db_pool, &agency.id, &provider, username, password, secret_key, &claims.sub)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    state.runtime.invalidate_agency(&agency.id).await;
+    log_change(
+        &state,
+        &claims,
+        "agency_credential_updated",
+        &agency.id,
+        Some(serde_json::json!({ "provider": provider, "username": username })),
+    )
+    .await;
+    tracing::info!("🔑 Acente provider kullanıcı bilgisi güncellendi: {} / {}", agency.name, provider);
+
+    Ok((StatusCode::OK, Json(credential)))
+}
+
+pub async fn delete_credential_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, provider)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let agency = accessible_agency(&state, &claims, &id).await?;
+    let provider = credential_provider(&provider)?;
+    let deleted = agencies::delete_credential(&state.db_pool, &agency.id, &provider)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if !deleted {
+        return Err(ApiError::FormValidation("Kullanıcı bilgisi bulunamadı".to_string()));
+    }
+    state.runtime.invalidate_agency(&agency.id).await;
+    log_change(
+        &state,
+        &claims,
+        "agency_credential_deleted",
+        &agency.id,
+        Some(serde_json::json!({ "provider": provider })),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn credential_provider(provider: &str) -> Result<String, ApiError> {
+    let provider = provider.trim().to_lowercase();
+    if !AGENCY_CREDENTIAL_PROVIDERS.contains(&provider.as_str()) {
+        return Err(ApiError::FormValidation(format!(
+            "Acente kullanıcı bilgisi desteklenmeyen provider: {} (desteklenen: {})",
+            provider,
+            AGENCY_CREDENTIAL_PROVIDERS.join(", ")
+        )));
+    }
+    Ok(provider)
+}
+
+async fn find_agency(state: &AppState, id: &str) -> Result<Agency, ApiError> {
+    agencies::get_agency(&state.db_pool, id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Acente bulunamadı".to_string()))
+}
+
+/// Başka acente bulunamadı sayılır (super-admin hariç)
+async fn accessible_agency(state: &AppState, claims: &Claims, id: &str) -> Result<Agency, ApiError> {
+    if !claims.can_access_agency(id) {
+        return Err(ApiError::FormValidation("Acente bulunamadı".to_string()));
+    }
+    find_agency(state, id).await
+}
+
+async fn log_change(state: &AppState, claims: &Claims, action: &str, agency_id: &str, metadata: Option<serde_json::Value>) {
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        action,
+        Some("agency"),
+        Some(agency_id.to_string()),
+        metadata,
+        None,
+    )
+    .await;
+}
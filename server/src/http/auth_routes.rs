@@ -1,4 +1,5 @@
 use crate::auth::{create_token, hash_password, verify_password};
+use crate::db::agencies::{self, DEFAULT_AGENCY_ID};
 use crate::db::users;
 use crate::http::{ApiError, AppState};
 use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
@@ -25,6 +26,7 @@ pub struct UserInfo {
     pub email: String,
     pub name: String,
     pub role: String,
+    pub agency_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -52,13 +54,22 @@ pub async fn login_handler(
         return Err(ApiError::LoginFailed("Şifre hatalı".to_string()));
     }
     
+    // Pasif acentenin kullanıcıları giriş yapamaz
+    let agency_active = agencies::get_agency(&state.db_pool, &user.agency_id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .is_some_and(|a| a.is_active);
+    if !agency_active {
+        return Err(ApiError::LoginFailed("Acente hesabı pasif".to_string()));
+    }
+    
     // Last login güncelle
     users::update_last_login(&state.db_pool, &user.id)
         .await
         .ok();
     
     // JWT token oluştur
-    let token = create_token(&user.id, &user.email, &user.role, &state.jwt_secret)
+    let token = create_token(&user.id, &user.email, &user.role, &user.agency_id, &state.jwt_secret)
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
     let response = LoginResponse {
@@ -68,6 +79,7 @@ pub async fn login_handler(
             email: user.email,
             name: user.name,
             role: user.role,
+            agency_id: user.agency_id,
         },
     };
     
@@ -89,6 +101,7 @@ pub async fn register_handler(
         &password_hash,
         &req.name,
         "agent", // Default role
+        DEFAULT_AGENCY_ID,
     )
     .await
     .map_err(|e| {
@@ -99,8 +112,12 @@ pub async fn register_handler(
         }
     })?;
     
+    if let Err(e) = state.email.send_welcome(&user.id, &user.email, &user.name).await {
+        tracing::warn!("⚠️ Hoş geldiniz e-postası kuyruğa alınamadı: {}", e);
+    }
+    
     // Token oluştur
-    let token = create_token(&user.id, &user.email, &user.role, &state.jwt_secret)
+    let token = create_token(&user.id, &user.email, &user.role, &user.agency_id, &state.jwt_secret)
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
     
     let response = LoginResponse {
@@ -110,6 +127,7 @@ pub async fn register_handler(
             email: user.email,
             name: user.name,
             role: user.role,
+            agency_id: user.agency_id,
         },
     };
     
@@ -0,0 +1,247 @@
+use crate::auth::Claims;
+use crate::db::commissions::{self, CommissionRuleInput};
+use crate::db::logs;
+use crate::db::models::CommissionRule;
+use crate::http::{ApiError, AppState, ProductType, VehicleUsage};
+use crate::services::commission::{calculate_commission, validate_rule, CommissionContext};
+use crate::utils::parse_portal_date;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+
+/// Boş koşul "tümü" demektir. Tarihler 2024-01-31 veya 31.01.2024
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommissionRuleRequest {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub product_type: Option<ProductType>,
+    #[serde(default)]
+    pub usage: Option<VehicleUsage>,
+    #[serde(default)]
+    pub min_premium: Option<f64>,
+    #[serde(default)]
+    pub max_premium: Option<f64>,
+    pub rate_percent: f64,
+    pub valid_from: String,
+    #[serde(default)]
+    pub valid_to: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+fn parse_day(value: &str, field: &str) -> Result<NaiveDate, ApiError> {
+    parse_portal_date(value).ok_or_else(|| ApiError::FormValidation(format!("Geçersiz {} tarihi: {}", field, value)))
+}
+
+impl CommissionRuleRequest {
+    fn into_input(self) -> Result<CommissionRuleInput, ApiError> {
+        let valid_from = parse_day(&self.valid_from, "başlangıç")?;
+        let valid_to = match self.valid_to.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => Some(parse_day(value, "bitiş")?),
+            None => None,
+        };
+
+        let input = CommissionRuleInput {
+            provider: self.provider.map(|p| p.trim().to_string()).filter(|p| !p.is_empty()),
+            product_type: self.product_type.map(|p| p.as_str().to_string()),
+            usage: self.usage.map(|u| u.as_str().to_string()),
+            min_premium: self.min_premium,
+            max_premium: self.max_premium,
+            rate_percent: self.rate_percent,
+            valid_from: valid_from.format("%Y-%m-%d").to_string(),
+            valid_to: valid_to.map(|d| d.format("%Y-%m-%d").to_string()),
+            note: self.note.filter(|n| !n.trim().is_empty()),
+        };
+        validate_rule(&input).map_err(ApiError::FormValidation)?;
+        Ok(input)
+    }
+}
+
+/// Poliçelerde kullanılmış kuralda oran ve koşullar değişmez; yalnızca bitiş tarihi ve not
+fn changes_terms(rule: &CommissionRule, input: &CommissionRuleInput) -> bool {
+    rule.provider != input.provider
+        || rule.product_type != input.product_type
+        || rule.usage != input.usage
+        || rule.min_premium != input.min_premium
+        || rule.max_premium != input.max_premium
+        || rule.rate_percent != input.rate_percent
+        || rule.valid_from != input.valid_from
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommissionRuleListQuery {
+    /// Yalnızca bu gün (YYYY-MM-DD) yürürlükte olan kurallar
+    #[serde(default)]
+    pub on: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+pub async fn list_commission_rules_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Query(params): Query<CommissionRuleListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let on = params
+        .on
+        .as_deref()
+        .map(|value| parse_day(value, "yürürlük").map(|d| d.format("%Y-%m-%d").to_string()))
+        .transpose()?;
+    let rules = commissions::list_rules(&state.db_pool, on.as_deref(), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "rules": rules,
+            "defaultRatePercent": state.runtime.config().default_commission_rate,
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
+
+pub async fn create_commission_rule_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CommissionRuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let input = req.into_input()?;
+    let rule = commissions::create_rule(&state.db_pool, &input, Some(&claims.sub))
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    tracing::info!("💰 Komisyon kuralı eklendi: {} (%{})", rule.id, rule.rate_percent);
+    log_rule_activity(&state, &claims, "commission_rule_created", &rule).await;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+pub async fn update_commission_rule_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<CommissionRuleRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let input = req.into_input()?;
+    let existing = find_rule(&state, &id).await?;
+
+    let used_by = commissions::count_policies_using_rule(&state.db_pool, &id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if used_by > 0 && changes_terms(&existing, &input) {
+        return Err(ApiError::Conflict(format!(
+            "Kural {} poliçede kullanıldı; oran ve koşullar değiştirilemez. Bitiş tarihi verip yeni kural ekleyin",
+            used_by
+        )));
+    }
+
+    let rule = commissions::update_rule(&state.db_pool, &id, &input)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Komisyon kuralı bulunamadı".to_string()))?;
+
+    tracing::info!("💰 Komisyon kuralı güncellendi: {} (%{})", rule.id, rule.rate_percent);
+    log_rule_activity(&state, &claims, "commission_rule_updated", &rule).await;
+
+    Ok((StatusCode::OK, Json(rule)))
+}
+
+pub async fn delete_commission_rule_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let rule = find_rule(&state, &id).await?;
+
+    let used_by = commissions::count_policies_using_rule(&state.db_pool, &id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if used_by > 0 {
+        return Err(ApiError::Conflict(format!(
+            "Kural {} poliçede kullanıldı ve silinemez; bitiş tarihi verin",
+            used_by
+        )));
+    }
+
+    commissions::delete_rule(&state.db_pool, &id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    tracing::info!("🗑️ Komisyon kuralı silindi: {}", id);
+    log_rule_activity(&state, &claims, "commission_rule_deleted", &rule).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommissionMatchQuery {
+    pub provider: String,
+    pub product_type: ProductType,
+    #[serde(default)]
+    pub usage: Option<VehicleUsage>,
+    pub premium: f64,
+    /// Varsayılan bugün
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+/// Verilen poliçe bilgileri için hangi kuralın ve tutarın uygulanacağını gösterir
+pub async fn match_commission_rule_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Query(params): Query<CommissionMatchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let date = match params.date.as_deref() {
+        Some(value) => parse_day(value, "tanzim")?,
+        None => Utc::now().date_naive(),
+    };
+    let ctx = CommissionContext {
+        provider: &params.provider,
+        product_type: params.product_type.as_str(),
+        usage: params
+            .product_type
+            .is_vehicle()
+            .then(|| params.usage.unwrap_or_default().as_str()),
+        premium: params.premium,
+        date,
+    };
+    let commission = calculate_commission(&state.db_pool, state.runtime.config().default_commission_rate, &ctx).await?;
+
+    Ok((StatusCode::OK, Json(commission)))
+}
+
+async fn find_rule(state: &AppState, id: &str) -> Result<CommissionRule, ApiError> {
+    commissions::get_rule(&state.db_pool, id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Komisyon kuralı bulunamadı".to_string()))
+}
+
+async fn log_rule_activity(state: &AppState, claims: &Claims, action: &str, rule: &CommissionRule) {
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        action,
+        Some("commission_rule"),
+        Some(rule.id.clone()),
+        serde_json::to_value(rule).ok(),
+        None,
+    )
+    .await;
+}
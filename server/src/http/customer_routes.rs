@@ -0,0 +1,125 @@
+use crate::auth::Claims;
+use crate::db::{customers, vehicles};
+use crate::db::models::Customer;
+use crate::http::{ApiError, AppState, UserQuoteResponse};
+use crate::services::customer::search_filter;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+
+/// Admin acentesinin tüm sigortalılarını (ve araçlarını) görür; diğer kullanıcılar yalnızca kendi teklif aldıklarını
+pub(crate) fn owner(claims: &Claims) -> Option<&str> {
+    (!claims.is_admin()).then_some(claims.sub.as_str())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CustomerListQuery {
+    /// Ad, TCKN/VKN veya telefon
+    #[serde(default)]
+    pub q: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+pub async fn list_customers_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<CustomerListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let search = search_filter(params.q.as_deref().unwrap_or_default());
+    let customers = customers::search_customers(
+        &state.db_pool,
+        &search,
+        owner(&claims),
+        claims.agency_scope(),
+        params.limit,
+        params.offset,
+    )
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "customers": customers,
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
+
+pub async fn get_customer_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let customer = find_customer(&state, &claims, &id).await?;
+    Ok((StatusCode::OK, Json(customer)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CustomerHistoryQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+pub async fn get_customer_quotes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<CustomerHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let customer = find_customer(&state, &claims, &id).await?;
+    let quotes = customers::list_customer_quotes(&state.db_pool, &customer.id, owner(&claims), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    let response: Vec<UserQuoteResponse> = quotes.into_iter().map(UserQuoteResponse::from).collect();
+    Ok((StatusCode::OK, Json(response)))
+}
+
+pub async fn get_customer_policies_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<CustomerHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let customer = find_customer(&state, &claims, &id).await?;
+    let policies = customers::list_customer_policies(&state.db_pool, &customer.id, owner(&claims), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(policies)))
+}
+
+pub async fn get_customer_vehicles_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let customer = find_customer(&state, &claims, &id).await?;
+    let vehicles = vehicles::list_customer_vehicles(&state.db_pool, &customer.id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(vehicles)))
+}
+
+async fn find_customer(state: &AppState, claims: &Claims, id: &str) -> Result<Customer, ApiError> {
+    customers::get_customer(&state.db_pool, id, owner(claims), claims.agency_scope())
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Sigortalı bulunamadı".to_string()))
+}
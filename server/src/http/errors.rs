@@ -20,6 +20,9 @@ pub enum ApiError {
     #[error("Form validasyon hatası: {0}")]
     FormValidation(String),
     
+    #[error("Çakışma: {0}")]
+    Conflict(String),
+    
     #[error("Erişim engellendi: {0}")]
     Blocked(String),
     
@@ -49,6 +52,7 @@ pub enum ErrorCode {
     InternalServerError,
     LoginFailed,
     FormValidation,
+    Conflict,
     Blocked,
     HumanActionRequired,
     Timeout,
@@ -73,12 +77,27 @@ pub struct ErrorDetail {
 }
 
 impl ApiError {
+    /// İstek portala gönderilmeden oluşan hatalar (doğrulama, login/session, erişim).
+    /// Timeout / ParseError / Unknown gibi diğer hatalarda portalın isteği işleyip işlemediği bilinmez.
+    pub fn is_before_submission(&self) -> bool {
+        matches!(
+            self,
+            ApiError::Unauthorized(_)
+                | ApiError::LoginFailed(_)
+                | ApiError::FormValidation(_)
+                | ApiError::Blocked(_)
+                | ApiError::HumanActionRequired(_)
+                | ApiError::ProviderInactive(_)
+        )
+    }
+
     fn to_error_code(&self) -> ErrorCode {
         match self {
             ApiError::Unauthorized(_) => ErrorCode::Unauthorized,
             ApiError::InternalServerError(_) => ErrorCode::InternalServerError,
             ApiError::LoginFailed(_) => ErrorCode::LoginFailed,
             ApiError::FormValidation(_) => ErrorCode::FormValidation,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
             ApiError::Blocked(_) => ErrorCode::Blocked,
             ApiError::HumanActionRequired(_) => ErrorCode::HumanActionRequired,
             ApiError::Timeout(_) => ErrorCode::Timeout,
@@ -95,6 +114,7 @@ impl ApiError {
             ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::LoginFailed(_) => StatusCode::UNAUTHORIZED,
             ApiError::FormValidation(_) => StatusCode::BAD_REQUEST,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
             ApiError::Blocked(_) => StatusCode::FORBIDDEN,
             ApiError::HumanActionRequired(_) => StatusCode::PRECONDITION_FAILED,
             ApiError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
@@ -0,0 +1,179 @@
+use crate::auth::Claims;
+use crate::db::exports::ExportFilter;
+use crate::db::logs;
+use crate::http::{ApiError, AppState};
+use crate::services::export::{self, CsvSink, ExportFormat, ExportKind, ExportLocale, XlsxSink};
+use crate::utils::parse_portal_date;
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+/// Liste filtreleri + biçim seçenekleri. `format`: csv | xlsx, `locale`: tr | iso
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default)]
+    pub locale: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub quote_no: Option<String>,
+    /// Yalnızca aktivite dışa aktarımında
+    #[serde(default)]
+    pub action: Option<String>,
+    /// Başlangıç/bitiş günü (dahil): 2025-01-31 veya 31.01.2025
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Yalnızca admin dışa aktarımında; belirtilmezse tüm kullanıcılar
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+pub(crate) fn parse_day(value: Option<&str>, field: &str) -> Result<Option<String>, ApiError> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(value) => parse_portal_date(value)
+            .map(|d| Some(d.format("%Y-%m-%d").to_string()))
+            .ok_or_else(|| ApiError::FormValidation(format!("Geçersiz {} tarihi: {}", field, value))),
+        None => Ok(None),
+    }
+}
+
+impl ExportQuery {
+    /// Acente filtresi token'dan gelir; super-admin tüm acentelerde
+    fn filter(&self, claims: &Claims, user_id: Option<String>) -> Result<ExportFilter, ApiError> {
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        Ok(ExportFilter {
+            agency_id: claims.agency_scope().map(str::to_string),
+            user_id,
+            status: non_empty(&self.status),
+            provider: non_empty(&self.provider),
+            quote_no: non_empty(&self.quote_no),
+            action: non_empty(&self.action),
+            from: parse_day(self.from.as_deref(), "başlangıç")?,
+            to: parse_day(self.to.as_deref(), "bitiş")?,
+        })
+    }
+}
+
+/// Kullanıcının kendi teklifleri
+pub async fn export_quotes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let filter = query.filter(&claims, Some(claims.sub.clone()))?;
+    export(state, &claims, ExportKind::Quotes, &query, filter, false).await
+}
+
+/// Kullanıcının kendi poliçeleri
+pub async fn export_policies_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let filter = query.filter(&claims, Some(claims.sub.clone()))?;
+    export(state, &claims, ExportKind::Policies, &query, filter, false).await
+}
+
+/// Acentenin tüm kullanıcıları: /api/v1/admin/export/{quotes|policies|activity}
+pub(crate) async fn admin_export_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(kind): Path<String>,
+    Query(query): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let kind = ExportKind::parse(&kind)
+        .ok_or_else(|| ApiError::FormValidation(format!("Bilinmeyen dışa aktarma türü: {}", kind)))?;
+    let filter = query.filter(&claims, query.user_id.clone())?;
+    export(state, &claims, kind, &query, filter, true).await
+}
+
+async fn export(
+    state: AppState,
+    claims: &Claims,
+    kind: ExportKind,
+    query: &ExportQuery,
+    filter: ExportFilter,
+    include_user: bool,
+) -> Result<Response, ApiError> {
+    let format_value = query.format.as_deref().unwrap_or("csv");
+    let format = ExportFormat::parse(format_value)
+        .ok_or_else(|| ApiError::FormValidation(format!("Desteklenmeyen dosya biçimi: {}", format_value)))?;
+    let locale_value = query.locale.as_deref().unwrap_or("tr");
+    let locale = ExportLocale::parse(locale_value)
+        .ok_or_else(|| ApiError::FormValidation(format!("Desteklenmeyen biçim dili: {}", locale_value)))?;
+
+    let file_name = kind.file_name(format, Utc::now().date_naive());
+    tracing::info!("📤 Dışa aktarma başladı: {} ({})", file_name, claims.email);
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "export",
+        Some(kind.as_str()),
+        None,
+        Some(serde_json::json!({
+            "format": format.extension(),
+            "userId": filter.user_id,
+            "status": filter.status,
+            "provider": filter.provider,
+            "from": filter.from,
+            "to": filter.to,
+        })),
+        None,
+    )
+    .await;
+
+    let body = match format {
+        ExportFormat::Csv => {
+            // Satırlar okundukça gönderilir; büyük listeler belleğe alınmaz
+            let (tx, rx) = mpsc::channel(8);
+            let pool = state.db_pool.clone();
+            tokio::spawn(async move {
+                let mut sink = CsvSink::new(locale, tx.clone());
+                let result = match export::write_rows(&pool, kind, &filter, include_user, &mut sink).await {
+                    Ok(count) => sink.finish().await.map(|_| count),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(count) => tracing::info!("✅ CSV dışa aktarma tamamlandı: {} satır", count),
+                    Err(e) => {
+                        tracing::warn!("⚠️ CSV dışa aktarma yarıda kaldı: {}", e);
+                        let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+                    }
+                }
+            });
+            Body::from_stream(futures::stream::unfold(rx, |mut rx| async move {
+                rx.recv().await.map(|chunk| (chunk, rx))
+            }))
+        }
+        ExportFormat::Xlsx => {
+            let mut sink = XlsxSink::new(kind, locale)?;
+            let count = export::write_rows(&state.db_pool, kind, &filter, include_user, &mut sink).await?;
+            let bytes = sink.finish()?;
+            tracing::info!("✅ Excel dışa aktarma tamamlandı: {} satır", count);
+            Body::from(bytes)
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", file_name)),
+        ],
+        body,
+    )
+        .into_response())
+}
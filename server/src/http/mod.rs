@@ -1,12 +1,21 @@
 pub mod admin_routes;
+pub mod agency_routes;
 pub mod auth_routes;
+pub mod commission_routes;
+pub mod customer_routes;
 pub mod errors;
+pub mod export_routes;
 pub mod models;
+pub mod policy_routes;
 pub mod quotes_routes;
 pub mod rate_limit;
 pub mod routes;
+pub mod search_routes;
+pub mod sms_routes;
 pub mod state;
+pub mod statement_routes;
 pub mod user_routes;
+pub mod vehicle_routes;
 
 pub use errors::ApiError;
 pub use models::*;
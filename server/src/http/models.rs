@@ -6,12 +6,33 @@ use uuid::Uuid;
 #[serde(rename_all = "camelCase")]
 pub struct QuoteRequest {
     pub insured: InsuredInfo,
+    /// Konut tekliflerinde gönderilmeyebilir
+    #[serde(default)]
     pub vehicle: VehicleInfo,
+    /// Kayıtlı araç: verilirse `vehicle` bu kayıttan doldurulur
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vehicle_id: Option<String>,
+    /// Konut teklifleri için adres/bina bilgileri
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub property: Option<PropertyInfo>,
     pub coverage: CoverageInfo,
     #[serde(default)]
     pub quote_meta: QuoteMeta,
 }
 
+impl QuoteRequest {
+    /// Provider'a gitmeden önceki istek kontrolü; `vehicleId` çözüldükten sonra çağrılır
+    pub fn validate(&self) -> Result<(), String> {
+        if self.coverage.product_type.is_vehicle() && self.vehicle.plate.trim().is_empty() {
+            return Err(format!(
+                "{} teklifi için araç bilgisi (vehicle veya vehicleId) gerekli",
+                self.coverage.product_type.as_str()
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InsuredInfo {
@@ -22,7 +43,7 @@ pub struct InsuredInfo {
     pub email: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VehicleInfo {
     pub plate: String,
@@ -32,15 +53,157 @@ pub struct VehicleInfo {
     pub model: String,
     pub year: u16,
     pub usage: VehicleUsage,
+    #[serde(default)]
+    pub category: VehicleCategory,
+    /// Ruhsat belge seri (ör. "AB") ve no
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration_serial: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration_no: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine_no: Option<String>,
+    /// Tescil tarihi
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration_date: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum VehicleUsage {
+    #[default]
     Hususi,
     Ticari,
 }
 
+impl VehicleUsage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VehicleUsage::Hususi => "hususi",
+            VehicleUsage::Ticari => "ticari",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hususi" => Some(VehicleUsage::Hususi),
+            "ticari" => Some(VehicleUsage::Ticari),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VehicleCategory {
+    #[default]
+    Otomobil,
+    Kamyonet,
+    Minibus,
+    Otobus,
+    Kamyon,
+    Motosiklet,
+}
+
+impl VehicleCategory {
+    pub const ALL: [VehicleCategory; 6] = [
+        VehicleCategory::Otomobil,
+        VehicleCategory::Kamyonet,
+        VehicleCategory::Minibus,
+        VehicleCategory::Otobus,
+        VehicleCategory::Kamyon,
+        VehicleCategory::Motosiklet,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VehicleCategory::Otomobil => "otomobil",
+            VehicleCategory::Kamyonet => "kamyonet",
+            VehicleCategory::Minibus => "minibus",
+            VehicleCategory::Otobus => "otobus",
+            VehicleCategory::Kamyon => "kamyon",
+            VehicleCategory::Motosiklet => "motosiklet",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == value)
+    }
+}
+
+/// Konut bilgileri. DASK poliçe numarası verilirse diğer alanlar portalden doldurulur.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PropertyInfo {
+    #[serde(default)]
+    pub address: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub district: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub building_type: Option<BuildingType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub square_meters: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub building_year: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number_of_floors: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub floor: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dask_policy_no: Option<String>,
+}
+
+impl PropertyInfo {
+    /// DASK sorgusu olmadan teklif için eksik alanlar
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.address.trim().is_empty() {
+            missing.push("address");
+        }
+        if self.city.trim().is_empty() {
+            missing.push("city");
+        }
+        if self.district.trim().is_empty() {
+            missing.push("district");
+        }
+        if self.building_type.is_none() {
+            missing.push("buildingType");
+        }
+        if self.square_meters.is_none() {
+            missing.push("squareMeters");
+        }
+        if self.building_year.is_none() {
+            missing.push("buildingYear");
+        }
+        if self.number_of_floors.is_none() {
+            missing.push("numberOfFloors");
+        }
+        if self.floor.is_none() {
+            missing.push("floor");
+        }
+        missing
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildingType {
+    Apartman,
+    Mustakil,
+    Villa,
+}
+
+impl BuildingType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildingType::Apartman => "apartman",
+            BuildingType::Mustakil => "mustakil",
+            BuildingType::Villa => "villa",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CoverageInfo {
@@ -50,7 +213,7 @@ pub struct CoverageInfo {
     pub addons: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ProductType {
     Trafik,
@@ -59,6 +222,22 @@ pub enum ProductType {
     Saglik,
 }
 
+impl ProductType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProductType::Trafik => "trafik",
+            ProductType::Kasko => "kasko",
+            ProductType::Konut => "konut",
+            ProductType::Saglik => "saglik",
+        }
+    }
+    
+    /// Araç bilgisi (kullanım tipi, kategori) gerektiren ürünler
+    pub fn is_vehicle(&self) -> bool {
+        matches!(self, ProductType::Trafik | ProductType::Kasko)
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteMeta {
@@ -85,12 +264,29 @@ pub struct QuoteResponse {
     pub coverages: Vec<Coverage>,
     #[serde(default)]
     pub warnings: Vec<String>,
+    /// Portalın verdiği teklif numarası (poliçeleştirme / yeniden basımda kullanılır)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_quote_no: Option<String>,
+    /// Teklifin portalda geçerli olduğu son gün (YYYY-MM-DD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub raw: Option<RawData>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timings: Option<Timings>,
 }
 
+/// Kaydedilmiş teklif yanıtı; poliçe kesiminde `quoteId` gönderilir
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedQuoteResponse {
+    /// Kayıt başarısızsa yok (teklif yine döner, poliçeleştirilemez)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<String>,
+    #[serde(flatten)]
+    pub quote: QuoteResponse,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PremiumDetail {
     pub net: f64,
@@ -132,6 +328,120 @@ pub struct Timings {
     pub scrape_ms: u64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentMethod {
+    CreditCard,
+    BankTransfer,
+    Cash,
+}
+
+impl PaymentMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::CreditCard => "credit_card",
+            PaymentMethod::BankTransfer => "bank_transfer",
+            PaymentMethod::Cash => "cash",
+        }
+    }
+}
+
+/// Kart bilgisi: yalnızca portala iletilir, DB'ye yazılmaz ve loglanmaz
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CardDetails {
+    pub holder_name: String,
+    pub number: String,
+    pub expiry_month: u8,
+    pub expiry_year: u16,
+    pub cvv: String,
+}
+
+impl std::fmt::Debug for CardDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CardDetails")
+            .field("holder_name", &self.holder_name)
+            .field("number", &self.masked_number())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CardDetails {
+    /// "**** **** **** 1234" formatında maskeli kart numarası
+    pub fn masked_number(&self) -> String {
+        let digits: String = self.number.chars().filter(|c| c.is_ascii_digit()).collect();
+        let last4 = &digits[digits.len().saturating_sub(4)..];
+        format!("**** **** **** {}", last4)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentDetails {
+    pub method: PaymentMethod,
+    #[serde(default = "default_installment_count")]
+    pub installment_count: u8,
+    #[serde(default)]
+    pub card: Option<CardDetails>,
+}
+
+fn default_installment_count() -> u8 {
+    1
+}
+
+impl PaymentDetails {
+    /// Portala gitmeden önce ödeme bilgisinin tutarlılığı
+    pub fn validate(&self) -> Result<(), String> {
+        if self.installment_count == 0 {
+            return Err("Taksit sayısı en az 1 olmalı".to_string());
+        }
+        match (&self.method, &self.card) {
+            (PaymentMethod::CreditCard, None) => Err("Kredi kartı ödemesi için kart bilgisi gerekli".to_string()),
+            (PaymentMethod::CreditCard, Some(card)) => {
+                let digits = card.number.chars().filter(|c| c.is_ascii_digit()).count();
+                if !(15..=19).contains(&digits) {
+                    return Err("Kart numarası geçersiz".to_string());
+                }
+                if !(1..=12).contains(&card.expiry_month) {
+                    return Err("Kart son kullanma ayı geçersiz".to_string());
+                }
+                if !(3..=4).contains(&card.cvv.len()) || !card.cvv.chars().all(|c| c.is_ascii_digit()) {
+                    return Err("CVV geçersiz".to_string());
+                }
+                Ok(())
+            }
+            (_, _) if self.installment_count > 1 => {
+                Err("Taksitli ödeme yalnızca kredi kartı ile yapılabilir".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Kaydedilmiş teklifin poliçeye dönüştürülmesi için provider'a giden istek
+#[derive(Debug, Clone)]
+pub struct PolicyIssueRequest {
+    pub quote_request: QuoteRequest,
+    pub quote: QuoteResponse,
+    pub payment: PaymentDetails,
+}
+
+/// Portalda kesilen poliçenin bilgileri
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedPolicy {
+    pub policy_number: String,
+    /// YYYY-MM-DD
+    pub start_date: String,
+    /// YYYY-MM-DD
+    pub end_date: String,
+    /// Portalın kesinleştirdiği brüt prim (tekliften farklıysa)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub premium: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthResponse {
     pub ok: bool,
@@ -146,7 +456,70 @@ pub struct ProviderInfo {
     pub active: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
-    pub supported_products: Vec<String>,
+    #[serde(flatten)]
+    pub capabilities: ProviderCapabilities,
+}
+
+/// Provider'ın teklif verebildiği ürün / ek teminat / araç kombinasyonları
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderCapabilities {
+    pub supported_products: Vec<ProductType>,
+    /// Forma işlenebilen ek teminat kodları (coverage.addons)
+    #[serde(default)]
+    pub supported_addons: Vec<String>,
+    pub usage_types: Vec<VehicleUsage>,
+    pub vehicle_categories: Vec<VehicleCategory>,
+    /// Teklifi portal üzerinden poliçeye dönüştürebiliyor mu
+    #[serde(default)]
+    pub policy_issuance: bool,
+}
+
+impl Default for ProviderCapabilities {
+    fn default() -> Self {
+        Self {
+            supported_products: vec![ProductType::Trafik, ProductType::Kasko],
+            supported_addons: vec![],
+            usage_types: vec![VehicleUsage::Hususi, VehicleUsage::Ticari],
+            vehicle_categories: vec![VehicleCategory::Otomobil],
+            policy_issuance: false,
+        }
+    }
+}
+
+impl ProviderCapabilities {
+    pub fn supports_product(&self, product_type: &ProductType) -> bool {
+        self.supported_products.contains(product_type)
+    }
+    
+    /// Forma işlenemeyen ek teminatlar; teklif bunlar olmadan alınır ve uyarı eklenir
+    pub fn unsupported_addons<'a>(&self, request: &'a QuoteRequest) -> Vec<&'a str> {
+        request
+            .coverage
+            .addons
+            .iter()
+            .filter(|a| !self.supported_addons.iter().any(|s| s.eq_ignore_ascii_case(a)))
+            .map(|a| a.as_str())
+            .collect()
+    }
+    
+    /// İstek bu provider'la karşılanabilir mi; değilse nedeni (ek teminatlar reddedilmez)
+    pub fn check(&self, request: &QuoteRequest) -> Result<(), String> {
+        let product_type = &request.coverage.product_type;
+        if !self.supports_product(product_type) {
+            return Err(format!("{} ürünü desteklenmiyor", product_type.as_str()));
+        }
+        
+        if product_type.is_vehicle() {
+            if !self.usage_types.contains(&request.vehicle.usage) {
+                return Err(format!("{} kullanım tipi desteklenmiyor", request.vehicle.usage.as_str()));
+            }
+            if !self.vehicle_categories.contains(&request.vehicle.category) {
+                return Err(format!("{} araç kategorisi desteklenmiyor", request.vehicle.category.as_str()));
+            }
+        }
+        
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -166,6 +539,12 @@ pub struct UserQuoteResponse {
     pub status: String,
     pub created_at: String,
     pub request_data: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_quote_no: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_id: Option<String>,
 }
 
 impl From<crate::db::models::Quote> for UserQuoteResponse {
@@ -177,6 +556,9 @@ impl From<crate::db::models::Quote> for UserQuoteResponse {
             status: q.status,
             created_at: q.created_at,
             request_data: q.request_data,
+            provider_quote_no: q.provider_quote_no,
+            valid_until: q.valid_until,
+            customer_id: q.customer_id,
         }
     }
 }
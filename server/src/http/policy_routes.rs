@@ -0,0 +1,490 @@
+use crate::auth::Claims;
+use crate::db::models::Policy;
+use crate::db::policies::{self, NewPolicyEvent, PolicyChanges};
+use crate::db::{agencies, logs, quotes, renewals};
+use crate::http::{ApiError, AppState};
+use crate::services::pdf::{pdf_file_name, AgencyInfo, PdfService};
+use crate::services::policy_lifecycle::{apply_endorsement, calculate_refund, PolicyStatus, RefundMethod};
+use crate::utils::{one_year_after, parse_portal_date};
+use crate::http::routes::PaginationParams;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelPolicyRequest {
+    pub reason: String,
+    /// Varsayılan: bugün
+    #[serde(default)]
+    pub effective_date: Option<String>,
+    #[serde(default)]
+    pub refund_method: RefundMethod,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndorsePolicyRequest {
+    pub reason: String,
+    /// Alan yolu -> yeni değer, ör. {"vehicle.plate": "34XYZ99"}
+    pub changes: serde_json::Map<String, serde_json::Value>,
+    /// Ek prim (+) veya iade (-)
+    #[serde(default)]
+    pub premium_difference: f64,
+    #[serde(default)]
+    pub effective_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewPolicyRequest {
+    /// Yenileme teklifinden kesilmiş yeni poliçe
+    pub renewal_policy_id: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TransitionNote {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Poliçeyi bulur; başkasının poliçesi (acentenin admini hariç) bulunamadı sayılır
+async fn load_policy(state: &AppState, claims: &Claims, id: &str) -> Result<(Policy, PolicyStatus), ApiError> {
+    let policy = policies::get_policy_by_id(&state.db_pool, id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .filter(|p| claims.can_access(&p.user_id, &p.agency_id))
+        .ok_or_else(|| ApiError::FormValidation("Poliçe bulunamadı".to_string()))?;
+
+    let status = PolicyStatus::parse(&policy.status)
+        .ok_or_else(|| ApiError::Unknown(format!("Bilinmeyen poliçe durumu: {}", policy.status)))?;
+    Ok((policy, status))
+}
+
+fn ensure_transition(from: PolicyStatus, to: PolicyStatus) -> Result<(), ApiError> {
+    if from.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(ApiError::Conflict(format!(
+            "Poliçe durumu {} iken {} yapılamaz",
+            from.as_str(),
+            to.as_str()
+        )))
+    }
+}
+
+fn parse_effective_date(value: Option<&str>) -> Result<NaiveDate, ApiError> {
+    match value {
+        Some(text) => parse_portal_date(text)
+            .ok_or_else(|| ApiError::FormValidation(format!("Geçersiz tarih: {}", text))),
+        None => Ok(Utc::now().date_naive()),
+    }
+}
+
+fn require_reason(reason: &str) -> Result<(), ApiError> {
+    if reason.trim().is_empty() {
+        return Err(ApiError::FormValidation("Gerekçe zorunludur".to_string()));
+    }
+    Ok(())
+}
+
+/// transition_policy None döndüyse poliçe bu arada başka bir istekle değişmiştir
+fn changed_concurrently(result: Option<Policy>) -> Result<Policy, ApiError> {
+    result.ok_or_else(|| ApiError::Conflict("Poliçe başka bir işlem tarafından güncellendi, tekrar deneyin".to_string()))
+}
+
+async fn log_transition(state: &AppState, claims: &Claims, action: &str, policy: &Policy, metadata: serde_json::Value) {
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        action,
+        Some("policy"),
+        Some(policy.id.clone()),
+        Some(metadata),
+        None,
+    )
+    .await;
+}
+
+pub async fn get_policy_events_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (policy, _) = load_policy(&state, &claims, &id).await?;
+    let events = policies::list_policy_events(&state.db_pool, &policy.id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(events)))
+}
+
+/// Poliçe iptali: iade, kısa dönem (sigortalı talebi) veya gün esasına göre hesaplanır
+pub async fn cancel_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<CancelPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_reason(&req.reason)?;
+    let (policy, status) = load_policy(&state, &claims, &id).await?;
+    ensure_transition(status, PolicyStatus::Cancelled)?;
+
+    let effective = parse_effective_date(req.effective_date.as_deref())?;
+    let start = policy
+        .starts_at
+        .as_deref()
+        .or(Some(policy.created_at.as_str()))
+        .and_then(parse_portal_date)
+        .ok_or_else(|| ApiError::Unknown("Poliçe başlangıç tarihi okunamadı".to_string()))?;
+    let end = policy
+        .expires_at
+        .as_deref()
+        .and_then(parse_portal_date)
+        .unwrap_or_else(|| one_year_after(start));
+
+    let refund = calculate_refund(policy.premium, start, end, effective, req.refund_method);
+
+    let changes = PolicyChanges {
+        cancelled_at: Some(effective.format("%Y-%m-%d").to_string()),
+        refund_amount: Some(refund.refund),
+        ..Default::default()
+    };
+    let event = NewPolicyEvent {
+        event_type: "cancel",
+        actor_id: &claims.sub,
+        reason: Some(req.reason.trim()),
+        data: Some(serde_json::json!({
+            "effectiveDate": effective.format("%Y-%m-%d").to_string(),
+            "refund": refund,
+        })),
+    };
+    let cancelled = policies::transition_policy(&state.db_pool, &policy.id, status.as_str(), "cancelled", changes, event)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))
+        .and_then(changed_concurrently)?;
+
+    tracing::info!(
+        "🚫 Poliçe iptal edildi: {} (iade {:.2} TL, {})",
+        cancelled.policy_number,
+        refund.refund,
+        refund.method.as_str()
+    );
+    log_transition(&state, &claims, "policy_cancelled", &cancelled, serde_json::json!({
+        "policyNumber": cancelled.policy_number,
+        "refund": refund.refund,
+        "refundMethod": refund.method,
+    }))
+    .await;
+
+    Ok((StatusCode::OK, Json(cancelled)))
+}
+
+/// Zeyilname: değişen alanlar poliçe verisine işlenir, prim farkı poliçe primine eklenir
+pub async fn endorse_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<EndorsePolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    require_reason(&req.reason)?;
+    let (policy, status) = load_policy(&state, &claims, &id).await?;
+    ensure_transition(status, PolicyStatus::Endorsed)?;
+
+    let effective = parse_effective_date(req.effective_date.as_deref())?;
+    if let Some(end) = policy.expires_at.as_deref().and_then(parse_portal_date) {
+        if effective >= end {
+            return Err(ApiError::FormValidation("Zeyilname tarihi poliçe vadesi içinde olmalı".to_string()));
+        }
+    }
+
+    let new_premium = ((policy.premium + req.premium_difference) * 100.0).round() / 100.0;
+    if new_premium < 0.0 {
+        return Err(ApiError::FormValidation("Prim farkı poliçe priminden büyük olamaz".to_string()));
+    }
+
+    let mut policy_data = policy.policy_data.clone();
+    if !policy_data.is_object() {
+        policy_data = serde_json::json!({});
+    }
+    let request = policy_data
+        .as_object_mut()
+        .map(|data| data.entry("request").or_insert_with(|| serde_json::json!({})))
+        .ok_or_else(|| ApiError::Unknown("Poliçe verisi okunamadı".to_string()))?;
+    let applied = apply_endorsement(request, &req.changes).map_err(ApiError::FormValidation)?;
+
+    // Komisyon mevcut oranla prim farkına uygulanır
+    let commission = policy.commission.map(|commission| {
+        let rate = if policy.premium > 0.0 { commission / policy.premium } else { 0.0 };
+        ((commission + req.premium_difference * rate) * 100.0).round() / 100.0
+    });
+
+    let changes = PolicyChanges {
+        premium: Some(new_premium),
+        commission,
+        policy_data: Some(policy_data),
+        ..Default::default()
+    };
+    let event = NewPolicyEvent {
+        event_type: "endorse",
+        actor_id: &claims.sub,
+        reason: Some(req.reason.trim()),
+        data: Some(serde_json::json!({
+            "effectiveDate": effective.format("%Y-%m-%d").to_string(),
+            "changes": applied,
+            "premiumDifference": req.premium_difference,
+            "previousPremium": policy.premium,
+            "newPremium": new_premium,
+        })),
+    };
+    let mut endorsed = policies::transition_policy(&state.db_pool, &policy.id, status.as_str(), "endorsed", changes, event)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))
+        .and_then(changed_concurrently)?;
+
+    // Eski PDF zeyilname öncesi bilgileri içerir
+    if endorsed.pdf_path.is_some() {
+        let _ = policies::set_policy_pdf_path(&state.db_pool, &endorsed.id, None).await;
+        endorsed.pdf_path = None;
+    }
+
+    tracing::info!(
+        "📝 Zeyilname: {} ({} alan, prim farkı {:.2} TL)",
+        endorsed.policy_number,
+        applied.len(),
+        req.premium_difference
+    );
+    log_transition(&state, &claims, "policy_endorsed", &endorsed, serde_json::json!({
+        "policyNumber": endorsed.policy_number,
+        "fields": applied.iter().map(|c| c.field.as_str()).collect::<Vec<_>>(),
+        "premiumDifference": req.premium_difference,
+    }))
+    .await;
+
+    Ok((StatusCode::OK, Json(endorsed)))
+}
+
+/// Yenileme: eski poliçe renewed olur ve yeni poliçeye bağlanır
+pub async fn renew_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<RenewPolicyRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (policy, status) = load_policy(&state, &claims, &id).await?;
+    ensure_transition(status, PolicyStatus::Renewed)?;
+
+    let (renewal, renewal_status) = load_policy(&state, &claims, &req.renewal_policy_id).await?;
+    if renewal.id == policy.id || renewal.user_id != policy.user_id {
+        return Err(ApiError::FormValidation("Yenileme poliçesi bu poliçeye bağlanamaz".to_string()));
+    }
+    if !renewal_status.is_in_force() && renewal_status != PolicyStatus::PendingIssue {
+        return Err(ApiError::FormValidation(format!(
+            "Yenileme poliçesi {} durumunda",
+            renewal_status.as_str()
+        )));
+    }
+    if renewal.renewal_of_policy_id.is_some() {
+        return Err(ApiError::Conflict("Yeni poliçe zaten başka bir poliçenin yenilemesi".to_string()));
+    }
+
+    let changes = PolicyChanges {
+        renewed_by_policy_id: Some(renewal.id.clone()),
+        ..Default::default()
+    };
+    let event = NewPolicyEvent {
+        event_type: "renew",
+        actor_id: &claims.sub,
+        reason: req.reason.as_deref(),
+        data: Some(serde_json::json!({
+            "renewalPolicyId": renewal.id,
+            "renewalPolicyNumber": renewal.policy_number,
+            "provider": renewal.provider,
+            "premium": renewal.premium,
+        })),
+    };
+    let renewed = policies::transition_policy(&state.db_pool, &policy.id, status.as_str(), "renewed", changes, event)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))
+        .and_then(changed_concurrently)?;
+
+    tracing::info!("🔁 Poliçe yenilendi: {} -> {}", renewed.policy_number, renewal.policy_number);
+    log_transition(&state, &claims, "policy_renewed", &renewed, serde_json::json!({
+        "policyNumber": renewed.policy_number,
+        "renewalPolicyNumber": renewal.policy_number,
+    }))
+    .await;
+
+    Ok((StatusCode::OK, Json(renewed)))
+}
+
+/// Admin: portalda kesildiği doğrulanan, kaydı kesim bekleyen olarak yazılmış poliçeyi aktifleştirir;
+/// teklif issue_review durumundan issued'a alınır
+pub async fn activate_policy_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    body: Option<Json<TransitionNote>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let note = body.map(|Json(note)| note).unwrap_or_default();
+    let (policy, status) = load_policy(&state, &claims, &id).await?;
+    ensure_transition(status, PolicyStatus::Active)?;
+
+    let event = NewPolicyEvent {
+        event_type: "activate",
+        actor_id: &claims.sub,
+        reason: note.reason.as_deref(),
+        data: None,
+    };
+    let activated = policies::transition_policy(&state.db_pool, &policy.id, status.as_str(), "active", PolicyChanges::default(), event)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))
+        .and_then(changed_concurrently)?;
+    if let Some(quote_id) = activated.quote_id.as_deref() {
+        quotes::mark_quote_issued(&state.db_pool, quote_id)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    }
+
+    log_transition(&state, &claims, "policy_activated", &activated, serde_json::json!({
+        "policyNumber": activated.policy_number,
+    }))
+    .await;
+
+    Ok((StatusCode::OK, Json(activated)))
+}
+
+/// Admin: vadesi geçmiş poliçeleri expired yapar
+pub async fn expire_policies_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let expired = policies::expire_due_policies(&state.db_pool, &today)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    if !expired.is_empty() {
+        tracing::info!("⌛ {} poliçe vadesi doldu", expired.len());
+    }
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "policies_expired",
+        Some("policy"),
+        None,
+        Some(serde_json::json!({ "count": expired.len() })),
+        None,
+    )
+    .await;
+
+    Ok((StatusCode::OK, Json(serde_json::json!({ "expired": expired.len() }))))
+}
+
+/// Kullanıcının poliçeleri için alınmış yenileme teklifleri
+pub(crate) async fn list_renewal_offers_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<PaginationParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let offers = renewals::list_offers_by_user(&state.db_pool, &claims.sub, params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(offers)))
+}
+
+pub async fn get_policy_renewals_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (policy, _) = load_policy(&state, &claims, &id).await?;
+    let offers = renewals::list_offers_for_policy(&state.db_pool, &policy.id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(offers)))
+}
+
+/// Admin: yenileme taramasını zamanlayıcıyı beklemeden çalıştırır
+pub async fn run_renewals_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let summary = state.renewals.run_once(Utc::now().date_naive()).await?;
+
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "renewals_run",
+        None,
+        None,
+        Some(serde_json::json!(summary)),
+        None,
+    )
+    .await;
+
+    Ok((StatusCode::OK, Json(summary)))
+}
+
+/// Poliçe PDF'ini üretir, PDF_DIR'e kaydeder ve yolunu poliçeye yazar.
+/// Dosya adı poliçe id'sidir: numara şirketler arasında çakışabilir, yalnızca indirme adında kullanılır.
+pub(crate) async fn store_policy_pdf(state: &AppState, policy: &Policy) -> Result<(Vec<u8>, String), ApiError> {
+    let pdf = agency_pdf_service(state, &policy.agency_id).await?;
+    let bytes = pdf.generate_policy_pdf(policy)?;
+    let path = pdf.save(&pdf_file_name("police", &policy.id), &bytes).await?;
+    policies::set_policy_pdf_path(&state.db_pool, &policy.id, Some(&path))
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    tracing::info!("📄 Poliçe PDF'i oluşturuldu: {} -> {}", policy.policy_number, path);
+    Ok((bytes, path))
+}
+
+/// PDF başlığı kaydın acentesinden
+pub(crate) async fn agency_pdf_service(state: &AppState, agency_id: &str) -> Result<PdfService, ApiError> {
+    let agency = agencies::get_agency(&state.db_pool, agency_id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Acente bulunamadı".to_string()))?;
+    let config = state.runtime.config();
+    Ok(PdfService::new(&config, AgencyInfo::for_agency(&config, &agency)))
+}
+
+/// Kayıtlı PDF varsa döner; yoksa (veya dosya silinmişse) yeniden üretir
+pub async fn get_policy_pdf_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let (policy, _) = load_policy(&state, &claims, &id).await?;
+
+    let stored = match &policy.pdf_path {
+        Some(path) => tokio::fs::read(path).await.ok(),
+        None => None,
+    };
+    let bytes = match stored {
+        Some(bytes) => bytes,
+        None => store_policy_pdf(&state, &policy).await?.0,
+    };
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", pdf_file_name("police", &policy.policy_number)),
+            ),
+        ],
+        bytes,
+    ))
+}
@@ -1,14 +1,56 @@
-use crate::auth::{admin_middleware, auth_middleware, Claims};
-use crate::db::{logs, policies, quotes};
+use crate::auth::{admin_middleware, auth_middleware, super_admin_middleware, AuthState, Claims};
+use crate::db::policies::{self, NewPolicy};
+use crate::db::quotes::{self, NewQuote};
+use crate::db::{logs, renewals, users};
 use crate::http::admin_routes::{
-    get_activity_logs_handler, get_admin_stats_handler, get_user_handler, get_users_handler,
+    get_activity_logs_handler, get_admin_stats_handler, get_emails_handler, get_sms_handler,
+    get_user_handler, get_users_handler, reload_config_handler, retry_email_handler,
+};
+use crate::http::agency_routes::{
+    assign_user_handler, create_agency_handler, delete_credential_handler, list_agencies_handler,
+    list_credentials_handler, put_credential_handler, update_agency_handler,
 };
 use crate::http::auth_routes::{login_handler, register_handler};
+use crate::http::commission_routes::{
+    create_commission_rule_handler, delete_commission_rule_handler, list_commission_rules_handler,
+    match_commission_rule_handler, update_commission_rule_handler,
+};
+use crate::http::customer_routes::{
+    get_customer_handler, get_customer_policies_handler, get_customer_quotes_handler, get_customer_vehicles_handler,
+    list_customers_handler, owner,
+};
+use crate::http::export_routes::{admin_export_handler, export_policies_handler, export_quotes_handler};
+use crate::http::policy_routes::{
+    activate_policy_handler, agency_pdf_service, cancel_policy_handler, endorse_policy_handler,
+    expire_policies_handler, get_policy_events_handler, get_policy_pdf_handler, get_policy_renewals_handler,
+    list_renewal_offers_handler, renew_policy_handler, run_renewals_handler, store_policy_pdf,
+};
+use crate::http::search_routes::{
+    admin_search_policies_handler, admin_search_quotes_handler, search_policies_handler, search_quotes_handler,
+};
+use crate::http::sms_routes::{add_sms_opt_out_handler, list_sms_opt_outs_handler, remove_sms_opt_out_handler};
+use crate::http::statement_routes::{
+    get_statement_handler, list_statement_items_handler, list_statements_handler, resolve_statement_item_handler,
+    upload_statement_handler, STATEMENT_BODY_LIMIT,
+};
 use crate::http::user_routes::{change_password_handler, update_profile_handler};
-use crate::http::{ApiError, AppState, HealthResponse, QuoteRequest};
+use crate::http::vehicle_routes::{
+    find_vehicles_handler, get_vehicle_handler, get_vehicle_policies_handler, get_vehicle_quotes_handler,
+};
+use crate::db::models::{Customer, Policy, Quote, Vehicle};
+use crate::services::commission::{calculate_commission, commission_amount, Commission, CommissionContext};
+use crate::services::customer::record_customer;
+use crate::services::vehicle::{apply_vehicle_id, record_vehicle};
+use crate::services::pdf::pdf_file_name;
+use crate::services::PolicyStatus;
+use crate::utils::parse_portal_date;
+use crate::http::{
+    ApiError, AppState, CardDetails, HealthResponse, PaymentDetails, PaymentMethod, PolicyIssueRequest,
+    QuoteRequest, QuoteResponse, SavedQuoteResponse,
+};
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware,
     response::IntoResponse,
     routing::{get, post},
@@ -21,6 +63,11 @@ use std::time::SystemTime;
 use uuid::Uuid;
 
 pub fn create_router(state: AppState) -> Router {
+    let auth_state = AuthState {
+        jwt_secret: state.jwt_secret.clone(),
+        db_pool: state.db_pool.clone(),
+    };
+
     // Public routes (no auth required)
     let public_routes = Router::new()
         .route("/health", get(health_handler))
@@ -34,33 +81,93 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/v1/quote", post(quote_all_handler))
         .route("/api/v1/quote/:provider", post(quote_single_handler))
         .route("/api/v1/quotes/compare", post(compare_quotes_handler))
+        .route("/api/v1/quotes/compare/pdf", post(compare_quotes_pdf_handler))
         .route("/api/v1/quotes", get(list_user_quotes_handler))
+        .route("/api/v1/quotes/export", get(export_quotes_handler))
+        .route("/api/v1/quotes/search", get(search_quotes_handler))
         .route("/api/v1/policies", post(create_policy_handler))
         .route("/api/v1/policies", get(list_user_policies_handler))
+        .route("/api/v1/policies/export", get(export_policies_handler))
+        .route("/api/v1/policies/search", get(search_policies_handler))
+        .route("/api/v1/policies/:id/events", get(get_policy_events_handler))
+        .route("/api/v1/policies/:id/pdf", get(get_policy_pdf_handler))
+        .route("/api/v1/policies/:id/cancel", post(cancel_policy_handler))
+        .route("/api/v1/policies/:id/endorse", post(endorse_policy_handler))
+        .route("/api/v1/policies/:id/renew", post(renew_policy_handler))
+        .route("/api/v1/policies/:id/renewals", get(get_policy_renewals_handler))
+        .route("/api/v1/renewals", get(list_renewal_offers_handler))
+        .route("/api/v1/customers", get(list_customers_handler))
+        .route("/api/v1/customers/:id", get(get_customer_handler))
+        .route("/api/v1/customers/:id/quotes", get(get_customer_quotes_handler))
+        .route("/api/v1/customers/:id/policies", get(get_customer_policies_handler))
+        .route("/api/v1/customers/:id/vehicles", get(get_customer_vehicles_handler))
+        .route("/api/v1/vehicles", get(find_vehicles_handler))
+        .route("/api/v1/vehicles/:id", get(get_vehicle_handler))
+        .route("/api/v1/vehicles/:id/quotes", get(get_vehicle_quotes_handler))
+        .route("/api/v1/vehicles/:id/policies", get(get_vehicle_policies_handler))
+        .route("/api/v1/sms/opt-outs", post(add_sms_opt_out_handler))
         .route("/api/v1/users/profile", axum::routing::put(update_profile_handler))
         .route("/api/v1/users/password", axum::routing::put(change_password_handler))
-        .layer(middleware::from_fn_with_state(
-            state.jwt_secret.clone(),
-            auth_middleware,
-        ))
+        .layer(middleware::from_fn_with_state(auth_state.clone(), auth_middleware))
         .with_state(state.clone());
     
-    // Admin routes
+    // Admin routes (acente admini kendi acentesini, super-admin tüm acenteleri görür)
     let admin_routes = Router::new()
         .route("/api/v1/admin/users", get(get_users_handler))
         .route("/api/v1/admin/users/:id", get(get_user_handler))
         .route("/api/v1/admin/logs", get(get_activity_logs_handler))
         .route("/api/v1/admin/stats", get(get_admin_stats_handler))
+        .route("/api/v1/admin/policies/:id/activate", post(activate_policy_handler))
+        .route("/api/v1/admin/export/:kind", get(admin_export_handler))
+        .route("/api/v1/admin/quotes/search", get(admin_search_quotes_handler))
+        .route("/api/v1/admin/policies/search", get(admin_search_policies_handler))
+        .route("/api/v1/admin/sms/opt-outs", get(list_sms_opt_outs_handler))
+        .route("/api/v1/admin/sms/opt-outs/:phone", axum::routing::delete(remove_sms_opt_out_handler))
+        .route("/api/v1/admin/agencies/:id/credentials", get(list_credentials_handler))
+        .route(
+            "/api/v1/admin/agencies/:id/credentials/:provider",
+            axum::routing::put(put_credential_handler).delete(delete_credential_handler),
+        )
         .layer(middleware::from_fn(admin_middleware))
-        .layer(middleware::from_fn_with_state(
-            state.jwt_secret.clone(),
-            auth_middleware,
-        ));
+        .layer(middleware::from_fn_with_state(auth_state.clone(), auth_middleware));
+
+    // Super-admin routes (acenteler arası / deployment geneli işlemler)
+    let super_admin_routes = Router::new()
+        .route("/api/v1/admin/config/reload", post(reload_config_handler))
+        .route("/api/v1/admin/policies/expire", post(expire_policies_handler))
+        .route("/api/v1/admin/renewals/run", post(run_renewals_handler))
+        .route("/api/v1/admin/emails", get(get_emails_handler))
+        .route("/api/v1/admin/emails/:id/retry", post(retry_email_handler))
+        .route("/api/v1/admin/sms", get(get_sms_handler))
+        .route(
+            "/api/v1/admin/commission-rules",
+            get(list_commission_rules_handler).post(create_commission_rule_handler),
+        )
+        .route("/api/v1/admin/commission-rules/match", get(match_commission_rule_handler))
+        .route(
+            "/api/v1/admin/commission-rules/:id",
+            axum::routing::put(update_commission_rule_handler).delete(delete_commission_rule_handler),
+        )
+        .route(
+            "/api/v1/admin/statements",
+            get(list_statements_handler)
+                .post(upload_statement_handler)
+                .layer(axum::extract::DefaultBodyLimit::max(STATEMENT_BODY_LIMIT)),
+        )
+        .route("/api/v1/admin/statements/:id", get(get_statement_handler))
+        .route("/api/v1/admin/statement-items", get(list_statement_items_handler))
+        .route("/api/v1/admin/statement-items/:id/resolve", post(resolve_statement_item_handler))
+        .route("/api/v1/admin/agencies", get(list_agencies_handler).post(create_agency_handler))
+        .route("/api/v1/admin/agencies/:id", axum::routing::put(update_agency_handler))
+        .route("/api/v1/admin/agencies/:id/users/:user_id", axum::routing::put(assign_user_handler))
+        .layer(middleware::from_fn(super_admin_middleware))
+        .layer(middleware::from_fn_with_state(auth_state.clone(), auth_middleware));
     
     // Tüm route'ları birleştir ve state ekle
     public_routes
         .merge(protected_routes)
         .merge(admin_routes)
+        .merge(super_admin_routes)
         .with_state(state)
 }
 
@@ -94,24 +201,28 @@ async fn metrics_handler() -> impl IntoResponse {
     (StatusCode::OK, metrics)
 }
 
-async fn list_providers_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let providers_info = state.registry.get_providers_info();
-    (StatusCode::OK, Json(providers_info))
+/// Kullanıcının acentesindeki provider'lar (acente kullanıcı bilgisine göre aktif/pasif)
+async fn list_providers_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, ApiError> {
+    let registry = state.runtime.agency_registry(&state.db_pool, &claims.agency_id).await?;
+    Ok((StatusCode::OK, Json(registry.get_providers_info())))
 }
 
 async fn quote_all_handler(
     State(state): State<AppState>,
-    Json(request): Json<QuoteRequest>,
+    Extension(claims): Extension<Claims>,
+    Json(mut request): Json<QuoteRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    apply_vehicle_id(&state.db_pool, &mut request, owner(&claims), &claims.agency_id).await?;
     tracing::info!("📥 Tüm provider'lardan teklif istendi: request_id={}", request.quote_meta.request_id);
     
-    let active_providers = state.registry.get_active_providers();
-    
-    if active_providers.is_empty() {
-        return Err(ApiError::ProviderInactive(
-            "Hiç aktif provider yok".to_string()
-        ));
-    }
+    let active_providers = state
+        .runtime
+        .agency_registry(&state.db_pool, &claims.agency_id)
+        .await?
+        .providers_for_request(&request)?;
     
     let mut quotes = Vec::new();
     let mut errors = Vec::new();
@@ -145,16 +256,31 @@ async fn quote_all_handler(
     Ok((StatusCode::OK, Json(quotes)))
 }
 
+/// Teklif isteğindeki sigortalıyı ve (araç ürünlerinde) aracı kullanıcının acentesinde kaydeder
+async fn record_quote_parties(state: &AppState, request: &QuoteRequest, claims: &Claims) -> (Option<Customer>, Option<Vehicle>) {
+    let customer = record_customer(&state.db_pool, &request.insured, &claims.sub, &claims.agency_id).await;
+    let vehicle = match &customer {
+        Some(customer) if request.coverage.product_type.is_vehicle() => {
+            record_vehicle(&state.db_pool, customer, &request.vehicle, &claims.sub).await
+        }
+        _ => None,
+    };
+    (customer, vehicle)
+}
+
 async fn quote_single_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(provider_name): Path<String>,
-    Json(request): Json<QuoteRequest>,
+    Json(mut request): Json<QuoteRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    apply_vehicle_id(&state.db_pool, &mut request, owner(&claims), &claims.agency_id).await?;
     tracing::info!("📥 {} provider'dan teklif istendi: request_id={}", provider_name, request.quote_meta.request_id);
     
     let provider = state
-        .registry
+        .runtime
+        .agency_registry(&state.db_pool, &claims.agency_id)
+        .await?
         .get_provider(&provider_name)
         .ok_or_else(|| ApiError::ProviderInactive(format!("Provider bulunamadı: {}", provider_name)))?;
     
@@ -166,68 +292,193 @@ async fn quote_single_handler(
         )));
     }
     
+    // Desteklenmeyen ürün / ek teminat / araç kombinasyonu: browser açılmadan reddet
+    provider.check_request(&request)?;
+    
     let quote = provider.fetch_quote(request.clone()).await?;
     
     // Database'e kaydet
-    let _ = quotes::create_quote(
+    let (customer, vehicle) = record_quote_parties(&state, &request, &claims).await;
+    let saved = quotes::create_quote(
         &state.db_pool,
-        &claims.sub,
-        &quote.request_id,
-        serde_json::to_value(&request).unwrap_or_default(),
-        &quote.company,
-        quote.premium.gross,
-        serde_json::to_value(&quote).unwrap_or_default(),
+        &NewQuote {
+            user_id: &claims.sub,
+            request_id: &quote.request_id,
+            request_data: serde_json::to_value(&request).unwrap_or_default(),
+            provider: &quote.company,
+            premium: quote.premium.gross,
+            response_data: serde_json::to_value(&quote).unwrap_or_default(),
+            provider_quote_no: quote.provider_quote_no.as_deref(),
+            valid_until: quote.valid_until.as_deref(),
+            customer_id: customer.as_ref().map(|c| c.id.as_str()),
+            vehicle_id: vehicle.as_ref().map(|v| v.id.as_str()),
+        },
     )
     .await;
     
     tracing::info!("✅ {} - Teklif başarılı: {} TRY", provider.name(), quote.premium.gross);
     
-    Ok((StatusCode::OK, Json(quote)))
+    Ok((StatusCode::OK, Json(saved_quote(quote, saved))))
 }
 
 async fn compare_quotes_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Json(request): Json<QuoteRequest>,
+    Json(mut request): Json<QuoteRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
+    apply_vehicle_id(&state.db_pool, &mut request, owner(&claims), &claims.agency_id).await?;
     tracing::info!("🔍 Tüm provider'lardan karşılaştırmalı teklif istendi");
     
-    let quotes = state.aggregator.fetch_all_quotes(request.clone()).await?;
+    let quotes = state.aggregator.fetch_all_quotes(&claims.agency_id, request.clone()).await?;
     
     // Database'e kaydet
+    let (customer, vehicle) = record_quote_parties(&state, &request, &claims).await;
+    let mut responses = Vec::with_capacity(quotes.len());
     for quote in &quotes {
-        let _ = quotes::create_quote(
+        let saved = quotes::create_quote(
             &state.db_pool,
-            &claims.sub,
-            &quote.request_id,
-            serde_json::to_value(&request).unwrap_or_default(),
-            &quote.company,
-            quote.premium.gross,
-            serde_json::to_value(&quote).unwrap_or_default(),
+            &NewQuote {
+                user_id: &claims.sub,
+                request_id: &quote.request_id,
+                request_data: serde_json::to_value(&request).unwrap_or_default(),
+                provider: &quote.company,
+                premium: quote.premium.gross,
+                response_data: serde_json::to_value(quote).unwrap_or_default(),
+                provider_quote_no: quote.provider_quote_no.as_deref(),
+                valid_until: quote.valid_until.as_deref(),
+                customer_id: customer.as_ref().map(|c| c.id.as_str()),
+                vehicle_id: vehicle.as_ref().map(|v| v.id.as_str()),
+            },
         )
         .await;
+        responses.push(saved_quote(quote.clone(), saved));
     }
     
-    Ok((StatusCode::OK, Json(quotes)))
+    if let Ok(Some(user)) = users::get_user_by_id(&state.db_pool, &claims.sub).await {
+        if let Err(e) = state
+            .email
+            .send_quote_ready(&user.id, &user.email, &user.name, &request, &quotes)
+            .await
+        {
+            tracing::warn!("⚠️ Teklif e-postası kuyruğa alınamadı: {}", e);
+        }
+    }
+    
+    // SMS firması yanıtı isteği bekletmesin
+    let sms = state.sms.clone();
+    let user_id = claims.sub.clone();
+    let sms_request = request.clone();
+    let sms_quotes = quotes.clone();
+    tokio::spawn(async move {
+        if let Err(e) = sms.send_quote_ready(&user_id, &sms_request.insured.phone, &sms_request, &sms_quotes).await {
+            tracing::warn!("⚠️ Teklif SMS'i gönderilemedi: {}", e);
+        }
+    });
+    
+    Ok((StatusCode::OK, Json(responses)))
+}
+
+/// Kaydedilen teklifin id'si yanıta eklenir; kayıt hatası teklifi engellemez
+fn saved_quote(quote: QuoteResponse, saved: Result<Quote, sqlx::Error>) -> SavedQuoteResponse {
+    let quote_id = match saved {
+        Ok(saved) => Some(saved.id),
+        Err(e) => {
+            tracing::error!("❌ Teklif kaydedilemedi ({}): {}", quote.company, e);
+            None
+        }
+    };
+    SavedQuoteResponse { quote_id, quote }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonPdfRequest {
+    /// `POST /api/v1/quotes/compare` isteğinin quoteMeta.requestId değeri
+    pub request_id: String,
+}
+
+/// Kaydedilmiş karşılaştırma tekliflerinden PDF üretir (portaldan yeniden teklif alınmaz)
+pub async fn compare_quotes_pdf_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ComparisonPdfRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stored = quotes::list_quotes_by_request_id(&state.db_pool, &claims.sub, &req.request_id)
+        .await
+        .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+    let first = stored
+        .first()
+        .ok_or_else(|| ApiError::FormValidation("Karşılaştırma teklifleri bulunamadı".to_string()))?;
+
+    let request: QuoteRequest = serde_json::from_value(first.request_data.clone())
+        .map_err(|e| ApiError::ParseError(format!("Teklif isteği okunamadı: {}", e)))?;
+    let responses: Vec<QuoteResponse> = stored
+        .iter()
+        .filter_map(|q| serde_json::from_value(q.response_data.clone()).ok())
+        .collect();
+
+    let bytes = agency_pdf_service(&state, &first.agency_id)
+        .await?
+        .generate_quote_comparison_pdf(&request, &responses)?;
+    tracing::info!("📄 Karşılaştırma PDF'i oluşturuldu: {} ({} teklif)", req.request_id, responses.len());
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{}\"", pdf_file_name("karsilastirma", &req.request_id)),
+            ),
+        ],
+        bytes,
+    ))
 }
 
 #[derive(Debug, Deserialize)]
-struct PaginationParams {
+pub(crate) struct PaginationParams {
     #[serde(default = "default_limit")]
-    limit: i64,
+    pub(crate) limit: i64,
     #[serde(default)]
-    offset: i64,
+    pub(crate) offset: i64,
 }
 
 fn default_limit() -> i64 {
     20
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteListParams {
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+    /// Portal teklif numarasıyla arama
+    #[serde(default)]
+    quote_no: Option<String>,
+}
+
 async fn list_user_quotes_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
-    Query(params): Query<PaginationParams>,
+    Query(params): Query<QuoteListParams>,
 ) -> Result<impl IntoResponse, ApiError> {
+    if let Some(quote_no) = params.quote_no.as_deref().filter(|q| !q.trim().is_empty()) {
+        let found = quotes::find_quotes_by_provider_quote_no(&state.db_pool, &claims.sub, quote_no)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+        let total = found.len();
+        
+        return Ok((
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "quotes": found,
+                "total": total,
+            })),
+        ));
+    }
+    
     let quotes_list = quotes::list_quotes_by_user(&state.db_pool, &claims.sub, params.limit, params.offset)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?;
@@ -245,45 +496,297 @@ async fn list_user_quotes_handler(
     ))
 }
 
+/// Bu süreden eski ve sonuçlanmamış "issuing" denemeleri manuel kontrole alınır (ör. sunucu istek ortasında yeniden başladı)
+const ISSUING_STALE_MINUTES: i64 = 15;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct CreatePolicyRequest {
     quote_id: String,
-    payment_method: String,
-    installment_count: u8,
+    payment_method: PaymentMethod,
+    #[serde(default)]
+    installment_count: Option<u8>,
+    #[serde(default)]
+    card: Option<CardDetails>,
 }
 
+/// Kaydedilmiş teklifi provider portalında poliçeye dönüştürür.
+/// Teklif önce "issuing" durumuna alınır. Portala gönderilmeden oluşan hatalarda bu durumda kalır ve yeniden denenebilir;
+/// sonucu belirsiz hatalarda (timeout, parse) issue_review durumuna alınır.
 async fn create_policy_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Json(req): Json<CreatePolicyRequest>,
 ) -> Result<impl IntoResponse, ApiError> {
-    // Quote'u bul
+    let payment = PaymentDetails {
+        method: req.payment_method,
+        installment_count: req.installment_count.unwrap_or(1),
+        card: req.card,
+    };
+    payment.validate().map_err(ApiError::FormValidation)?;
+    
+    // Teklif yanıtındaki quoteId (başkasının teklifi bulunamadı sayılır).
+    // requestId ile aranmaz: karşılaştırmada aynı requestId altında şirket başına bir kayıt vardır.
     let quote = quotes::get_quote_by_id(&state.db_pool, &req.quote_id)
         .await
         .map_err(|e| ApiError::Unknown(e.to_string()))?
-        .ok_or_else(|| ApiError::FormValidation("Quote bulunamadı".to_string()))?;
+    .filter(|q| claims.can_access(&q.user_id, &q.agency_id))
+    .ok_or_else(|| ApiError::FormValidation("Quote bulunamadı".to_string()))?;
+    
+    if quote.status == "issued" {
+        return Err(ApiError::Conflict("Bu teklif zaten poliçeleştirildi".to_string()));
+    }
+    if quote.status == "issue_review" {
+        return Err(ApiError::Conflict(format!(
+            "Bu teklifin poliçe kesimi manuel kontrol bekliyor: {}",
+            quote.issue_error.as_deref().unwrap_or_default()
+        )));
+    }
     
-    // Poliçe numarası oluştur
-    let policy_number = format!("POL-{}-{}", chrono::Utc::now().timestamp(), uuid::Uuid::new_v4().to_string().split('-').next().unwrap());
+    let quote_request: QuoteRequest = serde_json::from_value(quote.request_data.clone())
+        .map_err(|e| ApiError::FormValidation(format!("Teklif isteği okunamadı: {}", e)))?;
+    let quote_response: QuoteResponse = serde_json::from_value(quote.response_data.clone())
+        .map_err(|e| ApiError::FormValidation(format!("Teklif yanıtı okunamadı: {}", e)))?;
     
-    // Komisyon hesapla (%10 varsayım)
-    let commission = quote.premium * 0.10;
+    // Süresi dolmuş teklif portalda poliçeleştirilemez
+    let valid_until = quote.valid_until.as_deref().or(quote_response.valid_until.as_deref());
+    if let Some(valid_until) = valid_until.and_then(parse_portal_date) {
+        if valid_until < Utc::now().date_naive() {
+            return Err(ApiError::FormValidation(format!(
+                "Teklifin geçerlilik süresi {} tarihinde doldu, yeniden teklif alın",
+                valid_until.format("%d.%m.%Y")
+            )));
+        }
+    }
     
-    let policy = policies::create_policy(
+    if !quote_response.installments.is_empty()
+        && !quote_response.installments.iter().any(|i| i.count == payment.installment_count)
+    {
+        return Err(ApiError::FormValidation(format!(
+            "{} taksit seçeneği bu teklifte yok",
+            payment.installment_count
+        )));
+    }
+    
+    // Poliçe teklifin acentesinin portal hesabıyla kesilir.
+    // Python scraper şirket adını "Sompo Sigorta" gibi döndürebilir
+    let registry = state.runtime.agency_registry(&state.db_pool, &quote.agency_id).await?;
+    let provider = registry
+        .get_provider(&quote.provider)
+        .or_else(|| registry.get_provider(quote.provider.split_whitespace().next().unwrap_or_default()))
+        .ok_or_else(|| ApiError::ProviderInactive(format!("Provider bulunamadı: {}", quote.provider)))?;
+    
+    if !provider.capabilities().policy_issuance {
+        return Err(ApiError::ProviderInactive(format!(
+            "{} poliçe kesimini desteklemiyor",
+            provider.name()
+        )));
+    }
+    
+    let stale_before = (Utc::now() - chrono::Duration::minutes(ISSUING_STALE_MINUTES))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let moved_to_review = quotes::mark_stale_issuing_for_review(&state.db_pool, &quote.id, &stale_before)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if moved_to_review {
+        tracing::warn!("⚠️ Yarıda kalan poliçe kesimi manuel kontrole alındı: quote_id={}", quote.id);
+        return Err(ApiError::Conflict(
+            "Bu teklifin poliçe kesimi yarıda kaldı, portalda kontrol edilmesi gerekiyor".to_string(),
+        ));
+    }
+    
+    let claimed = quotes::claim_quote_for_issuing(&state.db_pool, &quote.id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if !claimed {
+        return Err(ApiError::Conflict("Bu teklif için poliçe kesimi devam ediyor".to_string()));
+    }
+    
+    tracing::info!(
+        "🧾 Poliçe kesimi başlatıldı: quote_id={}, provider={}, deneme={}",
+        quote.id,
+        provider.name(),
+        quote.issue_attempts + 1
+    );
+    
+    let issue_request = PolicyIssueRequest {
+        quote_request: quote_request.clone(),
+        quote: quote_response.clone(),
+        payment: payment.clone(),
+    };
+    
+    let issued = match provider.issue_policy(issue_request).await {
+        Ok(issued) => issued,
+        Err(e) => {
+            tracing::error!("❌ Poliçe kesilemedi: quote_id={} - {}", quote.id, e);
+            let retryable = e.is_before_submission();
+            if retryable {
+                let _ = quotes::mark_quote_issue_failed(&state.db_pool, &quote.id, &e.to_string()).await;
+            } else {
+                // Timeout / parse hatası portal poliçeyi kestikten sonra da oluşabilir: tekrar denemek mükerrer poliçe üretir
+                let note = format!(
+                    "Poliçe kesim sonucu belirsiz, portalda teklif {} kontrol edilmeli: {}",
+                    quote.provider_quote_no.as_deref().unwrap_or("-"),
+                    e
+                );
+                tracing::warn!("⚠️ {}", note);
+                let _ = quotes::mark_quote_issue_review(&state.db_pool, &quote.id, &note).await;
+            }
+            let _ = logs::log_activity(
+                &state.db_pool,
+                &claims.sub,
+                "policy_issue_failed",
+                Some("quote"),
+                Some(quote.id.clone()),
+                Some(serde_json::json!({
+                    "provider": provider.name(),
+                    "error": e.to_string(),
+                    "retryable": retryable,
+                })),
+                None,
+            )
+            .await;
+            return Err(e);
+        }
+    };
+    
+    let premium = issued.premium.unwrap_or(quote.premium);
+    
+    // Komisyon: şirket / ürün / kullanım tipi / prim bandına uyan kural, yoksa DEFAULT_COMMISSION_RATE.
+    // Poliçe portalda kesildi; kural okunamazsa kesim hata vermez, varsayılan oran uygulanır.
+    let default_rate = state.runtime.config().default_commission_rate;
+    let commission_context = CommissionContext {
+        provider: provider.name(),
+        product_type: quote_request.coverage.product_type.as_str(),
+        usage: quote_request
+            .coverage
+            .product_type
+            .is_vehicle()
+            .then(|| quote_request.vehicle.usage.as_str()),
+        premium,
+        date: Utc::now().date_naive(),
+    };
+    let commission = calculate_commission(&state.db_pool, default_rate, &commission_context)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::error!("❌ Komisyon kuralı okunamadı, varsayılan oran uygulandı: {}", e);
+            Commission {
+                amount: commission_amount(premium, default_rate),
+                rate_percent: default_rate,
+                rule_id: None,
+            }
+        });
+    
+    // Kart bilgisi saklanmaz; yalnızca maskeli numara
+    // request: zeyilnamelerle güncellenen sigortalı/araç bilgileri
+    let policy_data = serde_json::json!({
+        "request": quote_request,
+        "quote": quote_response,
+        "payment": {
+            "method": payment.method,
+            "installmentCount": payment.installment_count,
+            "cardNumber": payment.card.as_ref().map(|c| c.masked_number()),
+        },
+        "portal": issued.raw,
+    });
+    
+    let mut record = NewPolicy {
+        user_id: &quote.user_id,
+        quote_id: Some(&quote.id),
+        policy_number: &issued.policy_number,
+        provider: provider.name(),
+        product_type: &quote_response.product_type,
+        premium,
+        commission: Some(commission.amount),
+        commission_rule_id: commission.rule_id.as_deref(),
+        policy_data,
+        starts_at: Some(&issued.start_date),
+        expires_at: Some(&issued.end_date),
+        status: None,
+    };
+    let policy = match policies::create_policy(&state.db_pool, &record).await {
+        Ok(policy) => policy,
+        Err(e) => {
+            // Portalda poliçe kesildi: tekrar denemek mükerrer poliçe üretir.
+            // Kesim bekleyen (pending_issue) olarak kaydedilir, admin portalda kontrol edip aktifleştirir.
+            let message = format!("Poliçe {} portalda kesildi ancak kaydedilemedi: {}", issued.policy_number, e);
+            tracing::error!("❌ {}", message);
+            record.status = Some(PolicyStatus::PendingIssue.as_str());
+            record.commission_rule_id = None;
+            return match policies::create_policy(&state.db_pool, &record).await {
+                Ok(pending) => {
+                    let note = format!("{}; kesim bekleyen poliçe olarak kaydedildi ({})", message, pending.id);
+                    let _ = quotes::mark_quote_issue_review(&state.db_pool, &quote.id, &note).await;
+                    let _ = policies::record_policy_event(
+                        &state.db_pool,
+                        &pending.id,
+                        &pending.status,
+                        policies::NewPolicyEvent {
+                            event_type: "issue",
+                            actor_id: &claims.sub,
+                            reason: Some(&message),
+                            data: Some(serde_json::json!({ "quoteId": quote.id, "provider": provider.name() })),
+                        },
+                    )
+                    .await;
+                    let _ = logs::log_activity(
+                        &state.db_pool,
+                        &claims.sub,
+                        "policy_pending_issue",
+                        Some("policy"),
+                        Some(pending.id.clone()),
+                        Some(serde_json::json!({ "policyNumber": pending.policy_number, "error": e.to_string() })),
+                        None,
+                    )
+                    .await;
+                    tracing::warn!("⏳ Poliçe kesim bekleyen olarak kaydedildi: {}", pending.policy_number);
+                    Ok((StatusCode::ACCEPTED, Json(pending)))
+                }
+                Err(e) => {
+                    // Numara hatada saklanır, manuel kontrol gerekir
+                    let message = format!("{} (kesim bekleyen kayıt da yazılamadı: {})", message, e);
+                    tracing::error!("❌ {}", message);
+                    let _ = quotes::mark_quote_issue_review(&state.db_pool, &quote.id, &message).await;
+                    Err(ApiError::InternalServerError(message))
+                }
+            };
+        }
+    };
+    
+    quotes::mark_quote_issued(&state.db_pool, &quote.id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    
+    let _ = policies::record_policy_event(
         &state.db_pool,
-        &claims.sub,
-        Some(&req.quote_id),
-        &policy_number,
-        &quote.provider,
-        "trafik", // quote.response_data'dan alınabilir
-        quote.premium,
-        Some(commission),
-        quote.response_data.clone(),
-        None,
+        &policy.id,
+        &policy.status,
+        policies::NewPolicyEvent {
+            event_type: "issue",
+            actor_id: &claims.sub,
+            reason: None,
+            data: Some(serde_json::json!({ "quoteId": quote.id, "provider": provider.name() })),
+        },
     )
-    .await
-    .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    .await;
+    
+    tracing::info!("✅ Poliçe kesildi: {} ({})", policy.policy_number, provider.name());
+    
+    notify_policy_created(&state, &policy).await;
+    
+    let sms = state.sms.clone();
+    let (phone, sms_policy) = (quote_request.insured.phone.clone(), policy.clone());
+    tokio::spawn(async move {
+        if let Err(e) = sms.send_policy_issued(&sms_policy.user_id, &phone, &sms_policy).await {
+            tracing::warn!("⚠️ Poliçe SMS'i gönderilemedi: {}", e);
+        }
+    });
+    
+    // Yenileme teklifinden kesildiyse eski poliçe yeni poliçeye bağlanır
+    if let Ok(Some(offer)) = renewals::get_offer_by_quote_id(&state.db_pool, &quote.id).await {
+        link_renewed_policy(&state, &claims, &offer.policy_id, &policy).await;
+    }
     
     // Activity log
     let _ = logs::log_activity(
@@ -295,6 +798,9 @@ async fn create_policy_handler(
         Some(serde_json::json!({
             "provider": policy.provider,
             "premium": policy.premium,
+            "policyNumber": policy.policy_number,
+            "commissionRatePercent": commission.rate_percent,
+            "commissionRuleId": commission.rule_id,
         })),
         None,
     )
@@ -303,6 +809,59 @@ async fn create_policy_handler(
     Ok((StatusCode::CREATED, Json(policy)))
 }
 
+/// Poliçe PDF'ini arşivler ve sahibine PDF ekli bildirim gönderir (PDF üretilemezse eksiz gönderilir)
+async fn notify_policy_created(state: &AppState, policy: &Policy) {
+    let pdf = store_policy_pdf(state, policy)
+        .await
+        .map(|(bytes, _)| bytes)
+        .map_err(|e| tracing::warn!("⚠️ Poliçe PDF'i üretilemedi: {} - {}", policy.policy_number, e))
+        .ok();
+    let Ok(Some(owner)) = users::get_user_by_id(&state.db_pool, &policy.user_id).await else {
+        return;
+    };
+
+    if let Err(e) = state
+        .email
+        .send_policy_created(&owner.id, &owner.email, &owner.name, policy, pdf.as_deref())
+        .await
+    {
+        tracing::warn!("⚠️ Poliçe e-postası kuyruğa alınamadı: {}", e);
+    }
+}
+
+async fn link_renewed_policy(state: &AppState, claims: &Claims, previous_id: &str, renewal: &Policy) {
+    let Ok(Some(previous)) = policies::get_policy_by_id(&state.db_pool, previous_id).await else {
+        return;
+    };
+    let can_renew = PolicyStatus::parse(&previous.status)
+        .map(|status| status.can_transition_to(PolicyStatus::Renewed))
+        .unwrap_or(false);
+    if !can_renew || previous.renewed_by_policy_id.is_some() {
+        return;
+    }
+
+    let changes = policies::PolicyChanges {
+        renewed_by_policy_id: Some(renewal.id.clone()),
+        ..Default::default()
+    };
+    let event = policies::NewPolicyEvent {
+        event_type: "renew",
+        actor_id: &claims.sub,
+        reason: Some("Yenileme teklifinden poliçe kesildi"),
+        data: Some(serde_json::json!({
+            "renewalPolicyId": renewal.id,
+            "renewalPolicyNumber": renewal.policy_number,
+            "provider": renewal.provider,
+            "premium": renewal.premium,
+        })),
+    };
+    match policies::transition_policy(&state.db_pool, &previous.id, &previous.status, "renewed", changes, event).await {
+        Ok(Some(_)) => tracing::info!("🔁 Poliçe yenilendi: {} -> {}", previous.policy_number, renewal.policy_number),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("⚠️ Yenileme bağlantısı kurulamadı: {} - {}", previous.policy_number, e),
+    }
+}
+
 async fn list_user_policies_handler(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -0,0 +1,178 @@
+use crate::auth::Claims;
+use crate::db::search::{self, Cursor, Page, SearchFilter, SearchSort, SortDirection, SortField};
+use crate::http::export_routes::parse_day;
+use crate::http::{ApiError, AppState, UserQuoteResponse};
+use crate::services::vehicle::normalize_plate;
+use crate::utils::fold_turkish;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+/// Teklif / poliçe arama filtreleri. `sort`: createdAt | premium, `direction`: asc | desc.
+/// `cursor` bir önceki yanıtın `nextCursor` değeri; aynı sıralamayla gönderilmeli.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub product_type: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Başlangıç/bitiş günü (dahil): 2025-01-31 veya 31.01.2025
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub min_premium: Option<f64>,
+    #[serde(default)]
+    pub max_premium: Option<f64>,
+    #[serde(default)]
+    pub plate: Option<String>,
+    #[serde(default)]
+    pub tckn: Option<String>,
+    #[serde(default)]
+    pub customer_name: Option<String>,
+    #[serde(default)]
+    pub sort: Option<String>,
+    #[serde(default)]
+    pub direction: Option<String>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    /// Yalnızca admin aramasında; belirtilmezse tüm kullanıcılar
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl SearchQuery {
+    /// Acente filtresi token'dan gelir; super-admin tüm acentelerde
+    fn filter(&self, claims: &Claims, user_id: Option<String>) -> Result<SearchFilter, ApiError> {
+        if let (Some(min), Some(max)) = (self.min_premium, self.max_premium) {
+            if min > max {
+                return Err(ApiError::FormValidation("minPremium, maxPremium'dan büyük olamaz".to_string()));
+            }
+        }
+        Ok(SearchFilter {
+            agency_id: claims.agency_scope().map(str::to_string),
+            user_id,
+            provider: non_empty(&self.provider).map(str::to_string),
+            product_type: non_empty(&self.product_type).map(str::to_lowercase),
+            status: non_empty(&self.status).map(str::to_lowercase),
+            from: parse_day(self.from.as_deref(), "başlangıç")?,
+            to: parse_day(self.to.as_deref(), "bitiş")?,
+            min_premium: self.min_premium,
+            max_premium: self.max_premium,
+            plate: non_empty(&self.plate).map(normalize_plate).filter(|p| !p.is_empty()),
+            identity_no: non_empty(&self.tckn).map(str::to_string),
+            customer_name: non_empty(&self.customer_name)
+                .map(|name| fold_turkish(&name.split_whitespace().collect::<Vec<_>>().join(" "))),
+        })
+    }
+
+    fn sort(&self) -> Result<SearchSort, ApiError> {
+        let mut sort = SearchSort::default();
+        if let Some(value) = self.sort.as_deref().filter(|v| !v.is_empty()) {
+            sort.field = SortField::parse(value)
+                .ok_or_else(|| ApiError::FormValidation(format!("Geçersiz sıralama alanı: {}", value)))?;
+        }
+        if let Some(value) = self.direction.as_deref().filter(|v| !v.is_empty()) {
+            sort.direction = SortDirection::parse(value)
+                .ok_or_else(|| ApiError::FormValidation(format!("Geçersiz sıralama yönü: {}", value)))?;
+        }
+        Ok(sort)
+    }
+
+    fn cursor(&self, sort: SearchSort) -> Result<Option<Cursor>, ApiError> {
+        match self.cursor.as_deref().filter(|v| !v.is_empty()) {
+            Some(value) => Cursor::decode(value, sort)
+                .map(Some)
+                .ok_or_else(|| ApiError::FormValidation("Geçersiz veya sıralamayla uyuşmayan imleç".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    fn limit(&self) -> i64 {
+        self.limit.clamp(1, MAX_SEARCH_LIMIT)
+    }
+}
+
+/// Kullanıcının kendi teklifleri
+pub async fn search_quotes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filter = query.filter(&claims, Some(claims.sub.clone()))?;
+    quotes(&state, &query, filter).await
+}
+
+/// Kullanıcının kendi poliçeleri
+pub async fn search_policies_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filter = query.filter(&claims, Some(claims.sub.clone()))?;
+    policies(&state, &query, filter).await
+}
+
+/// Admin: acentenin tüm kullanıcıları veya `userId`
+pub async fn admin_search_quotes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filter = query.filter(&claims, query.user_id.clone().filter(|id| !id.trim().is_empty()))?;
+    quotes(&state, &query, filter).await
+}
+
+/// Admin: acentenin tüm kullanıcıları veya `userId`
+pub async fn admin_search_policies_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<SearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let filter = query.filter(&claims, query.user_id.clone().filter(|id| !id.trim().is_empty()))?;
+    policies(&state, &query, filter).await
+}
+
+async fn quotes(state: &AppState, query: &SearchQuery, filter: SearchFilter) -> Result<impl IntoResponse, ApiError> {
+    let sort = query.sort()?;
+    let cursor = query.cursor(sort)?;
+    let page = search::search_quotes(&state.db_pool, &filter, sort, cursor.as_ref(), query.limit())
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    let response = Page {
+        items: page.items.into_iter().map(UserQuoteResponse::from).collect::<Vec<_>>(),
+        next_cursor: page.next_cursor,
+    };
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn policies(state: &AppState, query: &SearchQuery, filter: SearchFilter) -> Result<impl IntoResponse, ApiError> {
+    let sort = query.sort()?;
+    let cursor = query.cursor(sort)?;
+    let page = search::search_policies(&state.db_pool, &filter, sort, cursor.as_ref(), query.limit())
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(page)))
+}
@@ -0,0 +1,91 @@
+use crate::auth::Claims;
+use crate::db::{logs, sms};
+use crate::http::routes::PaginationParams;
+use crate::http::{ApiError, AppState};
+use crate::services::sms::normalize_phone;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SmsOptOutRequest {
+    pub phone: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+fn parse_phone(value: &str) -> Result<String, ApiError> {
+    normalize_phone(value).ok_or_else(|| ApiError::FormValidation(format!("Geçersiz cep telefonu: {}", value)))
+}
+
+/// Sigortalının numarasını acentenin SMS red listesine ekler (teklif, poliçe ve yenileme SMS'leri gönderilmez)
+pub async fn add_sms_opt_out_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<SmsOptOutRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let phone = parse_phone(&req.phone)?;
+    let opt_out = sms::add_opt_out(&state.db_pool, &claims.agency_id, &phone, req.reason.as_deref(), Some(&claims.sub))
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    tracing::info!("🔕 SMS red listesine eklendi: {}", phone);
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "sms_opt_out",
+        Some("sms_opt_out"),
+        Some(phone),
+        req.reason.map(|reason| serde_json::json!({ "reason": reason })),
+        None,
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(opt_out)))
+}
+
+/// Admin: numarayı acentenin red listesinden çıkarır (yalnızca sigortalının talebiyle)
+pub async fn remove_sms_opt_out_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(phone): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let phone = parse_phone(&phone)?;
+    let removed = sms::remove_opt_out(&state.db_pool, &phone, claims.agency_scope())
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    if !removed {
+        return Err(ApiError::FormValidation("Numara SMS red listesinde bulunamadı".to_string()));
+    }
+
+    tracing::info!("🔔 SMS red listesinden çıkarıldı: {}", phone);
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "sms_opt_in",
+        Some("sms_opt_out"),
+        Some(phone),
+        None,
+        None,
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Admin: acentenin red listesi (super-admin tüm acenteler)
+pub(crate) async fn list_sms_opt_outs_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<PaginationParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    let opt_outs = sms::list_opt_outs(&state.db_pool, claims.agency_scope(), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(opt_outs)))
+}
@@ -1,15 +1,19 @@
 use crate::config::Config;
 use crate::db::DbPool;
-use crate::providers::ProviderRegistry;
-use crate::services::QuoteAggregator;
+use crate::services::{EmailService, QuoteAggregator, RenewalService, RuntimeHandle, SmsService};
 use std::sync::Arc;
 use std::time::SystemTime;
 
 #[derive(Clone)]
 pub struct AppState {
+    /// Başlangıç config'i (restart gerektiren alanlar: HTTP_ADDR, DATABASE_URL, ...)
     pub config: Arc<Config>,
-    pub registry: Arc<ProviderRegistry>,
+    /// Hot reload edilebilen config + provider registry
+    pub runtime: Arc<RuntimeHandle>,
     pub aggregator: Arc<QuoteAggregator>,
+    pub renewals: Arc<RenewalService>,
+    pub email: Arc<EmailService>,
+    pub sms: Arc<SmsService>,
     pub db_pool: DbPool,
     pub jwt_secret: String,
     pub start_time: SystemTime,
@@ -0,0 +1,253 @@
+use crate::auth::Claims;
+use crate::db::statements::NewStatement;
+use crate::db::{agencies, logs, statements};
+use crate::http::{ApiError, AppState};
+use crate::services::statement::import_statement;
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+/// Ekstre dosyası için izin verilen en büyük gövde
+pub const STATEMENT_BODY_LIMIT: usize = 20 * 1024 * 1024;
+
+const ITEM_STATUSES: &[&str] = &["matched", "amount_mismatch", "missing_ours", "missing_theirs"];
+
+/// Dosya ham gövde olarak gönderilir (CSV veya XLSX)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementUploadQuery {
+    /// Ekstrenin ait olduğu acente; mutabakat yalnızca bu acentenin poliçeleriyle yapılır
+    pub agency_id: String,
+    pub provider: String,
+    /// YYYY-MM
+    pub period: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
+}
+
+fn validate_period(period: &str) -> Result<(), ApiError> {
+    NaiveDate::parse_from_str(&format!("{}-01", period), "%Y-%m-%d")
+        .ok()
+        .filter(|_| period.len() == 7)
+        .map(|_| ())
+        .ok_or_else(|| ApiError::FormValidation(format!("Dönem YYYY-MM olmalı: {}", period)))
+}
+
+fn validate_status(status: Option<&str>) -> Result<(), ApiError> {
+    match status {
+        Some(status) if !ITEM_STATUSES.contains(&status) => {
+            Err(ApiError::FormValidation(format!("Geçersiz durum: {}", status)))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub async fn upload_statement_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<StatementUploadQuery>,
+    body: Bytes,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_period(&params.period)?;
+    if body.is_empty() {
+        return Err(ApiError::FormValidation("Ekstre dosyası boş".to_string()));
+    }
+
+    let agency = agencies::get_agency(&state.db_pool, &params.agency_id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Acente bulunamadı".to_string()))?;
+
+    let registry = state.runtime.registry();
+    let provider = registry
+        .get_provider(&params.provider)
+        .ok_or_else(|| ApiError::FormValidation(format!("Provider bulunamadı: {}", params.provider)))?;
+    let mapping = registry.statement_mapping(provider.name());
+
+    tracing::info!(
+        "📥 {} {} {} komisyon ekstresi yükleniyor ({} bayt)",
+        agency.name,
+        provider.name(),
+        params.period,
+        body.len()
+    );
+    let statement = import_statement(
+        &state.db_pool,
+        &NewStatement {
+            agency_id: &agency.id,
+            provider: provider.name(),
+            period: &params.period,
+            file_name: params.file_name.as_deref(),
+            uploaded_by: Some(&claims.sub),
+        },
+        &body,
+        mapping.as_ref(),
+    )
+    .await?;
+
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "statement_imported",
+        Some("provider_statement"),
+        Some(statement.id.clone()),
+        serde_json::to_value(&statement).ok(),
+        None,
+    )
+    .await;
+
+    Ok((StatusCode::CREATED, Json(statement)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementListQuery {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+pub async fn list_statements_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Query(params): Query<StatementListQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let statements = statements::list_statements(&state.db_pool, params.provider.as_deref(), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "statements": statements,
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementItemsQuery {
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Yalnızca çözülmemiş farklar
+    #[serde(default)]
+    pub open: bool,
+    #[serde(default = "default_item_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_item_limit() -> i64 {
+    500
+}
+
+/// Mutabakat raporu: özet ve satırlar
+pub async fn get_statement_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<StatementItemsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_status(params.status.as_deref())?;
+    let statement = statements::get_statement(&state.db_pool, &id)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Ekstre bulunamadı".to_string()))?;
+    let items = statements::list_items(
+        &state.db_pool,
+        Some(&id),
+        params.status.as_deref(),
+        params.open,
+        params.limit,
+        params.offset,
+    )
+    .await
+    .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "statement": statement,
+            "items": items,
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
+
+/// Tüm ekstrelerdeki farklar (takip listesi)
+pub async fn list_statement_items_handler(
+    State(state): State<AppState>,
+    _claims: Extension<Claims>,
+    Query(params): Query<StatementItemsQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    validate_status(params.status.as_deref())?;
+    let items = statements::list_items(
+        &state.db_pool,
+        None,
+        params.status.as_deref(),
+        params.open,
+        params.limit,
+        params.offset,
+    )
+    .await
+    .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "items": items,
+            "limit": params.limit,
+            "offset": params.offset,
+        })),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveItemRequest {
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+pub async fn resolve_statement_item_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(req): Json<ResolveItemRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let note = req.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    let item = statements::resolve_item(&state.db_pool, &id, note, &claims.sub)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| {
+            ApiError::FormValidation("Açık mutabakat farkı bulunamadı (eşleşmiş veya zaten çözülmüş)".to_string())
+        })?;
+
+    tracing::info!("✅ Mutabakat farkı çözüldü: {} ({})", item.policy_number, item.status);
+    let _ = logs::log_activity(
+        &state.db_pool,
+        &claims.sub,
+        "statement_item_resolved",
+        Some("statement_item"),
+        Some(item.id.clone()),
+        Some(serde_json::json!({ "statementId": item.statement_id, "note": note })),
+        None,
+    )
+    .await;
+
+    Ok((StatusCode::OK, Json(item)))
+}
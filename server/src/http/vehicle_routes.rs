@@ -0,0 +1,97 @@
+use crate::auth::Claims;
+use crate::db::models::Vehicle;
+use crate::db::vehicles;
+use crate::http::customer_routes::owner;
+use crate::http::{ApiError, AppState, UserQuoteResponse};
+use crate::services::vehicle::normalize_plate;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct VehicleSearchQuery {
+    #[serde(default)]
+    pub plate: Option<String>,
+    #[serde(default)]
+    pub vin: Option<String>,
+}
+
+/// Plaka veya şasi no ile araç (teklifte `vehicleId` olarak kullanılır)
+pub async fn find_vehicles_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<VehicleSearchQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let plate = params.plate.as_deref().map(normalize_plate).filter(|p| !p.is_empty());
+    let vin = params.vin.as_deref().map(str::trim).filter(|v| !v.is_empty());
+    if plate.is_none() && vin.is_none() {
+        return Err(ApiError::FormValidation("Plaka veya şasi no gerekli".to_string()));
+    }
+
+    let vehicles = vehicles::find_vehicles(&state.db_pool, plate.as_deref(), vin, owner(&claims), claims.agency_scope(), 50)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(vehicles)))
+}
+
+pub async fn get_vehicle_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let vehicle = find_vehicle(&state, &claims, &id).await?;
+    Ok((StatusCode::OK, Json(vehicle)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VehicleHistoryQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+pub async fn get_vehicle_quotes_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<VehicleHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let vehicle = find_vehicle(&state, &claims, &id).await?;
+    let quotes = vehicles::list_vehicle_quotes(&state.db_pool, &vehicle.id, owner(&claims), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    let response: Vec<UserQuoteResponse> = quotes.into_iter().map(UserQuoteResponse::from).collect();
+    Ok((StatusCode::OK, Json(response)))
+}
+
+pub async fn get_vehicle_policies_handler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<VehicleHistoryQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let vehicle = find_vehicle(&state, &claims, &id).await?;
+    let policies = vehicles::list_vehicle_policies(&state.db_pool, &vehicle.id, owner(&claims), params.limit, params.offset)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    Ok((StatusCode::OK, Json(policies)))
+}
+
+async fn find_vehicle(state: &AppState, claims: &Claims, id: &str) -> Result<Vehicle, ApiError> {
+    vehicles::get_vehicle(&state.db_pool, id, owner(claims), claims.agency_scope())
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation("Araç bulunamadı".to_string()))
+}
@@ -0,0 +1,8 @@
+pub mod auth;
+pub mod browser;
+pub mod config;
+pub mod db;
+pub mod http;
+pub mod providers;
+pub mod services;
+pub mod utils;
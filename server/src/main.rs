@@ -1,17 +1,7 @@
-mod auth;
-mod browser;
-mod config;
-mod db;
-mod http;
-mod providers;
-mod services;
-mod utils;
-
-use crate::config::Config;
-use crate::db::{create_pool, run_migrations};
-use crate::http::{create_router, AppState};
-use crate::providers::ProviderRegistry;
-use crate::services::QuoteAggregator;
+use sigorta_server::config::Config;
+use sigorta_server::db::{create_pool, run_migrations};
+use sigorta_server::http::{create_router, AppState};
+use sigorta_server::services::{EmailService, QuoteAggregator, RenewalService, RuntimeHandle, SmsService};
 use std::sync::Arc;
 use std::time::SystemTime;
 use tower_http::cors::{Any, CorsLayer};
@@ -67,8 +57,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap_or_else(|_| "dev-secret-change-in-production".to_string());
     
     // Provider registry oluştur
-    let registry = Arc::new(ProviderRegistry::new(config.clone()));
-    let providers_info = registry.get_providers_info();
+    let runtime = Arc::new(
+        RuntimeHandle::new(config.clone()).expect("❌ Config / provider config geçersiz!")
+    );
+    let providers_info = runtime.registry().get_providers_info();
     tracing::info!("✅ Provider registry oluşturuldu");
     tracing::info!("   Toplam Provider: {}", providers_info.total);
     tracing::info!("   Aktif Provider: {}", providers_info.active_count);
@@ -82,13 +74,36 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // Quote aggregator
-    let aggregator = Arc::new(QuoteAggregator::new(registry.clone()));
+    let aggregator = Arc::new(QuoteAggregator::new(runtime.clone(), db_pool.clone()));
+    
+    // .env / PROVIDERS_CONFIG değişikliklerini izle (restart gerekmez)
+    runtime.clone().spawn_watcher();
+    
+    // E-posta outbox worker'ı (SMTP_HOST yoksa e-posta gönderilmez)
+    let email = Arc::new(EmailService::new(&config, db_pool.clone()));
+    email.clone().spawn_worker();
+    
+    // Sigortalıya SMS bildirimleri (SMS_GATEWAY boşsa gönderilmez)
+    let sms = Arc::new(SmsService::new(&config, db_pool.clone()));
+    
+    // Vadesi yaklaşan poliçeler için yenileme teklifleri
+    let renewals = Arc::new(RenewalService::new(
+        runtime.clone(),
+        aggregator.clone(),
+        email.clone(),
+        sms.clone(),
+        db_pool.clone(),
+    ));
+    renewals.clone().spawn_scheduler();
     
     // App state
     let state = AppState {
         config: config.clone(),
-        registry,
+        runtime,
         aggregator,
+        renewals,
+        email,
+        sms,
         db_pool,
         jwt_secret,
         start_time: SystemTime::now(),
@@ -0,0 +1,73 @@
+use crate::browser::session::SessionManager;
+use crate::browser::{any_present, capture_session, click_first, fill_first, restore_session};
+use crate::config::Config;
+use crate::http::ApiError;
+use crate::providers::anadolu::selectors::AnadoluSelectors;
+use crate::utils::mask_sensitive;
+use fantoccini::{Client, Locator};
+use std::sync::Arc;
+
+pub async fn login_to_anadolu(
+    client: &Client,
+    config: Arc<Config>,
+    session_manager: &SessionManager,
+) -> Result<(), ApiError> {
+    tracing::info!("🔍 Anadolu'ya bağlanılıyor: {}", config.anadolu_url);
+    tracing::info!("👤 Kullanıcı: {}", mask_sensitive(&config.anadolu_username));
+
+    // Önce session cache'i dene
+    if let Some(session) = session_manager.load_session("anadolu") {
+        tracing::info!("📦 Cached Anadolu session bulundu, yükleniyor...");
+
+        match restore_session(client, &session, &config.anadolu_url).await {
+            Ok(()) if any_present(client, AnadoluSelectors::LOGGED_IN_INDICATORS).await => {
+                tracing::info!("✅ Anadolu session geçerli, login atlandı");
+                return Ok(());
+            }
+            Ok(()) => tracing::warn!("⚠️ Anadolu session geçersiz, yeniden login..."),
+            Err(e) => tracing::warn!("⚠️ Anadolu session restore başarısız: {}, yeniden login...", e),
+        }
+        session_manager.clear_session("anadolu").ok();
+    }
+
+    client.goto(&config.anadolu_url).await
+        .map_err(|e| ApiError::WebDriverError(format!("Sayfa yüklenemedi: {}", e)))?;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+
+    if !fill_first(client, AnadoluSelectors::USERNAME_INPUTS, &config.anadolu_username).await {
+        return Err(ApiError::LoginFailed("Anadolu kullanıcı adı alanı bulunamadı".to_string()));
+    }
+    if !fill_first(client, AnadoluSelectors::PASSWORD_INPUTS, &config.anadolu_password).await {
+        return Err(ApiError::LoginFailed("Anadolu şifre alanı bulunamadı".to_string()));
+    }
+
+    if !click_first(client, AnadoluSelectors::LOGIN_BUTTONS).await {
+        return Err(ApiError::LoginFailed("Anadolu login butonu bulunamadı".to_string()));
+    }
+    tracing::info!("✅ Anadolu login butonu tıklandı");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
+
+    // Hata mesajı kontrolü
+    for selector in AnadoluSelectors::LOGIN_ERRORS {
+        if let Ok(elem) = client.find(Locator::Css(selector)).await {
+            let text = elem.text().await.unwrap_or_default();
+            if !text.trim().is_empty() {
+                return Err(ApiError::LoginFailed(format!("Anadolu login hatası: {}", text.trim())));
+            }
+        }
+    }
+
+    if !any_present(client, AnadoluSelectors::LOGGED_IN_INDICATORS).await {
+        return Err(ApiError::LoginFailed("Anadolu login doğrulanamadı".to_string()));
+    }
+
+    tracing::info!("✅ Anadolu login başarılı");
+
+    let session = capture_session(client).await?;
+    session_manager.save_session("anadolu", session)
+        .map_err(|e| ApiError::Unknown(format!("Session kaydetme hatası: {}", e)))?;
+
+    Ok(())
+}
@@ -1,4 +1,10 @@
+mod login;
+pub mod parser;
+pub mod quote;
+mod selectors;
+
 use crate::config::Config;
+use crate::http::models::{ProductType, ProviderCapabilities, VehicleCategory, VehicleUsage};
 use crate::http::{ApiError, QuoteRequest, QuoteResponse};
 use crate::providers::base::InsuranceProvider;
 use async_trait::async_trait;
@@ -21,28 +27,42 @@ impl InsuranceProvider for AnadoluProvider {
     }
     
     fn is_active(&self) -> bool {
-        std::env::var("ANADOLU_USERNAME").is_ok()
+        !self.config.anadolu_url.is_empty()
+            && !self.config.anadolu_username.is_empty()
+            && !self.config.anadolu_password.is_empty()
     }
     
     fn inactive_reason(&self) -> Option<String> {
-        if !self.is_active() {
-            Some("Henüz kayıtlı değil".to_string())
+        if self.config.anadolu_url.is_empty() {
+            Some("ANADOLU_URL yapılandırılmamış".to_string())
+        } else if !self.is_active() {
+            Some("Credentials yapılandırılmamış".to_string())
         } else {
             None
         }
     }
     
-    fn supported_products(&self) -> Vec<String> {
-        vec!["trafik".to_string(), "kasko".to_string(), "konut".to_string()]
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supported_products: vec![ProductType::Trafik, ProductType::Kasko, ProductType::Konut],
+            supported_addons: vec![],
+            usage_types: vec![VehicleUsage::Hususi, VehicleUsage::Ticari],
+            vehicle_categories: vec![
+                VehicleCategory::Otomobil,
+                VehicleCategory::Kamyonet,
+                VehicleCategory::Motosiklet,
+            ],
+            policy_issuance: false,
+        }
     }
     
-    async fn fetch_quote(&self, _request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+    async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
         if !self.is_active() {
             return Err(ApiError::ProviderInactive(
                 "Anadolu credentials yapılandırılmamış".to_string()
             ));
         }
-        Err(ApiError::ProviderInactive("Anadolu entegrasyonu devam ediyor".to_string()))
+
+        quote::fetch_anadolu_quote(self.config.clone(), request).await
     }
 }
-
@@ -0,0 +1,56 @@
+use crate::http::{ApiError, Coverage, Installment};
+use crate::providers::anadolu::selectors::AnadoluSelectors;
+use crate::providers::parsing::{
+    extract_coverages, extract_installments, extract_quote_reference, find_price, ParsedQuote,
+};
+use scraper::Html;
+
+/// Anadolu sonuç sayfası HTML'inden fiyat, taksit ve teminatları çıkarır (browser gerektirmez)
+pub fn parse_anadolu_html(html: &str, product_type: &str) -> Result<ParsedQuote, ApiError> {
+    let document = Html::parse_document(html);
+
+    // Konut primleri trafik aralığının altında kalabilir
+    let premium = find_price(&document, AnadoluSelectors::PRICE_ELEMENTS, 100.0, 250000.0)
+        .ok_or_else(|| ApiError::ParseError("Anadolu fiyat bulunamadı".to_string()))?;
+
+    let mut installments = extract_installments(&document);
+    if installments.is_empty() {
+        installments = vec![
+            Installment {
+                count: 1,
+                per_installment: premium,
+                total: premium,
+            },
+        ];
+    }
+
+    let mut coverages = extract_coverages(&document);
+    if coverages.is_empty() {
+        coverages = match product_type {
+            "trafik" => vec![Coverage {
+                code: "TRAFIK_ZORUNLU".to_string(),
+                name: "Zorunlu Trafik Sigortası".to_string(),
+                limit: None,
+                included: true,
+            }],
+            "konut" => vec![Coverage {
+                code: "KONUT_YANGIN".to_string(),
+                name: "Yangın".to_string(),
+                limit: None,
+                included: true,
+            }],
+            _ => vec![],
+        };
+    }
+
+    Ok(ParsedQuote {
+        premium,
+        installments,
+        coverages,
+        reference: extract_quote_reference(
+            &document,
+            AnadoluSelectors::QUOTE_NO_ELEMENTS,
+            chrono::Utc::now().date_naive(),
+        ),
+    })
+}
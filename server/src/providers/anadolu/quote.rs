@@ -0,0 +1,268 @@
+use crate::browser::{any_present, click_first, create_webdriver_client, fill_first, select_first, SessionManager};
+use crate::config::Config;
+use crate::http::models::{ProductType, PropertyInfo};
+use crate::http::{ApiError, PremiumDetail, QuoteRequest, QuoteResponse, Timings};
+use crate::providers::anadolu::login::login_to_anadolu;
+use crate::providers::anadolu::parser::parse_anadolu_html;
+use crate::providers::anadolu::selectors::AnadoluSelectors;
+use fantoccini::{Client, Locator};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub async fn fetch_anadolu_quote(
+    config: Arc<Config>,
+    request: QuoteRequest,
+) -> Result<QuoteResponse, ApiError> {
+    let scrape_start = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let product_type = match request.coverage.product_type {
+        ProductType::Trafik => "trafik",
+        ProductType::Kasko => "kasko",
+        ProductType::Konut => "konut",
+        _ => return Err(ApiError::FormValidation("Desteklenmeyen ürün tipi".to_string())),
+    };
+
+    // Konut bilgileri browser açılmadan doğrulanır
+    if product_type == "konut" {
+        validate_property(request.property.as_ref())?;
+    }
+
+    tracing::info!("🚀 Anadolu {} teklifi başlatıldı: request_id={}", product_type, request.quote_meta.request_id);
+
+    let client = create_webdriver_client(&config).await
+        .map_err(|e| ApiError::WebDriverError(format!("WebDriver: {}", e)))?;
+
+    let session_manager = SessionManager::new(&config.session_dir);
+
+    if let Err(e) = login_to_anadolu(&client, config.clone(), &session_manager).await {
+        let _ = client.close().await;
+        return Err(e);
+    }
+
+    let result = fill_and_parse(&client, &config, &request, product_type, scrape_start).await;
+
+    // Hata durumunda screenshot (debug için)
+    if result.is_err() {
+        let screenshot_path = format!("{}/anadolu_error_{}.png", config.artifact_dir, request.quote_meta.request_id);
+        if let Ok(png_data) = client.screenshot().await {
+            if std::fs::write(&screenshot_path, &png_data).is_ok() {
+                tracing::info!("📸 Error screenshot: {}", screenshot_path);
+            }
+        }
+    }
+
+    let _ = client.close().await;
+
+    result
+}
+
+/// Konut teklifi için DASK poliçe no veya eksiksiz adres/bina bilgisi gerekir
+pub fn validate_property(property: Option<&PropertyInfo>) -> Result<&PropertyInfo, ApiError> {
+    let property = property.ok_or_else(|| {
+        ApiError::FormValidation("Konut teklifi için property bilgileri gerekli".to_string())
+    })?;
+
+    let has_dask = property.dask_policy_no.as_deref().is_some_and(|no| !no.trim().is_empty());
+    let missing = property.missing_fields();
+    if !has_dask && !missing.is_empty() {
+        return Err(ApiError::FormValidation(format!(
+            "Konut teklifi için eksik alanlar: {} (veya daskPolicyNo gönderin)",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(property)
+}
+
+async fn fill_and_parse(
+    client: &Client,
+    config: &Config,
+    request: &QuoteRequest,
+    product_type: &str,
+    scrape_start: u64,
+) -> Result<QuoteResponse, ApiError> {
+    // Ürün sayfasına geç (link yoksa form zaten açık varsayılır)
+    let product_links = match product_type {
+        "kasko" => AnadoluSelectors::KASKO_LINKS,
+        "konut" => AnadoluSelectors::KONUT_LINKS,
+        _ => AnadoluSelectors::TRAFIK_LINKS,
+    };
+    if click_first(client, product_links).await {
+        tracing::info!("✅ Anadolu {} formu açıldı", product_type);
+        tokio::time::sleep(Duration::from_millis(2000)).await;
+    }
+
+    fill_first(client, AnadoluSelectors::TCKN_INPUTS, &request.insured.tckn).await;
+    fill_first(client, AnadoluSelectors::BIRTH_DATE_INPUTS, &request.insured.birth_date).await;
+
+    let mut warnings = Vec::new();
+
+    if product_type == "konut" {
+        let property = validate_property(request.property.as_ref())?;
+        fill_property_form(client, property, &mut warnings).await?;
+    } else {
+        if !fill_first(client, AnadoluSelectors::PLATE_INPUTS, &request.vehicle.plate).await {
+            return Err(ApiError::FormValidation("Anadolu plaka alanı bulunamadı".to_string()));
+        }
+        if product_type == "kasko" {
+            fill_first(client, AnadoluSelectors::BRAND_INPUTS, &request.vehicle.brand).await;
+            fill_first(client, AnadoluSelectors::MODEL_INPUTS, &request.vehicle.model).await;
+            fill_first(client, AnadoluSelectors::MODEL_YEAR_INPUTS, &request.vehicle.year.to_string()).await;
+        }
+    }
+
+    if !click_first(client, AnadoluSelectors::FORM_SUBMIT_BUTTONS).await {
+        return Err(ApiError::FormValidation("Anadolu form submit butonu bulunamadı".to_string()));
+    }
+
+    // Python: page.wait_for_selector('.result .price', timeout=30000)
+    client
+        .wait()
+        .at_most(Duration::from_millis(config.request_timeout_ms.min(30000)))
+        .for_element(Locator::Css(AnadoluSelectors::RESULT_CONTAINER))
+        .await
+        .map_err(|e| ApiError::Timeout(format!("Anadolu sonuç sayfası yüklenmedi: {}", e)))?;
+
+    let html = client.source().await
+        .map_err(|e| ApiError::ParseError(format!("Anadolu sayfa kaynağı alınamadı: {}", e)))?;
+
+    let parsed = parse_anadolu_html(&html, product_type)?;
+    let premium = parsed.premium;
+
+    let net = premium / 1.18;
+    let taxes = premium - net;
+
+    let scrape_elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64 - scrape_start;
+
+    tracing::info!("✅ Anadolu teklifi alındı: {:.2} TL", premium);
+
+    Ok(QuoteResponse {
+        request_id: request.quote_meta.request_id.clone(),
+        company: "Anadolu".to_string(),
+        product_type: product_type.to_string(),
+        premium: PremiumDetail {
+            net: (net * 100.0).round() / 100.0,
+            gross: (premium * 100.0).round() / 100.0,
+            taxes: (taxes * 100.0).round() / 100.0,
+            currency: "TRY".to_string(),
+        },
+        installments: parsed.installments,
+        coverages: parsed.coverages,
+        warnings,
+        provider_quote_no: parsed.reference.quote_no.clone(),
+        valid_until: parsed.reference.valid_until_string(),
+        raw: None,
+        timings: Some(Timings {
+            queued_ms: 0,
+            scrape_ms: scrape_elapsed,
+        }),
+    })
+}
+
+/// Konut formunu doldurur: önce DASK sorgusu, başarısızsa manuel adres/bina bilgileri
+async fn fill_property_form(
+    client: &Client,
+    property: &PropertyInfo,
+    warnings: &mut Vec<String>,
+) -> Result<(), ApiError> {
+    if let Some(dask_no) = property.dask_policy_no.as_deref().filter(|no| !no.trim().is_empty()) {
+        match lookup_dask_policy(client, dask_no.trim()).await {
+            Ok(()) => {
+                tracing::info!("✅ DASK poliçesi bulundu, adres bilgileri portalden dolduruldu");
+                return Ok(());
+            }
+            Err(e) if property.missing_fields().is_empty() => {
+                tracing::warn!("⚠️ DASK sorgusu başarısız: {}, manuel bilgilerle devam ediliyor", e);
+                warnings.push(format!("DASK poliçe sorgusu başarısız, girilen adres bilgileri kullanıldı: {}", e));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    if !fill_first(client, AnadoluSelectors::CITY_INPUTS, &property.city).await {
+        return Err(ApiError::FormValidation("Anadolu il alanı bulunamadı".to_string()));
+    }
+    fill_first(client, AnadoluSelectors::DISTRICT_INPUTS, &property.district).await;
+    fill_first(client, AnadoluSelectors::ADDRESS_INPUTS, &property.address).await;
+
+    if let Some(building_type) = &property.building_type {
+        if !select_first(client, AnadoluSelectors::BUILDING_TYPE_SELECTS, building_type.as_str()).await {
+            tracing::warn!("⚠️ Anadolu bina tipi seçilemedi: {}", building_type.as_str());
+        }
+    }
+
+    let numeric_fields = [
+        (AnadoluSelectors::SQUARE_METERS_INPUTS, property.square_meters.map(|v| v.to_string())),
+        (AnadoluSelectors::BUILDING_YEAR_INPUTS, property.building_year.map(|v| v.to_string())),
+        (AnadoluSelectors::FLOOR_COUNT_INPUTS, property.number_of_floors.map(|v| v.to_string())),
+        (AnadoluSelectors::FLOOR_INPUTS, property.floor.map(|v| v.to_string())),
+    ];
+    for (selectors, value) in numeric_fields {
+        if let Some(value) = value {
+            fill_first(client, selectors, &value).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// DASK poliçe numarasıyla portalde adres/bina bilgisi sorgular
+async fn lookup_dask_policy(client: &Client, dask_no: &str) -> Result<(), ApiError> {
+    tracing::info!("🏠 DASK poliçe sorgusu: {}", dask_no);
+
+    if !fill_first(client, AnadoluSelectors::DASK_POLICY_INPUTS, dask_no).await {
+        return Err(ApiError::FormValidation("Anadolu DASK poliçe no alanı bulunamadı".to_string()));
+    }
+    if !click_first(client, AnadoluSelectors::DASK_LOOKUP_BUTTONS).await {
+        return Err(ApiError::FormValidation("Anadolu DASK sorgu butonu bulunamadı".to_string()));
+    }
+
+    client
+        .wait()
+        .at_most(Duration::from_secs(15))
+        .for_element(Locator::Css(AnadoluSelectors::DASK_LOOKUP_RESULT))
+        .await
+        .map_err(|e| ApiError::Timeout(format!("DASK sorgu sonucu gelmedi: {}", e)))?;
+
+    if any_present(client, AnadoluSelectors::DASK_LOOKUP_ERRORS).await {
+        return Err(ApiError::FormValidation(format!("DASK poliçesi bulunamadı: {}", dask_no)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::models::BuildingType;
+
+    #[test]
+    fn test_validate_property() {
+        assert!(validate_property(None).is_err());
+
+        let dask_only = PropertyInfo {
+            dask_policy_no: Some("12345678".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_property(Some(&dask_only)).is_ok());
+
+        let partial = PropertyInfo {
+            city: "İstanbul".to_string(),
+            building_type: Some(BuildingType::Apartman),
+            ..Default::default()
+        };
+        match validate_property(Some(&partial)) {
+            Err(ApiError::FormValidation(msg)) => {
+                assert!(msg.contains("address"));
+                assert!(!msg.contains("city"));
+            }
+            other => panic!("FormValidation bekleniyordu: {:?}", other.map(|_| ())),
+        }
+    }
+}
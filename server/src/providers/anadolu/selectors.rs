@@ -0,0 +1,166 @@
+/// Anadolu Sigorta acente portalı selector'ları
+/// Python connectors/anadolu.py'den referans alınarak oluşturulmuştur
+pub struct AnadoluSelectors;
+
+impl AnadoluSelectors {
+    // Login selectors
+    pub const USERNAME_INPUTS: &'static [&'static str] = &[
+        "#username",
+        "input[name='username']",
+    ];
+
+    pub const PASSWORD_INPUTS: &'static [&'static str] = &[
+        "#password",
+        "input[name='password']",
+        "input[type='password']",
+    ];
+
+    pub const LOGIN_BUTTONS: &'static [&'static str] = &[
+        "button[type='submit']",
+        "input[type='submit']",
+    ];
+
+    pub const LOGIN_ERRORS: &'static [&'static str] = &[
+        ".alert-danger",
+        ".login-error",
+        ".error-message",
+    ];
+
+    // Login sonrası göstergeler
+    pub const LOGGED_IN_INDICATORS: &'static [&'static str] = &[
+        "a[href*='logout']",
+        "a[href*='cikis']",
+        ".user-menu",
+        "input[name='plate']",
+    ];
+
+    // Ürün seçimi
+    pub const TRAFIK_LINKS: &'static [&'static str] = &[
+        "a[href*='trafik']",
+        "[data-product='trafik']",
+    ];
+
+    pub const KASKO_LINKS: &'static [&'static str] = &[
+        "a[href*='kasko']",
+        "[data-product='kasko']",
+    ];
+
+    pub const KONUT_LINKS: &'static [&'static str] = &[
+        "a[href*='konut']",
+        "[data-product='konut']",
+    ];
+
+    // Ortak form inputs
+    pub const TCKN_INPUTS: &'static [&'static str] = &[
+        "input[name='tckn']",
+        "input[name='kimlikNo']",
+    ];
+
+    pub const BIRTH_DATE_INPUTS: &'static [&'static str] = &[
+        "input[name='birthDate']",
+        "input[name='dogumTarihi']",
+    ];
+
+    // Araç formu
+    pub const PLATE_INPUTS: &'static [&'static str] = &[
+        "input[name='plate']",
+        "input[name='plaka']",
+    ];
+
+    pub const BRAND_INPUTS: &'static [&'static str] = &[
+        "input[name='brand']",
+        "input[name='marka']",
+    ];
+
+    pub const MODEL_INPUTS: &'static [&'static str] = &[
+        "input[name='model']",
+    ];
+
+    pub const MODEL_YEAR_INPUTS: &'static [&'static str] = &[
+        "input[name='modelYear']",
+        "input[name='modelYili']",
+    ];
+
+    // Konut formu: DASK poliçe sorgusu
+    pub const DASK_POLICY_INPUTS: &'static [&'static str] = &[
+        "input[name='daskPolicyNo']",
+        "input[name='daskPoliceNo']",
+    ];
+
+    pub const DASK_LOOKUP_BUTTONS: &'static [&'static str] = &[
+        "#dask-lookup",
+        "button[data-action='dask-lookup']",
+    ];
+
+    /// DASK sorgusu başarılıysa adres bilgilerinin gösterildiği alan
+    pub const DASK_LOOKUP_RESULT: &'static str = ".dask-info";
+
+    pub const DASK_LOOKUP_ERRORS: &'static [&'static str] = &[
+        ".dask-error",
+        ".dask-info .error",
+    ];
+
+    // Konut formu: adres ve bina bilgileri
+    pub const CITY_INPUTS: &'static [&'static str] = &[
+        "input[name='city']",
+        "input[name='il']",
+    ];
+
+    pub const DISTRICT_INPUTS: &'static [&'static str] = &[
+        "input[name='district']",
+        "input[name='ilce']",
+    ];
+
+    pub const ADDRESS_INPUTS: &'static [&'static str] = &[
+        "textarea[name='address']",
+        "input[name='address']",
+        "textarea[name='adres']",
+    ];
+
+    pub const BUILDING_TYPE_SELECTS: &'static [&'static str] = &[
+        "select[name='buildingType']",
+        "select[name='binaTipi']",
+    ];
+
+    pub const SQUARE_METERS_INPUTS: &'static [&'static str] = &[
+        "input[name='squareMeters']",
+        "input[name='brutM2']",
+    ];
+
+    pub const BUILDING_YEAR_INPUTS: &'static [&'static str] = &[
+        "input[name='buildingYear']",
+        "input[name='insaYili']",
+    ];
+
+    pub const FLOOR_COUNT_INPUTS: &'static [&'static str] = &[
+        "input[name='numberOfFloors']",
+        "input[name='katSayisi']",
+    ];
+
+    pub const FLOOR_INPUTS: &'static [&'static str] = &[
+        "input[name='floor']",
+        "input[name='bulunduguKat']",
+    ];
+
+    pub const FORM_SUBMIT_BUTTONS: &'static [&'static str] = &[
+        "button[type='submit']",
+        "input[type='submit']",
+    ];
+
+    // Sonuç
+    pub const RESULT_CONTAINER: &'static str = ".result";
+
+    pub const PRICE_ELEMENTS: &'static [&'static str] = &[
+        ".result .price",
+        ".result .premium",
+        ".result [class*='price']",
+        ".price",
+    ];
+
+    // Teklif numarası (bulunamazsa sayfa metnindeki "Teklif No:" kalıbı denenir)
+    pub const QUOTE_NO_ELEMENTS: &'static [&'static str] = &[
+        ".teklif-no",
+        ".proposal-no",
+        "[data-field='teklifNo']",
+    ];
+}
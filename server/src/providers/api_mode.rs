@@ -0,0 +1,456 @@
+use crate::browser::session::SessionData;
+use crate::http::{ApiError, PolicyIssueRequest, QuoteRequest};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Tek bir portal endpoint'i için istek şablonu.
+/// Body içindeki "{{alan}}" placeholder'ları QuoteRequest'ten doldurulur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiRequestTemplate {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+/// Ürün tipi (trafik, kasko, ...) -> istek şablonu
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiTemplateSet {
+    pub base_url: String,
+    pub products: HashMap<String, ApiRequestTemplate>,
+    /// Teklif → poliçe dönüşümü şablonları (ürün tipi -> şablon)
+    #[serde(default)]
+    pub policies: HashMap<String, ApiRequestTemplate>,
+}
+
+impl ApiTemplateSet {
+    /// JSON dosyasından şablonları yükler
+    pub fn from_file(path: &str) -> Result<Self, ApiError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ApiError::InternalServerError(format!("API şablon dosyası okunamadı ({}): {}", path, e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| ApiError::InternalServerError(format!("API şablon dosyası geçersiz ({}): {}", path, e)))
+    }
+
+    pub fn template_for(&self, product_type: &str) -> Option<&ApiRequestTemplate> {
+        self.products.get(product_type)
+    }
+
+    pub fn policy_template_for(&self, product_type: &str) -> Option<&ApiRequestTemplate> {
+        self.policies.get(product_type)
+    }
+
+    /// Ürün şablonunun gövdesi `{{name}}` placeholder'ını içeriyor mu
+    pub fn product_sends(&self, product_type: &str, name: &str) -> bool {
+        let placeholder = format!("{{{{{}}}}}", name);
+        self.template_for(product_type)
+            .and_then(|t| t.body.as_ref())
+            .is_some_and(|body| body.to_string().contains(&placeholder))
+    }
+}
+
+/// API mode hatası: session reddedildiyse çağıran taraf browser akışına düşmeli
+#[derive(Debug)]
+pub enum ApiModeError {
+    SessionRejected(String),
+    Failed(ApiError),
+}
+
+impl std::fmt::Display for ApiModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiModeError::SessionRejected(msg) => write!(f, "Session reddedildi: {}", msg),
+            ApiModeError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<ApiError> for ApiModeError {
+    fn from(err: ApiError) -> Self {
+        ApiModeError::Failed(err)
+    }
+}
+
+/// Browser login'inden alınan session (cookie + token) ile portal JSON endpoint'lerini çağırır
+pub struct SessionHttpClient {
+    client: reqwest::Client,
+    base_url: String,
+    cookie_header: String,
+    bearer_token: Option<String>,
+}
+
+impl SessionHttpClient {
+    pub fn new(
+        base_url: &str,
+        session: &SessionData,
+        user_agent: &str,
+        accept_language: &str,
+        timeout_ms: u64,
+    ) -> Result<Self, ApiError> {
+        let client = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .timeout(Duration::from_millis(timeout_ms))
+            // Login'e redirect = session geçersiz; redirect'i takip etme
+            .redirect(reqwest::redirect::Policy::none())
+            .default_headers({
+                let mut headers = reqwest::header::HeaderMap::new();
+                if let Ok(value) = accept_language.parse() {
+                    headers.insert(reqwest::header::ACCEPT_LANGUAGE, value);
+                }
+                headers
+            })
+            .build()
+            .map_err(|e| ApiError::InternalServerError(format!("HTTP client oluşturulamadı: {}", e)))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cookie_header: cookie_header(session),
+            bearer_token: bearer_token(session),
+        })
+    }
+
+    /// Şablonu doldurup isteği gönderir, JSON yanıtı döndürür
+    pub async fn execute(
+        &self,
+        template: &ApiRequestTemplate,
+        request: &QuoteRequest,
+    ) -> Result<serde_json::Value, ApiModeError> {
+        self.send(template, placeholder_values(request)).await
+    }
+
+    /// Poliçe kesim şablonunu (ödeme alanları dahil) doldurup gönderir
+    pub async fn execute_policy(
+        &self,
+        template: &ApiRequestTemplate,
+        request: &PolicyIssueRequest,
+    ) -> Result<serde_json::Value, ApiModeError> {
+        self.send(template, policy_placeholder_values(request)).await
+    }
+
+    async fn send(
+        &self,
+        template: &ApiRequestTemplate,
+        values: HashMap<&'static str, serde_json::Value>,
+    ) -> Result<serde_json::Value, ApiModeError> {
+        let url = format!("{}/{}", self.base_url, template.path.trim_start_matches('/'));
+        let method = reqwest::Method::from_bytes(template.method.to_uppercase().as_bytes())
+            .map_err(|e| ApiError::FormValidation(format!("Geçersiz HTTP method: {}", e)))?;
+
+        tracing::info!("🌐 API isteği: {} {}", method, url);
+
+        let mut builder = self
+            .client
+            .request(method, &url)
+            .header(reqwest::header::ACCEPT, "application/json");
+
+        if !self.cookie_header.is_empty() {
+            builder = builder.header(reqwest::header::COOKIE, &self.cookie_header);
+        }
+        if let Some(token) = &self.bearer_token {
+            builder = builder.bearer_auth(token);
+        }
+        for (name, value) in &template.headers {
+            builder = builder.header(name, value);
+        }
+        // Body kart bilgisi içerebilir; loglanmaz
+        if let Some(body) = &template.body {
+            builder = builder.json(&render_value(body, &values));
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            if e.is_timeout() {
+                ApiModeError::Failed(ApiError::Timeout(format!("API isteği zaman aşımı: {}", e)))
+            } else {
+                ApiModeError::Failed(ApiError::Unknown(format!("API isteği başarısız: {}", e)))
+            }
+        })?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED
+            || status == reqwest::StatusCode::FORBIDDEN
+            || status.is_redirection()
+        {
+            return Err(ApiModeError::SessionRejected(format!("HTTP {}", status)));
+        }
+
+        let is_json = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.contains("json"))
+            .unwrap_or(false);
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| ApiError::Unknown(format!("API yanıtı okunamadı: {}", e)))?;
+
+        if !status.is_success() {
+            return Err(ApiModeError::Failed(ApiError::Unknown(format!(
+                "API HTTP {}: {}",
+                status,
+                text.chars().take(300).collect::<String>()
+            ))));
+        }
+
+        // SPA'lar session düşünce 200 + login HTML döndürebilir
+        if !is_json && text.trim_start().starts_with('<') {
+            return Err(ApiModeError::SessionRejected("JSON yerine HTML döndü (login sayfası)".to_string()));
+        }
+
+        serde_json::from_str(&text).map_err(|e| ApiModeError::Failed(ApiError::from(e)))
+    }
+}
+
+fn cookie_header(session: &SessionData) -> String {
+    session
+        .cookies
+        .iter()
+        .map(|c| format!("{}={}", c.name, c.value))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// localStorage'daki token'ı bulur (SPA'lar genelde "token" / "access_token" altında saklar)
+fn bearer_token(session: &SessionData) -> Option<String> {
+    const TOKEN_KEYS: &[&str] = &["access_token", "accesstoken", "token", "auth_token", "authtoken", "id_token"];
+
+    TOKEN_KEYS.iter().find_map(|key| {
+        session
+            .local_storage
+            .iter()
+            .find(|(k, _)| k.to_lowercase() == *key)
+            .map(|(_, v)| v.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+/// QuoteRequest alanları için placeholder değerleri (tam eşleşmede tipli JSON değeri kullanılır)
+fn placeholder_values(request: &QuoteRequest) -> HashMap<&'static str, serde_json::Value> {
+    let product_type = serde_json::to_value(&request.coverage.product_type).unwrap_or_default();
+    let usage = serde_json::to_value(&request.vehicle.usage).unwrap_or_default();
+
+    HashMap::from([
+        ("requestId", serde_json::json!(request.quote_meta.request_id)),
+        ("tckn", serde_json::json!(request.insured.tckn)),
+        ("name", serde_json::json!(request.insured.name)),
+        ("birthDate", serde_json::json!(request.insured.birth_date)),
+        ("phone", serde_json::json!(request.insured.phone)),
+        ("email", serde_json::json!(request.insured.email)),
+        ("plate", serde_json::json!(request.vehicle.plate)),
+        ("vin", serde_json::json!(request.vehicle.vin.clone().unwrap_or_default())),
+        ("brand", serde_json::json!(request.vehicle.brand)),
+        ("model", serde_json::json!(request.vehicle.model)),
+        ("year", serde_json::json!(request.vehicle.year)),
+        ("usage", usage),
+        ("productType", product_type),
+        ("startDate", serde_json::json!(request.coverage.start_date)),
+        ("addons", serde_json::json!(request.coverage.addons)),
+    ])
+}
+
+/// Teklif alanlarına ek olarak prim ve ödeme placeholder'ları
+fn policy_placeholder_values(request: &PolicyIssueRequest) -> HashMap<&'static str, serde_json::Value> {
+    let mut values = placeholder_values(&request.quote_request);
+    let payment = &request.payment;
+    let card = payment.card.as_ref();
+
+    values.extend([
+        ("providerQuoteNo", serde_json::json!(request.quote.provider_quote_no.clone().unwrap_or_default())),
+        ("premium", serde_json::json!(request.quote.premium.gross)),
+        ("netPremium", serde_json::json!(request.quote.premium.net)),
+        ("paymentMethod", serde_json::json!(payment.method.as_str())),
+        ("installmentCount", serde_json::json!(payment.installment_count)),
+        ("cardHolder", serde_json::json!(card.map(|c| c.holder_name.as_str()).unwrap_or_default())),
+        ("cardNumber", serde_json::json!(card.map(|c| c.number.replace([' ', '-'], "")).unwrap_or_default())),
+        ("cardExpiryMonth", serde_json::json!(card.map(|c| format!("{:02}", c.expiry_month)).unwrap_or_default())),
+        ("cardExpiryYear", serde_json::json!(card.map(|c| c.expiry_year.to_string()).unwrap_or_default())),
+        ("cardCvv", serde_json::json!(card.map(|c| c.cvv.as_str()).unwrap_or_default())),
+    ]);
+    values
+}
+
+/// Şablondaki "{{alan}}" placeholder'larını doldurur
+pub fn render_template(template: &serde_json::Value, request: &QuoteRequest) -> serde_json::Value {
+    let values = placeholder_values(request);
+    render_value(template, &values)
+}
+
+/// Poliçe şablonundaki teklif + ödeme placeholder'larını doldurur
+pub fn render_policy_template(template: &serde_json::Value, request: &PolicyIssueRequest) -> serde_json::Value {
+    let values = policy_placeholder_values(request);
+    render_value(template, &values)
+}
+
+fn render_value(
+    value: &serde_json::Value,
+    values: &HashMap<&'static str, serde_json::Value>,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => {
+            // Tam eşleşme: tipli değer (ör. year -> number)
+            if let Some(key) = s.strip_prefix("{{").and_then(|r| r.strip_suffix("}}")) {
+                if let Some(v) = values.get(key.trim()) {
+                    return v.clone();
+                }
+            }
+
+            let mut rendered = s.clone();
+            for (key, v) in values {
+                let placeholder = format!("{{{{{}}}}}", key);
+                if rendered.contains(&placeholder) {
+                    let text = v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+                    rendered = rendered.replace(&placeholder, &text);
+                }
+            }
+            serde_json::Value::String(rendered)
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|v| render_value(v, values)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_value(v, values)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::session::Cookie;
+    use crate::http::{CoverageInfo, InsuredInfo, ProductType, QuoteMeta, VehicleCategory, VehicleInfo, VehicleUsage};
+    use serde_json::json;
+
+    fn sample_request() -> QuoteRequest {
+        QuoteRequest {
+            insured: InsuredInfo {
+                tckn: "12345678901".to_string(),
+                name: "Ali Veli".to_string(),
+                birth_date: "1990-01-01".to_string(),
+                phone: "5551112233".to_string(),
+                email: "ali@example.com".to_string(),
+            },
+            vehicle: VehicleInfo {
+                plate: "34ABC123".to_string(),
+                vin: None,
+                brand: "Renault".to_string(),
+                model: "Clio".to_string(),
+                year: 2020,
+                usage: VehicleUsage::Hususi,
+                category: VehicleCategory::Otomobil,
+                ..Default::default()
+            },
+            vehicle_id: None,
+            property: None,
+            coverage: CoverageInfo {
+                product_type: ProductType::Trafik,
+                start_date: "2024-06-01".to_string(),
+                addons: vec![],
+            },
+            quote_meta: QuoteMeta {
+                request_id: "req-1".to_string(),
+                webhook_url: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let template = json!({
+            "plaka": "{{plate}}",
+            "modelYili": "{{year}}",
+            "kullanim": "{{usage}}",
+            "aciklama": "{{productType}} - {{plate}}",
+            "sigortali": { "tckn": "{{tckn}}" },
+            "sabit": 1
+        });
+
+        let rendered = render_template(&template, &sample_request());
+        assert_eq!(rendered["plaka"], "34ABC123");
+        assert_eq!(rendered["modelYili"], 2020);
+        assert_eq!(rendered["kullanim"], "hususi");
+        assert_eq!(rendered["aciklama"], "trafik - 34ABC123");
+        assert_eq!(rendered["sigortali"]["tckn"], "12345678901");
+        assert_eq!(rendered["sabit"], 1);
+    }
+
+    #[test]
+    fn test_render_policy_template() {
+        let quote_request = sample_request();
+        let quote = crate::providers::mock::build_mock_quote(&quote_request, 0);
+        let request = PolicyIssueRequest {
+            quote_request,
+            quote,
+            payment: serde_json::from_value(json!({
+                "method": "credit_card",
+                "installmentCount": 3,
+                "card": { "holderName": "ALI VELI", "number": "4111 1111 1111 1111", "expiryMonth": 7, "expiryYear": 2028, "cvv": "123" }
+            }))
+            .unwrap(),
+        };
+
+        let template = json!({
+            "proposalNo": "{{providerQuoteNo}}",
+            "plateNo": "{{plate}}",
+            "premium": "{{premium}}",
+            "payment": {
+                "type": "{{paymentMethod}}",
+                "installments": "{{installmentCount}}",
+                "cardNo": "{{cardNumber}}",
+                "expiry": "{{cardExpiryMonth}}/{{cardExpiryYear}}"
+            }
+        });
+
+        let rendered = render_policy_template(&template, &request);
+        assert_eq!(rendered["proposalNo"], request.quote.provider_quote_no.clone().unwrap());
+        assert_eq!(rendered["plateNo"], "34ABC123");
+        assert_eq!(rendered["premium"], request.quote.premium.gross);
+        assert_eq!(rendered["payment"]["type"], "credit_card");
+        assert_eq!(rendered["payment"]["installments"], 3);
+        assert_eq!(rendered["payment"]["cardNo"], "4111111111111111");
+        assert_eq!(rendered["payment"]["expiry"], "07/2028");
+        assert!(!format!("{:?}", request.payment).contains("4111111111111111"));
+    }
+
+    #[test]
+    fn test_session_credentials() {
+        let session = SessionData {
+            cookies: vec![
+                Cookie {
+                    name: "JSESSIONID".to_string(),
+                    value: "abc".to_string(),
+                    domain: "portal".to_string(),
+                    path: "/".to_string(),
+                    secure: true,
+                    http_only: true,
+                },
+                Cookie {
+                    name: "lang".to_string(),
+                    value: "tr".to_string(),
+                    domain: "portal".to_string(),
+                    path: "/".to_string(),
+                    secure: false,
+                    http_only: false,
+                },
+            ],
+            local_storage: HashMap::from([("AccessToken".to_string(), "\"eyJ.x.y\"".to_string())]),
+            timestamp: 0,
+            valid_until: 0,
+        };
+
+        assert_eq!(cookie_header(&session), "JSESSIONID=abc; lang=tr");
+        assert_eq!(bearer_token(&session).as_deref(), Some("eyJ.x.y"));
+    }
+}
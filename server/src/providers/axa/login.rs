@@ -0,0 +1,77 @@
+use crate::browser::session::SessionManager;
+use crate::browser::{any_present, capture_session, click_first, fill_first, restore_session};
+use crate::config::Config;
+use crate::http::ApiError;
+use crate::providers::axa::selectors::AxaSelectors;
+use crate::utils::mask_sensitive;
+use fantoccini::{Client, Locator};
+use std::sync::Arc;
+
+pub async fn login_to_axa(
+    client: &Client,
+    config: Arc<Config>,
+    session_manager: &SessionManager,
+) -> Result<(), ApiError> {
+    tracing::info!("🔍 Axa'ya bağlanılıyor: {}", config.axa_url);
+    tracing::info!("👤 Kullanıcı: {}", mask_sensitive(&config.axa_username));
+
+    // Önce session cache'i dene
+    if let Some(session) = session_manager.load_session("axa") {
+        tracing::info!("📦 Cached Axa session bulundu, yükleniyor...");
+
+        match restore_session(client, &session, &config.axa_url).await {
+            Ok(()) if is_logged_in(client).await => {
+                tracing::info!("✅ Axa session geçerli, login atlandı");
+                return Ok(());
+            }
+            Ok(()) => tracing::warn!("⚠️ Axa session geçersiz, yeniden login..."),
+            Err(e) => tracing::warn!("⚠️ Axa session restore başarısız: {}, yeniden login...", e),
+        }
+        session_manager.clear_session("axa").ok();
+    }
+
+    client.goto(&config.axa_url).await
+        .map_err(|e| ApiError::WebDriverError(format!("Sayfa yüklenemedi: {}", e)))?;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+
+    if !fill_first(client, AxaSelectors::USERNAME_INPUTS, &config.axa_username).await {
+        return Err(ApiError::LoginFailed("Axa kullanıcı adı alanı bulunamadı".to_string()));
+    }
+    if !fill_first(client, AxaSelectors::PASSWORD_INPUTS, &config.axa_password).await {
+        return Err(ApiError::LoginFailed("Axa şifre alanı bulunamadı".to_string()));
+    }
+
+    if !click_first(client, AxaSelectors::LOGIN_BUTTONS).await {
+        return Err(ApiError::LoginFailed("Axa login butonu bulunamadı".to_string()));
+    }
+    tracing::info!("✅ Axa login butonu tıklandı");
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
+
+    // Hata mesajı kontrolü
+    for selector in AxaSelectors::LOGIN_ERRORS {
+        if let Ok(elem) = client.find(Locator::Css(selector)).await {
+            let text = elem.text().await.unwrap_or_default();
+            if !text.trim().is_empty() {
+                return Err(ApiError::LoginFailed(format!("Axa login hatası: {}", text.trim())));
+            }
+        }
+    }
+
+    if !is_logged_in(client).await {
+        return Err(ApiError::LoginFailed("Axa login doğrulanamadı".to_string()));
+    }
+
+    tracing::info!("✅ Axa login başarılı");
+
+    let session = capture_session(client).await?;
+    session_manager.save_session("axa", session)
+        .map_err(|e| ApiError::Unknown(format!("Session kaydetme hatası: {}", e)))?;
+
+    Ok(())
+}
+
+async fn is_logged_in(client: &Client) -> bool {
+    any_present(client, AxaSelectors::LOGGED_IN_INDICATORS).await
+}
@@ -1,4 +1,10 @@
+mod login;
+pub mod parser;
+mod quote;
+mod selectors;
+
 use crate::config::Config;
+use crate::http::models::{ProductType, ProviderCapabilities, VehicleCategory, VehicleUsage};
 use crate::http::{ApiError, QuoteRequest, QuoteResponse};
 use crate::providers::base::InsuranceProvider;
 use async_trait::async_trait;
@@ -19,30 +25,40 @@ impl InsuranceProvider for AxaProvider {
     fn name(&self) -> &str {
         "Axa"
     }
-    
+
     fn is_active(&self) -> bool {
-        std::env::var("AXA_USERNAME").is_ok()
+        !self.config.axa_url.is_empty()
+            && !self.config.axa_username.is_empty()
+            && !self.config.axa_password.is_empty()
     }
-    
+
     fn inactive_reason(&self) -> Option<String> {
-        if !self.is_active() {
-            Some("Henüz kayıtlı değil".to_string())
+        if self.config.axa_url.is_empty() {
+            Some("AXA_URL yapılandırılmamış".to_string())
+        } else if !self.is_active() {
+            Some("Credentials yapılandırılmamış".to_string())
         } else {
             None
         }
     }
-    
-    fn supported_products(&self) -> Vec<String> {
-        vec!["trafik".to_string(), "kasko".to_string()]
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supported_products: vec![ProductType::Trafik, ProductType::Kasko],
+            supported_addons: vec![],
+            usage_types: vec![VehicleUsage::Hususi, VehicleUsage::Ticari],
+            vehicle_categories: vec![VehicleCategory::Otomobil, VehicleCategory::Kamyonet],
+            policy_issuance: false,
+        }
     }
-    
-    async fn fetch_quote(&self, _request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+
+    async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
         if !self.is_active() {
             return Err(ApiError::ProviderInactive(
                 "Axa credentials yapılandırılmamış".to_string()
             ));
         }
-        Err(ApiError::ProviderInactive("Axa entegrasyonu devam ediyor".to_string()))
+
+        quote::fetch_axa_quote(self.config.clone(), request).await
     }
 }
-
@@ -0,0 +1,49 @@
+use crate::http::{ApiError, Coverage, Installment};
+use crate::providers::axa::selectors::AxaSelectors;
+use crate::providers::parsing::{
+    extract_coverages, extract_installments, extract_quote_reference, find_price, ParsedQuote,
+};
+use scraper::Html;
+
+/// Axa sonuç sayfası HTML'inden fiyat, taksit ve teminatları çıkarır (browser gerektirmez)
+pub fn parse_axa_html(html: &str, product_type: &str) -> Result<ParsedQuote, ApiError> {
+    let document = Html::parse_document(html);
+
+    // Kasko primleri trafik aralığını aşabilir
+    let premium = find_price(&document, AxaSelectors::PRICE_ELEMENTS, 500.0, 250000.0)
+        .ok_or_else(|| ApiError::ParseError("Axa fiyat bulunamadı".to_string()))?;
+
+    let mut installments = extract_installments(&document);
+    if installments.is_empty() {
+        installments = vec![
+            Installment {
+                count: 1,
+                per_installment: premium,
+                total: premium,
+            },
+        ];
+    }
+
+    let mut coverages = extract_coverages(&document);
+    if coverages.is_empty() && product_type == "trafik" {
+        coverages = vec![
+            Coverage {
+                code: "TRAFIK_ZORUNLU".to_string(),
+                name: "Zorunlu Trafik Sigortası".to_string(),
+                limit: None,
+                included: true,
+            },
+        ];
+    }
+
+    Ok(ParsedQuote {
+        premium,
+        installments,
+        coverages,
+        reference: extract_quote_reference(
+            &document,
+            AxaSelectors::QUOTE_NO_ELEMENTS,
+            chrono::Utc::now().date_naive(),
+        ),
+    })
+}
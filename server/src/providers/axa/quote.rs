@@ -0,0 +1,135 @@
+use crate::browser::{click_first, create_webdriver_client, fill_first, SessionManager};
+use crate::config::Config;
+use crate::http::models::ProductType;
+use crate::http::{ApiError, PremiumDetail, QuoteRequest, QuoteResponse, Timings};
+use crate::providers::axa::login::login_to_axa;
+use crate::providers::axa::parser::parse_axa_html;
+use crate::providers::axa::selectors::AxaSelectors;
+use fantoccini::{Client, Locator};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub async fn fetch_axa_quote(
+    config: Arc<Config>,
+    request: QuoteRequest,
+) -> Result<QuoteResponse, ApiError> {
+    let scrape_start = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let product_type = match request.coverage.product_type {
+        ProductType::Trafik => "trafik",
+        ProductType::Kasko => "kasko",
+        _ => return Err(ApiError::FormValidation("Desteklenmeyen ürün tipi".to_string())),
+    };
+
+    tracing::info!("🚀 Axa {} teklifi başlatıldı: request_id={}", product_type, request.quote_meta.request_id);
+
+    let client = create_webdriver_client(&config).await
+        .map_err(|e| ApiError::WebDriverError(format!("WebDriver: {}", e)))?;
+
+    let session_manager = SessionManager::new(&config.session_dir);
+
+    if let Err(e) = login_to_axa(&client, config.clone(), &session_manager).await {
+        let _ = client.close().await;
+        return Err(e);
+    }
+
+    let result = fill_and_parse(&client, &config, &request, product_type, scrape_start).await;
+
+    // Hata durumunda screenshot (debug için)
+    if result.is_err() {
+        let screenshot_path = format!("{}/axa_error_{}.png", config.artifact_dir, request.quote_meta.request_id);
+        if let Ok(png_data) = client.screenshot().await {
+            if std::fs::write(&screenshot_path, &png_data).is_ok() {
+                tracing::info!("📸 Error screenshot: {}", screenshot_path);
+            }
+        }
+    }
+
+    let _ = client.close().await;
+
+    result
+}
+
+async fn fill_and_parse(
+    client: &Client,
+    config: &Config,
+    request: &QuoteRequest,
+    product_type: &str,
+    scrape_start: u64,
+) -> Result<QuoteResponse, ApiError> {
+    // Ürün sayfasına geç (link yoksa form zaten açık varsayılır)
+    let product_links = if product_type == "kasko" {
+        AxaSelectors::KASKO_LINKS
+    } else {
+        AxaSelectors::TRAFIK_LINKS
+    };
+    if click_first(client, product_links).await {
+        tracing::info!("✅ Axa {} formu açıldı", product_type);
+        tokio::time::sleep(Duration::from_millis(2000)).await;
+    }
+
+    if !fill_first(client, AxaSelectors::PLATE_INPUTS, &request.vehicle.plate).await {
+        return Err(ApiError::FormValidation("Axa plaka alanı bulunamadı".to_string()));
+    }
+    fill_first(client, AxaSelectors::TCKN_INPUTS, &request.insured.tckn).await;
+    fill_first(client, AxaSelectors::BIRTH_DATE_INPUTS, &request.insured.birth_date).await;
+
+    if product_type == "kasko" {
+        fill_first(client, AxaSelectors::BRAND_INPUTS, &request.vehicle.brand).await;
+        fill_first(client, AxaSelectors::MODEL_INPUTS, &request.vehicle.model).await;
+        fill_first(client, AxaSelectors::MODEL_YEAR_INPUTS, &request.vehicle.year.to_string()).await;
+    }
+
+    if !click_first(client, AxaSelectors::FORM_SUBMIT_BUTTONS).await {
+        return Err(ApiError::FormValidation("Axa form submit butonu bulunamadı".to_string()));
+    }
+
+    // Python: page.wait_for_selector('.result .price', timeout=30000)
+    client
+        .wait()
+        .at_most(Duration::from_millis(config.request_timeout_ms.min(30000)))
+        .for_element(Locator::Css(AxaSelectors::RESULT_CONTAINER))
+        .await
+        .map_err(|e| ApiError::Timeout(format!("Axa sonuç sayfası yüklenmedi: {}", e)))?;
+
+    let html = client.source().await
+        .map_err(|e| ApiError::ParseError(format!("Axa sayfa kaynağı alınamadı: {}", e)))?;
+
+    let parsed = parse_axa_html(&html, product_type)?;
+    let premium = parsed.premium;
+
+    let net = premium / 1.18;
+    let taxes = premium - net;
+
+    let scrape_elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64 - scrape_start;
+
+    tracing::info!("✅ Axa teklifi alındı: {:.2} TL", premium);
+
+    Ok(QuoteResponse {
+        request_id: request.quote_meta.request_id.clone(),
+        company: "Axa".to_string(),
+        product_type: product_type.to_string(),
+        premium: PremiumDetail {
+            net: (net * 100.0).round() / 100.0,
+            gross: (premium * 100.0).round() / 100.0,
+            taxes: (taxes * 100.0).round() / 100.0,
+            currency: "TRY".to_string(),
+        },
+        installments: parsed.installments,
+        coverages: parsed.coverages,
+        warnings: vec![],
+        provider_quote_no: parsed.reference.quote_no.clone(),
+        valid_until: parsed.reference.valid_until_string(),
+        raw: None,
+        timings: Some(Timings {
+            queued_ms: 0,
+            scrape_ms: scrape_elapsed,
+        }),
+    })
+}
@@ -0,0 +1,100 @@
+/// Axa acente portalı selector'ları
+/// Python connectors/axa.py'den referans alınarak oluşturulmuştur
+pub struct AxaSelectors;
+
+impl AxaSelectors {
+    // Login selectors
+    pub const USERNAME_INPUTS: &'static [&'static str] = &[
+        "input[name='user']",
+        "input[name='username']",
+        "#username",
+    ];
+
+    pub const PASSWORD_INPUTS: &'static [&'static str] = &[
+        "input[name='pass']",
+        "input[name='password']",
+        "input[type='password']",
+    ];
+
+    pub const LOGIN_BUTTONS: &'static [&'static str] = &[
+        "button[type='submit']",
+        "input[type='submit']",
+    ];
+
+    pub const LOGIN_ERRORS: &'static [&'static str] = &[
+        ".alert-danger",
+        ".login-error",
+        ".error-message",
+    ];
+
+    // Login sonrası göstergeler
+    pub const LOGGED_IN_INDICATORS: &'static [&'static str] = &[
+        "a[href*='logout']",
+        ".user-menu",
+        "input[name='plate']",
+    ];
+
+    // Ürün seçimi
+    pub const TRAFIK_LINKS: &'static [&'static str] = &[
+        "a[href*='trafik']",
+        "[data-product='trafik']",
+    ];
+
+    pub const KASKO_LINKS: &'static [&'static str] = &[
+        "a[href*='kasko']",
+        "[data-product='kasko']",
+    ];
+
+    // Form inputs
+    pub const PLATE_INPUTS: &'static [&'static str] = &[
+        "input[name='plate']",
+        "input[name='plaka']",
+    ];
+
+    pub const TCKN_INPUTS: &'static [&'static str] = &[
+        "input[name='tckn']",
+        "input[name='identityNo']",
+    ];
+
+    pub const BIRTH_DATE_INPUTS: &'static [&'static str] = &[
+        "input[name='birthDate']",
+        "input[name='dogumTarihi']",
+    ];
+
+    // Kasko ek alanları
+    pub const BRAND_INPUTS: &'static [&'static str] = &[
+        "input[name='brand']",
+        "input[name='marka']",
+    ];
+
+    pub const MODEL_INPUTS: &'static [&'static str] = &[
+        "input[name='model']",
+    ];
+
+    pub const MODEL_YEAR_INPUTS: &'static [&'static str] = &[
+        "input[name='modelYear']",
+        "input[name='year']",
+    ];
+
+    pub const FORM_SUBMIT_BUTTONS: &'static [&'static str] = &[
+        "button[type='submit']",
+        "input[type='submit']",
+    ];
+
+    // Sonuç
+    pub const RESULT_CONTAINER: &'static str = ".result";
+
+    pub const PRICE_ELEMENTS: &'static [&'static str] = &[
+        ".result .price",
+        ".result .premium",
+        ".result [class*='price']",
+        ".price",
+    ];
+
+    // Teklif numarası (bulunamazsa sayfa metnindeki "Teklif No:" kalıbı denenir)
+    pub const QUOTE_NO_ELEMENTS: &'static [&'static str] = &[
+        ".quote-no",
+        ".teklif-no",
+        "[data-field='quoteNo']",
+    ];
+}
@@ -1,3 +1,4 @@
+use crate::http::models::{IssuedPolicy, PolicyIssueRequest, ProductType, ProviderCapabilities};
 use crate::http::{ApiError, QuoteRequest, QuoteResponse};
 use async_trait::async_trait;
 
@@ -14,12 +15,32 @@ pub trait InsuranceProvider: Send + Sync {
         None
     }
     
-    /// Desteklenen ürün tiplerini döndürür
-    fn supported_products(&self) -> Vec<String> {
-        vec!["trafik".to_string(), "kasko".to_string()]
+    /// Desteklenen ürün, ek teminat, kullanım tipi ve araç kategorileri
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+    
+    /// Ürün tipini destekliyor mu (aggregator dispatch'i için)
+    fn supports_product(&self, product_type: &ProductType) -> bool {
+        self.capabilities().supports_product(product_type)
+    }
+    
+    /// Browser açılmadan önce istek/capability uyumluluğu kontrolü
+    fn check_request(&self, request: &QuoteRequest) -> Result<(), ApiError> {
+        request.validate().map_err(ApiError::FormValidation)?;
+        self.capabilities()
+            .check(request)
+            .map_err(|reason| ApiError::FormValidation(format!("{}: {}", self.name(), reason)))
     }
     
     /// Teklif al
     async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError>;
+    
+    /// Kaydedilmiş teklifi portalda poliçeye dönüştürür (ödeme dahil).
+    /// Yalnızca `ApiError::is_before_submission` hataları poliçenin kesilmediğini gösterir;
+    /// diğer hatalarda teklif manuel kontrole (issue_review) alınır.
+    async fn issue_policy(&self, _request: PolicyIssueRequest) -> Result<IssuedPolicy, ApiError> {
+        Err(ApiError::ProviderInactive(format!("{} poliçe kesimini desteklemiyor", self.name())))
+    }
 }
 
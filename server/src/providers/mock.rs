@@ -0,0 +1,317 @@
+//! Geliştirme ve CI için in-process sahte provider (MOCK_PROVIDER=true).
+//! Primler istekten (plaka, model yılı, ürün) deterministik türetilir; aynı istek her zaman aynı fiyatı alır.
+
+use crate::config::Config;
+use crate::http::models::{IssuedPolicy, PolicyIssueRequest, ProductType, ProviderCapabilities, VehicleCategory, VehicleUsage};
+use crate::http::{ApiError, Coverage, Installment, PremiumDetail, QuoteRequest, QuoteResponse, Timings};
+use crate::providers::base::InsuranceProvider;
+use crate::utils::{one_year_after, parse_portal_date};
+use async_trait::async_trait;
+use chrono::Datelike;
+use std::sync::Arc;
+
+/// Mock tekliflerin geçerlilik süresi (portallardaki tipik 15 gün)
+const MOCK_QUOTE_VALID_DAYS: u64 = 15;
+
+pub struct MockProvider {
+    config: Arc<Config>,
+}
+
+impl MockProvider {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl InsuranceProvider for MockProvider {
+    fn name(&self) -> &str {
+        "Mock"
+    }
+
+    fn is_active(&self) -> bool {
+        self.config.mock_provider
+    }
+
+    fn inactive_reason(&self) -> Option<String> {
+        if !self.is_active() {
+            Some("MOCK_PROVIDER kapalı".to_string())
+        } else {
+            None
+        }
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supported_products: vec![
+                ProductType::Trafik,
+                ProductType::Kasko,
+                ProductType::Konut,
+                ProductType::Saglik,
+            ],
+            supported_addons: ["IMM", "FERDI_KAZA", "HUKUKSAL_KORUMA", "MINI_ONARIM", "IKAME_ARAC"]
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+            usage_types: vec![VehicleUsage::Hususi, VehicleUsage::Ticari],
+            vehicle_categories: VehicleCategory::ALL.to_vec(),
+            policy_issuance: true,
+        }
+    }
+
+    async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+        tracing::info!("🧪 Mock teklif: {} (request_id={})", request.vehicle.plate, request.quote_meta.request_id);
+
+        tokio::time::sleep(std::time::Duration::from_millis(self.config.mock_latency_ms)).await;
+
+        if let Some(error) = mock_failure(
+            &request.quote_meta.request_id,
+            self.config.mock_failure_rate,
+            self.config.mock_error.as_deref(),
+        ) {
+            tracing::warn!("🧪 Mock hata üretildi: {}", error);
+            return Err(error);
+        }
+
+        Ok(build_mock_quote(&request, self.config.mock_latency_ms))
+    }
+
+    async fn issue_policy(&self, request: PolicyIssueRequest) -> Result<IssuedPolicy, ApiError> {
+        tracing::info!("🧪 Mock poliçe kesimi: request_id={}", request.quote.request_id);
+
+        tokio::time::sleep(std::time::Duration::from_millis(self.config.mock_latency_ms)).await;
+
+        if let Some(error) = mock_failure(
+            &format!("policy:{}", request.quote.request_id),
+            self.config.mock_failure_rate,
+            self.config.mock_error.as_deref(),
+        ) {
+            tracing::warn!("🧪 Mock poliçe hatası üretildi: {}", error);
+            return Err(error);
+        }
+
+        build_mock_policy(&request)
+    }
+}
+
+/// Teklif request_id'sinden deterministik poliçe numarası; tarih aralığı teminat başlangıcından 1 yıl
+pub fn build_mock_policy(request: &PolicyIssueRequest) -> Result<IssuedPolicy, ApiError> {
+    let start = parse_portal_date(&request.quote_request.coverage.start_date).ok_or_else(|| {
+        ApiError::FormValidation(format!(
+            "Geçersiz başlangıç tarihi: {}",
+            request.quote_request.coverage.start_date
+        ))
+    })?;
+
+    Ok(IssuedPolicy {
+        policy_number: format!("MOCK-{:010}", fnv1a(&request.quote.request_id) % 10_000_000_000),
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: one_year_after(start).format("%Y-%m-%d").to_string(),
+        premium: Some(request.quote.premium.gross),
+        raw: None,
+    })
+}
+
+/// İstekten deterministik brüt prim üretir
+pub fn mock_premium(request: &QuoteRequest) -> f64 {
+    let (base, spread) = match request.coverage.product_type {
+        ProductType::Trafik => (3500.0, 2500.0),
+        ProductType::Kasko => (12000.0, 10000.0),
+        ProductType::Konut => (900.0, 800.0),
+        ProductType::Saglik => (5000.0, 4000.0),
+    };
+
+    let key = format!("{}|{:?}", normalize_plate(&request.vehicle.plate), request.coverage.product_type);
+    let variation = (fnv1a(&key) % 10_000) as f64 / 10_000.0 * spread;
+
+    // Araç yaşı teminat başlangıcına göre (saate bağlı değil): kaskoda eski araç ucuzlar, trafikte biraz pahalanır
+    let start_year = parse_portal_date(&request.coverage.start_date)
+        .map(|d| d.year())
+        .unwrap_or(request.vehicle.year as i32);
+    let age = start_year - request.vehicle.year as i32;
+    let age = age.clamp(0, 30) as f64;
+    let age_factor = match request.coverage.product_type {
+        ProductType::Kasko => 1.0 - age * 0.02,
+        ProductType::Trafik => 1.0 + age * 0.01,
+        _ => 1.0,
+    };
+
+    (((base + variation) * age_factor) * 100.0).round() / 100.0
+}
+
+/// Hata oranına göre (request_id hash'i ile deterministik) hata üretir
+pub fn mock_failure(request_id: &str, failure_rate: f64, error_kind: Option<&str>) -> Option<ApiError> {
+    if failure_rate <= 0.0 {
+        return None;
+    }
+
+    let hash = fnv1a(request_id);
+    let roll = (hash % 10_000) as f64 / 10_000.0;
+    if roll >= failure_rate {
+        return None;
+    }
+
+    let kind = error_kind.unwrap_or(match (hash / 10_000) % 3 {
+        0 => "otp",
+        1 => "blocked",
+        _ => "timeout",
+    });
+
+    Some(match kind {
+        "otp" => ApiError::HumanActionRequired("Mock: OTP doğrulaması gerekli".to_string()),
+        "blocked" => ApiError::Blocked("Mock: Erişim engellendi".to_string()),
+        "timeout" => ApiError::Timeout("Mock: Portal yanıt vermedi".to_string()),
+        other => ApiError::Unknown(format!("Mock: bilinmeyen hata tipi '{}'", other)),
+    })
+}
+
+pub(crate) fn build_mock_quote(request: &QuoteRequest, scrape_ms: u64) -> QuoteResponse {
+    let gross = mock_premium(request);
+    let net = gross / 1.18;
+    let product_type = format!("{:?}", request.coverage.product_type).to_lowercase();
+
+    let installments = [1u8, 3, 6]
+        .iter()
+        .map(|&count| Installment {
+            count,
+            per_installment: ((gross / count as f64) * 100.0).round() / 100.0,
+            total: gross,
+        })
+        .collect();
+
+    let coverages = match request.coverage.product_type {
+        ProductType::Trafik => vec![mock_coverage("TRAFIK_ZORUNLU", "Zorunlu Trafik Sigortası", None)],
+        ProductType::Kasko => vec![
+            mock_coverage("KASKO", "Kasko", Some("Araç Rayiç Değeri")),
+            mock_coverage("IMM", "İhtiyari Mali Mesuliyet", Some("1.000.000 TL")),
+        ],
+        ProductType::Konut => vec![mock_coverage("KONUT_YANGIN", "Yangın", Some("500.000 TL"))],
+        ProductType::Saglik => vec![mock_coverage("SAGLIK_YATARAK", "Yatarak Tedavi", None)],
+    };
+
+    QuoteResponse {
+        request_id: request.quote_meta.request_id.clone(),
+        company: "Mock".to_string(),
+        product_type,
+        premium: PremiumDetail {
+            net: (net * 100.0).round() / 100.0,
+            gross,
+            taxes: ((gross - net) * 100.0).round() / 100.0,
+            currency: "TRY".to_string(),
+        },
+        installments,
+        coverages,
+        warnings: vec!["Mock provider: gerçek teklif değildir".to_string()],
+        provider_quote_no: Some(format!("MT-{:08}", fnv1a(&request.quote_meta.request_id) % 100_000_000)),
+        valid_until: chrono::Utc::now()
+            .date_naive()
+            .checked_add_days(chrono::Days::new(MOCK_QUOTE_VALID_DAYS))
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+        raw: None,
+        timings: Some(Timings {
+            queued_ms: 0,
+            scrape_ms,
+        }),
+    }
+}
+
+fn mock_coverage(code: &str, name: &str, limit: Option<&str>) -> Coverage {
+    Coverage {
+        code: code.to_string(),
+        name: name.to_string(),
+        limit: limit.map(|l| l.to_string()),
+        included: true,
+    }
+}
+
+fn normalize_plate(plate: &str) -> String {
+    plate.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase()
+}
+
+// FNV-1a: std hasher'ın aksine sürümler arası sabit
+fn fnv1a(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::models::{CoverageInfo, InsuredInfo, QuoteMeta, VehicleInfo};
+
+    fn request(plate: &str, product_type: ProductType) -> QuoteRequest {
+        QuoteRequest {
+            insured: InsuredInfo {
+                tckn: "12345678901".to_string(),
+                name: "Test Kullanıcı".to_string(),
+                birth_date: "1990-01-01".to_string(),
+                phone: "5551234567".to_string(),
+                email: "test@example.com".to_string(),
+            },
+            vehicle: VehicleInfo {
+                plate: plate.to_string(),
+                vin: None,
+                brand: "Renault".to_string(),
+                model: "Clio".to_string(),
+                year: 2020,
+                usage: VehicleUsage::Hususi,
+                category: VehicleCategory::Otomobil,
+                ..Default::default()
+            },
+            vehicle_id: None,
+            property: None,
+            coverage: CoverageInfo {
+                product_type,
+                start_date: "2025-01-01".to_string(),
+                addons: vec![],
+            },
+            quote_meta: QuoteMeta {
+                request_id: "req-1".to_string(),
+                webhook_url: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_premium_is_deterministic() {
+        let a = mock_premium(&request("34 ABC 123", ProductType::Trafik));
+        let b = mock_premium(&request("34abc123", ProductType::Trafik));
+        assert_eq!(a, b);
+        assert!(a > 0.0);
+
+        let kasko = mock_premium(&request("34ABC123", ProductType::Kasko));
+        assert_ne!(a, kasko);
+
+        // Araç yaşı teminat başlangıcından: ileri tarihli kasko daha eski araç sayılır
+        let mut later = request("34ABC123", ProductType::Kasko);
+        later.coverage.start_date = "2030-01-01".to_string();
+        assert!(mock_premium(&later) < kasko);
+    }
+
+    #[test]
+    fn test_mock_policy_is_deterministic() {
+        let quote_request = request("34ABC123", ProductType::Trafik);
+        let issue = PolicyIssueRequest {
+            quote: build_mock_quote(&quote_request, 0),
+            quote_request,
+            payment: serde_json::from_value(serde_json::json!({ "method": "cash" })).unwrap(),
+        };
+
+        let a = build_mock_policy(&issue).unwrap();
+        let b = build_mock_policy(&issue).unwrap();
+        assert_eq!(a.policy_number, b.policy_number);
+        assert!(a.policy_number.starts_with("MOCK-"));
+        assert_eq!(a.start_date, "2025-01-01");
+        assert_eq!(a.end_date, "2026-01-01");
+    }
+
+    #[test]
+    fn test_failure_rate() {
+        assert!(mock_failure("req-1", 0.0, Some("otp")).is_none());
+        assert!(matches!(
+            mock_failure("req-1", 1.0, Some("otp")),
+            Some(ApiError::HumanActionRequired(_))
+        ));
+        assert!(matches!(mock_failure("req-1", 1.0, Some("blocked")), Some(ApiError::Blocked(_))));
+        assert!(matches!(mock_failure("req-1", 1.0, Some("timeout")), Some(ApiError::Timeout(_))));
+    }
+}
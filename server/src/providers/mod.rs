@@ -1,8 +1,12 @@
 pub mod anadolu;
+pub mod api_mode;
 pub mod axa;
 pub mod base;
+pub mod mock;
+pub mod parsing;
 pub mod quick;
 pub mod registry;
+pub mod settings;
 pub mod sompo;
 
 pub use base::InsuranceProvider;
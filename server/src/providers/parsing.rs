@@ -0,0 +1,265 @@
+//! Sonuç sayfası HTML'inden fiyat, taksit ve teminat çıkaran ortak (browser'sız) fonksiyonlar.
+//! Provider parser'ları canlı sayfada `client.source()` ile aldıkları HTML'i buraya verir;
+//! böylece aynı kod `tests/fixtures/` altındaki kayıtlı sayfalarla test edilebilir.
+
+use crate::http::{Coverage, Installment};
+use crate::utils::html::{element_text, leaf_texts, texts_by_selectors};
+use crate::utils::{parse_portal_date, parse_tl_price};
+use chrono::{Days, NaiveDate};
+use regex::Regex;
+use scraper::{Html, Selector};
+
+const INSTALLMENT_ROW_SELECTORS: &[&str] = &[
+    "[class*='taksit'] tr",
+    "[class*='installment'] tr",
+    "[class*='taksit'] li",
+    "[class*='installment'] li",
+    ".taksit-item",
+    ".installment-item",
+];
+
+const COVERAGE_ROW_SELECTORS: &[&str] = &[
+    "[class*='teminat'] tr",
+    "[class*='coverage'] tr",
+    "[class*='teminat'] li",
+    "[class*='coverage'] li",
+    ".teminat-item",
+    ".coverage-item",
+];
+
+/// Sonuç sayfasından çıkarılan ham teklif bilgisi (provider'a özel varsayımlar uygulanmadan önce)
+#[derive(Debug, Clone)]
+pub struct ParsedQuote {
+    pub premium: f64,
+    pub installments: Vec<Installment>,
+    pub coverages: Vec<Coverage>,
+    pub reference: QuoteReference,
+}
+
+/// Portalın verdiği teklif numarası ve geçerlilik tarihi (poliçeleştirme / yeniden basım için)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuoteReference {
+    pub quote_no: Option<String>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+impl QuoteReference {
+    /// QuoteResponse.valid_until formatı (YYYY-MM-DD)
+    pub fn valid_until_string(&self) -> Option<String> {
+        self.valid_until.map(|d| d.format("%Y-%m-%d").to_string())
+    }
+}
+
+fn tl_amount_regex() -> Regex {
+    Regex::new(r"(\d{1,3}(?:\.\d{3})+(?:,\d{1,2})?|\d+(?:,\d{1,2})?)\s*(?:TL|₺)").expect("static regex")
+}
+
+/// Fiyat adayları arasından makul aralıktaki ilk değeri bulur.
+/// Önce provider'a özel selector'lar, sonra TL içeren leaf elementler denenir.
+pub fn find_price(document: &Html, selectors: &[&str], min: f64, max: f64) -> Option<f64> {
+    let re = tl_amount_regex();
+    let in_range = |text: &str| -> Option<f64> {
+        // Çok uzun metinler muhtemelen container element
+        if text.chars().count() >= 50 {
+            return None;
+        }
+        let caps = re.captures(text)?;
+        parse_tl_price(&caps[1]).ok().filter(|v| *v >= min && *v <= max)
+    };
+
+    texts_by_selectors(document, selectors)
+        .iter()
+        .chain(leaf_texts(document).iter())
+        .find_map(|t| in_range(t))
+}
+
+/// Taksit tablosunu parse eder ("Peşin 4.350,00 TL", "3 Taksit 1.450,00 TL" gibi satırlar)
+pub fn extract_installments(document: &Html) -> Vec<Installment> {
+    let count_re = Regex::new(r"(?i)(\d{1,2})\s*(?:x\s*)?taksit").expect("static regex");
+    let cash_re = Regex::new(r"(?i)peşin|tek çekim").expect("static regex");
+    let amount_re = tl_amount_regex();
+
+    let mut installments: Vec<Installment> = Vec::new();
+
+    for text in texts_by_selectors(document, INSTALLMENT_ROW_SELECTORS) {
+        let count = if let Some(caps) = count_re.captures(&text) {
+            caps[1].parse::<u8>().ok()
+        } else if cash_re.is_match(&text) {
+            Some(1)
+        } else {
+            None
+        };
+
+        let Some(count) = count.filter(|c| *c > 0) else {
+            continue;
+        };
+
+        if installments.iter().any(|i| i.count == count) {
+            continue;
+        }
+
+        let amounts: Vec<f64> = amount_re
+            .captures_iter(&text)
+            .filter_map(|c| parse_tl_price(&c[1]).ok())
+            .collect();
+
+        let (per_installment, total) = match amounts.as_slice() {
+            [] => continue,
+            [single] if count == 1 => (*single, *single),
+            [single] => (*single, ((single * count as f64) * 100.0).round() / 100.0),
+            [per, total, ..] => (*per, *total),
+        };
+
+        installments.push(Installment {
+            count,
+            per_installment,
+            total,
+        });
+    }
+
+    installments.sort_by_key(|i| i.count);
+    installments
+}
+
+/// Teminat tablosunu parse eder (ilk hücre teminat adı, sonraki hücre limit)
+pub fn extract_coverages(document: &Html) -> Vec<Coverage> {
+    let td = Selector::parse("td").expect("static selector");
+    let span = Selector::parse("span").expect("static selector");
+    let mut coverages: Vec<Coverage> = Vec::new();
+
+    for selector in COVERAGE_ROW_SELECTORS.iter().filter_map(|s| Selector::parse(s).ok()) {
+        for row in document.select(&selector) {
+            // Tablo satırında td, liste elemanında span hücreleri
+            let mut cells: Vec<String> = row.select(&td).map(|c| element_text(&c)).collect();
+            if cells.is_empty() {
+                cells = row.select(&span).map(|c| element_text(&c)).collect();
+            }
+            cells.retain(|t| !t.is_empty());
+
+            let Some(name) = cells.first().cloned() else {
+                continue;
+            };
+
+            let code = coverage_code(&name);
+            if code.is_empty() || coverages.iter().any(|c| c.code == code) {
+                continue;
+            }
+
+            let row_text = element_text(&row).to_lowercase();
+            let included = !(row_text.contains("dahil değil") || row_text.contains("yok"));
+
+            coverages.push(Coverage {
+                code,
+                name,
+                limit: cells.get(1).cloned().filter(|l| !l.is_empty()),
+                included,
+            });
+        }
+    }
+
+    coverages
+}
+
+/// Teklif numarasını ve geçerlilik tarihini bulur.
+/// Önce provider'a özel selector'lar, sonra sayfa metnindeki "Teklif No: ..." kalıbı denenir.
+/// Geçerlilik "15.02.2024" gibi tarih ya da "15 gün" gibi süre olarak verilebilir (süre `quoted_on`'a eklenir).
+pub fn extract_quote_reference(document: &Html, selectors: &[&str], quoted_on: NaiveDate) -> QuoteReference {
+    let labeled_re = Regex::new(r"(?i:teklif\s*(?:no|numarası|numarasi))\s*[:#]?\s*([A-Z0-9][A-Z0-9\-/]{3,})")
+        .expect("static regex");
+    let token_re = Regex::new(r"^[A-Z0-9][A-Z0-9\-/]{3,}$").expect("static regex");
+    let date_re = Regex::new(r"(?i)geçerli(?:lik)?[^0-9]{0,40}?(\d{2}[./]\d{2}[./]\d{4}|\d{4}-\d{2}-\d{2})")
+        .expect("static regex");
+    let days_re = Regex::new(r"(?i)geçerli(?:lik)?[^0-9]{0,40}?(\d{1,3})\s*gün").expect("static regex");
+
+    let body = Selector::parse("body").expect("static selector");
+    let page_text = document
+        .select(&body)
+        .next()
+        .map(|b| element_text(&b))
+        .unwrap_or_default();
+
+    // Numara en az bir rakam içermeli ("Teklif No: HESAPLANAMADI" gibi metinler elenir)
+    let has_digit = |s: &String| s.chars().any(|c| c.is_ascii_digit());
+    let quote_no = texts_by_selectors(document, selectors)
+        .iter()
+        .find_map(|text| {
+            labeled_re
+                .captures(text)
+                .map(|c| c[1].to_string())
+                .or_else(|| token_re.is_match(text).then(|| text.clone()))
+                .filter(has_digit)
+        })
+        .or_else(|| {
+            labeled_re
+                .captures_iter(&page_text)
+                .map(|c| c[1].to_string())
+                .find(has_digit)
+        });
+
+    let valid_until = date_re
+        .captures(&page_text)
+        .and_then(|c| parse_portal_date(&c[1]))
+        .or_else(|| {
+            days_re
+                .captures(&page_text)
+                .and_then(|c| c[1].parse::<u64>().ok())
+                .and_then(|days| quoted_on.checked_add_days(Days::new(days)))
+        });
+
+    QuoteReference { quote_no, valid_until }
+}
+
+/// Teminat adından stabil kod üretir ("İhtiyari Mali Mesuliyet" -> "IHTIYARI_MALI_MESULIYET")
+pub fn coverage_code(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'ı' | 'İ' | 'i' => 'I',
+            'ş' | 'Ş' => 'S',
+            'ğ' | 'Ğ' => 'G',
+            'ü' | 'Ü' => 'U',
+            'ö' | 'Ö' => 'O',
+            'ç' | 'Ç' => 'C',
+            c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+            _ => ' ',
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_quote_reference() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+
+        let doc = Html::parse_document(
+            r#"<html><body><div class="ref"><label>Teklif No</label><b>AX-99812</b></div>
+               <p>Teklif geçerlilik tarihi: 25.01.2024</p></body></html>"#,
+        );
+        let reference = extract_quote_reference(&doc, &[".ref b"], today);
+        assert_eq!(reference.quote_no.as_deref(), Some("AX-99812"));
+        assert_eq!(reference.valid_until, NaiveDate::from_ymd_opt(2024, 1, 25));
+
+        // Selector yoksa sayfa metni; geçerlilik süre olarak
+        let doc = Html::parse_document(
+            r#"<html><body><span>Teklif Numarası: 4410023</span><p>Teklif geçerlilik süresi: 15 gün</p></body></html>"#,
+        );
+        let reference = extract_quote_reference(&doc, &[], today);
+        assert_eq!(reference.quote_no.as_deref(), Some("4410023"));
+        assert_eq!(reference.valid_until_string().as_deref(), Some("2024-01-25"));
+
+        let doc = Html::parse_document("<html><body><p>Toplam 4.350,00 TL</p></body></html>");
+        assert_eq!(extract_quote_reference(&doc, &[], today), QuoteReference::default());
+    }
+
+    #[test]
+    fn test_coverage_code() {
+        assert_eq!(coverage_code("İhtiyari Mali Mesuliyet"), "IHTIYARI_MALI_MESULIYET");
+        assert_eq!(coverage_code("Ferdi Kaza (Sürücü)"), "FERDI_KAZA_SURUCU");
+        assert_eq!(coverage_code("  "), "");
+    }
+}
@@ -3,21 +3,17 @@ use crate::config::Config;
 use crate::http::ApiError;
 use crate::providers::quick::selectors::QuickSelectors;
 use fantoccini::{Client, Locator};
-use std::sync::Arc;
 
 pub async fn login_to_quick(
     client: &Client,
-    _config: Arc<Config>,
+    config: &Config,
     _session_manager: &SessionManager,
 ) -> Result<(), ApiError> {
-    let username = std::env::var("QUICK_USERNAME").unwrap_or_default();
-    let password = std::env::var("QUICK_PASSWORD").unwrap_or_default();
-    let url = std::env::var("QUICK_URL")
-        .unwrap_or_else(|_| "https://www.quicksigorta.com.tr/agent/login".to_string());
+    let (url, username, password) = (&config.quick_url, &config.quick_username, &config.quick_password);
     
     tracing::info!("🔍 Quick'e bağlanılıyor: {}", url);
     
-    client.goto(&url).await
+    client.goto(url).await
         .map_err(|e| ApiError::WebDriverError(format!("Sayfa yüklenemedi: {}", e)))?;
     
     tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
@@ -25,7 +21,7 @@ pub async fn login_to_quick(
     // Username
     for selector in QuickSelectors::USERNAME_INPUTS {
         if let Ok(elem) = client.find(Locator::Css(selector)).await {
-            if let Ok(_) = elem.send_keys(&username).await {
+            if let Ok(_) = elem.send_keys(username).await {
                 tracing::info!("✅ Quick username dolduruldu");
                 break;
             }
@@ -35,7 +31,7 @@ pub async fn login_to_quick(
     // Password
     for selector in QuickSelectors::PASSWORD_INPUTS {
         if let Ok(elem) = client.find(Locator::Css(selector)).await {
-            if let Ok(_) = elem.send_keys(&password).await {
+            if let Ok(_) = elem.send_keys(password).await {
                 tracing::info!("✅ Quick password dolduruldu");
                 break;
             }
@@ -1,9 +1,10 @@
 mod login;
-mod parser;
-mod quote;
+pub mod parser;
+pub mod quote;
 mod selectors;
 
 use crate::config::Config;
+use crate::http::models::{ProductType, ProviderCapabilities, VehicleCategory, VehicleUsage};
 use crate::http::{ApiError, QuoteRequest, QuoteResponse};
 use crate::providers::base::InsuranceProvider;
 use async_trait::async_trait;
@@ -19,6 +20,11 @@ impl QuickProvider {
     }
 }
 
+/// Acente config'inde (bkz. Config::for_agency) Quick kullanıcı bilgisi var mı
+fn is_configured(config: &Config) -> bool {
+    !config.quick_username.is_empty() && !config.quick_password.is_empty()
+}
+
 #[async_trait]
 impl InsuranceProvider for QuickProvider {
     fn name(&self) -> &str {
@@ -26,9 +32,7 @@ impl InsuranceProvider for QuickProvider {
     }
     
     fn is_active(&self) -> bool {
-        // TODO: Quick credentials için env variable ekle
-        std::env::var("QUICK_USERNAME").is_ok()
-            && std::env::var("QUICK_PASSWORD").is_ok()
+        is_configured(&self.config)
     }
     
     fn inactive_reason(&self) -> Option<String> {
@@ -39,8 +43,15 @@ impl InsuranceProvider for QuickProvider {
         }
     }
     
-    fn supported_products(&self) -> Vec<String> {
-        vec!["trafik".to_string(), "kasko".to_string()]
+    fn capabilities(&self) -> ProviderCapabilities {
+        // Quick scraper'ı yalnızca trafik formunu dolduruyor
+        ProviderCapabilities {
+            supported_products: vec![ProductType::Trafik],
+            supported_addons: vec![],
+            usage_types: vec![VehicleUsage::Hususi],
+            vehicle_categories: vec![VehicleCategory::Otomobil],
+            policy_issuance: false,
+        }
     }
     
     async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
@@ -1,48 +1,32 @@
 use crate::http::{ApiError, Coverage, Installment, PremiumDetail, QuoteResponse, Timings};
+use crate::providers::parsing::{
+    extract_coverages, extract_installments, extract_quote_reference, find_price, ParsedQuote,
+};
 use crate::providers::quick::selectors::QuickSelectors;
-use fantoccini::{Client, Locator};
+use fantoccini::Client;
+use scraper::Html;
 
 pub async fn parse_quick_quote(
     client: &Client,
     request_id: String,
     scrape_start_ms: u64,
 ) -> Result<QuoteResponse, ApiError> {
-    let mut price_value: Option<f64> = None;
-    
-    for selector in QuickSelectors::PRICE_ELEMENTS {
-        if let Ok(elements) = client.find_all(Locator::Css(selector)).await {
-            for elem in elements {
-                if let Ok(text) = elem.text().await {
-                    if text.contains("TL") {
-                        // Parse TL price (e.g., "1.234,56 TL" -> 1234.56)
-                        let cleaned = text.replace("TL", "").replace(".", "").replace(",", ".").trim().to_string();
-                        if let Ok(value) = cleaned.parse::<f64>() {
-                            if value >= 1000.0 && value <= 50000.0 {
-                                price_value = Some(value);
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-            if price_value.is_some() {
-                break;
-            }
-        }
-    }
-    
-    let premium = price_value.ok_or_else(|| {
-        ApiError::ParseError("Quick fiyat bulunamadı".to_string())
-    })?;
-    
+    let html = client
+        .source()
+        .await
+        .map_err(|e| ApiError::ParseError(format!("Quick sayfa kaynağı alınamadı: {}", e)))?;
+
+    let parsed = parse_quick_html(&html)?;
+    let premium = parsed.premium;
+
     let net = premium / 1.18;
     let taxes = premium - net;
-    
+
     let scrape_elapsed = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_millis() as u64 - scrape_start_ms;
-    
+
     Ok(QuoteResponse {
         request_id,
         company: "Quick".to_string(),
@@ -53,27 +37,57 @@ pub async fn parse_quick_quote(
             taxes: (taxes * 100.0).round() / 100.0,
             currency: "TRY".to_string(),
         },
-        installments: vec![
+        installments: parsed.installments,
+        coverages: parsed.coverages,
+        warnings: vec![],
+        provider_quote_no: parsed.reference.quote_no.clone(),
+        valid_until: parsed.reference.valid_until_string(),
+        raw: None,
+        timings: Some(Timings {
+            queued_ms: 0,
+            scrape_ms: scrape_elapsed,
+        }),
+    })
+}
+
+/// Quick sonuç sayfası HTML'inden fiyat, taksit ve teminatları çıkarır (browser gerektirmez)
+pub fn parse_quick_html(html: &str) -> Result<ParsedQuote, ApiError> {
+    let document = Html::parse_document(html);
+
+    let premium = find_price(&document, QuickSelectors::PRICE_ELEMENTS, 1000.0, 50000.0)
+        .ok_or_else(|| ApiError::ParseError("Quick fiyat bulunamadı".to_string()))?;
+
+    let mut installments = extract_installments(&document);
+    if installments.is_empty() {
+        installments = vec![
             Installment {
                 count: 1,
                 per_installment: premium,
                 total: premium,
             },
-        ],
-        coverages: vec![
+        ];
+    }
+
+    let mut coverages = extract_coverages(&document);
+    if coverages.is_empty() {
+        coverages = vec![
             Coverage {
                 code: "TRAFIK_ZORUNLU".to_string(),
                 name: "Zorunlu Trafik Sigortası".to_string(),
                 limit: None,
                 included: true,
             },
-        ],
-        warnings: vec![],
-        raw: None,
-        timings: Some(Timings {
-            queued_ms: 0,
-            scrape_ms: scrape_elapsed,
-        }),
+        ];
+    }
+
+    Ok(ParsedQuote {
+        premium,
+        installments,
+        coverages,
+        reference: extract_quote_reference(
+            &document,
+            QuickSelectors::QUOTE_NO_ELEMENTS,
+            chrono::Utc::now().date_naive(),
+        ),
     })
 }
-
@@ -22,7 +22,7 @@ pub async fn fetch_quick_quote(
     
     let session_manager = SessionManager::new(&config.session_dir);
     
-    if let Err(e) = login_to_quick(&client, config.clone(), &session_manager).await {
+    if let Err(e) = login_to_quick(&client, &config, &session_manager).await {
         let _ = client.close().await;
         return Err(e);
     }
@@ -50,5 +50,12 @@ impl QuickSelectors {
         "td:has-text('TL')",
         "span:has-text('TL')",
     ];
+
+    // Teklif numarası (bulunamazsa sayfa metnindeki "Teklif No:" kalıbı denenir)
+    pub const QUOTE_NO_ELEMENTS: &'static [&'static str] = &[
+        ".teklif-no",
+        ".quote-number",
+        "[data-field='teklifNo']",
+    ];
 }
 
@@ -1,27 +1,97 @@
 use crate::config::Config;
-use crate::http::{ProviderInfo, ProvidersResponse};
+use crate::http::models::ProductType;
+use crate::http::{ApiError, ProviderInfo, ProvidersResponse, QuoteRequest};
 use crate::providers::anadolu::AnadoluProvider;
 use crate::providers::axa::AxaProvider;
 use crate::providers::base::InsuranceProvider;
+use crate::providers::mock::MockProvider;
 use crate::providers::quick::QuickProvider;
+use crate::providers::settings::{ConfiguredProvider, ProviderSettings, ProvidersFile, StatementMapping};
 use crate::providers::sompo::SompoProvider;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 pub struct ProviderRegistry {
     providers: Vec<Arc<dyn InsuranceProvider>>,
+    /// Küçük harfli provider adı -> ekstre sütunları
+    statement_mappings: HashMap<String, StatementMapping>,
+}
+
+/// Registry'nin tanıdığı provider adları (config dosyasındaki "name" ile eşleşir)
+const KNOWN_PROVIDERS: &[&str] = &["sompo", "quick", "axa", "anadolu", "mock"];
+
+fn build_provider(name: &str, config: &Arc<Config>) -> Option<Arc<dyn InsuranceProvider>> {
+    match name.to_lowercase().as_str() {
+        "sompo" => Some(Arc::new(SompoProvider::new(config.clone()))),
+        "quick" => Some(Arc::new(QuickProvider::new(config.clone()))),
+        "axa" => Some(Arc::new(AxaProvider::new(config.clone()))),
+        "anadolu" => Some(Arc::new(AnadoluProvider::new(config.clone()))),
+        "mock" => Some(Arc::new(MockProvider::new(config.clone()))),
+        _ => None,
+    }
 }
 
 impl ProviderRegistry {
-    pub fn new(config: Arc<Config>) -> Self {
-        let mut providers: Vec<Arc<dyn InsuranceProvider>> = Vec::new();
+    /// PROVIDERS_CONFIG varsa dosyadan, yoksa varsayılan ayarlarla registry oluşturur
+    pub fn new(config: Arc<Config>) -> Result<Self, ApiError> {
+        let file = match &config.providers_config {
+            Some(path) => {
+                tracing::info!("📄 Provider config yükleniyor: {}", path);
+                ProvidersFile::from_file(path)?
+            }
+            None => ProvidersFile::default(),
+        };
+        
+        Ok(Self::from_settings(config, &file))
+    }
+    
+    pub fn from_settings(config: Arc<Config>, file: &ProvidersFile) -> Self {
+        for settings in &file.providers {
+            if !KNOWN_PROVIDERS.contains(&settings.name.to_lowercase().as_str()) {
+                tracing::warn!("⚠️ Provider config'de bilinmeyen provider: {}", settings.name);
+            }
+        }
         
-        // Tüm provider'ları ekle
-        providers.push(Arc::new(SompoProvider::new(config.clone())));
-        providers.push(Arc::new(QuickProvider::new(config.clone())));
-        providers.push(Arc::new(AxaProvider::new(config.clone())));
-        providers.push(Arc::new(AnadoluProvider::new(config.clone())));
+        let mut providers: Vec<ConfiguredProvider> = Vec::new();
         
-        Self { providers }
+        for (index, name) in KNOWN_PROVIDERS.iter().enumerate() {
+            let settings = match file.settings_for(name) {
+                Some(settings) => settings.clone(),
+                // Geliştirme / CI: mock yalnızca flag veya config ile kayıtlı
+                None if *name == "mock" && !config.mock_provider => continue,
+                None => ProviderSettings::defaults(name, 100 + index as i32),
+            };
+            
+            if *name == "mock" && settings.enabled {
+                tracing::warn!("🧪 Mock provider kayıtlı (gerçek teklif değildir)");
+            }
+            
+            if let Some(provider) = build_provider(name, &config) {
+                providers.push(ConfiguredProvider::new(provider, settings));
+            }
+        }
+        
+        // Öncelik sırası (eşitlikte kayıt sırası korunur)
+        providers.sort_by_key(|p| p.priority());
+        
+        let statement_mappings = file
+            .providers
+            .iter()
+            .filter_map(|s| Some((s.name.to_lowercase(), s.statement.clone()?)))
+            .collect();
+        
+        Self {
+            providers: providers
+                .into_iter()
+                .map(|p| Arc::new(p) as Arc<dyn InsuranceProvider>)
+                .collect(),
+            statement_mappings,
+        }
+    }
+    
+    /// PROVIDERS_CONFIG'teki komisyon ekstresi sütunları
+    pub fn statement_mapping(&self, name: &str) -> Option<StatementMapping> {
+        self.statement_mappings.get(&name.to_lowercase()).cloned()
     }
     
     pub fn get_provider(&self, name: &str) -> Option<Arc<dyn InsuranceProvider>> {
@@ -39,6 +109,53 @@ impl ProviderRegistry {
             .collect()
     }
     
+    /// Ürün tipini destekleyen aktif provider'lar (öncelik sırasıyla)
+    pub fn get_active_providers_for(&self, product_type: &ProductType) -> Vec<Arc<dyn InsuranceProvider>> {
+        self.providers
+            .iter()
+            .filter(|p| p.is_active() && p.supports_product(product_type))
+            .cloned()
+            .collect()
+    }
+    
+    /// İsteği karşılayabilen aktif provider'lar. Capability uyumsuzluğu olanlar browser açılmadan atlanır.
+    pub fn providers_for_request(
+        &self,
+        request: &QuoteRequest,
+    ) -> Result<Vec<Arc<dyn InsuranceProvider>>, ApiError> {
+        request.validate().map_err(ApiError::FormValidation)?;
+        let product_type = request.coverage.product_type.as_str();
+        let mut eligible = Vec::new();
+        let mut skipped = Vec::new();
+        
+        for provider in self.get_active_providers_for(&request.coverage.product_type) {
+            match provider.capabilities().check(request) {
+                Ok(()) => eligible.push(provider),
+                Err(reason) => {
+                    tracing::info!("⏭️ {} atlandı: {}", provider.name(), reason);
+                    skipped.push(format!("{}: {}", provider.name(), reason));
+                }
+            }
+        }
+        
+        if !eligible.is_empty() {
+            return Ok(eligible);
+        }
+        
+        if skipped.is_empty() {
+            Err(ApiError::ProviderInactive(format!(
+                "{} ürününü destekleyen aktif provider yok",
+                product_type
+            )))
+        } else {
+            Err(ApiError::FormValidation(format!(
+                "Bu {} isteğini karşılayabilen provider yok ({})",
+                product_type,
+                skipped.join("; ")
+            )))
+        }
+    }
+    
     pub fn get_all_providers(&self) -> Vec<Arc<dyn InsuranceProvider>> {
         self.providers.clone()
     }
@@ -51,7 +168,7 @@ impl ProviderRegistry {
                 name: p.name().to_string(),
                 active: p.is_active(),
                 reason: p.inactive_reason(),
-                supported_products: p.supported_products(),
+                capabilities: p.capabilities(),
             })
             .collect();
         
@@ -0,0 +1,345 @@
+use crate::http::models::{IssuedPolicy, PolicyIssueRequest, ProviderCapabilities};
+use crate::http::{ApiError, QuoteRequest, QuoteResponse};
+use crate::providers::base::InsuranceProvider;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Provider yapılandırma dosyası (PROVIDERS_CONFIG)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProvidersFile {
+    #[serde(default)]
+    pub providers: Vec<ProviderSettings>,
+}
+
+/// Tek bir provider'ın ayarları. Dosyada olmayan alanlar varsayılan davranışı korur.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSettings {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Küçük değer önce çalışır / listelenir
+    #[serde(default = "default_priority")]
+    pub priority: i32,
+    /// Ürün -> açık/kapalı. Verilmezse provider'ın kendi listesi kullanılır;
+    /// verilirse yalnızca burada açık olan ürünler desteklenir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub products: Option<BTreeMap<String, bool>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Aynı anda en fazla kaç teklif (browser oturumu) çalışabilir
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    /// Komisyon ekstresi sütunları; verilmezse başlıklardan tahmin edilir
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement: Option<StatementMapping>,
+}
+
+/// Şirket ekstresindeki sütunlar: başlık adı ("Poliçe No") veya sütun harfi ("C")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementMapping {
+    pub policy_number: String,
+    pub commission: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub premium: Option<String>,
+    /// XLSX sayfa adı (varsayılan ilk sayfa)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sheet: Option<String>,
+    /// Başlık satırı (1'den başlar); verilmezse ilk 20 satırda aranır
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_row: Option<usize>,
+    /// CSV ayracı (varsayılan ilk satırdan tahmin: ; , veya sekme)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<char>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_priority() -> i32 {
+    100
+}
+
+impl ProviderSettings {
+    /// Dosyada listelenmeyen provider için varsayılanlar
+    pub fn defaults(name: &str, priority: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            enabled: true,
+            priority,
+            products: None,
+            timeout_ms: None,
+            max_concurrency: None,
+            statement: None,
+        }
+    }
+}
+
+impl ProvidersFile {
+    /// JSON dosyasından provider ayarlarını yükler
+    pub fn from_file(path: &str) -> Result<Self, ApiError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ApiError::InternalServerError(format!("Provider config okunamadı ({}): {}", path, e)))?;
+        let file: Self = serde_json::from_str(&content)
+            .map_err(|e| ApiError::InternalServerError(format!("Provider config geçersiz ({}): {}", path, e)))?;
+        file.validate()?;
+        Ok(file)
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        for (i, settings) in self.providers.iter().enumerate() {
+            if self.providers[..i].iter().any(|s| s.name.eq_ignore_ascii_case(&settings.name)) {
+                return Err(ApiError::InternalServerError(format!(
+                    "Provider config'de tekrar eden provider: {}",
+                    settings.name
+                )));
+            }
+            if settings.max_concurrency == Some(0) {
+                return Err(ApiError::InternalServerError(format!(
+                    "{}: maxConcurrency 0 olamaz",
+                    settings.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn settings_for(&self, name: &str) -> Option<&ProviderSettings> {
+        self.providers.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Config dosyasındaki ayarları (enabled, ürünler, timeout, eşzamanlılık) provider'a uygular
+pub struct ConfiguredProvider {
+    inner: Arc<dyn InsuranceProvider>,
+    settings: ProviderSettings,
+    semaphore: Option<Arc<Semaphore>>,
+}
+
+impl ConfiguredProvider {
+    pub fn new(inner: Arc<dyn InsuranceProvider>, settings: ProviderSettings) -> Self {
+        let semaphore = settings.max_concurrency.map(|n| Arc::new(Semaphore::new(n)));
+        Self {
+            inner,
+            settings,
+            semaphore,
+        }
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.settings.priority
+    }
+
+    /// Eşzamanlılık limiti: boş slot yoksa sıra beklenir
+    async fn acquire_slot(&self) -> Result<Option<OwnedSemaphorePermit>, ApiError> {
+        match &self.semaphore {
+            Some(semaphore) => semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .map(Some)
+                .map_err(|e| ApiError::InternalServerError(format!("Semaphore kapandı: {}", e))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl InsuranceProvider for ConfiguredProvider {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_active(&self) -> bool {
+        self.settings.enabled && self.inner.is_active()
+    }
+
+    fn inactive_reason(&self) -> Option<String> {
+        if !self.settings.enabled {
+            Some("Provider config'de devre dışı".to_string())
+        } else {
+            self.inner.inactive_reason()
+        }
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        let mut capabilities = self.inner.capabilities();
+        if let Some(products) = &self.settings.products {
+            capabilities
+                .supported_products
+                .retain(|p| products.get(p.as_str()).copied().unwrap_or(false));
+        }
+        capabilities
+    }
+
+    async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+        self.check_request(&request)?;
+        let skipped_addons = self.capabilities().unsupported_addons(&request).join(", ");
+
+        let _permit = self.acquire_slot().await?;
+
+        let mut quote = match self.settings.timeout_ms {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(ms), self.inner.fetch_quote(request))
+                .await
+                .map_err(|_| ApiError::Timeout(format!("{} teklifi {} ms içinde tamamlanmadı", self.name(), ms)))?,
+            None => self.inner.fetch_quote(request).await,
+        }?;
+        if !skipped_addons.is_empty() {
+            quote.warnings.push(format!("Ek teminat teklife dahil edilmedi: {}", skipped_addons));
+        }
+        Ok(quote)
+    }
+
+    async fn issue_policy(&self, request: PolicyIssueRequest) -> Result<IssuedPolicy, ApiError> {
+        if !self.is_active() {
+            return Err(ApiError::ProviderInactive(format!("{} aktif değil", self.name())));
+        }
+
+        let _permit = self.acquire_slot().await?;
+
+        // Teklif timeout'u burada uygulanmaz: ödeme ortasında kesilen istek portalda poliçe bırakabilir
+        self.inner.issue_policy(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::models::ProductType;
+
+    struct SlowProvider {
+        delay_ms: u64,
+    }
+
+    #[async_trait]
+    impl InsuranceProvider for SlowProvider {
+        fn name(&self) -> &str {
+            "Slow"
+        }
+
+        fn is_active(&self) -> bool {
+            true
+        }
+
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                supported_products: vec![ProductType::Trafik, ProductType::Kasko, ProductType::Konut],
+                ..Default::default()
+            }
+        }
+
+        async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            Ok(crate::providers::mock::build_mock_quote(&request, self.delay_ms))
+        }
+    }
+
+    fn configured(json: serde_json::Value, delay_ms: u64) -> ConfiguredProvider {
+        let settings: ProviderSettings = serde_json::from_value(json).unwrap();
+        ConfiguredProvider::new(Arc::new(SlowProvider { delay_ms }), settings)
+    }
+
+    fn konut_request() -> QuoteRequest {
+        serde_json::from_value(serde_json::json!({
+            "insured": { "tckn": "12345678901", "name": "Test", "birthDate": "1990-01-01", "phone": "5551234567", "email": "t@example.com" },
+            "property": { "daskPolicyNo": "12345678" },
+            "coverage": { "productType": "konut", "startDate": "2025-01-01" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_product_overrides() {
+        let provider = configured(
+            serde_json::json!({ "name": "slow", "products": { "trafik": true, "konut": false } }),
+            0,
+        );
+        assert_eq!(provider.capabilities().supported_products, vec![ProductType::Trafik]);
+        assert!(provider.supports_product(&ProductType::Trafik));
+        assert!(!provider.supports_product(&ProductType::Konut));
+
+        let defaults = configured(serde_json::json!({ "name": "slow" }), 0);
+        assert!(defaults.is_active());
+        assert_eq!(defaults.priority(), 100);
+        assert!(defaults.supports_product(&ProductType::Konut));
+
+        let disabled = configured(serde_json::json!({ "name": "slow", "enabled": false }), 0);
+        assert!(!disabled.is_active());
+        assert!(disabled.inactive_reason().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_product_and_timeout() {
+        let no_konut = configured(serde_json::json!({ "name": "slow", "products": { "trafik": true } }), 0);
+        assert!(matches!(
+            no_konut.fetch_quote(konut_request()).await,
+            Err(ApiError::FormValidation(_))
+        ));
+
+        let slow = configured(serde_json::json!({ "name": "slow", "timeoutMs": 20 }), 500);
+        assert!(matches!(slow.fetch_quote(konut_request()).await, Err(ApiError::Timeout(_))));
+    }
+
+    #[test]
+    fn test_duplicate_provider_rejected() {
+        let file: ProvidersFile = serde_json::from_value(serde_json::json!({
+            "providers": [{ "name": "sompo" }, { "name": "Sompo", "priority": 1 }]
+        }))
+        .unwrap();
+        assert!(file.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_capability_mismatch_rejected_before_fetch() {
+        // Gecikme 10 sn: fetch'e ulaşılsaydı test zaman aşımına düşerdi
+        let provider = configured(serde_json::json!({ "name": "slow" }), 10_000);
+
+        let mut request: QuoteRequest = serde_json::from_value(serde_json::json!({
+            "insured": { "tckn": "12345678901", "name": "Test", "birthDate": "1990-01-01", "phone": "5551234567", "email": "t@example.com" },
+            "vehicle": { "plate": "34ABC123", "brand": "Ford", "model": "Transit", "year": 2019, "usage": "ticari", "category": "kamyonet" },
+            "coverage": { "productType": "kasko", "startDate": "2025-01-01", "addons": ["IMM"] }
+        }))
+        .unwrap();
+
+        // Desteklenmeyen ek teminat isteği reddetmez
+        assert_eq!(provider.capabilities().unsupported_addons(&request), ["IMM"]);
+        let err = provider.fetch_quote(request.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("kamyonet"));
+
+        request.coverage.addons.clear();
+        let err = provider.check_request(&request).unwrap_err();
+        assert!(err.to_string().contains("kamyonet"));
+
+        // Araç ürününde plaka (vehicle / vehicleId) zorunlu
+        request.vehicle = Default::default();
+        let err = provider.fetch_quote(request).await.unwrap_err();
+        assert!(err.to_string().contains("araç bilgisi"));
+
+        // Konut isteğinde araç alanları kontrol edilmez
+        assert!(provider.check_request(&konut_request()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_addons_become_warning() {
+        let provider = configured(serde_json::json!({ "name": "slow" }), 0);
+        let mut request: QuoteRequest = serde_json::from_value(serde_json::json!({
+            "insured": { "tckn": "12345678901", "name": "Test", "birthDate": "1990-01-01", "phone": "5551234567", "email": "t@example.com" },
+            "vehicle": { "plate": "34ABC123", "brand": "Fiat", "model": "Egea", "year": 2020, "usage": "hususi" },
+            "coverage": { "productType": "trafik", "startDate": "2025-01-01", "addons": ["IMM", "FERDI_KAZA"] }
+        }))
+        .unwrap();
+
+        let quote = provider.fetch_quote(request.clone()).await.unwrap();
+        assert!(quote.warnings.iter().any(|w| w.contains("IMM, FERDI_KAZA")));
+
+        request.coverage.addons.clear();
+        let quote = provider.fetch_quote(request).await.unwrap();
+        assert!(!quote.warnings.iter().any(|w| w.contains("Ek teminat")));
+    }
+}
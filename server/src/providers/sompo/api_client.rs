@@ -0,0 +1,240 @@
+use crate::browser::session::{SessionData, SessionManager};
+use crate::config::Config;
+use crate::http::{ApiError, IssuedPolicy, PolicyIssueRequest, QuoteRequest, QuoteResponse};
+use crate::providers::api_mode::{ApiModeError, ApiRequestTemplate, ApiTemplateSet, SessionHttpClient};
+use crate::providers::sompo::api_parser::{build_quote_response, parse_policy_api_response, parse_quote_api_response};
+use crate::providers::sompo::python_login::login_via_python;
+use crate::utils::{one_year_after, parse_portal_date};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SOMPO_PORTAL_URL: &str = "https://ejento.somposigorta.com.tr";
+
+/// Varsayılan Sompo endpoint şablonları. Portal değiştiğinde SOMPO_API_TEMPLATES ile dosyadan override edilir.
+fn default_templates() -> ApiTemplateSet {
+    let vehicle_body = serde_json::json!({
+        "plateNo": "{{plate}}",
+        "identityNo": "{{tckn}}",
+        "birthDate": "{{birthDate}}",
+        "modelYear": "{{year}}",
+        "brand": "{{brand}}",
+        "model": "{{model}}",
+        "usageType": "{{usage}}",
+        "startDate": "{{startDate}}"
+    });
+
+    let policy_body = serde_json::json!({
+        "proposalNo": "{{providerQuoteNo}}",
+        "plateNo": "{{plate}}",
+        "identityNo": "{{tckn}}",
+        "startDate": "{{startDate}}",
+        "grossPremium": "{{premium}}",
+        "payment": {
+            "type": "{{paymentMethod}}",
+            "installmentCount": "{{installmentCount}}",
+            "card": {
+                "holderName": "{{cardHolder}}",
+                "number": "{{cardNumber}}",
+                "expiryMonth": "{{cardExpiryMonth}}",
+                "expiryYear": "{{cardExpiryYear}}",
+                "cvv": "{{cardCvv}}"
+            }
+        }
+    });
+
+    ApiTemplateSet {
+        base_url: SOMPO_PORTAL_URL.to_string(),
+        products: HashMap::from([
+            (
+                "trafik".to_string(),
+                ApiRequestTemplate {
+                    method: "POST".to_string(),
+                    path: "/api/proposal/traffic/calculate".to_string(),
+                    headers: HashMap::new(),
+                    body: Some(vehicle_body.clone()),
+                },
+            ),
+            (
+                "kasko".to_string(),
+                ApiRequestTemplate {
+                    method: "POST".to_string(),
+                    path: "/api/proposal/casco/calculate".to_string(),
+                    headers: HashMap::new(),
+                    body: Some(vehicle_body),
+                },
+            ),
+        ]),
+        policies: HashMap::from([
+            (
+                "trafik".to_string(),
+                ApiRequestTemplate {
+                    method: "POST".to_string(),
+                    path: "/api/proposal/traffic/issue".to_string(),
+                    headers: HashMap::new(),
+                    body: Some(policy_body.clone()),
+                },
+            ),
+            (
+                "kasko".to_string(),
+                ApiRequestTemplate {
+                    method: "POST".to_string(),
+                    path: "/api/proposal/casco/issue".to_string(),
+                    headers: HashMap::new(),
+                    body: Some(policy_body),
+                },
+            ),
+        ]),
+    }
+}
+
+/// SOMPO_API_TEMPLATES dosyası varsa onu, yoksa varsayılan şablonları yükler
+pub fn load_templates(config: &Config) -> Result<ApiTemplateSet, ApiError> {
+    match &config.sompo_api_templates {
+        Some(path) => ApiTemplateSet::from_file(path),
+        None => Ok(default_templates()),
+    }
+}
+
+/// Browser ile alınmış session üzerinden portal quote endpoint'ini direkt çağırır.
+/// Session reddedilirse `ApiModeError::SessionRejected` döner; çağıran browser akışına düşer.
+pub async fn fetch_sompo_quote_api(
+    config: Arc<Config>,
+    templates: Arc<ApiTemplateSet>,
+    request: QuoteRequest,
+) -> Result<QuoteResponse, ApiModeError> {
+    let scrape_start = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let product_type = match request.coverage.product_type {
+        crate::http::models::ProductType::Trafik => "trafik",
+        crate::http::models::ProductType::Kasko => "kasko",
+        _ => {
+            return Err(ApiModeError::Failed(ApiError::FormValidation(
+                "Desteklenmeyen ürün tipi".to_string(),
+            )))
+        }
+    };
+
+    let template = templates.template_for(product_type).ok_or_else(|| {
+        ApiError::FormValidation(format!("Sompo API şablonu yok: {}", product_type))
+    })?;
+
+    tracing::info!("⚡ Sompo API mode: {} teklifi (request_id={})", product_type, request.quote_meta.request_id);
+
+    let session = get_or_create_session(&config).await?;
+
+    let client = SessionHttpClient::new(
+        &templates.base_url,
+        &session,
+        &config.user_agent,
+        &config.accept_language,
+        config.request_timeout_ms,
+    )?;
+
+    let body = client.execute(template, &request).await?;
+
+    let api_quote = parse_quote_api_response(&body).ok_or_else(|| {
+        ApiError::ParseError("Sompo API yanıtında prim bulunamadı".to_string())
+    })?;
+
+    let scrape_elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64 - scrape_start;
+
+    tracing::info!("✅ Sompo API mode başarılı: {:.2} TL ({}ms)", api_quote.gross, scrape_elapsed);
+
+    Ok(build_quote_response(&request, api_quote, product_type, scrape_elapsed))
+}
+
+/// Kaydedilmiş teklifi portalın teklif→poliçe endpoint'i ile poliçeye dönüştürür.
+/// Session reddedilirse (istek işlenmeden) bir kez yeniden login olunur; diğer hatalarda tekrar denenmez.
+pub async fn issue_sompo_policy_api(
+    config: Arc<Config>,
+    templates: Arc<ApiTemplateSet>,
+    request: PolicyIssueRequest,
+) -> Result<IssuedPolicy, ApiError> {
+    let product_type = request.quote.product_type.as_str();
+    let template = templates.policy_template_for(product_type).ok_or_else(|| {
+        ApiError::FormValidation(format!("Sompo poliçe şablonu yok: {}", product_type))
+    })?;
+
+    tracing::info!(
+        "🧾 Sompo poliçe kesimi: {} (request_id={}, ödeme={}, taksit={})",
+        product_type,
+        request.quote.request_id,
+        request.payment.method.as_str(),
+        request.payment.installment_count
+    );
+
+    let mut retried = false;
+    let body = loop {
+        let session = get_or_create_session(&config).await?;
+        let client = SessionHttpClient::new(
+            &templates.base_url,
+            &session,
+            &config.user_agent,
+            &config.accept_language,
+            config.request_timeout_ms,
+        )?;
+
+        match client.execute_policy(template, &request).await {
+            Ok(body) => break body,
+            Err(ApiModeError::SessionRejected(msg)) if !retried => {
+                tracing::warn!("⚠️ Sompo poliçe isteğinde session reddedildi ({}), yeniden login...", msg);
+                SessionManager::new(&config.session_dir).clear_session("sompo").ok();
+                retried = true;
+            }
+            Err(ApiModeError::SessionRejected(msg)) => {
+                return Err(ApiError::LoginFailed(format!("Sompo session reddedildi: {}", msg)))
+            }
+            Err(ApiModeError::Failed(e)) => return Err(e),
+        }
+    };
+
+    let api_policy = parse_policy_api_response(&body).ok_or_else(|| {
+        ApiError::ParseError("Sompo poliçe yanıtında poliçe numarası bulunamadı".to_string())
+    })?;
+
+    let start = match api_policy.start_date {
+        Some(date) => date,
+        None => {
+            tracing::warn!("⚠️ Sompo poliçe yanıtında başlangıç tarihi yok, teklif tarihi kullanılıyor");
+            parse_portal_date(&request.quote_request.coverage.start_date).ok_or_else(|| {
+                ApiError::ParseError("Poliçe başlangıç tarihi belirlenemedi".to_string())
+            })?
+        }
+    };
+    let end = api_policy.end_date.unwrap_or_else(|| {
+        tracing::warn!("⚠️ Sompo poliçe yanıtında bitiş tarihi yok, 1 yıllık vade varsayıldı");
+        one_year_after(start)
+    });
+
+    tracing::info!("✅ Sompo poliçe kesildi: {}", api_policy.policy_number);
+
+    Ok(IssuedPolicy {
+        policy_number: api_policy.policy_number,
+        start_date: start.format("%Y-%m-%d").to_string(),
+        end_date: end.format("%Y-%m-%d").to_string(),
+        premium: api_policy.gross,
+        raw: Some(body),
+    })
+}
+
+/// Cache'deki session'ı kullan; yoksa browser (Python) login ile yenisini al
+async fn get_or_create_session(config: &Config) -> Result<SessionData, ApiError> {
+    let session_manager = SessionManager::new(&config.session_dir);
+
+    if let Some(session) = session_manager.load_session("sompo") {
+        return Ok(session);
+    }
+
+    tracing::info!("🔐 Geçerli Sompo session yok, browser login yapılıyor...");
+    let session = login_via_python(config).await?;
+    session_manager.save_session("sompo", session.clone()).ok();
+
+    Ok(session)
+}
@@ -0,0 +1,400 @@
+use crate::http::{Coverage, Installment, PremiumDetail, QuoteRequest, QuoteResponse, Timings};
+use crate::utils::{parse_portal_date, parse_tl_price};
+use chrono::NaiveDate;
+use serde_json::{Map, Value};
+
+// Portal JSON'larında görülen alan adları (küçük harf, Türkçe + İngilizce varyantlar)
+const GROSS_KEYS: &[&str] = &["grosspremium", "brutprim", "totalpremium", "toplamprim", "odenecekprim"];
+// Genel "premium"/"prim" alanları teminat satırlarında da geçer; yalnızca belirgin alan yoksa kullanılır
+const GENERIC_GROSS_KEYS: &[&str] = &["premium", "prim"];
+const NET_KEYS: &[&str] = &["netpremium", "netprim"];
+const TAX_KEYS: &[&str] = &["taxamount", "totaltax", "vergi", "vergitutari", "taxes", "tax"];
+const INSTALLMENT_LIST_KEYS: &[&str] = &["installments", "installmentoptions", "taksitler", "paymentplans", "odemeplanlari"];
+const INSTALLMENT_COUNT_KEYS: &[&str] = &["installmentcount", "taksitsayisi", "count"];
+const INSTALLMENT_AMOUNT_KEYS: &[&str] = &["installmentamount", "taksittutari", "amount", "perinstallment"];
+const INSTALLMENT_TOTAL_KEYS: &[&str] = &["totalamount", "toplamtutar", "total"];
+const COVERAGE_LIST_KEYS: &[&str] = &["coverages", "teminatlar", "covers"];
+const COVERAGE_CODE_KEYS: &[&str] = &["code", "coveragecode", "teminatkodu", "kod"];
+const COVERAGE_NAME_KEYS: &[&str] = &["name", "coveragename", "teminatadi", "description", "aciklama"];
+const COVERAGE_LIMIT_KEYS: &[&str] = &["limit", "teminatlimiti", "amount", "bedel"];
+const QUOTE_NO_KEYS: &[&str] = &["proposalno", "quoteno", "quotenumber", "teklifno", "teklifnumarasi", "proposalnumber"];
+const QUOTE_VALID_KEYS: &[&str] = &[
+    "validuntil", "validitydate", "expiredate", "expirydate", "gecerliliktarihi", "teklifgecerliliktarihi", "songecerliliktarihi",
+];
+const POLICY_NUMBER_KEYS: &[&str] = &["policyno", "policynumber", "policeno", "policenumarasi", "polno"];
+const POLICY_START_KEYS: &[&str] = &["startdate", "policystartdate", "baslangictarihi", "vadebaslangic"];
+const POLICY_END_KEYS: &[&str] = &["enddate", "policyenddate", "bitistarihi", "vadebitis"];
+
+/// Portal quote API yanıtından çıkarılan yapılandırılmış fiyat bilgisi
+#[derive(Debug, Clone)]
+pub struct ApiQuote {
+    pub gross: f64,
+    pub net: Option<f64>,
+    pub taxes: Option<f64>,
+    pub installments: Vec<Installment>,
+    pub coverages: Vec<Coverage>,
+    pub quote_no: Option<String>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+/// Quote API JSON'ından prim, vergi, taksit ve teminatları çıkarır.
+/// Prim bulunamazsa None döner (çağıran taraf DOM parse'a düşer).
+pub fn parse_quote_api_response(body: &Value) -> Option<ApiQuote> {
+    let (premium_obj, gross) = find_gross_premium(body)?;
+
+    let installments = find_array_by_keys(body, INSTALLMENT_LIST_KEYS)
+        .map(|items| items.iter().filter_map(|i| parse_installment(i, gross)).collect())
+        .unwrap_or_default();
+
+    let coverages = find_array_by_keys(body, COVERAGE_LIST_KEYS)
+        .map(|items| items.iter().filter_map(parse_coverage).collect())
+        .unwrap_or_default();
+
+    Some(ApiQuote {
+        gross,
+        net: number_by_keys(premium_obj, NET_KEYS),
+        taxes: number_by_keys(premium_obj, TAX_KEYS),
+        installments,
+        coverages,
+        quote_no: find_object_with_text_key(body, QUOTE_NO_KEYS).and_then(|obj| text_by_keys(obj, QUOTE_NO_KEYS)),
+        valid_until: find_object_with_text_key(body, QUOTE_VALID_KEYS)
+            .and_then(|obj| text_by_keys(obj, QUOTE_VALID_KEYS))
+            .and_then(|text| parse_portal_date(&text)),
+    })
+}
+
+/// Teklif → poliçe API yanıtından çıkarılan poliçe bilgisi
+#[derive(Debug, Clone)]
+pub struct ApiPolicy {
+    pub policy_number: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub gross: Option<f64>,
+}
+
+/// Poliçe API JSON'ından poliçe numarası, vade tarihleri ve kesinleşen primi çıkarır.
+/// Poliçe numarası bulunamazsa None döner (poliçe kesildi sayılmaz).
+pub fn parse_policy_api_response(body: &Value) -> Option<ApiPolicy> {
+    let policy_obj = find_object_with_text_key(body, POLICY_NUMBER_KEYS)?;
+    let policy_number = text_by_keys(policy_obj, POLICY_NUMBER_KEYS)?;
+
+    let date = |keys: &[&str]| {
+        text_by_keys(policy_obj, keys)
+            .or_else(|| find_object_with_text_key(body, keys).and_then(|obj| text_by_keys(obj, keys)))
+            .and_then(|text| parse_portal_date(&text))
+    };
+
+    Some(ApiPolicy {
+        policy_number,
+        start_date: date(POLICY_START_KEYS),
+        end_date: date(POLICY_END_KEYS),
+        gross: find_gross_premium(body).map(|(_, gross)| gross),
+    })
+}
+
+/// ApiQuote'tan QuoteResponse oluştur. Eksik alanlar DOM akışındaki varsayımlarla tamamlanır.
+pub fn build_quote_response(
+    request: &QuoteRequest,
+    api_quote: ApiQuote,
+    product_type: &str,
+    scrape_ms: u64,
+) -> QuoteResponse {
+    let gross = api_quote.gross;
+    let net = api_quote
+        .net
+        .or_else(|| api_quote.taxes.map(|t| gross - t))
+        .unwrap_or(gross / 1.18);
+    let taxes = api_quote.taxes.unwrap_or(gross - net);
+
+    let installments = if api_quote.installments.is_empty() {
+        vec![Installment {
+            count: 1,
+            per_installment: gross,
+            total: gross,
+        }]
+    } else {
+        api_quote.installments
+    };
+
+    let coverages = if api_quote.coverages.is_empty() && product_type == "trafik" {
+        vec![Coverage {
+            code: "TRAFIK_ZORUNLU".to_string(),
+            name: "Zorunlu Trafik Sigortası".to_string(),
+            limit: None,
+            included: true,
+        }]
+    } else {
+        api_quote.coverages
+    };
+
+    QuoteResponse {
+        request_id: request.quote_meta.request_id.clone(),
+        company: "Sompo".to_string(),
+        product_type: product_type.to_string(),
+        premium: PremiumDetail {
+            net: (net * 100.0).round() / 100.0,
+            gross: (gross * 100.0).round() / 100.0,
+            taxes: (taxes * 100.0).round() / 100.0,
+            currency: "TRY".to_string(),
+        },
+        installments,
+        coverages,
+        warnings: vec![],
+        provider_quote_no: api_quote.quote_no,
+        valid_until: api_quote.valid_until.map(|d| d.format("%Y-%m-%d").to_string()),
+        raw: None,
+        timings: Some(Timings {
+            queued_ms: 0,
+            scrape_ms,
+        }),
+    }
+}
+
+fn parse_installment(item: &Value, gross: f64) -> Option<Installment> {
+    let obj = item.as_object()?;
+    let count = number_by_keys(obj, INSTALLMENT_COUNT_KEYS)? as u8;
+    if count == 0 {
+        return None;
+    }
+
+    let total = number_by_keys(obj, INSTALLMENT_TOTAL_KEYS).unwrap_or(gross);
+    let per_installment = number_by_keys(obj, INSTALLMENT_AMOUNT_KEYS)
+        .unwrap_or_else(|| ((total / count as f64) * 100.0).round() / 100.0);
+
+    Some(Installment {
+        count,
+        per_installment,
+        total,
+    })
+}
+
+fn parse_coverage(item: &Value) -> Option<Coverage> {
+    let obj = item.as_object()?;
+    let name = string_by_keys(obj, COVERAGE_NAME_KEYS)?;
+    let code = string_by_keys(obj, COVERAGE_CODE_KEYS).unwrap_or_else(|| name.to_uppercase().replace(' ', "_"));
+
+    let included = obj
+        .iter()
+        .find(|(k, _)| matches!(k.to_lowercase().as_str(), "included" | "selected" | "dahil" | "secili"))
+        .and_then(|(_, v)| v.as_bool())
+        .unwrap_or(true);
+
+    Some(Coverage {
+        code,
+        name,
+        limit: obj
+            .iter()
+            .find(|(k, _)| COVERAGE_LIMIT_KEYS.contains(&k.to_lowercase().as_str()))
+            .and_then(|(_, v)| match v {
+                Value::String(s) if !s.is_empty() => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }),
+        included,
+    })
+}
+
+/// Brüt primi ve onu taşıyan object'i bulur: önce tüm ağaçta belirgin alanlar
+/// (grossPremium, brutPrim...), bulunamazsa genel "premium"/"prim" alanları aranır.
+fn find_gross_premium(body: &Value) -> Option<(&Map<String, Value>, f64)> {
+    [GROSS_KEYS, GENERIC_GROSS_KEYS].into_iter().find_map(|keys| {
+        let obj = find_object_with_key(body, keys)?;
+        number_by_keys(obj, keys).filter(|v| *v > 0.0).map(|gross| (obj, gross))
+    })
+}
+
+/// Key listesindeki herhangi bir alanı (öncelik sırasıyla) içeren ilk object'i bulur (DFS).
+/// Teminat ve taksit listelerine inilmez; oradaki primler brüt prim değildir.
+fn find_object_with_key<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Map<String, Value>> {
+    match value {
+        Value::Object(map) => {
+            if number_by_keys(map, keys).is_some() {
+                return Some(map);
+            }
+            map.iter()
+                .filter(|(k, _)| {
+                    let key = k.to_lowercase();
+                    !COVERAGE_LIST_KEYS.contains(&key.as_str()) && !INSTALLMENT_LIST_KEYS.contains(&key.as_str())
+                })
+                .find_map(|(_, v)| find_object_with_key(v, keys))
+        }
+        Value::Array(items) => items.iter().find_map(|v| find_object_with_key(v, keys)),
+        _ => None,
+    }
+}
+
+fn find_object_with_text_key<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Map<String, Value>> {
+    match value {
+        Value::Object(map) => {
+            if text_by_keys(map, keys).is_some() {
+                return Some(map);
+            }
+            map.values().find_map(|v| find_object_with_text_key(v, keys))
+        }
+        Value::Array(items) => items.iter().find_map(|v| find_object_with_text_key(v, keys)),
+        _ => None,
+    }
+}
+
+fn find_array_by_keys<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a Vec<Value>> {
+    match value {
+        Value::Object(map) => {
+            for key in keys {
+                if let Some(Value::Array(items)) = map.iter().find(|(k, _)| k.to_lowercase() == *key).map(|(_, v)| v) {
+                    return Some(items);
+                }
+            }
+            map.values().find_map(|v| find_array_by_keys(v, keys))
+        }
+        Value::Array(items) => items.iter().find_map(|v| find_array_by_keys(v, keys)),
+        _ => None,
+    }
+}
+
+fn number_by_keys(map: &Map<String, Value>, keys: &[&str]) -> Option<f64> {
+    keys.iter().find_map(|key| {
+        map.iter()
+            .find(|(k, _)| k.to_lowercase() == *key)
+            .and_then(|(_, v)| as_number(v))
+    })
+}
+
+fn string_by_keys(map: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        map.iter()
+            .find(|(k, _)| k.to_lowercase() == *key)
+            .and_then(|(_, v)| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.trim().to_string())
+    })
+}
+
+/// String veya sayı olarak gelen kimlik alanları (poliçe no, tarih)
+fn text_by_keys(map: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| {
+        map.iter()
+            .find(|(k, _)| k.to_lowercase() == *key)
+            .and_then(|(_, v)| match v {
+                Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+    })
+}
+
+/// Sayı veya Türkçe formatlı string ("4.350,00 TL") değerini f64'e çevirir
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => parse_tl_price(s).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_nested_quote_response() {
+        let body = json!({
+            "success": true,
+            "data": {
+                "proposalNo": "T-123",
+                "validUntil": "15.06.2024",
+                "premiumInfo": {
+                    "netPremium": 3686.44,
+                    "taxAmount": "663,56",
+                    "grossPremium": "4.350,00"
+                },
+                "installments": [
+                    { "installmentCount": 1, "installmentAmount": 4350.0, "totalAmount": 4350.0 },
+                    { "installmentCount": 3, "installmentAmount": 1450.0 }
+                ],
+                "coverages": [
+                    { "coverageCode": "IMM", "coverageName": "İhtiyari Mali Mesuliyet", "limit": "1.000.000 TL" },
+                    { "coverageCode": "FK", "coverageName": "Ferdi Kaza", "selected": false }
+                ]
+            }
+        });
+
+        let quote = parse_quote_api_response(&body).unwrap();
+        assert_eq!(quote.gross, 4350.0);
+        assert_eq!(quote.quote_no.as_deref(), Some("T-123"));
+        assert_eq!(quote.valid_until, NaiveDate::from_ymd_opt(2024, 6, 15));
+        assert_eq!(quote.net, Some(3686.44));
+        assert_eq!(quote.taxes, Some(663.56));
+        assert_eq!(quote.installments.len(), 2);
+        assert_eq!(quote.installments[1].count, 3);
+        assert_eq!(quote.installments[1].total, 4350.0);
+        assert_eq!(quote.coverages.len(), 2);
+        assert_eq!(quote.coverages[0].code, "IMM");
+        assert_eq!(quote.coverages[0].limit.as_deref(), Some("1.000.000 TL"));
+        assert!(!quote.coverages[1].included);
+    }
+
+    #[test]
+    fn test_parse_turkish_keys() {
+        let body = json!({ "sonuc": { "brutPrim": 5120.5, "netPrim": 4339.41, "vergi": 781.09 } });
+
+        let quote = parse_quote_api_response(&body).unwrap();
+        assert_eq!(quote.gross, 5120.5);
+        assert_eq!(quote.net, Some(4339.41));
+        assert_eq!(quote.taxes, Some(781.09));
+        assert!(quote.installments.is_empty());
+    }
+
+    #[test]
+    fn test_parse_policy_response() {
+        let body = json!({
+            "success": true,
+            "data": {
+                "policy": { "policyNo": 1200345678, "startDate": "2024-06-01T00:00:00", "endDate": "01.06.2025" },
+                "premiumInfo": { "grossPremium": "4.350,00" }
+            }
+        });
+
+        let policy = parse_policy_api_response(&body).unwrap();
+        assert_eq!(policy.policy_number, "1200345678");
+        assert_eq!(policy.start_date, NaiveDate::from_ymd_opt(2024, 6, 1));
+        assert_eq!(policy.end_date, NaiveDate::from_ymd_opt(2025, 6, 1));
+        assert_eq!(policy.gross, Some(4350.0));
+
+        let turkish = json!({ "sonuc": { "policeNo": "P-77", "baslangicTarihi": "15.03.2024" } });
+        let policy = parse_policy_api_response(&turkish).unwrap();
+        assert_eq!(policy.policy_number, "P-77");
+        assert_eq!(policy.start_date, NaiveDate::from_ymd_opt(2024, 3, 15));
+        assert!(policy.end_date.is_none());
+
+        // Teklif yanıtı poliçe sayılmamalı
+        assert!(parse_policy_api_response(&json!({ "data": { "proposalNo": "T-1", "premium": 100 } })).is_none());
+    }
+
+    #[test]
+    fn test_coverage_premium_is_not_gross() {
+        let body = json!({
+            "data": {
+                "coverages": [{ "name": "IMM", "premium": 120 }],
+                "premiumInfo": { "grossPremium": 4350 }
+            }
+        });
+
+        assert_eq!(parse_quote_api_response(&body).unwrap().gross, 4350.0);
+
+        let policy = json!({
+            "data": {
+                "coverages": [{ "name": "IMM", "premium": 120 }],
+                "installments": [{ "installmentCount": 1, "prim": 4350 }],
+                "policy": { "policyNo": "P-1", "premiumInfo": { "premium": 4350 } }
+            }
+        });
+        assert_eq!(parse_policy_api_response(&policy).unwrap().gross, Some(4350.0));
+    }
+
+    #[test]
+    fn test_unrelated_response_returns_none() {
+        assert!(parse_quote_api_response(&json!({ "menu": ["Trafik", "Kasko"] })).is_none());
+        assert!(parse_quote_api_response(&json!({ "premium": 0 })).is_none());
+    }
+}
@@ -1,11 +1,11 @@
-use crate::browser::session::{Cookie, SessionData, SessionManager};
+use crate::browser::driver::{capture_session, restore_session};
+use crate::browser::session::SessionManager;
 use crate::config::Config;
 use crate::http::ApiError;
 use crate::providers::sompo::selectors::SompoSelectors;
 use crate::utils::mask_sensitive;
 use fantoccini::{Client, Locator};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 pub async fn login_to_sompo(
     client: &Client,
@@ -1101,144 +1101,10 @@ async fn is_logged_in(client: &Client) -> bool {
 }
 
 async fn save_current_session(client: &Client, session_manager: &SessionManager) -> Result<(), ApiError> {
-    // Fantoccini'den cookie'leri al
-    let cookies_raw = client.get_all_cookies().await
-        .map_err(|e| ApiError::WebDriverError(format!("Cookie alınamadı: {}", e)))?;
-    
-    let cookies: Vec<Cookie> = cookies_raw
-        .into_iter()
-        .map(|c| Cookie {
-            name: c.name().to_string(),
-            value: c.value().to_string(),
-            domain: c.domain().unwrap_or("").to_string(),
-            path: c.path().unwrap_or("/").to_string(),
-            secure: c.secure().unwrap_or(false),
-            http_only: c.http_only().unwrap_or(false),
-        })
-        .collect();
-    
-    // LocalStorage'ı al (Python kodundan gelen özellik)
-    let js_get_local_storage = r#"
-        try {
-            const items = {};
-            for (let i = 0; i < localStorage.length; i++) {
-                const key = localStorage.key(i);
-                items[key] = localStorage.getItem(key);
-            }
-            return JSON.stringify(items);
-        } catch (e) {
-            return JSON.stringify({});
-        }
-    "#;
-    
-    let local_storage = match client.execute(js_get_local_storage, vec![]).await {
-        Ok(result) => {
-            if let Some(json_str) = result.as_str() {
-                match serde_json::from_str::<std::collections::HashMap<String, String>>(json_str) {
-                    Ok(map) => {
-                        tracing::info!("💾 LocalStorage alındı: {} items", map.len());
-                        map
-                    }
-                    Err(e) => {
-                        tracing::warn!("LocalStorage parse hatası: {}", e);
-                        std::collections::HashMap::new()
-                    }
-                }
-            } else {
-                std::collections::HashMap::new()
-            }
-        }
-        Err(e) => {
-            tracing::warn!("LocalStorage alınamadı: {}", e);
-            std::collections::HashMap::new()
-        }
-    };
-    
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    
-    // Session 1 saat geçerli
-    let valid_until = now + 3600;
-    
-    let session = SessionData {
-        cookies,
-        local_storage,
-        timestamp: now,
-        valid_until,
-    };
+    let session = capture_session(client).await?;
     
     session_manager.save_session("sompo", session)
         .map_err(|e| ApiError::Unknown(format!("Session kaydetme hatası: {}", e)))?;
     
     Ok(())
 }
-
-async fn restore_session(client: &Client, session: &SessionData, base_url: &str) -> Result<(), String> {
-    // Önce domain'e git ki cookie'leri set edebilsin
-    client.goto(base_url).await
-        .map_err(|e| format!("Sayfa yüklenemedi: {}", e))?;
-    
-    // Cookie'leri yükle
-    for cookie in &session.cookies {
-        let cookie_value = serde_json::json!({
-            "name": cookie.name,
-            "value": cookie.value,
-            "domain": cookie.domain,
-            "path": cookie.path,
-            "secure": cookie.secure,
-            "httpOnly": cookie.http_only,
-        });
-        
-        // Fantoccini'de add_cookie fonksiyonu farklı şekilde çalışıyor
-        // Bu yüzden script ile ekleyeceğiz
-        let script = format!(
-            r#"document.cookie = "{}={}; domain={}; path={}; {}{}""#,
-            cookie.name,
-            cookie.value,
-            cookie.domain,
-            cookie.path,
-            if cookie.secure { "secure; " } else { "" },
-            if cookie.http_only { "httpOnly; " } else { "" }
-        );
-        
-        if let Err(e) = client.execute(&script, vec![]).await {
-            tracing::warn!("Cookie set edilemedi: {:?}", e);
-        }
-    }
-    
-    // LocalStorage'ı yükle (Python kodundan gelen özellik)
-    if !session.local_storage.is_empty() {
-        let local_storage_json = serde_json::to_string(&session.local_storage)
-            .unwrap_or_else(|_| "{}".to_string());
-        
-        let js_set_local_storage = format!(r#"
-            try {{
-                const data = {};
-                for (const [key, value] of Object.entries(data)) {{
-                    localStorage.setItem(key, value);
-                }}
-                return true;
-            }} catch (e) {{
-                return false;
-            }}
-        "#, local_storage_json);
-        
-        match client.execute(&js_set_local_storage, vec![]).await {
-            Ok(_) => {
-                tracing::info!("💾 LocalStorage yüklendi: {} items", session.local_storage.len());
-            }
-            Err(e) => {
-                tracing::warn!("LocalStorage yüklenemedi: {}", e);
-            }
-        }
-    }
-    
-    // Sayfayı yenile
-    client.refresh().await
-        .map_err(|e| format!("Sayfa yenilenemedi: {}", e))?;
-    
-    Ok(())
-}
-
@@ -1,24 +1,43 @@
-mod login;
-mod parser;
+mod api_client;
+mod api_parser;
+pub mod login;
+pub mod parser;
 mod quote;
-mod quote_cdp;  // CDP implementation
+pub mod quote_cdp;  // CDP implementation
 mod python_login;  // Python subprocess login
 mod python_scraper;  // Python full scraper (Login + Quote) - RECOMMENDED
 mod selectors;
 
+use crate::browser::SessionManager;
 use crate::config::Config;
+use crate::http::models::{IssuedPolicy, PolicyIssueRequest, ProductType, ProviderCapabilities, VehicleCategory, VehicleUsage};
 use crate::http::{ApiError, QuoteRequest, QuoteResponse};
+use crate::providers::api_mode::{ApiModeError, ApiTemplateSet};
 use crate::providers::base::InsuranceProvider;
 use async_trait::async_trait;
 use std::sync::Arc;
 
+/// API mode'da coverage.addons olarak iletilen ek teminat kodları
+const API_MODE_ADDONS: &[&str] = &["IMM", "FERDI_KAZA", "HUKUKSAL_KORUMA", "MINI_ONARIM", "IKAME_ARAC"];
+
 pub struct SompoProvider {
     config: Arc<Config>,
+    /// API şablonları provider oluşturulurken (başlangıç / reload) bir kez okunur.
+    /// Dosya okunamaz veya geçersizse hata saklanır; API mode istekleri bu hatayla düşer.
+    templates: Result<Arc<ApiTemplateSet>, String>,
 }
 
 impl SompoProvider {
     pub fn new(config: Arc<Config>) -> Self {
-        Self { config }
+        let templates = api_client::load_templates(&config).map(Arc::new).map_err(|e| {
+            tracing::error!("❌ Sompo API şablonları yüklenemedi: {}", e);
+            e.to_string()
+        });
+        Self { config, templates }
+    }
+
+    fn templates(&self) -> Result<Arc<ApiTemplateSet>, ApiError> {
+        self.templates.clone().map_err(ApiError::InternalServerError)
     }
 }
 
@@ -41,8 +60,25 @@ impl InsuranceProvider for SompoProvider {
         }
     }
     
-    fn supported_products(&self) -> Vec<String> {
-        vec!["trafik".to_string(), "kasko".to_string()]
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            supported_products: vec![ProductType::Trafik, ProductType::Kasko],
+            // Ek teminatlar yalnızca API mode'da, şablon `{{addons}}` gönderiyorsa işlenir
+            supported_addons: if self.config.sompo_api_mode
+                && self
+                    .templates
+                    .as_ref()
+                    .is_ok_and(|t| ["trafik", "kasko"].iter().all(|p| t.product_sends(p, "addons")))
+            {
+                API_MODE_ADDONS.iter().map(|a| a.to_string()).collect()
+            } else {
+                vec![]
+            },
+            usage_types: vec![VehicleUsage::Hususi, VehicleUsage::Ticari],
+            vehicle_categories: vec![VehicleCategory::Otomobil, VehicleCategory::Kamyonet],
+            // Poliçe kesimi portalın teklif→poliçe endpoint'i üzerinden (API mode)
+            policy_issuance: self.config.sompo_api_mode,
+        }
     }
     
            async fn fetch_quote(&self, request: QuoteRequest) -> Result<QuoteResponse, ApiError> {
@@ -52,9 +88,45 @@ impl InsuranceProvider for SompoProvider {
                    ));
                }
 
+               // API mode: browser sadece session için, quote direkt HTTP ile
+               if self.config.sompo_api_mode {
+                   let result = match self.templates() {
+                       Ok(templates) => {
+                           api_client::fetch_sompo_quote_api(self.config.clone(), templates, request.clone()).await
+                       }
+                       Err(e) => Err(ApiModeError::Failed(e)),
+                   };
+                   match result {
+                       Ok(quote) => return Ok(quote),
+                       Err(ApiModeError::SessionRejected(msg)) => {
+                           tracing::warn!("⚠️ Sompo API session reddedildi ({}), browser akışına düşülüyor", msg);
+                           SessionManager::new(&self.config.session_dir).clear_session("sompo").ok();
+                       }
+                       Err(ApiModeError::Failed(e)) => {
+                           tracing::warn!("⚠️ Sompo API mode başarısız ({}), browser akışına düşülüyor", e);
+                       }
+                   }
+               }
+
                // MÜŞTERİNİN ÇALIŞAN SOMPO SCRAPER'INI KULLAN
                tracing::info!("🎯 Müşterinin çalışan Sompo scraper'ı kullanılıyor");
                python_scraper::fetch_sompo_quote_python(self.config.clone(), request).await
            }
+    
+    async fn issue_policy(&self, request: PolicyIssueRequest) -> Result<IssuedPolicy, ApiError> {
+        if !self.is_active() {
+            return Err(ApiError::ProviderInactive(
+                "Sompo credentials yapılandırılmamış".to_string()
+            ));
+        }
+        // Browser akışında poliçe adımı yok; yalnızca API mode
+        if !self.config.sompo_api_mode {
+            return Err(ApiError::ProviderInactive(
+                "Sompo poliçe kesimi için SOMPO_API_MODE gerekli".to_string()
+            ));
+        }
+        
+        api_client::issue_sompo_policy_api(self.config.clone(), self.templates()?, request).await
+    }
 }
 
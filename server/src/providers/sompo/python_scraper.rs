@@ -98,6 +98,10 @@ pub async fn fetch_sompo_quote_python(
         coverages: Vec<CoverageData>,
         #[serde(default)]
         warnings: Vec<String>,
+        #[serde(default)]
+        proposal_no: Option<String>,
+        #[serde(default)]
+        valid_until: Option<String>,
         timings: TimingsData,
     }
     
@@ -173,6 +177,13 @@ pub async fn fetch_sompo_quote_python(
             })
             .collect(),
         warnings: python_response.warnings,
+        provider_quote_no: python_response.proposal_no,
+        // Python script portal formatında (GG.AA.YYYY) döndürebilir
+        valid_until: python_response
+            .valid_until
+            .as_deref()
+            .and_then(crate::utils::parse_portal_date)
+            .map(|d| d.format("%Y-%m-%d").to_string()),
         raw: None,
         timings: Some(crate::http::Timings {
             queued_ms: 0,
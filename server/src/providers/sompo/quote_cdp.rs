@@ -1,8 +1,11 @@
-use crate::browser::{create_cdp_browser, inject_anti_detection, wait_for_navigation, wait_for_network_idle};
+use crate::browser::{create_cdp_browser, inject_anti_detection, start_har_capture, wait_for_navigation, wait_for_network_idle, HarRecorder, ResponseCapture};
 use crate::browser::session::SessionManager;
 use crate::config::Config;
 use crate::http::{ApiError, Coverage, Installment, PremiumDetail, QuoteRequest, QuoteResponse, Timings};
+use crate::providers::parsing::{extract_quote_reference, QuoteReference};
+use crate::providers::sompo::api_parser::{build_quote_response, parse_quote_api_response};
 use crate::providers::sompo::python_login::login_via_python;
+use crate::providers::sompo::selectors::SompoSelectors;
 use chromiumoxide::Page;
 use data_encoding::BASE32;
 use std::sync::Arc;
@@ -40,10 +43,16 @@ pub async fn fetch_sompo_quote_cdp(
             let page = browser.new_page("about:blank").await
                 .map_err(|e| ApiError::WebDriverError(format!("Page oluşturulamadı: {}", e)))?;
             inject_anti_detection(&page).await.ok();
-            login_to_sompo_cdp(&page, &config).await?;
+            let har = start_har_capture(&page, &config).await;
+            if let Err(e) = login_to_sompo_cdp(&page, &config).await {
+                save_har(har, &config, &request).await;
+                let _ = browser.close().await;
+                return Err(e);
+            }
             
             // Bu browser ile devam et - session yok
             let result = get_quote_cdp(&page, &request, scrape_start).await;
+            save_har(har, &config, &request).await;
             let _ = browser.close().await;
             return result;
         }
@@ -64,18 +73,40 @@ pub async fn fetch_sompo_quote_cdp(
     // Anti-detection
     inject_anti_detection(&page).await.ok();
     
+    // HAR kaydı (opt-in)
+    let har = start_har_capture(&page, &config).await;
+    
     // Session restore
-    restore_session(&page, session).await?;
+    if let Err(e) = restore_session(&page, session, &config.sompo_base_url).await {
+        save_har(har, &config, &request).await;
+        let _ = browser.close().await;
+        return Err(e);
+    }
     
     // Quote al
     let result = get_quote_cdp(&page, &request, scrape_start).await;
     
+    // HAR'ı artifact'lerle birlikte kaydet
+    save_har(har, &config, &request).await;
+    
     // Browser kapat
     let _ = browser.close().await;
     
     result
 }
 
+/// HAR kaydı açıksa dosyaya yaz (hata quote sonucunu etkilemez)
+async fn save_har(har: Option<HarRecorder>, config: &Config, request: &QuoteRequest) {
+    if let Some(recorder) = har {
+        if let Err(e) = recorder
+            .finish_and_save(&config.artifact_dir, "sompo", &request.quote_meta.request_id)
+            .await
+        {
+            tracing::warn!("⚠️ HAR kaydedilemedi: {}", e);
+        }
+    }
+}
+
 async fn login_to_sompo_cdp(
     page: &Page,
     config: &Config,
@@ -431,6 +462,15 @@ async fn get_quote_cdp(
     
     tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
     
+    // Quote API yanıtlarını submit'ten önce dinlemeye başla
+    let api_capture = match ResponseCapture::start(page, SompoSelectors::QUOTE_API_PATTERNS).await {
+        Ok(capture) => Some(capture),
+        Err(e) => {
+            tracing::warn!("⚠️ XHR yakalama başlatılamadı, DOM parse kullanılacak: {}", e);
+            None
+        }
+    };
+    
     // Submit
     tracing::info!("🔍 Submit button aranıyor...");
     
@@ -455,7 +495,34 @@ async fn get_quote_cdp(
     // Results bekle
     wait_for_network_idle(page, 15).await.ok();
     
-    // Fiyat parse et
+    let scrape_elapsed = || {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64 - scrape_start_ms
+    };
+    
+    // Önce portal'ın quote API yanıtı (yapılandırılmış JSON)
+    if let Some(capture) = &api_capture {
+        let responses = capture.responses().await;
+        tracing::info!("📡 {} quote API yanıtı yakalandı", responses.len());
+        
+        // En son gelen yanıt genellikle nihai hesaplamadır
+        let api_quote = responses
+            .iter()
+            .rev()
+            .filter(|r| (200..300).contains(&r.status))
+            .find_map(|r| parse_quote_api_response(&r.body).map(|q| (r.url.clone(), q)));
+        
+        if let Some((url, api_quote)) = api_quote {
+            tracing::info!("✅ Fiyat API yanıtından alındı: {:.2} TL ({})", api_quote.gross, url);
+            return Ok(build_quote_response(request, api_quote, "trafik", scrape_elapsed()));
+        }
+        
+        tracing::warn!("⚠️ Quote API yanıtında prim bulunamadı, DOM parse'a düşülüyor");
+    }
+    
+    // Fallback: DOM'dan fiyat parse et
     tracing::info!("💰 Fiyat parse ediliyor...");
     
     let js_parse_price = r#"
@@ -511,15 +578,23 @@ async fn get_quote_cdp(
     
     tracing::info!("✅ Fiyat: {:.2} TL", price);
     
+    // Teklif numarası / geçerlilik (sayfa kaynağından)
+    let reference = match page.content().await {
+        Ok(html) => extract_quote_reference(
+            &scraper::Html::parse_document(&html),
+            SompoSelectors::QUOTE_NO_ELEMENTS,
+            chrono::Utc::now().date_naive(),
+        ),
+        Err(e) => {
+            tracing::warn!("⚠️ Sayfa kaynağı alınamadı, teklif no okunamadı: {}", e);
+            QuoteReference::default()
+        }
+    };
+    
     // Response oluştur
     let net = price / 1.18;
     let taxes = price - net;
     
-    let scrape_elapsed = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as u64 - scrape_start_ms;
-    
     Ok(QuoteResponse {
         request_id: request.quote_meta.request_id.clone(),
         company: "Sompo".to_string(),
@@ -545,11 +620,13 @@ async fn get_quote_cdp(
                 included: true,
             },
         ],
-        warnings: vec![],
+        warnings: vec!["Fiyat DOM'dan okundu (quote API yanıtı bulunamadı)".to_string()],
+        provider_quote_no: reference.quote_no.clone(),
+        valid_until: reference.valid_until_string(),
         raw: None,
         timings: Some(Timings {
             queued_ms: 0,
-            scrape_ms: scrape_elapsed,
+            scrape_ms: scrape_elapsed(),
         }),
     })
 }
@@ -572,11 +649,17 @@ fn parse_tl_price(text: &str) -> Result<f64, ApiError> {
 async fn restore_session(
     page: &Page,
     session: crate::browser::session::SessionData,
+    base_url: &str,
 ) -> Result<(), ApiError> {
     tracing::info!("🔄 Session restore başlatılıyor...");
     
+    // Portal kökü (SOMPO_BASE_URL'den; mock portal ile de çalışsın)
+    let origin = reqwest::Url::parse(base_url)
+        .map(|u| u.origin().ascii_serialization())
+        .unwrap_or_else(|_| "https://ejento.somposigorta.com.tr".to_string());
+    
     // İlk olarak base URL'e git (cookies set etmek için)
-    page.goto("https://ejento.somposigorta.com.tr")
+    page.goto(origin.as_str())
         .await
         .map_err(|e| ApiError::WebDriverError(format!("Base URL yüklenemedi: {}", e)))?;
     
@@ -629,7 +712,7 @@ async fn restore_session(
     
     // Dashboard'a git (session restore sonrası)
     tracing::info!("🏠 Dashboard'a yönlendiriliyor...");
-    page.goto("https://ejento.somposigorta.com.tr/dashboard")
+    page.goto(format!("{}/dashboard", origin))
         .await
         .map_err(|e| ApiError::WebDriverError(format!("Dashboard yüklenemedi: {}", e)))?;
     
@@ -126,6 +126,13 @@ impl SompoSelectors {
         ".price-value",
         ".tutar",
     ];
+
+    // Teklif numarası (bulunamazsa sayfa metnindeki "Teklif No:" kalıbı denenir)
+    pub const QUOTE_NO_ELEMENTS: &'static [&'static str] = &[
+        ".proposal-no",
+        ".proposal-info span",
+        "[class*='proposal-no']",
+    ];
     
     // Loading/waiting indicators
     pub const LOADING_INDICATORS: &'static [&'static str] = &[
@@ -134,5 +141,14 @@ impl SompoSelectors {
         "[class*='loading']",
         "[class*='spinner']",
     ];
+    
+    // Quote API endpoint'leri (XHR yakalama için URL parçaları)
+    pub const QUOTE_API_PATTERNS: &'static [&'static str] = &[
+        "/api/",
+        "teklif",
+        "proposal",
+        "quote",
+        "premium",
+        "prim",
+    ];
 }
-
@@ -0,0 +1,143 @@
+use crate::db::commissions::{self, CommissionRuleInput};
+use crate::db::DbPool;
+use crate::http::ApiError;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Komisyon kuralı eşleştirmesi için poliçe bilgileri
+#[derive(Debug, Clone)]
+pub struct CommissionContext<'a> {
+    pub provider: &'a str,
+    /// trafik | kasko | konut | saglik
+    pub product_type: &'a str,
+    /// Araç ürünlerinde hususi | ticari
+    pub usage: Option<&'a str>,
+    pub premium: f64,
+    /// Tanzim günü; kural bu gün yürürlükte olmalı
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Commission {
+    pub amount: f64,
+    pub rate_percent: f64,
+    /// None: eşleşen kural yok, varsayılan oran uygulandı
+    pub rule_id: Option<String>,
+}
+
+/// Kuruşa yuvarlanmış komisyon tutarı
+pub fn commission_amount(premium: f64, rate_percent: f64) -> f64 {
+    (premium * rate_percent).round() / 100.0
+}
+
+/// Uyan en özel kuralın oranı; kural yoksa `default_rate_percent` (DEFAULT_COMMISSION_RATE)
+pub async fn calculate_commission(
+    pool: &DbPool,
+    default_rate_percent: f64,
+    ctx: &CommissionContext<'_>,
+) -> Result<Commission, ApiError> {
+    let date = ctx.date.format("%Y-%m-%d").to_string();
+    let rule = commissions::find_matching_rule(pool, ctx.provider, ctx.product_type, ctx.usage, ctx.premium, &date)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    let (rate_percent, rule_id) = match rule {
+        Some(rule) => (rule.rate_percent, Some(rule.id)),
+        None => {
+            tracing::warn!(
+                "⚠️ Komisyon kuralı bulunamadı, varsayılan oran (%{}) uygulandı: {} {} {:?}",
+                default_rate_percent,
+                ctx.provider,
+                ctx.product_type,
+                ctx.usage
+            );
+            (default_rate_percent, None)
+        }
+    };
+
+    Ok(Commission {
+        amount: commission_amount(ctx.premium, rate_percent),
+        rate_percent,
+        rule_id,
+    })
+}
+
+/// Oran, prim bandı ve yürürlük aralığı kontrolleri (tarihler YYYY-MM-DD olmalı)
+pub fn validate_rule(input: &CommissionRuleInput) -> Result<(), String> {
+    if !(0.0..=100.0).contains(&input.rate_percent) {
+        return Err(format!("Komisyon oranı %0 - %100 arası olmalı: {}", input.rate_percent));
+    }
+    if input.min_premium.is_some_and(|min| min < 0.0) || input.max_premium.is_some_and(|max| max <= 0.0) {
+        return Err("Prim bandı sınırları pozitif olmalı".to_string());
+    }
+    if let (Some(min), Some(max)) = (input.min_premium, input.max_premium) {
+        if min >= max {
+            return Err(format!("Prim bandı alt sınırı üst sınırdan küçük olmalı: {} - {}", min, max));
+        }
+    }
+    if let Some(usage) = &input.usage {
+        if matches!(input.product_type.as_deref(), Some("konut") | Some("saglik")) {
+            return Err(format!("Kullanım tipi ({}) yalnızca araç ürünlerinde kullanılabilir", usage));
+        }
+    }
+    if let Some(valid_to) = &input.valid_to {
+        if valid_to < &input.valid_from {
+            return Err(format!("Bitiş tarihi ({}) başlangıçtan ({}) önce olamaz", valid_to, input.valid_from));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[test]
+    fn test_validate_rule() {
+        let valid = CommissionRuleInput {
+            product_type: Some("kasko".to_string()),
+            usage: Some("ticari".to_string()),
+            min_premium: Some(0.0),
+            max_premium: Some(10000.0),
+            rate_percent: 12.5,
+            valid_from: "2024-01-01".to_string(),
+            valid_to: Some("2024-12-31".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_rule(&valid).is_ok());
+        assert!(validate_rule(&CommissionRuleInput { rate_percent: 120.0, ..valid.clone() }).is_err());
+        assert!(validate_rule(&CommissionRuleInput { min_premium: Some(10000.0), ..valid.clone() }).is_err());
+        assert!(validate_rule(&CommissionRuleInput { product_type: Some("konut".to_string()), ..valid.clone() }).is_err());
+        assert!(validate_rule(&CommissionRuleInput { valid_to: Some("2023-12-31".to_string()), ..valid }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_calculate_commission_uses_rule_or_default() {
+        let pool = test_pool().await;
+
+        let ctx = CommissionContext {
+            provider: "Sompo",
+            product_type: "trafik",
+            usage: Some("hususi"),
+            premium: 4321.0,
+            date: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+        };
+        let fallback = calculate_commission(&pool, 10.0, &ctx).await.unwrap();
+        assert_eq!(fallback.amount, 432.1);
+        assert!(fallback.rule_id.is_none());
+
+        let input = CommissionRuleInput {
+            provider: Some("Sompo".to_string()),
+            rate_percent: 7.5,
+            valid_from: "2024-01-01".to_string(),
+            ..Default::default()
+        };
+        let rule = commissions::create_rule(&pool, &input, None).await.unwrap();
+        let commission = calculate_commission(&pool, 10.0, &ctx).await.unwrap();
+        assert_eq!(commission.amount, 324.08);
+        assert_eq!(commission.rate_percent, 7.5);
+        assert_eq!(commission.rule_id, Some(rule.id));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::db::customers::{self, CustomerInput, CustomerSearch};
+use crate::db::models::Customer;
+use crate::db::DbPool;
+use crate::http::InsuredInfo;
+use crate::services::sms::normalize_phone;
+use crate::utils::{fold_turkish, parse_portal_date};
+
+/// 11 hane TCKN, 10 hane VKN; başka bir şey kimlik no sayılmaz
+pub fn identity_type(identity_no: &str) -> Option<&'static str> {
+    if !identity_no.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    match identity_no.len() {
+        11 if !identity_no.starts_with('0') => Some("tckn"),
+        10 => Some("vkn"),
+        _ => None,
+    }
+}
+
+/// Teklif isteğindeki sigortalıyı müşteri kaydına çevirir: boşluklar temizlenir,
+/// telefon 90'lı biçime, doğum tarihi YYYY-MM-DD'ye çevrilir
+pub fn customer_input(insured: &InsuredInfo) -> Result<CustomerInput, String> {
+    let identity_no: String = insured.tckn.chars().filter(|c| !c.is_whitespace()).collect();
+    let identity_type = identity_type(&identity_no).ok_or_else(|| format!("Geçersiz TCKN/VKN: {}", identity_no))?;
+    let name = insured.name.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err("Sigortalı adı boş".to_string());
+    }
+
+    let phone = insured.phone.trim();
+    let email = insured.email.trim().to_lowercase();
+    Ok(CustomerInput {
+        identity_no,
+        identity_type: identity_type.to_string(),
+        name_key: fold_turkish(&name),
+        name,
+        birth_date: parse_portal_date(&insured.birth_date).map(|d| d.format("%Y-%m-%d").to_string()),
+        phone: normalize_phone(phone).or_else(|| (!phone.is_empty()).then(|| phone.to_string())),
+        email: (!email.is_empty()).then_some(email),
+    })
+}
+
+/// Teklif alınırken sigortalıyı teklifi alanın acentesinde kaydeder veya günceller. Hata teklifi engellemez; None döner
+pub async fn record_customer(pool: &DbPool, insured: &InsuredInfo, user_id: &str, agency_id: &str) -> Option<Customer> {
+    let input = match customer_input(insured) {
+        Ok(input) => input,
+        Err(e) => {
+            tracing::warn!("⚠️ Sigortalı kaydedilmedi: {}", e);
+            return None;
+        }
+    };
+    match customers::upsert_customer(pool, &input, agency_id, Some(user_id)).await {
+        Ok(customer) => Some(customer),
+        Err(e) => {
+            tracing::warn!("⚠️ Sigortalı kaydedilemedi: {}", e);
+            None
+        }
+    }
+}
+
+/// Arama metni: 10-11 hane kimlik no, en az 4 rakam telefon, aksi halde ad.
+/// 5 ile başlayan 10 hane cep telefonu sayılır (VKN değil)
+pub fn search_filter(query: &str) -> CustomerSearch {
+    let query = query.trim();
+    let digits: String = query.chars().filter(|c| c.is_ascii_digit()).collect();
+    let only_digits = query.chars().all(|c| c.is_ascii_digit() || c.is_whitespace() || "+()-".contains(c));
+
+    if query.is_empty() {
+        CustomerSearch::default()
+    } else if only_digits && identity_type(&digits).is_some() && !digits.starts_with('5') {
+        CustomerSearch { identity_no: Some(digits), ..Default::default() }
+    } else if only_digits && digits.len() >= 4 {
+        // Kayıtlı numaralar 90 ile başlar; aramadaki 0/90 öneki atılır
+        let local = digits.strip_prefix("90").filter(|_| digits.len() == 12).unwrap_or(&digits);
+        let local = local.strip_prefix('0').filter(|_| local.len() == 11).unwrap_or(local);
+        CustomerSearch { phone: Some(local.to_string()), ..Default::default() }
+    } else {
+        let name_key = fold_turkish(&query.split_whitespace().collect::<Vec<_>>().join(" "));
+        CustomerSearch {
+            name_key: Some(name_key.replace(['%', '_'], "")),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insured(tckn: &str, phone: &str) -> InsuredInfo {
+        InsuredInfo {
+            tckn: tckn.to_string(),
+            name: "  ŞÜKRÜ   Işık ".to_string(),
+            birth_date: "01.06.1990".to_string(),
+            phone: phone.to_string(),
+            email: " Sukru@Example.com ".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_customer_input_normalizes() {
+        let input = customer_input(&insured("123 456 789 01", "0555 123 45 67")).unwrap();
+        assert_eq!(input.identity_no, "12345678901");
+        assert_eq!(input.identity_type, "tckn");
+        assert_eq!(input.name, "ŞÜKRÜ Işık");
+        assert_eq!(input.name_key, "sukru isik");
+        assert_eq!(input.birth_date.as_deref(), Some("1990-06-01"));
+        assert_eq!(input.phone.as_deref(), Some("905551234567"));
+        assert_eq!(input.email.as_deref(), Some("sukru@example.com"));
+
+        assert_eq!(customer_input(&insured("1234567890", "")).unwrap().identity_type, "vkn");
+        assert!(customer_input(&insured("1234567890", "")).unwrap().phone.is_none());
+        assert!(customer_input(&insured("12345", "")).is_err());
+    }
+
+    #[test]
+    fn test_search_filter() {
+        assert_eq!(search_filter("12345678901").identity_no.as_deref(), Some("12345678901"));
+        assert_eq!(search_filter("0555 123 45 67").phone.as_deref(), Some("5551234567"));
+        assert_eq!(search_filter("+90 555 123 4567").phone.as_deref(), Some("5551234567"));
+        assert_eq!(search_filter("4567").phone.as_deref(), Some("4567"));
+        assert_eq!(search_filter("Şükrü  Öz%").name_key.as_deref(), Some("sukru oz"));
+        assert_eq!(search_filter(" "), CustomerSearch::default());
+    }
+}
@@ -1,92 +1,417 @@
-use crate::http::ApiError;
+use crate::config::Config;
+use crate::db::models::{OutboxEmail, Policy, RenewalOffer};
+use crate::db::outbox::{self, NewOutboxEmail};
+use crate::db::DbPool;
+use crate::http::{ApiError, QuoteRequest, QuoteResponse};
+use crate::services::email_templates::{self, RenderedEmail};
+use chrono::Utc;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
 
-/// Email notification service
-/// TODO: Implement actual email sending with SMTP (e.g., using lettre crate)
+/// Tek turda gönderilecek en fazla e-posta
+const OUTBOX_BATCH_SIZE: i64 = 20;
+/// İlk tekrar denemesi; her denemede iki katına çıkar
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 3600;
+const SMTP_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxRunSummary {
+    pub sent: usize,
+    pub retrying: usize,
+    pub failed: usize,
+}
+
+/// E-posta bildirimleri: mesajlar email_outbox tablosuna yazılır, worker SMTP ile gönderir.
+/// SMTP sunucusu geçici olarak erişilemezse mesaj kaybolmaz, artan aralıklarla tekrar denenir.
 pub struct EmailService {
-    from_email: String,
-    smtp_configured: bool,
+    pool: DbPool,
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Mailbox,
+    max_attempts: u32,
+    interval_secs: u64,
+    wake: Notify,
+    // Worker ve admin tetiklemesi aynı e-postayı iki kez göndermesin
+    run_lock: Mutex<()>,
 }
 
 impl EmailService {
-    pub fn new() -> Self {
-        let from_email = std::env::var("EMAIL_FROM")
-            .unwrap_or_else(|_| "noreply@eesigorta.com".to_string());
-        
-        let smtp_configured = std::env::var("SMTP_HOST").is_ok() 
-            && std::env::var("SMTP_USER").is_ok();
-
-        if !smtp_configured {
-            tracing::warn!("📧 Email servisi yapılandırılmamış (SMTP credentials eksik)");
-        } else {
-            tracing::info!("✅ Email servisi hazır");
-        }
+    pub fn new(config: &Config, pool: DbPool) -> Self {
+        let transport = match build_transport(config) {
+            Ok(Some(transport)) => {
+                tracing::info!(
+                    "✅ Email servisi hazır: {}:{} ({})",
+                    config.smtp_host.as_deref().unwrap_or_default(),
+                    config.smtp_port,
+                    config.smtp_tls
+                );
+                Some(transport)
+            }
+            Ok(None) => {
+                tracing::warn!("📧 Email servisi yapılandırılmamış (SMTP_HOST eksik)");
+                None
+            }
+            Err(e) => {
+                tracing::error!("❌ SMTP yapılandırması geçersiz: {}", e);
+                None
+            }
+        };
+
+        let address = config
+            .email_from
+            .parse()
+            .unwrap_or_else(|_| "noreply@eesigorta.com".parse().expect("sabit adres geçerli"));
 
         Self {
-            from_email,
-            smtp_configured,
+            pool,
+            transport,
+            from: Mailbox::new(Some(config.email_from_name.clone()), address),
+            max_attempts: config.email_max_attempts.max(1),
+            interval_secs: config.email_outbox_interval_secs,
+            wake: Notify::new(),
+            run_lock: Mutex::new(()),
         }
     }
 
-    /// Send quote ready notification
-    pub async fn send_quote_ready(&self, to_email: &str, quote_id: &str, provider: &str) -> Result<(), ApiError> {
-        if !self.smtp_configured {
-            tracing::debug!("Email gönderimi atlandı (SMTP yapılandırılmamış)");
-            return Ok(());
+    pub fn is_configured(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    /// Outbox'ı periyodik (ve yeni e-posta eklendiğinde hemen) işler
+    pub fn spawn_worker(self: Arc<Self>) {
+        if !self.is_configured() {
+            return;
         }
+        let interval = Duration::from_secs(self.interval_secs.max(1));
 
-        // TODO: Implement actual email sending
-        tracing::info!(
-            "📧 [MOCK] Quote ready email: {} -> {} (Quote: {}, Provider: {})",
-            self.from_email,
-            to_email,
-            quote_id,
-            provider
-        );
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.process_outbox().await {
+                    tracing::error!("❌ Email outbox işlenemedi: {}", e);
+                }
+                let _ = tokio::time::timeout(interval, self.wake.notified()).await;
+            }
+        });
+    }
 
-        Ok(())
+    pub async fn send_welcome(&self, user_id: &str, to_email: &str, name: &str) -> Result<(), ApiError> {
+        self.enqueue(Some(user_id), "welcome", to_email, email_templates::welcome(name), None)
+            .await
     }
 
-    /// Send policy created notification
-    pub async fn send_policy_created(&self, to_email: &str, policy_number: &str, provider: &str) -> Result<(), ApiError> {
-        if !self.smtp_configured {
-            tracing::debug!("Email gönderimi atlandı (SMTP yapılandırılmamış)");
+    /// Karşılaştırma tablosuyla teklif bildirimi
+    pub async fn send_quote_ready(
+        &self,
+        user_id: &str,
+        to_email: &str,
+        name: &str,
+        request: &QuoteRequest,
+        quotes: &[QuoteResponse],
+    ) -> Result<(), ApiError> {
+        if quotes.is_empty() {
             return Ok(());
         }
+        let email = email_templates::quote_ready(name, request, quotes);
+        self.enqueue(Some(user_id), "quote_ready", to_email, email, None).await
+    }
 
-        // TODO: Implement actual email sending
-        tracing::info!(
-            "📧 [MOCK] Policy created email: {} -> {} (Policy: {}, Provider: {})",
-            self.from_email,
-            to_email,
-            policy_number,
-            provider
-        );
+    /// Poliçe bildirimi; PDF varsa eklenir
+    pub async fn send_policy_created(
+        &self,
+        user_id: &str,
+        to_email: &str,
+        name: &str,
+        policy: &Policy,
+        pdf: Option<&[u8]>,
+    ) -> Result<(), ApiError> {
+        let email = email_templates::policy_created(name, policy);
+        let filename = format!("police_{}.pdf", policy.policy_number);
+        let attachment = pdf.map(|data| (filename.as_str(), "application/pdf", data));
+        self.enqueue(Some(user_id), "policy_created", to_email, email, attachment)
+            .await
+    }
 
-        Ok(())
+    /// Vadesi yaklaşan poliçe için yenileme teklifleri
+    pub async fn send_renewal_reminder(
+        &self,
+        user_id: &str,
+        to_email: &str,
+        name: &str,
+        policy: &Policy,
+        offers: &[RenewalOffer],
+    ) -> Result<(), ApiError> {
+        let email = email_templates::renewal_reminder(name, policy, offers);
+        self.enqueue(Some(user_id), "renewal_reminder", to_email, email, None)
+            .await
     }
 
-    /// Send welcome email
-    pub async fn send_welcome(&self, to_email: &str, name: &str) -> Result<(), ApiError> {
-        if !self.smtp_configured {
-            tracing::debug!("Email gönderimi atlandı (SMTP yapılandırılmamış)");
+    async fn enqueue(
+        &self,
+        user_id: Option<&str>,
+        template: &str,
+        to_email: &str,
+        email: RenderedEmail,
+        attachment: Option<(&str, &str, &[u8])>,
+    ) -> Result<(), ApiError> {
+        if !self.is_configured() {
+            tracing::debug!("Email gönderimi atlandı (SMTP yapılandırılmamış): {} -> {}", template, to_email);
             return Ok(());
         }
 
-        // TODO: Implement actual email sending
-        tracing::info!(
-            "📧 [MOCK] Welcome email: {} -> {} (Name: {})",
-            self.from_email,
-            to_email,
-            name
-        );
+        outbox::enqueue_email(
+            &self.pool,
+            &NewOutboxEmail {
+                user_id,
+                template,
+                to_email,
+                subject: &email.subject,
+                html_body: &email.html,
+                text_body: &email.text,
+                attachment,
+            },
+        )
+        .await
+        .map_err(|e| ApiError::Unknown(format!("Email kuyruğa alınamadı: {}", e)))?;
 
+        tracing::info!("📧 Email kuyruğa alındı: {} -> {}", template, to_email);
+        self.wake.notify_one();
         Ok(())
     }
+
+    /// Zamanı gelen e-postaları gönderir
+    pub async fn process_outbox(&self) -> Result<OutboxRunSummary, ApiError> {
+        let Some(transport) = &self.transport else {
+            return Ok(OutboxRunSummary::default());
+        };
+        let _guard = self.run_lock.lock().await;
+
+        let now = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let due = outbox::list_due_emails(&self.pool, &now, OUTBOX_BATCH_SIZE)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+        let mut summary = OutboxRunSummary::default();
+        for email in due {
+            let result = match self.build_message(&email) {
+                Ok(message) => transport.send(message).await.map(|_| ()).map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    tracing::info!("✅ Email gönderildi: {} -> {}", email.template, email.to_email);
+                    outbox::mark_email_sent(&self.pool, &email.id)
+                        .await
+                        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+                    summary.sent += 1;
+                }
+                Err(error) => {
+                    let attempts = email.attempts + 1;
+                    let next_attempt_at = (attempts < self.max_attempts as i64).then(|| {
+                        (Utc::now() + chrono::Duration::seconds(retry_delay_secs(attempts)))
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    });
+                    match &next_attempt_at {
+                        Some(at) => {
+                            tracing::warn!("⚠️ Email gönderilemedi ({}. deneme, sonraki {}): {} - {}", attempts, at, email.to_email, error);
+                            summary.retrying += 1;
+                        }
+                        None => {
+                            tracing::error!("❌ Email kalıcı olarak gönderilemedi: {} - {}", email.to_email, error);
+                            summary.failed += 1;
+                        }
+                    }
+                    outbox::mark_email_attempt_failed(&self.pool, &email.id, &error, next_attempt_at.as_deref())
+                        .await
+                        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    fn build_message(&self, email: &OutboxEmail) -> Result<Message, String> {
+        let to: Mailbox = email
+            .to_email
+            .parse()
+            .map_err(|e| format!("Geçersiz alıcı adresi: {}", e))?;
+        let body = MultiPart::alternative_plain_html(email.text_body.clone(), email.html_body.clone());
+
+        let builder = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject.clone());
+
+        let message = match (&email.attachment_name, &email.attachment) {
+            (Some(name), Some(data)) => {
+                let content_type = email
+                    .attachment_type
+                    .as_deref()
+                    .and_then(|t| ContentType::parse(t).ok())
+                    .unwrap_or_else(|| ContentType::parse("application/octet-stream").expect("sabit MIME geçerli"));
+                let attachment = Attachment::new(name.clone()).body(data.clone(), content_type);
+                builder.multipart(MultiPart::mixed().multipart(body).singlepart(attachment))
+            }
+            _ => builder.multipart(body),
+        };
+
+        message.map_err(|e| format!("E-posta oluşturulamadı: {}", e))
+    }
 }
 
-impl Default for EmailService {
-    fn default() -> Self {
-        Self::new()
+/// 30s, 60s, 120s, ... en fazla 1 saat
+fn retry_delay_secs(attempts: i64) -> i64 {
+    let exponent = (attempts - 1).clamp(0, 16) as u32;
+    (RETRY_BASE_SECS * 2_i64.pow(exponent)).min(RETRY_MAX_SECS)
+}
+
+fn build_transport(config: &Config) -> Result<Option<AsyncSmtpTransport<Tokio1Executor>>, String> {
+    let Some(host) = config.smtp_host.as_deref() else {
+        return Ok(None);
+    };
+
+    let builder = match config.smtp_tls.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(|e| e.to_string())?,
+        "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(|e| e.to_string())?,
+    };
+
+    let mut builder = builder
+        .port(config.smtp_port)
+        .timeout(Some(Duration::from_secs(SMTP_TIMEOUT_SECS)));
+    if !config.smtp_username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            config.smtp_username.clone(),
+            config.smtp_password.clone(),
+        ));
     }
+
+    Ok(Some(builder.build()))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Minimal SMTP capture sunucusu: her DATA bloğunu kanala yazar
+    async fn spawn_capture_server() -> (u16, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let (read, mut write) = socket.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    write.write_all(b"220 capture ESMTP\r\n").await.unwrap();
+
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let command = line.to_uppercase();
+                        let reply: &[u8] = if command.starts_with("EHLO") {
+                            b"250-capture\r\n250 8BITMIME\r\n"
+                        } else if command.starts_with("DATA") {
+                            write.write_all(b"354 devam\r\n").await.unwrap();
+                            let mut data = String::new();
+                            while let Ok(Some(body_line)) = lines.next_line().await {
+                                if body_line == "." {
+                                    break;
+                                }
+                                data.push_str(&body_line);
+                                data.push('\n');
+                            }
+                            let _ = tx.send(data);
+                            b"250 kabul edildi\r\n"
+                        } else if command.starts_with("QUIT") {
+                            write.write_all(b"221 bye\r\n").await.unwrap();
+                            break;
+                        } else {
+                            b"250 OK\r\n"
+                        };
+                        write.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        (port, rx)
+    }
+
+    async fn test_service(port: u16) -> EmailService {
+        let pool = test_pool().await;
+
+        let mut config = Config::from_env().expect("config");
+        config.smtp_host = Some("127.0.0.1".to_string());
+        config.smtp_port = port;
+        config.smtp_tls = "none".to_string();
+        config.smtp_username = String::new();
+        config.email_from = "noreply@eesigorta.com".to_string();
+        config.email_max_attempts = 2;
+        EmailService::new(&config, pool)
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay_secs(1), 30);
+        assert_eq!(retry_delay_secs(3), 120);
+        assert_eq!(retry_delay_secs(20), RETRY_MAX_SECS);
+    }
+
+    #[tokio::test]
+    async fn test_outbox_delivers_to_smtp_capture_server() {
+        let (port, mut captured) = spawn_capture_server().await;
+        let service = test_service(port).await;
+
+        let welcome = email_templates::welcome("Ali");
+        service
+            .enqueue(None, "welcome", "a@example.com", welcome, Some(("police.pdf", "application/pdf", b"%PDF-1.4")))
+            .await
+            .unwrap();
+        let summary = service.process_outbox().await.unwrap();
+        assert_eq!(summary.sent, 1);
+
+        let data = captured.recv().await.unwrap();
+        assert!(data.contains("To: a@example.com"));
+        assert!(data.contains("text/html"));
+        assert!(data.contains("text/plain"));
+        assert!(data.contains("police.pdf"));
+
+        let sent = outbox::list_emails(&service.pool, Some("sent"), 10, 0).await.unwrap();
+        assert_eq!(sent.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_keeps_email_for_retry() {
+        // Dinlemeyen port: bağlantı reddedilir
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let service = test_service(port).await;
+
+        service
+            .enqueue(None, "welcome", "a@example.com", email_templates::welcome("Ali"), None)
+            .await
+            .unwrap();
+        let first = service.process_outbox().await.unwrap();
+        assert_eq!(first.retrying, 1);
+
+        let pending = outbox::list_emails(&service.pool, Some("pending"), 10, 0).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert!(pending[0].last_error.is_some());
+    }
+}
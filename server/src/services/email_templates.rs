@@ -0,0 +1,338 @@
+use crate::db::models::{Policy, RenewalOffer};
+use crate::http::{QuoteRequest, QuoteResponse};
+
+/// Şablondan üretilmiş e-posta (HTML + düz metin)
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 1234.5 -> "1.234,50" (Türkçe binlik/ondalık ayırıcı)
+pub fn format_decimal(amount: f64) -> String {
+    let cents = (amount.abs() * 100.0).round() as u64;
+    let whole = (cents / 100).to_string();
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i).is_multiple_of(3) {
+            grouped.push('.');
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0.0 && cents > 0 { "-" } else { "" };
+    format!("{}{},{:02}", sign, grouped, cents % 100)
+}
+
+/// 1234.5 -> "1.234,50 TL"
+pub fn format_tl(amount: f64) -> String {
+    format!("{} TL", format_decimal(amount))
+}
+
+/// "2025-01-01" -> "01.01.2025"
+pub fn format_date(value: &str) -> String {
+    crate::utils::parse_portal_date(value)
+        .map(|d| d.format("%d.%m.%Y").to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+fn layout(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="tr">
+<head><meta charset="utf-8"><title>{title}</title></head>
+<body style="margin:0;padding:24px;background:#f4f6f8;font-family:Arial,Helvetica,sans-serif;color:#1f2933;">
+  <div style="max-width:640px;margin:0 auto;background:#ffffff;border-radius:8px;padding:24px;">
+    <h2 style="margin-top:0;color:#0b4f8a;">{title}</h2>
+    {body}
+    <p style="margin-top:32px;font-size:12px;color:#7b8794;">Bu e-posta EES Sigorta tarafından otomatik gönderilmiştir.</p>
+  </div>
+</body>
+</html>"#,
+        title = escape(title),
+        body = body
+    )
+}
+
+const TABLE_STYLE: &str = "width:100%;border-collapse:collapse;font-size:14px;";
+const CELL_STYLE: &str = "padding:8px;border-bottom:1px solid #e4e7eb;text-align:left;";
+
+fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let head: String = headers
+        .iter()
+        .map(|h| format!(r#"<th style="{}background:#f0f4f8;">{}</th>"#, CELL_STYLE, escape(h)))
+        .collect();
+    let body: String = rows
+        .iter()
+        .map(|row| {
+            let cells: String = row
+                .iter()
+                .map(|c| format!(r#"<td style="{}">{}</td>"#, CELL_STYLE, escape(c)))
+                .collect();
+            format!("<tr>{}</tr>", cells)
+        })
+        .collect();
+    format!(r#"<table style="{}"><thead><tr>{}</tr></thead><tbody>{}</tbody></table>"#, TABLE_STYLE, head, body)
+}
+
+pub fn welcome(name: &str) -> RenderedEmail {
+    let subject = "EES Sigorta'ya hoş geldiniz".to_string();
+    let html = layout(
+        &subject,
+        &format!(
+            "<p>Merhaba {},</p><p>Hesabınız oluşturuldu. Artık tüm anlaşmalı sigorta şirketlerinden tek ekrandan teklif alabilir, karşılaştırabilir ve poliçe kesebilirsiniz.</p>",
+            escape(name)
+        ),
+    );
+    let text = format!(
+        "Merhaba {},\n\nHesabınız oluşturuldu. Artık tüm anlaşmalı sigorta şirketlerinden tek ekrandan teklif alabilir, karşılaştırabilir ve poliçe kesebilirsiniz.\n",
+        name
+    );
+    RenderedEmail { subject, html, text }
+}
+
+/// Karşılaştırma tablosu: en uygun teklif ilk sırada.
+/// Araç plakasıyla, konut adresiyle anılır; adres yoksa ürün adı kullanılır.
+pub fn quote_ready(name: &str, request: &QuoteRequest, quotes: &[QuoteResponse]) -> RenderedEmail {
+    let mut sorted: Vec<&QuoteResponse> = quotes.iter().collect();
+    sorted.sort_by(|a, b| a.premium.gross.total_cmp(&b.premium.gross));
+
+    let product = sorted.first().map(|q| q.product_type.as_str()).unwrap_or_default();
+    let address = request
+        .property
+        .as_ref()
+        .map(|p| p.address.trim())
+        .filter(|address| !address.is_empty());
+    let (title, subject_html, subject_text) = if request.coverage.product_type.is_vehicle() {
+        let plate = request.vehicle.plate.as_str();
+        (
+            plate.to_string(),
+            format!("<strong>{}</strong> plakalı araç", escape(plate)),
+            format!("{} plakalı araç", plate),
+        )
+    } else if let Some(address) = address {
+        (
+            address.to_string(),
+            format!("<strong>{}</strong> adresi", escape(address)),
+            format!("{} adresi", address),
+        )
+    } else {
+        (
+            "Talebiniz".to_string(),
+            format!("{} sigortası", escape(product)),
+            format!("{} sigortası", product),
+        )
+    };
+    let subject = format!("{} için {} teklifiniz hazır", title, product);
+
+    let rows: Vec<Vec<String>> = sorted
+        .iter()
+        .map(|q| {
+            let max_installment = q.installments.iter().map(|i| i.count).max().unwrap_or(1);
+            vec![
+                q.company.clone(),
+                format_tl(q.premium.net),
+                format_tl(q.premium.gross),
+                format!("{} taksit", max_installment),
+                q.provider_quote_no.clone().unwrap_or_else(|| "-".to_string()),
+                q.valid_until.as_deref().map(format_date).unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    let headers = ["Şirket", "Net Prim", "Brüt Prim", "Taksit", "Teklif No", "Geçerlilik"];
+
+    let html = layout(
+        &subject,
+        &format!(
+            "<p>Merhaba {},</p><p>{} için {} şirketten teklif alındı:</p>{}",
+            escape(name),
+            subject_html,
+            sorted.len(),
+            table(&headers, &rows)
+        ),
+    );
+
+    let mut text = format!(
+        "Merhaba {},\n\n{} için {} şirketten teklif alındı:\n\n",
+        name,
+        subject_text,
+        sorted.len()
+    );
+    for row in &rows {
+        text.push_str(&format!("- {}: {} (net {}), {}, teklif no {}, geçerlilik {}\n", row[0], row[2], row[1], row[3], row[4], row[5]));
+    }
+
+    RenderedEmail { subject, html, text }
+}
+
+pub fn policy_created(name: &str, policy: &Policy) -> RenderedEmail {
+    let subject = format!("Poliçeniz kesildi: {}", policy.policy_number);
+    let start = policy.starts_at.as_deref().map(format_date).unwrap_or_else(|| "-".to_string());
+    let end = policy.expires_at.as_deref().map(format_date).unwrap_or_else(|| "-".to_string());
+
+    let rows = vec![
+        vec!["Poliçe No".to_string(), policy.policy_number.clone()],
+        vec!["Şirket".to_string(), policy.provider.clone()],
+        vec!["Ürün".to_string(), policy.product_type.clone()],
+        vec!["Prim".to_string(), format_tl(policy.premium)],
+        vec!["Vade".to_string(), format!("{} - {}", start, end)],
+    ];
+
+    let html = layout(
+        &subject,
+        &format!(
+            "<p>Merhaba {},</p><p>Poliçeniz başarıyla kesildi. Poliçe dokümanı ektedir.</p>{}",
+            escape(name),
+            table(&["", ""], &rows)
+        ),
+    );
+    let mut text = format!("Merhaba {},\n\nPoliçeniz başarıyla kesildi. Poliçe dokümanı ektedir.\n\n", name);
+    for row in &rows {
+        text.push_str(&format!("{}: {}\n", row[0], row[1]));
+    }
+
+    RenderedEmail { subject, html, text }
+}
+
+pub fn renewal_reminder(name: &str, policy: &Policy, offers: &[RenewalOffer]) -> RenderedEmail {
+    let expires_at = policy.expires_at.as_deref().map(format_date).unwrap_or_else(|| "-".to_string());
+    let subject = format!("Poliçe {} {} tarihinde sona eriyor", policy.policy_number, expires_at);
+
+    let mut sorted: Vec<&RenewalOffer> = offers.iter().collect();
+    sorted.sort_by(|a, b| a.premium.total_cmp(&b.premium));
+    let rows: Vec<Vec<String>> = sorted
+        .iter()
+        .map(|o| {
+            let change = if o.premium_change > 0.0 {
+                format!("+{}", format_tl(o.premium_change))
+            } else {
+                format_tl(o.premium_change)
+            };
+            vec![o.provider.clone(), format_tl(o.premium), change]
+        })
+        .collect();
+
+    let offers_html = if rows.is_empty() {
+        "<p>Yenileme teklifi alınamadı, lütfen manuel teklif alın.</p>".to_string()
+    } else {
+        table(&["Şirket", "Yenileme Primi", "Fark"], &rows)
+    };
+    let html = layout(
+        &subject,
+        &format!(
+            "<p>Merhaba {},</p><p><strong>{}</strong> ({}, {}) numaralı poliçenin vadesi <strong>{}</strong> tarihinde doluyor. Mevcut prim: {}.</p>{}",
+            escape(name),
+            escape(&policy.policy_number),
+            escape(&policy.provider),
+            escape(&policy.product_type),
+            expires_at,
+            format_tl(policy.premium),
+            offers_html
+        ),
+    );
+
+    let mut text = format!(
+        "Merhaba {},\n\n{} ({}, {}) numaralı poliçenin vadesi {} tarihinde doluyor. Mevcut prim: {}.\n\n",
+        name,
+        policy.policy_number,
+        policy.provider,
+        policy.product_type,
+        expires_at,
+        format_tl(policy.premium)
+    );
+    if rows.is_empty() {
+        text.push_str("Yenileme teklifi alınamadı, lütfen manuel teklif alın.\n");
+    }
+    for row in &rows {
+        text.push_str(&format!("- {}: {} ({})\n", row[0], row[1], row[2]));
+    }
+
+    RenderedEmail { subject, html, text }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{Installment, PremiumDetail};
+
+    fn quote(company: &str, gross: f64) -> QuoteResponse {
+        QuoteResponse {
+            request_id: "req-1".to_string(),
+            company: company.to_string(),
+            product_type: "trafik".to_string(),
+            premium: PremiumDetail {
+                net: gross / 1.18,
+                gross,
+                taxes: gross - gross / 1.18,
+                currency: "TRY".to_string(),
+            },
+            installments: vec![Installment { count: 6, per_installment: gross / 6.0, total: gross }],
+            coverages: vec![],
+            warnings: vec![],
+            provider_quote_no: Some("T-1".to_string()),
+            valid_until: Some("2024-06-15".to_string()),
+            raw: None,
+            timings: None,
+        }
+    }
+
+    #[test]
+    fn test_format_tl() {
+        assert_eq!(format_tl(1234.5), "1.234,50 TL");
+        assert_eq!(format_tl(1234567.891), "1.234.567,89 TL");
+        assert_eq!(format_tl(12.0), "12,00 TL");
+        assert_eq!(format_tl(-250.0), "-250,00 TL");
+    }
+
+    fn request(product_type: &str, extra: serde_json::Value) -> QuoteRequest {
+        let mut body = serde_json::json!({
+            "insured": { "tckn": "12345678901", "name": "Ali Veli", "birthDate": "1990-01-01", "phone": "5551234567", "email": "ali@example.com" },
+            "coverage": { "productType": product_type, "startDate": "2024-01-15" },
+        });
+        body.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_quote_ready_sorts_and_escapes() {
+        let vehicle = request(
+            "trafik",
+            serde_json::json!({ "vehicle": { "plate": "34ABC123", "brand": "Fiat", "model": "Egea", "year": 2020, "usage": "hususi", "category": "otomobil" } }),
+        );
+        let email = quote_ready("Ali <Veli>", &vehicle, &[quote("Axa", 5200.0), quote("Sompo", 4100.0)]);
+        assert_eq!(email.subject, "34ABC123 için trafik teklifiniz hazır");
+        assert!(email.html.contains("Ali &lt;Veli&gt;"));
+        assert!(email.html.find("Sompo").unwrap() < email.html.find("Axa").unwrap());
+        assert!(email.text.contains("- Sompo: 4.100,00 TL"));
+        assert!(email.text.contains("15.06.2024"));
+    }
+
+    #[test]
+    fn test_quote_ready_without_vehicle() {
+        let home = request(
+            "konut",
+            serde_json::json!({ "property": { "address": "Moda Cad. No:5", "city": "İstanbul", "district": "Kadıköy" } }),
+        );
+        let mut konut = quote("Sompo", 1000.0);
+        konut.product_type = "konut".to_string();
+        let email = quote_ready("Ali", &home, std::slice::from_ref(&konut));
+        assert_eq!(email.subject, "Moda Cad. No:5 için konut teklifiniz hazır");
+        assert!(email.text.contains("Moda Cad. No:5 adresi için 1 şirketten"));
+        assert!(!email.html.contains("plakalı"));
+
+        let mut saglik = quote("Sompo", 1000.0);
+        saglik.product_type = "saglik".to_string();
+        let email = quote_ready("Ali", &request("saglik", serde_json::json!({})), &[saglik]);
+        assert!(email.text.contains("saglik sigortası için 1 şirketten"));
+        assert!(!email.text.contains("plakalı"));
+    }
+}
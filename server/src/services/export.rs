@@ -0,0 +1,570 @@
+use crate::db::exports::{self, ActivityExportRow, ExportFilter, PolicyExportRow, QuoteExportRow};
+use crate::db::DbPool;
+use crate::http::ApiError;
+use crate::services::email_templates::format_decimal;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use futures::StreamExt;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+/// XLSX'e yazılabilecek en fazla veri satırı. Dosya yanıt gönderilmeden önce bellekte tamamlandığı için
+/// sınır Excel'in sayfa sınırının (1.048.576) çok altında tutulur; büyük dökümler CSV ile alınır.
+const XLSX_MAX_ROWS: u32 = 100_000;
+/// CSV parçaları bu boyuta ulaşınca istemciye gönderilir
+const CSV_CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "xlsx" | "excel" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// `Tr`: 1.234,50 / 31.12.2024 ve ';' ayraç (Türkçe Excel'in beklediği);
+/// `Iso`: 1234.50 / 2024-12-31 ve ',' ayraç (diğer sistemlere aktarım)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportLocale {
+    Tr,
+    Iso,
+}
+
+impl ExportLocale {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "tr" => Some(Self::Tr),
+            "iso" | "en" => Some(Self::Iso),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    Quotes,
+    Policies,
+    Activity,
+}
+
+impl ExportKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "quotes" => Some(Self::Quotes),
+            "policies" => Some(Self::Policies),
+            "activity" => Some(Self::Activity),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Quotes => "quotes",
+            Self::Policies => "policies",
+            Self::Activity => "activity",
+        }
+    }
+
+    /// İndirilen dosyanın adı: teklifler_20250101.xlsx
+    pub fn file_name(&self, format: ExportFormat, date: NaiveDate) -> String {
+        let prefix = match self {
+            Self::Quotes => "teklifler",
+            Self::Policies => "policeler",
+            Self::Activity => "aktivite",
+        };
+        format!("{}_{}.{}", prefix, date.format("%Y%m%d"), format.extension())
+    }
+
+    fn sheet_name(&self) -> &'static str {
+        match self {
+            Self::Quotes => "Teklifler",
+            Self::Policies => "Poliçeler",
+            Self::Activity => "Aktivite",
+        }
+    }
+
+    /// Admin dışa aktarımlarında son sütun kullanıcının e-postası
+    pub fn columns(&self, include_user: bool) -> Vec<&'static str> {
+        let mut columns = match self {
+            Self::Quotes => vec![
+                "Teklif No",
+                "Tarih",
+                "Sigorta Şirketi",
+                "Ürün",
+                "Sigortalı",
+                "Plaka",
+                "Net Prim",
+                "Brüt Prim",
+                "Durum",
+                "Geçerlilik",
+            ],
+            Self::Policies => vec![
+                "Poliçe No",
+                "Tanzim Tarihi",
+                "Başlangıç",
+                "Bitiş",
+                "Sigorta Şirketi",
+                "Ürün",
+                "Sigortalı",
+                "Plaka",
+                "Prim",
+                "Komisyon",
+                "Durum",
+                "İade Tutarı",
+            ],
+            Self::Activity => vec!["Tarih", "İşlem", "Kayıt Türü", "Kayıt No", "IP Adresi", "Ayrıntı"],
+        };
+        if include_user {
+            columns.push("Kullanıcı");
+        }
+        columns
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    DateTime(NaiveDateTime),
+    Date(NaiveDate),
+    Empty,
+}
+
+impl Cell {
+    fn text(value: Option<&str>) -> Self {
+        match value {
+            Some(value) if !value.is_empty() => Self::Text(value.to_string()),
+            _ => Self::Empty,
+        }
+    }
+
+    fn number(value: Option<f64>) -> Self {
+        value.map(Self::Number).unwrap_or(Self::Empty)
+    }
+
+    /// SQLite CURRENT_TIMESTAMP ("2025-01-01 10:00:00") ve RFC 3339 kabul edilir
+    fn timestamp(value: Option<&str>) -> Self {
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            return Self::Empty;
+        };
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|d| d.naive_utc()))
+            .map(Self::DateTime)
+            .unwrap_or_else(|| Self::date(Some(value)))
+    }
+
+    fn date(value: Option<&str>) -> Self {
+        let Some(value) = value.filter(|v| !v.is_empty()) else {
+            return Self::Empty;
+        };
+        crate::utils::parse_portal_date(value)
+            .map(Self::Date)
+            .unwrap_or_else(|| Self::Text(value.to_string()))
+    }
+
+    /// CSV hücre metni. Excel'de formül olarak çalışmaması için =, +, -, @ ile başlayan metinlerin başına ' eklenir.
+    pub fn to_csv(&self, locale: ExportLocale) -> String {
+        match (self, locale) {
+            (Self::Text(text), _) if text.starts_with(['=', '+', '-', '@']) => format!("'{}", text),
+            (Self::Text(text), _) => text.clone(),
+            (Self::Number(n), ExportLocale::Tr) => format_decimal(*n),
+            (Self::Number(n), ExportLocale::Iso) => format!("{:.2}", n),
+            (Self::DateTime(d), ExportLocale::Tr) => d.format("%d.%m.%Y %H:%M").to_string(),
+            (Self::DateTime(d), ExportLocale::Iso) => d.format("%Y-%m-%d %H:%M:%S").to_string(),
+            (Self::Date(d), ExportLocale::Tr) => d.format("%d.%m.%Y").to_string(),
+            (Self::Date(d), ExportLocale::Iso) => d.format("%Y-%m-%d").to_string(),
+            (Self::Empty, _) => String::new(),
+        }
+    }
+}
+
+fn json_str<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(Value::as_str)
+}
+
+pub fn quote_cells(row: &QuoteExportRow, include_user: bool) -> Vec<Cell> {
+    let quote = &row.quote;
+    let mut cells = vec![
+        Cell::text(quote.provider_quote_no.as_deref()),
+        Cell::timestamp(Some(&quote.created_at)),
+        Cell::text(Some(&quote.provider)),
+        Cell::text(json_str(&quote.response_data, "/productType")),
+        Cell::text(json_str(&quote.request_data, "/insured/name")),
+        Cell::text(json_str(&quote.request_data, "/vehicle/plate")),
+        Cell::number(quote.response_data.pointer("/premium/net").and_then(Value::as_f64)),
+        Cell::Number(quote.premium),
+        Cell::text(Some(&quote.status)),
+        Cell::date(quote.valid_until.as_deref()),
+    ];
+    if include_user {
+        cells.push(Cell::text(row.user_email.as_deref()));
+    }
+    cells
+}
+
+pub fn policy_cells(row: &PolicyExportRow, include_user: bool) -> Vec<Cell> {
+    let policy = &row.policy;
+    let mut cells = vec![
+        Cell::text(Some(&policy.policy_number)),
+        Cell::timestamp(Some(&policy.created_at)),
+        Cell::date(policy.starts_at.as_deref()),
+        Cell::date(policy.expires_at.as_deref()),
+        Cell::text(Some(&policy.provider)),
+        Cell::text(Some(&policy.product_type)),
+        Cell::text(json_str(&policy.policy_data, "/request/insured/name")),
+        Cell::text(json_str(&policy.policy_data, "/request/vehicle/plate")),
+        Cell::Number(policy.premium),
+        Cell::number(policy.commission),
+        Cell::text(Some(&policy.status)),
+        Cell::number(policy.refund_amount),
+    ];
+    if include_user {
+        cells.push(Cell::text(row.user_email.as_deref()));
+    }
+    cells
+}
+
+pub fn activity_cells(row: &ActivityExportRow, include_user: bool) -> Vec<Cell> {
+    let log = &row.log;
+    let mut cells = vec![
+        Cell::timestamp(Some(&log.created_at)),
+        Cell::text(Some(&log.action)),
+        Cell::text(log.entity_type.as_deref()),
+        Cell::text(log.entity_id.as_deref()),
+        Cell::text(log.ip_address.as_deref()),
+        Cell::text(log.metadata.as_ref().map(Value::to_string).as_deref()),
+    ];
+    if include_user {
+        cells.push(Cell::text(row.user_email.as_deref()));
+    }
+    cells
+}
+
+/// Dışa aktarılan satırların yazıldığı hedef (CSV akışı veya XLSX dosyası)
+#[async_trait]
+pub trait RowSink: Send {
+    async fn write_row(&mut self, cells: &[Cell]) -> Result<(), ApiError>;
+}
+
+/// Filtreye uyan satırları veritabanından okuyup sırayla hedefe yazar; yazılan satır sayısını döner
+pub async fn write_rows(
+    pool: &DbPool,
+    kind: ExportKind,
+    filter: &ExportFilter,
+    include_user: bool,
+    sink: &mut dyn RowSink,
+) -> Result<u64, ApiError> {
+    let header: Vec<Cell> = kind
+        .columns(include_user)
+        .into_iter()
+        .map(|c| Cell::Text(c.to_string()))
+        .collect();
+    sink.write_row(&header).await?;
+
+    let db_error = |e: sqlx::Error| ApiError::Unknown(format!("Dışa aktarma sorgusu başarısız: {}", e));
+    let mut count = 0;
+    match kind {
+        ExportKind::Quotes => {
+            let mut rows = exports::stream_quotes(pool, filter);
+            while let Some(row) = rows.next().await {
+                sink.write_row(&quote_cells(&row.map_err(db_error)?, include_user)).await?;
+                count += 1;
+            }
+        }
+        ExportKind::Policies => {
+            let mut rows = exports::stream_policies(pool, filter);
+            while let Some(row) = rows.next().await {
+                sink.write_row(&policy_cells(&row.map_err(db_error)?, include_user)).await?;
+                count += 1;
+            }
+        }
+        ExportKind::Activity => {
+            let mut rows = exports::stream_activity_logs(pool, filter);
+            while let Some(row) = rows.next().await {
+                sink.write_row(&activity_cells(&row.map_err(db_error)?, include_user)).await?;
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
+
+/// HTTP gövdesine akan CSV parçaları; yarıda kalan dışa aktarma `Err` ile bağlantıyı keser
+pub type CsvChunk = Result<Vec<u8>, std::io::Error>;
+
+/// CSV'yi parçalar halinde kanala yazar; HTTP gövdesi kanalın diğer ucundan akar
+pub struct CsvSink {
+    locale: ExportLocale,
+    writer: csv::Writer<Vec<u8>>,
+    tx: mpsc::Sender<CsvChunk>,
+}
+
+impl CsvSink {
+    pub fn new(locale: ExportLocale, tx: mpsc::Sender<CsvChunk>) -> Self {
+        let mut buffer = Vec::with_capacity(CSV_CHUNK_BYTES);
+        if locale == ExportLocale::Tr {
+            // UTF-8 BOM: Excel Türkçe karakterleri ancak bununla doğru açar
+            buffer.extend_from_slice("\u{feff}".as_bytes());
+        }
+        Self {
+            locale,
+            writer: Self::writer(locale, buffer),
+            tx,
+        }
+    }
+
+    fn writer(locale: ExportLocale, buffer: Vec<u8>) -> csv::Writer<Vec<u8>> {
+        let delimiter = match locale {
+            ExportLocale::Tr => b';',
+            ExportLocale::Iso => b',',
+        };
+        csv::WriterBuilder::new().delimiter(delimiter).from_writer(buffer)
+    }
+
+    fn take_chunk(&mut self) -> Result<Vec<u8>, ApiError> {
+        let writer = std::mem::replace(&mut self.writer, Self::writer(self.locale, Vec::with_capacity(CSV_CHUNK_BYTES)));
+        writer
+            .into_inner()
+            .map_err(|e| ApiError::InternalServerError(format!("CSV yazılamadı: {}", e)))
+    }
+
+    async fn send(&mut self) -> Result<(), ApiError> {
+        let chunk = self.take_chunk()?;
+        if chunk.is_empty() {
+            return Ok(());
+        }
+        self.tx
+            .send(Ok(chunk))
+            .await
+            .map_err(|_| ApiError::Unknown("İstemci bağlantısı kapandı".to_string()))
+    }
+
+    /// Kalan satırları gönderir
+    pub async fn finish(mut self) -> Result<(), ApiError> {
+        self.send().await
+    }
+}
+
+#[async_trait]
+impl RowSink for CsvSink {
+    async fn write_row(&mut self, cells: &[Cell]) -> Result<(), ApiError> {
+        self.writer
+            .write_record(cells.iter().map(|c| c.to_csv(self.locale)))
+            .map_err(|e| ApiError::InternalServerError(format!("CSV yazılamadı: {}", e)))?;
+        if self.writer.get_ref().len() >= CSV_CHUNK_BYTES {
+            self.send().await?;
+        }
+        Ok(())
+    }
+}
+
+fn xlsx_error(e: XlsxError) -> ApiError {
+    ApiError::InternalServerError(format!("Excel dosyası oluşturulamadı: {}", e))
+}
+
+/// Tek sayfalık XLSX. Sabit bellek modunda satırlar geçici dosyaya akar, tablo bellekte tutulmaz.
+pub struct XlsxSink {
+    workbook: Workbook,
+    worksheet: Worksheet,
+    header: Format,
+    number: Format,
+    datetime: Format,
+    date: Format,
+    row: u32,
+    columns: u16,
+    /// Başlık hariç
+    max_rows: u32,
+}
+
+impl XlsxSink {
+    pub fn new(kind: ExportKind, locale: ExportLocale) -> Result<Self, ApiError> {
+        let mut workbook = Workbook::new();
+        let mut worksheet = workbook.new_worksheet_with_constant_memory();
+        worksheet.set_name(kind.sheet_name()).map_err(xlsx_error)?;
+
+        // Sayı biçimi Excel'in bölge ayarına göre gösterilir; tarih kalıbı yerel ayara göre seçilir
+        let (datetime, date) = match locale {
+            ExportLocale::Tr => ("dd.mm.yyyy hh:mm", "dd.mm.yyyy"),
+            ExportLocale::Iso => ("yyyy-mm-dd hh:mm:ss", "yyyy-mm-dd"),
+        };
+
+        Ok(Self {
+            workbook,
+            worksheet,
+            header: Format::new().set_bold(),
+            number: Format::new().set_num_format("#,##0.00"),
+            datetime: Format::new().set_num_format(datetime),
+            date: Format::new().set_num_format(date),
+            row: 0,
+            columns: 0,
+            max_rows: XLSX_MAX_ROWS,
+        })
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, ApiError> {
+        if self.columns > 0 {
+            self.worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+            self.worksheet
+                .autofilter(0, 0, self.row.saturating_sub(1), self.columns - 1)
+                .map_err(xlsx_error)?;
+        }
+        self.workbook.push_worksheet(self.worksheet);
+        self.workbook.save_to_buffer().map_err(xlsx_error)
+    }
+}
+
+#[async_trait]
+impl RowSink for XlsxSink {
+    async fn write_row(&mut self, cells: &[Cell]) -> Result<(), ApiError> {
+        if self.row > self.max_rows {
+            return Err(ApiError::FormValidation(format!(
+                "Excel dökümü en fazla {} satır olabilir; tarih aralığını daraltın veya CSV seçin",
+                self.max_rows
+            )));
+        }
+
+        let row = self.row;
+        let sheet = &mut self.worksheet;
+        for (col, cell) in cells.iter().enumerate() {
+            let col = col as u16;
+            let result = match cell {
+                Cell::Text(text) if row == 0 => sheet.write_string_with_format(row, col, text, &self.header),
+                Cell::Text(text) => sheet.write_string(row, col, text),
+                Cell::Number(n) => sheet.write_number_with_format(row, col, *n, &self.number),
+                Cell::DateTime(d) => sheet.write_datetime_with_format(row, col, d, &self.datetime),
+                Cell::Date(d) => sheet.write_datetime_with_format(row, col, d, &self.date),
+                Cell::Empty => continue,
+            };
+            result.map_err(xlsx_error)?;
+        }
+
+        if row == 0 {
+            self.columns = cells.len() as u16;
+            for col in 0..self.columns {
+                sheet.set_column_width(col, 18).map_err(xlsx_error)?;
+            }
+        }
+        self.row += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::Quote;
+
+    fn quote_row() -> QuoteExportRow {
+        QuoteExportRow {
+            quote: Quote {
+                id: "q1".to_string(),
+                user_id: "u1".to_string(),
+                request_id: "r1".to_string(),
+                request_data: serde_json::json!({ "insured": { "name": "Ayşe Yılmaz" }, "vehicle": { "plate": "34ABC123" } }),
+                provider: "Sompo".to_string(),
+                premium: 12345.5,
+                response_data: serde_json::json!({ "productType": "Kasko", "premium": { "net": 10000.0 } }),
+                status: "active".to_string(),
+                created_at: "2025-03-01 09:30:00".to_string(),
+                issue_attempts: 0,
+                issue_error: None,
+                issuing_started_at: None,
+                provider_quote_no: Some("=HYPERLINK(\"x\")".to_string()),
+                valid_until: Some("2025-03-15".to_string()),
+                customer_id: None,
+                vehicle_id: None,
+                agency_id: "default".to_string(),
+            },
+            user_email: Some("ayse@example.com".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_quote_cells() {
+        let cells = quote_cells(&quote_row(), true);
+        assert_eq!(cells.len(), ExportKind::Quotes.columns(true).len());
+        assert_eq!(cells[4], Cell::Text("Ayşe Yılmaz".to_string()));
+        assert_eq!(cells[5], Cell::Text("34ABC123".to_string()));
+        assert_eq!(cells[6], Cell::Number(10000.0));
+        assert_eq!(cells[9], Cell::Date(NaiveDate::from_ymd_opt(2025, 3, 15).unwrap()));
+        assert_eq!(cells[10], Cell::Text("ayse@example.com".to_string()));
+        assert_eq!(quote_cells(&quote_row(), false).len(), ExportKind::Quotes.columns(false).len());
+    }
+
+    #[test]
+    fn test_csv_cell_locale() {
+        let created = Cell::timestamp(Some("2025-03-01 09:30:00"));
+        assert_eq!(created.to_csv(ExportLocale::Tr), "01.03.2025 09:30");
+        assert_eq!(created.to_csv(ExportLocale::Iso), "2025-03-01 09:30:00");
+        assert_eq!(Cell::Number(12345.5).to_csv(ExportLocale::Tr), "12.345,50");
+        assert_eq!(Cell::Number(12345.5).to_csv(ExportLocale::Iso), "12345.50");
+        assert_eq!(Cell::text(Some("=1+1")).to_csv(ExportLocale::Tr), "'=1+1");
+    }
+
+    #[tokio::test]
+    async fn test_csv_sink_streams_chunks() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let mut sink = CsvSink::new(ExportLocale::Tr, tx);
+        sink.write_row(&[Cell::Text("Poliçe No".to_string()), Cell::Text("Prim".to_string())])
+            .await
+            .unwrap();
+        sink.write_row(&[Cell::Text("P-1".to_string()), Cell::Number(1500.0)]).await.unwrap();
+        sink.finish().await.unwrap();
+
+        let mut body = Vec::new();
+        while let Some(chunk) = rx.recv().await {
+            body.extend(chunk.unwrap());
+        }
+        let text = String::from_utf8(body).unwrap();
+        assert_eq!(text, "\u{feff}Poliçe No;Prim\nP-1;1.500,00\n");
+    }
+
+    #[tokio::test]
+    async fn test_xlsx_sink_builds_workbook() {
+        let mut sink = XlsxSink::new(ExportKind::Quotes, ExportLocale::Tr).unwrap();
+        let header: Vec<Cell> = ExportKind::Quotes
+            .columns(false)
+            .into_iter()
+            .map(|c| Cell::Text(c.to_string()))
+            .collect();
+        sink.write_row(&header).await.unwrap();
+        sink.write_row(&quote_cells(&quote_row(), false)).await.unwrap();
+        let bytes = sink.finish().unwrap();
+        // XLSX bir zip arşividir
+        assert!(bytes.starts_with(b"PK"));
+    }
+
+    #[tokio::test]
+    async fn test_xlsx_sink_rejects_rows_over_limit() {
+        let mut sink = XlsxSink::new(ExportKind::Quotes, ExportLocale::Tr).unwrap();
+        sink.max_rows = 1;
+        sink.write_row(&[Cell::Text("Teklif No".to_string())]).await.unwrap();
+        sink.write_row(&quote_cells(&quote_row(), false)).await.unwrap();
+        let err = sink.write_row(&quote_cells(&quote_row(), false)).await.unwrap_err();
+        assert!(matches!(err, ApiError::FormValidation(_)));
+    }
+}
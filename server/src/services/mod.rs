@@ -1,10 +1,24 @@
 pub mod cache;
+pub mod commission;
+pub mod customer;
 pub mod email;
+pub mod email_templates;
+pub mod export;
 pub mod pdf;
+pub mod policy_lifecycle;
 pub mod quote_aggregator;
+pub mod renewal;
+pub mod runtime;
+pub mod sms;
+pub mod statement;
+pub mod vehicle;
 
 pub use cache::CacheService;
 pub use email::EmailService;
 pub use pdf::PdfService;
+pub use policy_lifecycle::{PolicyStatus, RefundMethod};
 pub use quote_aggregator::QuoteAggregator;
+pub use renewal::{RenewalService, RenewalSummary};
+pub use runtime::{ReloadSummary, Runtime, RuntimeHandle};
+pub use sms::SmsService;
 
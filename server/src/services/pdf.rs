@@ -1,59 +1,639 @@
-use crate::http::ApiError;
+use crate::config::Config;
+use crate::db::agencies::DEFAULT_AGENCY_ID;
+use crate::db::models::{Agency, Policy};
+use crate::http::{ApiError, Coverage, QuoteRequest, QuoteResponse};
+use crate::services::email_templates::{format_date, format_tl};
+use printpdf::{
+    Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Rect, Rgb,
+};
+use std::path::PathBuf;
 
-/// PDF generation service
-/// TODO: Implement actual PDF generation (e.g., using printpdf or wkhtmltopdf)
-pub struct PdfService;
+/// Türkçe karakterler (ç, ğ, ı, İ, ö, ş, ü) için gömülü font
+static FONT_REGULAR: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+static FONT_BOLD: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans-Bold.ttf");
+
+const MARGIN: f32 = 15.0;
+const HEADER_HEIGHT: f32 = 26.0;
+const ROW_HEIGHT: f32 = 6.5;
+/// Karşılaştırma tablosunda yan yana gösterilen en fazla şirket
+const COVERAGE_COLUMNS: usize = 6;
+
+const BRAND: (f32, f32, f32) = (0.043, 0.31, 0.541);
+const TEXT: (f32, f32, f32) = (0.12, 0.16, 0.2);
+const MUTED: (f32, f32, f32) = (0.48, 0.53, 0.58);
+const ROW_FILL: (f32, f32, f32) = (0.94, 0.96, 0.97);
+const BEST_FILL: (f32, f32, f32) = (0.87, 0.95, 0.88);
+
+/// PDF başlığındaki acente bilgileri
+#[derive(Debug, Clone, Default)]
+pub struct AgencyInfo {
+    pub name: String,
+    pub address: String,
+    pub phone: String,
+    pub email: String,
+    pub license_no: String,
+}
+
+impl AgencyInfo {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            name: config.agency_name.clone(),
+            address: config.agency_address.clone(),
+            phone: config.agency_phone.clone(),
+            email: config.agency_email.clone(),
+            license_no: config.agency_license_no.clone(),
+        }
+    }
+
+    /// Acente kaydındaki bilgiler. Varsayılan acente .env'deki AGENCY_* ile markalanır;
+    /// kayıtta doldurulan alanlar .env'dekinin yerine geçer.
+    pub fn for_agency(config: &Config, agency: &Agency) -> Self {
+        if agency.id != DEFAULT_AGENCY_ID {
+            return Self {
+                name: agency.name.clone(),
+                address: agency.address.clone(),
+                phone: agency.phone.clone(),
+                email: agency.email.clone(),
+                license_no: agency.license_no.clone(),
+            };
+        }
+        let or_config = |value: &str, fallback: &str| {
+            if value.is_empty() { fallback } else { value }.to_string()
+        };
+        Self {
+            name: config.agency_name.clone(),
+            address: or_config(&agency.address, &config.agency_address),
+            phone: or_config(&agency.phone, &config.agency_phone),
+            email: or_config(&agency.email, &config.agency_email),
+            license_no: or_config(&agency.license_no, &config.agency_license_no),
+        }
+    }
+}
+
+/// Poliçe özeti ve çoklu şirket teklif karşılaştırması PDF'leri
+pub struct PdfService {
+    agency: AgencyInfo,
+    output_dir: PathBuf,
+}
 
 impl PdfService {
-    pub fn new() -> Self {
-        Self
+    /// `agency`: PDF başlığı, poliçenin/teklifin acentesinden (bkz. AgencyInfo::for_agency)
+    pub fn new(config: &Config, agency: AgencyInfo) -> Self {
+        Self {
+            agency,
+            output_dir: PathBuf::from(&config.pdf_dir),
+        }
     }
 
-    /// Generate policy PDF
-    pub async fn generate_policy_pdf(
-        &self,
-        policy_number: &str,
-        insured_name: &str,
-        provider: &str,
-        premium: &str,
-    ) -> Result<Vec<u8>, ApiError> {
-        // TODO: Implement actual PDF generation
-        tracing::info!(
-            "📄 [MOCK] Generating PDF: Policy {} for {} (Provider: {}, Premium: {})",
-            policy_number,
-            insured_name,
-            provider,
-            premium
-        );
+    /// Poliçe özeti: sigortalı, araç/konut, teminatlar ve prim detayı.
+    /// Bilgiler poliçe verisindeki teklif isteği ve yanıtından okunur (zeyilnameler dahil).
+    pub fn generate_policy_pdf(&self, policy: &Policy) -> Result<Vec<u8>, ApiError> {
+        let request: Option<QuoteRequest> = serde_json::from_value(policy.policy_data["request"].clone()).ok();
+        let quote: Option<QuoteResponse> = serde_json::from_value(policy.policy_data["quote"].clone()).ok();
+        let installment_count = policy.policy_data["payment"]["installmentCount"].as_u64().unwrap_or(1).max(1);
+
+        let mut page = PageWriter::new(&format!("Poliçe {}", policy.policy_number), 210.0, 297.0, &self.agency)?;
+        page.title("POLİÇE ÖZETİ");
 
-        // Mock PDF content
-        let pdf_content = format!(
-            "MOCK PDF POLICY\n\nPolicy Number: {}\nInsured: {}\nProvider: {}\nPremium: {}\n",
-            policy_number, insured_name, provider, premium
-        );
+        page.section("Poliçe Bilgileri");
+        let start = policy.starts_at.as_deref().map(format_date).unwrap_or_else(|| "-".to_string());
+        let end = policy.expires_at.as_deref().map(format_date).unwrap_or_else(|| "-".to_string());
+        page.field_pairs(&[
+            ("Poliçe No", policy.policy_number.clone()),
+            ("Sigorta Şirketi", policy.provider.clone()),
+            ("Ürün", product_label(&policy.product_type)),
+            ("Vade", format!("{} - {}", start, end)),
+            ("Düzenleme Tarihi", format_date(&policy.created_at)),
+        ]);
 
-        Ok(pdf_content.as_bytes().to_vec())
+        if let Some(request) = &request {
+            page.section("Sigortalı");
+            page.field_pairs(&[
+                ("Ad Soyad", request.insured.name.clone()),
+                ("TC Kimlik No", mask_tckn(&request.insured.tckn)),
+                ("Telefon", request.insured.phone.clone()),
+                ("E-posta", request.insured.email.clone()),
+            ]);
+
+            if request.coverage.product_type.is_vehicle() {
+                let vehicle = &request.vehicle;
+                page.section("Araç");
+                page.field_pairs(&[
+                    ("Plaka", vehicle.plate.clone()),
+                    ("Marka / Model", format!("{} {}", vehicle.brand, vehicle.model)),
+                    ("Model Yılı", vehicle.year.to_string()),
+                    ("Kullanım", format!("{} / {}", vehicle.usage.as_str(), vehicle.category.as_str())),
+                ]);
+            } else if let Some(property) = &request.property {
+                page.section("Konut");
+                page.field_pairs(&[
+                    ("Adres", property.address.clone()),
+                    ("İl / İlçe", format!("{} / {}", property.city, property.district)),
+                    ("Yüzölçümü", property.square_meters.map(|m| format!("{} m²", m)).unwrap_or_else(|| "-".to_string())),
+                    ("Bina Yılı", property.building_year.map(|y| y.to_string()).unwrap_or_else(|| "-".to_string())),
+                ]);
+            }
+        }
+
+        let coverages = quote.as_ref().map(|q| q.coverages.as_slice()).unwrap_or_default();
+        if !coverages.is_empty() {
+            page.section("Teminatlar");
+            let columns = [(MARGIN, 95.0), (MARGIN + 95.0, 55.0), (MARGIN + 150.0, 30.0)];
+            page.table_header(&columns, &["Teminat", "Limit", "Durum"]);
+            for (i, coverage) in coverages.iter().enumerate() {
+                page.table_row(&columns, &coverage_cells(coverage), (i % 2 == 1).then_some(ROW_FILL), false);
+            }
+        }
+
+        page.section("Prim Detayı");
+        let (net, taxes) = match &quote {
+            Some(q) => (format_tl(q.premium.net), format_tl(q.premium.taxes)),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        page.field_pairs(&[
+            ("Net Prim", net),
+            ("Vergi ve Fonlar", taxes),
+            ("Brüt Prim", format_tl(policy.premium)),
+            (
+                "Ödeme",
+                if installment_count > 1 {
+                    format!("{} taksit x {}", installment_count, format_tl(policy.premium / installment_count as f64))
+                } else {
+                    "Peşin".to_string()
+                },
+            ),
+        ]);
+
+        page.footer_note("Bu belge bilgilendirme amaçlıdır. Geçerli poliçe, sigorta şirketinin düzenlediği poliçe dokümanıdır.");
+        page.finish()
     }
 
-    /// Generate quote comparison PDF
-    pub async fn generate_quote_comparison_pdf(
-        &self,
-        quotes_count: usize,
-    ) -> Result<Vec<u8>, ApiError> {
-        tracing::info!("📄 [MOCK] Generating quote comparison PDF ({} quotes)", quotes_count);
+    /// Şirket bazında prim karşılaştırması (en uygun teklif vurgulanır) ve teminat matrisi
+    pub fn generate_quote_comparison_pdf(&self, request: &QuoteRequest, quotes: &[QuoteResponse]) -> Result<Vec<u8>, ApiError> {
+        if quotes.is_empty() {
+            return Err(ApiError::FormValidation("Karşılaştırılacak teklif yok".to_string()));
+        }
+        let mut sorted: Vec<&QuoteResponse> = quotes.iter().collect();
+        sorted.sort_by(|a, b| a.premium.gross.total_cmp(&b.premium.gross));
+
+        let mut page = PageWriter::new("Teklif Karşılaştırması", 297.0, 210.0, &self.agency)?;
+        page.title("TEKLİF KARŞILAŞTIRMASI");
+
+        let subject = if request.coverage.product_type.is_vehicle() {
+            let vehicle = &request.vehicle;
+            format!("{} - {} {} ({})", vehicle.plate, vehicle.brand, vehicle.model, vehicle.year)
+        } else {
+            request
+                .property
+                .as_ref()
+                .map(|p| format!("{}, {} / {}", p.address, p.district, p.city))
+                .unwrap_or_default()
+        };
+        page.field_pairs(&[
+            ("Sigortalı", request.insured.name.clone()),
+            ("Ürün", product_label(request.coverage.product_type.as_str())),
+            (if request.coverage.product_type.is_vehicle() { "Araç" } else { "Konut" }, subject),
+            ("Başlangıç", format_date(&request.coverage.start_date)),
+        ]);
 
-        let pdf_content = format!(
-            "MOCK QUOTE COMPARISON PDF\n\nTotal Quotes: {}\n",
-            quotes_count
-        );
+        page.section("Primler");
+        let columns = [
+            (MARGIN, 10.0),
+            (MARGIN + 10.0, 55.0),
+            (MARGIN + 65.0, 35.0),
+            (MARGIN + 100.0, 35.0),
+            (MARGIN + 135.0, 35.0),
+            (MARGIN + 170.0, 30.0),
+            (MARGIN + 200.0, 37.0),
+            (MARGIN + 237.0, 30.0),
+        ];
+        page.table_header(&columns, &["#", "Şirket", "Net Prim", "Vergiler", "Brüt Prim", "Taksit", "Teklif No", "Geçerlilik"]);
+        for (i, quote) in sorted.iter().enumerate() {
+            let max_installment = quote.installments.iter().map(|i| i.count).max().unwrap_or(1);
+            let cells = [
+                (i + 1).to_string(),
+                quote.company.clone(),
+                format_tl(quote.premium.net),
+                format_tl(quote.premium.taxes),
+                format_tl(quote.premium.gross),
+                format!("{} taksit", max_installment),
+                quote.provider_quote_no.clone().unwrap_or_else(|| "-".to_string()),
+                quote.valid_until.as_deref().map(format_date).unwrap_or_else(|| "-".to_string()),
+            ];
+            let fill = if i == 0 { Some(BEST_FILL) } else { (i % 2 == 1).then_some(ROW_FILL) };
+            page.table_row(&columns, &cells, fill, i == 0);
+        }
+
+        // Teminat matrisi: satırlar teminat, sütunlar şirket
+        let mut coverage_names: Vec<&str> = Vec::new();
+        for quote in &sorted {
+            for coverage in &quote.coverages {
+                if !coverage_names.contains(&coverage.name.as_str()) {
+                    coverage_names.push(&coverage.name);
+                }
+            }
+        }
+        if !coverage_names.is_empty() {
+            for chunk in sorted.chunks(COVERAGE_COLUMNS) {
+                page.section("Teminatlar");
+                let name_width = 75.0;
+                let column_width = (267.0 - name_width) / COVERAGE_COLUMNS as f32;
+                let mut columns = vec![(MARGIN, name_width)];
+                columns.extend((0..chunk.len()).map(|i| (MARGIN + name_width + i as f32 * column_width, column_width)));
+
+                let mut headers = vec!["Teminat"];
+                headers.extend(chunk.iter().map(|q| q.company.as_str()));
+                page.table_header(&columns, &headers);
+
+                for (i, name) in coverage_names.iter().enumerate() {
+                    let mut cells = vec![name.to_string()];
+                    cells.extend(chunk.iter().map(|q| {
+                        match q.coverages.iter().find(|c| c.name == *name) {
+                            Some(c) if c.included => c.limit.clone().unwrap_or_else(|| "Dahil".to_string()),
+                            _ => "-".to_string(),
+                        }
+                    }));
+                    page.table_row(&columns, &cells, (i % 2 == 1).then_some(ROW_FILL), false);
+                }
+            }
+        }
+
+        page.footer_note("Primler teklif tarihindeki şirket fiyatlarıdır; poliçeleştirme anında şirket tarafından güncellenebilir.");
+        page.finish()
+    }
 
-        Ok(pdf_content.as_bytes().to_vec())
+    /// PDF'i PDF_DIR altına yazar, dosya yolunu döndürür
+    pub async fn save(&self, file_name: &str, bytes: &[u8]) -> Result<String, ApiError> {
+        tokio::fs::create_dir_all(&self.output_dir)
+            .await
+            .map_err(|e| ApiError::InternalServerError(format!("PDF dizini oluşturulamadı: {}", e)))?;
+        let path = self.output_dir.join(file_name);
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|e| ApiError::InternalServerError(format!("PDF kaydedilemedi: {}", e)))?;
+        Ok(path.to_string_lossy().to_string())
     }
 }
 
-impl Default for PdfService {
-    fn default() -> Self {
-        Self::new()
+/// Dosya adında kullanılamayan karakterler '_' olur
+pub fn pdf_file_name(prefix: &str, id: &str) -> String {
+    let safe: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("{}_{}.pdf", prefix, safe)
+}
+
+fn product_label(product_type: &str) -> String {
+    match product_type {
+        "trafik" => "Zorunlu Trafik Sigortası".to_string(),
+        "kasko" => "Kasko".to_string(),
+        "konut" => "Konut Sigortası".to_string(),
+        "saglik" => "Sağlık Sigortası".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// 12345678901 -> 123******01
+fn mask_tckn(tckn: &str) -> String {
+    if tckn.len() != 11 {
+        return tckn.to_string();
+    }
+    format!("{}******{}", &tckn[..3], &tckn[9..])
+}
+
+fn coverage_cells(coverage: &Coverage) -> [String; 3] {
+    [
+        coverage.name.clone(),
+        coverage.limit.clone().unwrap_or_else(|| "-".to_string()),
+        if coverage.included { "Dahil" } else { "Hariç" }.to_string(),
+    ]
+}
+
+fn rgb((r, g, b): (f32, f32, f32)) -> Color {
+    Color::Rgb(Rgb::new(r, g, b, None))
+}
+
+/// Sütun genişliğine sığmayan metni kısaltır (DejaVu Sans ortalama glif genişliği ≈ 0.55 em)
+fn fit(text: &str, width_mm: f32, font_size: f32) -> String {
+    let max_chars = ((width_mm - 2.0) / (font_size * 0.3528 * 0.55)).max(1.0) as usize;
+    if text.chars().count() <= max_chars {
+        return text.to_string();
     }
+    let mut fitted: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    fitted.push('…');
+    fitted
+}
+
+/// Üstten aşağı yazan sayfa yardımcısı; yer kalmadığında yeni sayfa açar
+struct PageWriter<'a> {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    width: f32,
+    height: f32,
+    /// Sayfanın altından mm cinsinden imleç
+    y: f32,
+    pages: usize,
+    agency: &'a AgencyInfo,
 }
 
+impl<'a> PageWriter<'a> {
+    fn new(title: &str, width: f32, height: f32, agency: &'a AgencyInfo) -> Result<Self, ApiError> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(width), Mm(height), "Sayfa 1");
+        let font_error = |e: printpdf::Error| ApiError::InternalServerError(format!("PDF fontu yüklenemedi: {}", e));
+        let regular = doc.add_external_font(FONT_REGULAR).map_err(font_error)?;
+        let bold = doc.add_external_font(FONT_BOLD).map_err(font_error)?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        let mut writer = Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            width,
+            height,
+            y: height,
+            pages: 1,
+            agency,
+        };
+        writer.header();
+        Ok(writer)
+    }
+
+    fn header(&mut self) {
+        let top = self.height;
+        self.layer.set_fill_color(rgb(BRAND));
+        self.layer.add_rect(Rect::new(Mm(0.0), Mm(top - HEADER_HEIGHT), Mm(self.width), Mm(top)));
+
+        self.layer.set_fill_color(rgb((1.0, 1.0, 1.0)));
+        self.layer.use_text(&self.agency.name, 16.0, Mm(MARGIN), Mm(top - 11.0), &self.bold);
+
+        let mut contact = Vec::new();
+        if !self.agency.license_no.is_empty() {
+            contact.push(format!("Levha No: {}", self.agency.license_no));
+        }
+        if !self.agency.phone.is_empty() {
+            contact.push(format!("Tel: {}", self.agency.phone));
+        }
+        if !self.agency.email.is_empty() {
+            contact.push(self.agency.email.clone());
+        }
+        if !contact.is_empty() {
+            self.layer.use_text(contact.join("  ·  "), 8.5, Mm(MARGIN), Mm(top - 17.0), &self.regular);
+        }
+        if !self.agency.address.is_empty() {
+            self.layer.use_text(&self.agency.address, 8.5, Mm(MARGIN), Mm(top - 21.5), &self.regular);
+        }
+
+        self.y = top - HEADER_HEIGHT - 10.0;
+    }
+
+    fn new_page(&mut self) {
+        self.pages += 1;
+        let (page, layer) = self.doc.add_page(Mm(self.width), Mm(self.height), format!("Sayfa {}", self.pages));
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.header();
+    }
+
+    fn ensure_space(&mut self, needed: f32) {
+        if self.y - needed < MARGIN + 10.0 {
+            self.new_page();
+        }
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, bold: bool, color: (f32, f32, f32)) {
+        self.layer.set_fill_color(rgb(color));
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    fn title(&mut self, title: &str) {
+        self.text(title, 14.0, MARGIN, true, BRAND);
+        self.y -= 9.0;
+    }
+
+    fn section(&mut self, title: &str) {
+        self.ensure_space(20.0);
+        self.y -= 3.0;
+        self.text(title, 11.0, MARGIN, true, BRAND);
+        self.y -= 2.0;
+        self.layer.set_outline_color(rgb(BRAND));
+        self.layer.set_outline_thickness(0.6);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(self.width - MARGIN), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= 6.0;
+    }
+
+    /// "Etiket: değer" satırları, iki sütun halinde
+    fn field_pairs(&mut self, fields: &[(&str, String)]) {
+        let column_width = (self.width - 2.0 * MARGIN) / 2.0;
+        for pair in fields.chunks(2) {
+            self.ensure_space(ROW_HEIGHT);
+            for (i, (label, value)) in pair.iter().enumerate() {
+                let x = MARGIN + i as f32 * column_width;
+                self.text(label, 8.0, x, false, MUTED);
+                self.text(&fit(value, column_width - 35.0, 9.5), 9.5, x + 35.0, false, TEXT);
+            }
+            self.y -= ROW_HEIGHT;
+        }
+        self.y -= 2.0;
+    }
+
+    fn table_header(&mut self, columns: &[(f32, f32)], headers: &[&str]) {
+        self.ensure_space(ROW_HEIGHT * 3.0);
+        self.fill_row(BRAND);
+        for ((x, width), header) in columns.iter().zip(headers) {
+            self.text(&fit(header, *width, 8.5), 8.5, x + 1.0, true, (1.0, 1.0, 1.0));
+        }
+        self.y -= ROW_HEIGHT;
+    }
+
+    fn table_row(&mut self, columns: &[(f32, f32)], cells: &[String], fill: Option<(f32, f32, f32)>, bold: bool) {
+        self.ensure_space(ROW_HEIGHT);
+        if let Some(fill) = fill {
+            self.fill_row(fill);
+        }
+        for ((x, width), cell) in columns.iter().zip(cells) {
+            self.text(&fit(cell, *width, 8.5), 8.5, x + 1.0, bold, TEXT);
+        }
+        self.y -= ROW_HEIGHT;
+    }
+
+    /// Metin satırının arkasına zemin rengi
+    fn fill_row(&self, color: (f32, f32, f32)) {
+        self.layer.set_fill_color(rgb(color));
+        self.layer.add_rect(Rect::new(
+            Mm(MARGIN),
+            Mm(self.y - 2.0),
+            Mm(self.width - MARGIN),
+            Mm(self.y + ROW_HEIGHT - 2.0),
+        ));
+    }
+
+    fn footer_note(&mut self, note: &str) {
+        self.ensure_space(12.0);
+        self.y -= 4.0;
+        self.text(note, 7.5, MARGIN, false, MUTED);
+        self.y -= 4.5;
+        let generated = chrono::Local::now().format("%d.%m.%Y %H:%M").to_string();
+        self.text(&format!("Oluşturulma: {}", generated), 7.5, MARGIN, false, MUTED);
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ApiError> {
+        self.doc
+            .save_to_bytes()
+            .map_err(|e| ApiError::InternalServerError(format!("PDF oluşturulamadı: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{Installment, PremiumDetail};
+
+    fn service() -> PdfService {
+        let mut config = Config::from_env().expect("config");
+        config.agency_name = "Çağrı Sigorta Aracılık".to_string();
+        config.agency_license_no = "T091-12345".to_string();
+        config.pdf_dir = std::env::temp_dir().join(format!("pdfs_{}", uuid::Uuid::new_v4())).to_string_lossy().to_string();
+        PdfService::new(&config, AgencyInfo::from_config(&config))
+    }
+
+    fn request() -> QuoteRequest {
+        serde_json::from_value(serde_json::json!({
+            "insured": { "tckn": "12345678901", "name": "Şükrü Öztürk", "birthDate": "1990-01-01", "phone": "5551234567", "email": "sukru@example.com" },
+            "vehicle": { "plate": "34ABC123", "brand": "Renault", "model": "Clio", "year": 2020, "usage": "hususi" },
+            "coverage": { "productType": "kasko", "startDate": "2024-01-01" }
+        }))
+        .unwrap()
+    }
+
+    fn quote(company: &str, gross: f64) -> QuoteResponse {
+        QuoteResponse {
+            request_id: "req-1".to_string(),
+            company: company.to_string(),
+            product_type: "kasko".to_string(),
+            premium: PremiumDetail { net: gross / 1.18, gross, taxes: gross - gross / 1.18, currency: "TRY".to_string() },
+            installments: vec![Installment { count: 9, per_installment: gross / 9.0, total: gross }],
+            coverages: vec![
+                Coverage { code: "CAM".to_string(), name: "Cam Kırılması".to_string(), limit: None, included: true },
+                Coverage { code: "IMM".to_string(), name: "İhtiyari Mali Mesuliyet".to_string(), limit: Some("1.000.000 TL".to_string()), included: true },
+            ],
+            warnings: vec![],
+            provider_quote_no: Some("T-1".to_string()),
+            valid_until: Some("2024-01-15".to_string()),
+            raw: None,
+            timings: None,
+        }
+    }
+
+    fn policy() -> Policy {
+        Policy {
+            id: "p-1".to_string(),
+            user_id: "u-1".to_string(),
+            quote_id: None,
+            policy_number: "AX-2024/001".to_string(),
+            provider: "Axa".to_string(),
+            product_type: "kasko".to_string(),
+            premium: 12500.0,
+            commission: None,
+            status: "active".to_string(),
+            policy_data: serde_json::json!({
+                "request": request(),
+                "quote": quote("Axa", 12500.0),
+                "payment": { "installmentCount": 3 }
+            }),
+            created_at: "2024-01-01 10:00:00".to_string(),
+            expires_at: Some("2025-01-01".to_string()),
+            pdf_path: None,
+            starts_at: Some("2024-01-01".to_string()),
+            cancelled_at: None,
+            refund_amount: None,
+            renewal_of_policy_id: None,
+            renewed_by_policy_id: None,
+            renewal_quoted_at: None,
+            renewal_attempts: 0,
+            renewal_error: None,
+            commission_rule_id: None,
+            customer_id: None,
+            vehicle_id: None,
+            agency_id: "default".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_policy_pdf_embeds_unicode_font() {
+        let bytes = service().generate_policy_pdf(&policy()).unwrap();
+        assert!(bytes.starts_with(b"%PDF-"));
+        let content = String::from_utf8_lossy(&bytes);
+        // Identity-H kodlama + ToUnicode: Türkçe karakterler gömülü fonttan çizilir
+        assert!(content.contains("DejaVuSans"));
+        assert!(content.contains("Identity-H"));
+        assert!(content.contains("/ToUnicode"));
+    }
+
+    #[test]
+    fn test_comparison_pdf_breaks_pages() {
+        let service = service();
+        let quotes: Vec<QuoteResponse> = (0..40).map(|i| quote(&format!("Şirket {}", i), 10000.0 + i as f64)).collect();
+        let bytes = service.generate_quote_comparison_pdf(&request(), &quotes).unwrap();
+        assert!(bytes.starts_with(b"%PDF-"));
+        // 40 teklif tek yatay sayfaya sığmaz; her sayfa kendi katmanıyla eklenir
+        assert!(String::from_utf8_lossy(&bytes).contains("Sayfa 2"));
+
+        assert!(service.generate_quote_comparison_pdf(&request(), &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_writes_file() {
+        let service = service();
+        let name = pdf_file_name("police", "AX-2024/001");
+        assert_eq!(name, "police_AX-2024_001.pdf");
+        let path = service.save(&name, b"%PDF-1.3").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"%PDF-1.3");
+        let _ = std::fs::remove_dir_all(&service.output_dir);
+    }
+
+    #[test]
+    fn test_agency_info_from_agency_row() {
+        let mut config = Config::from_env().expect("config");
+        config.agency_name = "EES Sigorta".to_string();
+        config.agency_phone = "0212 000 00 00".to_string();
+        let mut agency = Agency {
+            id: "kadikoy".to_string(),
+            name: "Kadıköy Acente".to_string(),
+            is_active: true,
+            created_at: String::new(),
+            updated_at: String::new(),
+            address: "Moda Cad. No:5".to_string(),
+            phone: String::new(),
+            email: String::new(),
+            license_no: "T091-1".to_string(),
+        };
+        // Diğer acentelere .env'deki bilgiler taşınmaz
+        let info = AgencyInfo::for_agency(&config, &agency);
+        assert_eq!(info.name, "Kadıköy Acente");
+        assert_eq!(info.license_no, "T091-1");
+        assert!(info.phone.is_empty());
+
+        agency.id = DEFAULT_AGENCY_ID.to_string();
+        let info = AgencyInfo::for_agency(&config, &agency);
+        assert_eq!(info.name, "EES Sigorta");
+        assert_eq!(info.address, "Moda Cad. No:5");
+        assert_eq!(info.phone, "0212 000 00 00");
+    }
+
+    #[test]
+    fn test_mask_and_fit() {
+        assert_eq!(mask_tckn("12345678901"), "123******01");
+        assert_eq!(fit("Kısa", 50.0, 9.0), "Kısa");
+        assert!(fit(&"Uzun teminat adı ".repeat(10), 30.0, 9.0).ends_with('…'));
+    }
+}
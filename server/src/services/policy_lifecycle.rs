@@ -0,0 +1,285 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Poliçe durumları
+///
+/// pending_issue -> active | cancelled
+/// active | endorsed -> endorsed | cancelled | expired | renewed
+/// expired -> renewed
+/// cancelled, renewed: son durum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyStatus {
+    PendingIssue,
+    Active,
+    Cancelled,
+    Expired,
+    Renewed,
+    Endorsed,
+}
+
+impl PolicyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PolicyStatus::PendingIssue => "pending_issue",
+            PolicyStatus::Active => "active",
+            PolicyStatus::Cancelled => "cancelled",
+            PolicyStatus::Expired => "expired",
+            PolicyStatus::Renewed => "renewed",
+            PolicyStatus::Endorsed => "endorsed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending_issue" => Some(PolicyStatus::PendingIssue),
+            "active" => Some(PolicyStatus::Active),
+            "cancelled" => Some(PolicyStatus::Cancelled),
+            "expired" => Some(PolicyStatus::Expired),
+            "renewed" => Some(PolicyStatus::Renewed),
+            "endorsed" => Some(PolicyStatus::Endorsed),
+            _ => None,
+        }
+    }
+
+    /// Poliçe teminatı devam ediyor mu (prim/komisyon toplamlarına dahil)
+    pub fn is_in_force(&self) -> bool {
+        matches!(self, PolicyStatus::Active | PolicyStatus::Endorsed)
+    }
+
+    pub fn can_transition_to(&self, next: PolicyStatus) -> bool {
+        use PolicyStatus::*;
+        match self {
+            PendingIssue => matches!(next, Active | Cancelled),
+            Active | Endorsed => matches!(next, Endorsed | Cancelled | Expired | Renewed),
+            Expired => next == Renewed,
+            Cancelled | Renewed => false,
+        }
+    }
+}
+
+/// İptal iadesi hesap yöntemi
+/// - short_term: sigortalı talebiyle iptal, kısa dönem tablosu uygulanır
+/// - pro_rata: araç satışı, pert, şirket tarafından iptal vb. gün esaslı iade
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundMethod {
+    #[default]
+    ShortTerm,
+    ProRata,
+}
+
+impl RefundMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundMethod::ShortTerm => "short_term",
+            RefundMethod::ProRata => "pro_rata",
+        }
+    }
+}
+
+/// Kısa dönem tablosu: geçen süre (ay, yukarı yuvarlanır) -> şirkette kalan prim oranı
+const SHORT_TERM_SCALE: [(u32, f64); 12] = [
+    (1, 0.20),
+    (2, 0.30),
+    (3, 0.40),
+    (4, 0.50),
+    (5, 0.60),
+    (6, 0.70),
+    (7, 0.75),
+    (8, 0.80),
+    (9, 0.85),
+    (10, 0.90),
+    (11, 0.95),
+    (12, 1.00),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefundCalculation {
+    pub method: RefundMethod,
+    pub elapsed_days: i64,
+    pub total_days: i64,
+    /// Şirkette kalan prim oranı (0.0 - 1.0)
+    pub retained_rate: f64,
+    pub refund: f64,
+}
+
+/// İptal tarihine göre iade edilecek primi hesaplar.
+/// Vade başlamadan iptal tam iade, vade bitiminde/sonrasında iptal iadesiz sayılır.
+pub fn calculate_refund(
+    premium: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+    effective: NaiveDate,
+    method: RefundMethod,
+) -> RefundCalculation {
+    let total_days = (end - start).num_days().max(1);
+    let elapsed_days = (effective - start).num_days().clamp(0, total_days);
+
+    let retained_rate = if elapsed_days == 0 {
+        0.0
+    } else {
+        let pro_rata = elapsed_days as f64 / total_days as f64;
+        match method {
+            RefundMethod::ProRata => pro_rata,
+            RefundMethod::ShortTerm => {
+                let months = ((elapsed_days + 29) / 30) as u32;
+                let scale = SHORT_TERM_SCALE
+                    .iter()
+                    .find(|(limit, _)| months <= *limit)
+                    .map(|(_, rate)| *rate)
+                    .unwrap_or(1.0);
+                // Kısa dönem hiçbir zaman gün esasından lehte olamaz
+                scale.max(pro_rata)
+            }
+        }
+    };
+
+    let refund = ((premium * (1.0 - retained_rate)) * 100.0).round() / 100.0;
+
+    RefundCalculation {
+        method,
+        elapsed_days,
+        total_days,
+        retained_rate: (retained_rate * 10000.0).round() / 10000.0,
+        refund: refund.max(0.0),
+    }
+}
+
+/// Zeyilname ile değiştirilebilecek alanlar (poliçe verisindeki teklif isteğine göre)
+pub const ENDORSABLE_FIELDS: &[&str] = &[
+    "insured.name",
+    "insured.phone",
+    "insured.email",
+    "vehicle.plate",
+    "vehicle.vin",
+    "vehicle.usage",
+    "vehicle.category",
+    "property.address",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+/// Değişiklikleri `request` nesnesine uygular ve eski/yeni değerleri döner.
+/// İzin verilmeyen alan varsa hata mesajı döner, `request` değişmez.
+pub fn apply_endorsement(
+    request: &mut Value,
+    changes: &serde_json::Map<String, Value>,
+) -> Result<Vec<FieldChange>, String> {
+    if changes.is_empty() {
+        return Err("Zeyilname için en az bir değişiklik gerekli".to_string());
+    }
+    if let Some(field) = changes.keys().find(|f| !ENDORSABLE_FIELDS.contains(&f.as_str())) {
+        return Err(format!("'{}' alanı zeyilname ile değiştirilemez", field));
+    }
+
+    let mut applied = Vec::new();
+    for (field, new_value) in changes {
+        let pointer = format!("/{}", field.replace('.', "/"));
+        let old_value = request.pointer(&pointer).cloned().unwrap_or(Value::Null);
+        if &old_value == new_value {
+            continue;
+        }
+
+        let (parent, key) = field.split_once('.').unwrap_or(("", field));
+        if !request.is_object() {
+            *request = Value::Object(Default::default());
+        }
+        let parent_value = request
+            .as_object_mut()
+            .map(|root| root.entry(parent).or_insert_with(|| Value::Object(Default::default())));
+        if let Some(Value::Object(object)) = parent_value {
+            object.insert(key.to_string(), new_value.clone());
+        }
+
+        applied.push(FieldChange {
+            field: field.clone(),
+            old_value,
+            new_value: new_value.clone(),
+        });
+    }
+
+    if applied.is_empty() {
+        return Err("Değişiklikler mevcut poliçe bilgileriyle aynı".to_string());
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_transitions() {
+        use PolicyStatus::*;
+        assert!(PendingIssue.can_transition_to(Active));
+        assert!(Active.can_transition_to(Endorsed));
+        assert!(Endorsed.can_transition_to(Endorsed));
+        assert!(Endorsed.can_transition_to(Cancelled));
+        assert!(Expired.can_transition_to(Renewed));
+        assert!(!Expired.can_transition_to(Cancelled));
+        assert!(!Cancelled.can_transition_to(Active));
+        assert!(!Renewed.can_transition_to(Expired));
+        assert!(!PendingIssue.can_transition_to(Endorsed));
+        assert_eq!(PolicyStatus::parse("pending_issue"), Some(PendingIssue));
+        assert_eq!(PolicyStatus::parse("aktif"), None);
+    }
+
+    #[test]
+    fn test_calculate_refund() {
+        let start = date(2024, 1, 1);
+        let end = date(2025, 1, 1);
+
+        // Vade başlamadan iptal: tam iade
+        let before = calculate_refund(1000.0, start, end, date(2023, 12, 20), RefundMethod::ShortTerm);
+        assert_eq!(before.refund, 1000.0);
+
+        // 45 gün: kısa dönemde 2. ay (%30), gün esasında 45/366
+        let short = calculate_refund(1000.0, start, end, date(2024, 2, 15), RefundMethod::ShortTerm);
+        assert_eq!(short.elapsed_days, 45);
+        assert_eq!(short.retained_rate, 0.30);
+        assert_eq!(short.refund, 700.0);
+
+        let pro_rata = calculate_refund(1000.0, start, end, date(2024, 2, 15), RefundMethod::ProRata);
+        assert_eq!(pro_rata.refund, 877.05);
+
+        // Vade sonrası: iade yok
+        let after = calculate_refund(1000.0, start, end, date(2025, 3, 1), RefundMethod::ProRata);
+        assert_eq!(after.refund, 0.0);
+    }
+
+    #[test]
+    fn test_apply_endorsement() {
+        let mut request = json!({
+            "insured": { "name": "Ali Veli", "phone": "5551112233" },
+            "vehicle": { "plate": "34ABC123" }
+        });
+        let changes = json!({ "vehicle.plate": "34XYZ99", "insured.email": "ali@example.com" });
+        let applied = apply_endorsement(&mut request, changes.as_object().unwrap()).unwrap();
+
+        assert_eq!(applied.len(), 2);
+        let plate = applied.iter().find(|c| c.field == "vehicle.plate").unwrap();
+        assert_eq!(plate.old_value, json!("34ABC123"));
+        assert_eq!(request["vehicle"]["plate"], "34XYZ99");
+        assert_eq!(request["insured"]["email"], "ali@example.com");
+
+        let forbidden = json!({ "insured.tckn": "11111111111" });
+        assert!(apply_endorsement(&mut request, forbidden.as_object().unwrap()).is_err());
+
+        let unchanged = json!({ "vehicle.plate": "34XYZ99" });
+        assert!(apply_endorsement(&mut request, unchanged.as_object().unwrap()).is_err());
+    }
+}
@@ -1,32 +1,35 @@
+use crate::db::DbPool;
 use crate::http::{ApiError, QuoteRequest, QuoteResponse};
-use crate::providers::ProviderRegistry;
+use crate::services::runtime::RuntimeHandle;
 use std::sync::Arc;
 use tokio::task::JoinSet;
 
 pub struct QuoteAggregator {
-    registry: Arc<ProviderRegistry>,
+    runtime: Arc<RuntimeHandle>,
+    pool: DbPool,
 }
 
 impl QuoteAggregator {
-    pub fn new(registry: Arc<ProviderRegistry>) -> Self {
-        Self { registry }
+    pub fn new(runtime: Arc<RuntimeHandle>, pool: DbPool) -> Self {
+        Self { runtime, pool }
     }
     
+    /// Acentenin provider kullanıcı bilgileriyle tüm aktif provider'lardan teklif alır
     pub async fn fetch_all_quotes(
         &self,
+        agency_id: &str,
         request: QuoteRequest,
     ) -> Result<Vec<QuoteResponse>, ApiError> {
-        let active_providers = self.registry.get_active_providers();
-        
-        if active_providers.is_empty() {
-            return Err(ApiError::ProviderInactive(
-                "Hiç aktif provider yok".to_string()
-            ));
-        }
+        let active_providers = self
+            .runtime
+            .agency_registry(&self.pool, agency_id)
+            .await?
+            .providers_for_request(&request)?;
         
         tracing::info!(
-            "🚀 {} aktif provider'dan teklif alınıyor...",
-            active_providers.len()
+            "🚀 {} aktif provider'dan {} teklifi alınıyor...",
+            active_providers.len(),
+            request.coverage.product_type.as_str()
         );
         
         // JoinSet ile paralel task'lar oluştur
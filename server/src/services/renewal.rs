@@ -0,0 +1,329 @@
+use crate::db::models::{Policy, RenewalOffer};
+use crate::db::quotes::{self, NewQuote};
+use crate::db::{logs, renewals, users, DbPool};
+use crate::http::{ApiError, QuoteRequest};
+use crate::services::{EmailService, QuoteAggregator, RuntimeHandle, SmsService};
+use crate::utils::parse_portal_date;
+use chrono::{Days, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Tarama kapalıyken (RENEWAL_SCAN_INTERVAL_SECS=0) config'in yeniden kontrol aralığı
+const DISABLED_RECHECK_SECS: u64 = 60;
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenewalSummary {
+    /// Vadesi geçtiği için expired yapılan poliçeler
+    pub expired: usize,
+    /// Yenileme penceresindeki poliçeler
+    pub due: usize,
+    pub quoted: usize,
+    pub failed: usize,
+    pub offers: usize,
+}
+
+/// Vadesi yaklaşan poliçeler için aynı sigortalı/araçla yeniden teklif alır
+pub struct RenewalService {
+    runtime: Arc<RuntimeHandle>,
+    aggregator: Arc<QuoteAggregator>,
+    pool: DbPool,
+    email: Arc<EmailService>,
+    sms: Arc<SmsService>,
+    // Zamanlayıcı ve admin tetiklemesi aynı anda çalışmasın
+    run_lock: Mutex<()>,
+}
+
+impl RenewalService {
+    pub fn new(
+        runtime: Arc<RuntimeHandle>,
+        aggregator: Arc<QuoteAggregator>,
+        email: Arc<EmailService>,
+        sms: Arc<SmsService>,
+        pool: DbPool,
+    ) -> Self {
+        Self {
+            runtime,
+            aggregator,
+            pool,
+            email,
+            sms,
+            run_lock: Mutex::new(()),
+        }
+    }
+
+    /// Periyodik tarama; aralık her turda runtime config'ten okunur (hot reload)
+    pub fn spawn_scheduler(self: Arc<Self>) {
+        tokio::spawn(async move {
+            loop {
+                let interval_secs = self.runtime.config().renewal_scan_interval_secs;
+                if interval_secs == 0 {
+                    tokio::time::sleep(Duration::from_secs(DISABLED_RECHECK_SECS)).await;
+                    continue;
+                }
+
+                if let Err(e) = self.run_once(Utc::now().date_naive()).await {
+                    tracing::error!("❌ Yenileme taraması başarısız: {}", e);
+                }
+                tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+            }
+        });
+    }
+
+    pub async fn run_once(&self, today: NaiveDate) -> Result<RenewalSummary, ApiError> {
+        let _guard = self.run_lock.lock().await;
+        let config = self.runtime.config();
+        let today_str = today.format("%Y-%m-%d").to_string();
+        let until = today
+            .checked_add_days(Days::new(config.renewal_window_days as u64))
+            .unwrap_or(today)
+            .format("%Y-%m-%d")
+            .to_string();
+
+        let mut summary = RenewalSummary {
+            expired: crate::db::policies::expire_due_policies(&self.pool, &today_str)
+                .await
+                .map_err(|e| ApiError::Unknown(e.to_string()))?
+                .len(),
+            ..Default::default()
+        };
+
+        let due = renewals::find_policies_due_for_renewal(&self.pool, &today_str, &until, config.renewal_max_attempts as i64)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+        summary.due = due.len();
+
+        if !due.is_empty() {
+            tracing::info!("🔁 {} poliçe için yenileme teklifi alınıyor (vade ≤ {})", due.len(), until);
+        }
+
+        for policy in due {
+            match self.renew_policy(&policy).await {
+                Ok(offers) => {
+                    summary.quoted += 1;
+                    summary.offers += offers;
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Yenileme teklifi alınamadı: {} - {}", policy.policy_number, e);
+                    let _ = renewals::mark_renewal_failed(&self.pool, &policy.id, &e.to_string()).await;
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        if summary.due > 0 || summary.expired > 0 {
+            tracing::info!(
+                "📊 Yenileme taraması: {} expired, {} teklif alındı, {} hata, {} teklif",
+                summary.expired,
+                summary.quoted,
+                summary.failed,
+                summary.offers
+            );
+        }
+
+        Ok(summary)
+    }
+
+    async fn renew_policy(&self, policy: &Policy) -> Result<usize, ApiError> {
+        let stored_request = match &policy.quote_id {
+            Some(quote_id) => quotes::get_quote_by_id(&self.pool, quote_id)
+                .await
+                .map_err(|e| ApiError::Unknown(e.to_string()))?
+                .map(|q| q.request_data),
+            None => None,
+        };
+        let start = policy
+            .expires_at
+            .as_deref()
+            .and_then(parse_portal_date)
+            .ok_or_else(|| ApiError::FormValidation("Poliçe bitiş tarihi okunamadı".to_string()))?;
+        let request = build_renewal_request(&policy.policy_data, stored_request.as_ref(), start)
+            .map_err(ApiError::FormValidation)?;
+        let request_data = serde_json::to_value(&request).map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+        let responses = self.aggregator.fetch_all_quotes(&policy.agency_id, request.clone()).await?;
+
+        let mut offers = Vec::new();
+        for response in responses {
+            let response_data = serde_json::to_value(&response).map_err(|e| ApiError::Unknown(e.to_string()))?;
+            let quote = quotes::create_quote(
+                &self.pool,
+                &NewQuote {
+                    user_id: &policy.user_id,
+                    request_id: &request.quote_meta.request_id,
+                    request_data: request_data.clone(),
+                    provider: &response.company,
+                    premium: response.premium.gross,
+                    response_data,
+                    provider_quote_no: response.provider_quote_no.as_deref(),
+                    valid_until: response.valid_until.as_deref(),
+                    customer_id: policy.customer_id.as_deref(),
+                    vehicle_id: policy.vehicle_id.as_deref(),
+                },
+            )
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+            let offer = renewals::create_renewal_offer(&self.pool, policy, &quote.id, &response.company, response.premium.gross)
+                .await
+                .map_err(|e| ApiError::Unknown(e.to_string()))?;
+            offers.push(offer);
+        }
+
+        renewals::mark_renewal_quoted(&self.pool, &policy.id)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+        tracing::info!("✅ Yenileme teklifleri: {} ({} teklif)", policy.policy_number, offers.len());
+        self.notify_owner(policy, &offers).await;
+        if let Err(e) = self
+            .sms
+            .send_renewal_due(&policy.user_id, &request.insured.phone, policy, &offers)
+            .await
+        {
+            tracing::warn!("⚠️ Yenileme SMS'i gönderilemedi: {} - {}", policy.policy_number, e);
+        }
+
+        Ok(offers.len())
+    }
+
+    /// Poliçe sahibine (acente) aktivite kaydı + e-posta
+    async fn notify_owner(&self, policy: &Policy, offers: &[RenewalOffer]) {
+        let best_premium = offers.iter().map(|o| o.premium).reduce(f64::min);
+        let _ = logs::log_activity(
+            &self.pool,
+            &policy.user_id,
+            "renewal_offers_ready",
+            Some("policy"),
+            Some(policy.id.clone()),
+            Some(serde_json::json!({
+                "policyNumber": policy.policy_number,
+                "expiresAt": policy.expires_at,
+                "offers": offers.len(),
+                "bestPremium": best_premium,
+                "currentPremium": policy.premium,
+            })),
+            None,
+        )
+        .await;
+
+        if let Ok(Some(owner)) = users::get_user_by_id(&self.pool, &policy.user_id).await {
+            if let Err(e) = self
+                .email
+                .send_renewal_reminder(&owner.id, &owner.email, &owner.name, policy, offers)
+                .await
+            {
+                tracing::warn!("⚠️ Yenileme e-postası kuyruğa alınamadı: {} - {}", owner.email, e);
+            }
+        }
+    }
+}
+
+/// Poliçe verisindeki (zeyilnamelerle güncel) teklif isteğinden yenileme isteği üretir.
+/// Eski poliçelerde `request` yoksa orijinal teklifin isteği kullanılır.
+pub fn build_renewal_request(
+    policy_data: &Value,
+    stored_request: Option<&Value>,
+    start_date: NaiveDate,
+) -> Result<QuoteRequest, String> {
+    let source = policy_data
+        .get("request")
+        .filter(|r| r.is_object())
+        .or(stored_request)
+        .ok_or_else(|| "Poliçenin teklif isteği bulunamadı".to_string())?;
+
+    let mut request: QuoteRequest = serde_json::from_value(source.clone())
+        .map_err(|e| format!("Teklif isteği okunamadı: {}", e))?;
+    request.coverage.start_date = start_date.format("%Y-%m-%d").to_string();
+    request.quote_meta.request_id = format!("renewal-{}", Uuid::new_v4());
+    request.quote_meta.webhook_url = None;
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::db::policies::{self, NewPolicy};
+    use crate::db::{test_pool, test_user};
+
+    fn stored_request() -> Value {
+        serde_json::json!({
+            "insured": { "tckn": "12345678901", "name": "Ali Veli", "birthDate": "1990-01-01", "phone": "5551234567", "email": "ali@example.com" },
+            "vehicle": { "plate": "34ABC123", "brand": "Renault", "model": "Clio", "year": 2020, "usage": "hususi" },
+            "coverage": { "productType": "trafik", "startDate": "2024-01-01" },
+            "quoteMeta": { "requestId": "req-1" }
+        })
+    }
+
+    #[test]
+    fn test_build_renewal_request_prefers_endorsed_data() {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let mut endorsed = stored_request();
+        endorsed["vehicle"]["plate"] = serde_json::json!("34XYZ99");
+
+        let request = build_renewal_request(&serde_json::json!({ "request": endorsed }), Some(&stored_request()), start).unwrap();
+        assert_eq!(request.vehicle.plate, "34XYZ99");
+        assert_eq!(request.coverage.start_date, "2025-01-01");
+        assert_ne!(request.quote_meta.request_id, "req-1");
+
+        // Eski poliçe: orijinal teklif isteği
+        let legacy = build_renewal_request(&serde_json::json!({}), Some(&stored_request()), start).unwrap();
+        assert_eq!(legacy.vehicle.plate, "34ABC123");
+        assert!(build_renewal_request(&serde_json::json!({}), None, start).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_once_creates_offers_for_due_policies() {
+        let pool = test_pool().await;
+
+        let mut config = Config::from_env().expect("config");
+        config.providers_config = None;
+        config.mock_provider = true;
+        config.mock_latency_ms = 0;
+        config.mock_failure_rate = 0.0;
+        config.renewal_window_days = 30;
+        let runtime = Arc::new(RuntimeHandle::new(Arc::new(config)).unwrap());
+        let aggregator = Arc::new(QuoteAggregator::new(runtime.clone(), pool.clone()));
+        let email = Arc::new(EmailService::new(&runtime.config(), pool.clone()));
+        let sms = Arc::new(SmsService::with_gateway(pool.clone(), None));
+        let service = RenewalService::new(runtime, aggregator, email, sms, pool.clone());
+
+        let user = test_user(&pool, "A", "user").await;
+        let policy = policies::create_policy(
+            &pool,
+            &NewPolicy {
+                user_id: &user.id,
+                policy_number: "P-1",
+                provider: "Mock",
+                product_type: "trafik",
+                premium: 1000.0,
+                policy_data: serde_json::json!({ "request": stored_request() }),
+                starts_at: Some("2024-01-01"),
+                expires_at: Some("2025-01-01"),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2024, 12, 15).unwrap();
+        let summary = service.run_once(today).await.unwrap();
+        assert_eq!(summary.due, 1);
+        assert_eq!(summary.quoted, 1);
+        assert!(summary.offers >= 1);
+
+        let offers = renewals::list_offers_for_policy(&pool, &policy.id).await.unwrap();
+        assert_eq!(offers.len(), summary.offers);
+        let quote = quotes::get_quote_by_id(&pool, &offers[0].quote_id).await.unwrap().unwrap();
+        assert_eq!(quote.request_data["coverage"]["startDate"], "2025-01-01");
+
+        // Aynı poliçe ikinci taramada tekrar teklif almaz
+        let again = service.run_once(today).await.unwrap();
+        assert_eq!(again.due, 0);
+    }
+}
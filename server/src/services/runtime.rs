@@ -0,0 +1,264 @@
+use crate::config::Config;
+use crate::db::agencies::{self, DEFAULT_AGENCY_ID};
+use crate::db::DbPool;
+use crate::http::{ApiError, ProvidersResponse};
+use crate::providers::ProviderRegistry;
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+
+/// Hot reload ile birlikte değişen config + provider registry.
+/// Devam eden teklifler eski snapshot'ın Arc'larını tuttuğu için etkilenmez.
+pub struct Runtime {
+    pub config: Arc<Config>,
+    pub registry: Arc<ProviderRegistry>,
+    pub loaded_at: DateTime<Utc>,
+    /// Acente kullanıcı bilgileriyle kurulmuş registry'ler (reload'da boşalır)
+    agency_registries: Mutex<HashMap<String, Arc<ProviderRegistry>>>,
+}
+
+impl Runtime {
+    fn new(config: Arc<Config>, registry: Arc<ProviderRegistry>) -> Self {
+        Self {
+            config,
+            registry,
+            loaded_at: Utc::now(),
+            agency_registries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadSummary {
+    pub loaded_at: DateTime<Utc>,
+    pub providers: ProvidersResponse,
+    /// Yeni değer kaydedildi ama restart olmadan uygulanmayacak
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub restart_required: Vec<&'static str>,
+}
+
+pub struct RuntimeHandle {
+    current: ArcSwap<Runtime>,
+    // Aynı anda iki reload'un birbirini ezmesini engeller
+    reload_lock: Mutex<()>,
+}
+
+impl RuntimeHandle {
+    /// Başlangıç config'i reload ile aynı kurallarla doğrulanır; geçersizse sunucu açılmaz
+    pub fn new(config: Arc<Config>) -> Result<Self, ApiError> {
+        config.validate()?;
+        let registry = Arc::new(ProviderRegistry::new(config.clone())?);
+        Ok(Self {
+            current: ArcSwap::from_pointee(Runtime::new(config, registry)),
+            reload_lock: Mutex::new(()),
+        })
+    }
+
+    /// Geçerli snapshot
+    pub fn load(&self) -> Arc<Runtime> {
+        self.current.load_full()
+    }
+
+    pub fn config(&self) -> Arc<Config> {
+        self.load().config.clone()
+    }
+
+    pub fn registry(&self) -> Arc<ProviderRegistry> {
+        self.load().registry.clone()
+    }
+
+    /// Acentenin provider registry'si (bkz. Config::for_agency). Varsayılan acentede kayıt yoksa
+    /// .env registry'si kullanılır.
+    pub async fn agency_registry(&self, pool: &DbPool, agency_id: &str) -> Result<Arc<ProviderRegistry>, ApiError> {
+        let runtime = self.load();
+        let mut cache = runtime.agency_registries.lock().await;
+        if let Some(registry) = cache.get(agency_id) {
+            return Ok(registry.clone());
+        }
+
+        let credentials = agencies::list_credentials(pool, agency_id)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+        let registry = if agency_id == DEFAULT_AGENCY_ID && credentials.is_empty() {
+            runtime.registry.clone()
+        } else {
+            Arc::new(ProviderRegistry::new(Arc::new(runtime.config.for_agency(agency_id, &credentials)))?)
+        };
+        cache.insert(agency_id.to_string(), registry.clone());
+        Ok(registry)
+    }
+
+    /// Kullanıcı bilgileri değişince registry bir sonraki istekte yeniden kurulur
+    pub async fn invalidate_agency(&self, agency_id: &str) {
+        self.load().agency_registries.lock().await.remove(agency_id);
+    }
+
+    /// .env + PROVIDERS_CONFIG'i yeniden okur. Doğrulama başarısızsa eski runtime korunur.
+    pub async fn reload(&self) -> Result<ReloadSummary, ApiError> {
+        self.apply(Config::reload_from_env_file()).await
+    }
+
+    async fn apply(&self, loaded: Result<Config, ApiError>) -> Result<ReloadSummary, ApiError> {
+        let _guard = self.reload_lock.lock().await;
+        let previous = self.load();
+
+        let result = loaded.and_then(|config| {
+            let config = Arc::new(config);
+            let registry = ProviderRegistry::new(config.clone())?;
+            Ok((config, registry))
+        });
+
+        let (config, registry) = result.map_err(|e| {
+            tracing::error!("❌ Config reload reddedildi, mevcut config korunuyor: {}", e);
+            ApiError::FormValidation(format!("Config reload reddedildi, mevcut config korunuyor: {}", e))
+        })?;
+
+        let restart_required = previous.config.restart_required_changes(&config);
+        if !restart_required.is_empty() {
+            tracing::warn!("⚠️ Restart gerektiren değişiklikler uygulanmadı: {:?}", restart_required);
+        }
+
+        let runtime = Runtime::new(config, Arc::new(registry));
+        let summary = ReloadSummary {
+            loaded_at: runtime.loaded_at,
+            providers: runtime.registry.get_providers_info(),
+            restart_required,
+        };
+
+        self.current.store(Arc::new(runtime));
+        tracing::info!(
+            "🔄 Config yeniden yüklendi: {} provider ({} aktif)",
+            summary.providers.total,
+            summary.providers.active_count
+        );
+
+        Ok(summary)
+    }
+
+    /// .env ve provider config dosyalarını periyodik kontrol eder, değişince reload eder
+    pub fn spawn_watcher(self: Arc<Self>) {
+        let interval_secs = self.config().config_watch_interval_secs;
+        if interval_secs == 0 {
+            tracing::info!("⏸️ Config watcher kapalı (CONFIG_WATCH_INTERVAL_SECS=0)");
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut last_seen = self.watched_mtimes();
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+                let current = self.watched_mtimes();
+                if current == last_seen {
+                    continue;
+                }
+
+                tracing::info!("📝 Config dosyası değişti, yeniden yükleniyor...");
+                // Geçersiz config'te de last_seen güncellenir: aynı hatalı dosya her turda tekrar denenmez
+                let _ = self.reload().await;
+                last_seen = self.watched_mtimes();
+            }
+        });
+    }
+
+    fn watched_mtimes(&self) -> Vec<Option<SystemTime>> {
+        let config = self.config();
+        let mut paths = vec![".env".to_string()];
+        if let Some(path) = &config.providers_config {
+            paths.push(path.clone());
+        }
+        paths
+            .iter()
+            .map(|p| std::fs::metadata(Path::new(p)).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    fn base_config() -> Config {
+        let mut config = Config::from_env().expect("config");
+        config.providers_config = None;
+        config.mock_provider = false;
+        config
+    }
+
+    #[test]
+    fn test_invalid_startup_config_is_rejected() {
+        let mut config = base_config();
+        config.request_timeout_ms = 0;
+        assert!(RuntimeHandle::new(Arc::new(config)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_reload_keeps_previous_runtime() {
+        let handle = RuntimeHandle::new(Arc::new(base_config())).unwrap();
+        let before = handle.load();
+        assert!(handle.registry().get_provider("mock").is_none());
+
+        // Geçersiz provider config: eski runtime korunur
+        let path = std::env::temp_dir().join(format!("providers_{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "{ \"providers\": [ { \"name\": \"sompo\" }, { \"name\": \"sompo\" } ] }").unwrap();
+        let mut bad = base_config();
+        bad.providers_config = Some(path.to_string_lossy().to_string());
+        assert!(matches!(handle.apply(Ok(bad)).await, Err(ApiError::FormValidation(_))));
+        assert!(Arc::ptr_eq(&before, &handle.load()));
+
+        // Geçerli config: registry atomik olarak değişir
+        let mut good = base_config();
+        good.mock_provider = true;
+        let summary = handle.apply(Ok(good)).await.unwrap();
+        assert!(summary.providers.providers.iter().any(|p| p.name == "Mock"));
+        assert!(handle.registry().get_provider("mock").is_some());
+        // Eski snapshot'ı tutan (devam eden) istekler etkilenmez
+        assert!(before.registry.get_provider("mock").is_none());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_agency_registry_uses_agency_credentials() {
+        let pool = test_pool().await;
+        let mut config = base_config();
+        config.quick_username = "env-quick".to_string();
+        config.quick_password = "env-secret".to_string();
+        let handle = RuntimeHandle::new(Arc::new(config)).unwrap();
+
+        // Varsayılan acentede kayıt yoksa .env registry'si
+        let default = handle.agency_registry(&pool, DEFAULT_AGENCY_ID).await.unwrap();
+        assert!(Arc::ptr_eq(&default, &handle.registry()));
+        assert!(default.get_provider("quick").unwrap().is_active());
+
+        // Yeni acente .env kullanıcı bilgilerini devralmaz
+        let agency = agencies::create_agency(&pool, "Kadıköy Acente").await.unwrap();
+        let registry = handle.agency_registry(&pool, &agency.id).await.unwrap();
+        assert!(!registry.get_provider("sompo").unwrap().is_active());
+        assert!(!registry.get_provider("quick").unwrap().is_active());
+
+        let admin = crate::db::users::create_user(&pool, "admin@kadikoy.com", "hash", "Admin", "admin", &agency.id)
+            .await
+            .unwrap();
+        agencies::upsert_credential(&pool, &agency.id, "sompo", "kadikoy", "secret", None, &admin.id)
+            .await
+            .unwrap();
+        // Önbellekteki registry, invalidate edilene kadar kullanılır
+        let cached = handle.agency_registry(&pool, &agency.id).await.unwrap();
+        assert!(Arc::ptr_eq(&registry, &cached));
+
+        handle.invalidate_agency(&agency.id).await;
+        let registry = handle.agency_registry(&pool, &agency.id).await.unwrap();
+        assert!(registry.get_provider("sompo").unwrap().is_active());
+        assert!(!registry.get_provider("axa").unwrap().is_active());
+    }
+}
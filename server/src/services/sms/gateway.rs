@@ -0,0 +1,64 @@
+use crate::http::ApiError;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// SMS gönderim kanalı; numara `normalize_phone` ile 90XXXXXXXXXX formatına getirilmiş olarak gelir.
+/// Başarılı gönderimde firmanın mesaj/kampanya numarası döner.
+#[async_trait]
+pub trait SmsGateway: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn send(&self, phone: &str, message: &str) -> Result<String, ApiError>;
+}
+
+/// Geliştirme gateway'i: SMS göndermez, JSON satırı olarak dosyaya yazar
+pub struct LogSmsGateway {
+    path: PathBuf,
+}
+
+impl LogSmsGateway {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl SmsGateway for LogSmsGateway {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn send(&self, phone: &str, message: &str) -> Result<String, ApiError> {
+        let id = Uuid::new_v4().to_string();
+        let line = serde_json::json!({
+            "id": id,
+            "phone": phone,
+            "message": message,
+            "at": chrono::Utc::now().to_rfc3339(),
+        });
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| ApiError::InternalServerError(format!("SMS log dizini oluşturulamadı: {}", e)))?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|e| ApiError::InternalServerError(format!("SMS log dosyası açılamadı: {}", e)))?;
+        // tokio::fs::File yazımı arka planda tamamlar; flush edilmezse satır geç görünebilir
+        file.write_all(format!("{}\n", line).as_bytes())
+            .await
+            .map_err(|e| ApiError::InternalServerError(format!("SMS log yazılamadı: {}", e)))?;
+        file.flush()
+            .await
+            .map_err(|e| ApiError::InternalServerError(format!("SMS log yazılamadı: {}", e)))?;
+
+        tracing::info!("📱 [SMS log] {} -> {}", phone, message);
+        Ok(id)
+    }
+}
@@ -0,0 +1,267 @@
+use super::gateway::SmsGateway;
+use crate::http::ApiError;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+
+const SMS_HTTP_TIMEOUT_SECS: u64 = 15;
+
+/// Desteklenen SMS firmalarının istek/yanıt formatları
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsVendor {
+    /// Form POST, yanıt düz metin: "00 <bulk id>" başarılı, diğer kodlar hata
+    Netgsm,
+    /// JSON; başarılı yanıtta response.status.code = 200
+    IletiMerkezi,
+    /// JSON; HTTP 200 gövdesi kampanya numarası
+    Verimor,
+}
+
+impl SmsVendor {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "netgsm" => Some(Self::Netgsm),
+            "iletimerkezi" => Some(Self::IletiMerkezi),
+            "verimor" => Some(Self::Verimor),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Netgsm => "netgsm",
+            Self::IletiMerkezi => "iletimerkezi",
+            Self::Verimor => "verimor",
+        }
+    }
+}
+
+/// Kullanıcı adı/şifre ile çalışan SMS firması API'si (URL config'ten)
+pub struct HttpSmsGateway {
+    client: reqwest::Client,
+    vendor: SmsVendor,
+    url: String,
+    username: String,
+    password: String,
+    sender: String,
+}
+
+impl HttpSmsGateway {
+    pub fn new(
+        vendor: SmsVendor,
+        url: &str,
+        username: &str,
+        password: &str,
+        sender: &str,
+    ) -> Result<Self, ApiError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(SMS_HTTP_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| ApiError::InternalServerError(format!("HTTP client oluşturulamadı: {}", e)))?;
+
+        Ok(Self {
+            client,
+            vendor,
+            url: url.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            sender: sender.to_string(),
+        })
+    }
+
+    async fn send_netgsm(&self, phone: &str, message: &str) -> Result<String, ApiError> {
+        // Netgsm yerel formatı bekler (5XXXXXXXXX)
+        let gsmno = phone.strip_prefix("90").unwrap_or(phone);
+        let response = self
+            .client
+            .post(&self.url)
+            .form(&[
+                ("usercode", self.username.as_str()),
+                ("password", self.password.as_str()),
+                ("gsmno", gsmno),
+                ("message", message),
+                ("msgheader", self.sender.as_str()),
+                ("dil", "TR"),
+            ])
+            .send()
+            .await
+            .map_err(request_error)?;
+        let body = response_text(response).await?;
+        parse_netgsm_response(&body)
+    }
+
+    async fn send_iletimerkezi(&self, phone: &str, message: &str) -> Result<String, ApiError> {
+        let payload = serde_json::json!({
+            "request": {
+                "authentication": { "username": self.username, "password": self.password },
+                "order": {
+                    "sender": self.sender,
+                    "sendDateTime": [],
+                    "message": {
+                        "text": message,
+                        "receipents": { "number": [phone] }
+                    }
+                }
+            }
+        });
+        let response = self.client.post(&self.url).json(&payload).send().await.map_err(request_error)?;
+        let body = response.text().await.map_err(request_error)?;
+        parse_iletimerkezi_response(&body)
+    }
+
+    async fn send_verimor(&self, phone: &str, message: &str) -> Result<String, ApiError> {
+        let payload = serde_json::json!({
+            "username": self.username,
+            "password": self.password,
+            "source_addr": self.sender,
+            "messages": [{ "msg": message, "dest": phone }]
+        });
+        let response = self.client.post(&self.url).json(&payload).send().await.map_err(request_error)?;
+        let campaign_id = response_text(response).await?;
+        Ok(campaign_id.trim().to_string())
+    }
+}
+
+#[async_trait]
+impl SmsGateway for HttpSmsGateway {
+    fn name(&self) -> &str {
+        self.vendor.as_str()
+    }
+
+    async fn send(&self, phone: &str, message: &str) -> Result<String, ApiError> {
+        match self.vendor {
+            SmsVendor::Netgsm => self.send_netgsm(phone, message).await,
+            SmsVendor::IletiMerkezi => self.send_iletimerkezi(phone, message).await,
+            SmsVendor::Verimor => self.send_verimor(phone, message).await,
+        }
+    }
+}
+
+fn request_error(e: reqwest::Error) -> ApiError {
+    if e.is_timeout() {
+        ApiError::Timeout(format!("SMS firması yanıt vermedi: {}", e))
+    } else {
+        ApiError::InternalServerError(format!("SMS isteği başarısız: {}", e))
+    }
+}
+
+/// 2xx dışındaki yanıtlar gövdesiyle birlikte hata
+async fn response_text(response: reqwest::Response) -> Result<String, ApiError> {
+    let status = response.status();
+    let body = response.text().await.map_err(request_error)?;
+    if !status.is_success() {
+        return Err(ApiError::InternalServerError(format!("SMS firması HTTP {}: {}", status.as_u16(), body.trim())));
+    }
+    Ok(body)
+}
+
+fn parse_netgsm_response(body: &str) -> Result<String, ApiError> {
+    let mut parts = body.split_whitespace();
+    let code = parts.next().unwrap_or_default();
+    match code {
+        "00" | "01" | "02" => Ok(parts.next().unwrap_or_default().to_string()),
+        "20" => Err(ApiError::FormValidation("Netgsm: mesaj metni hatalı veya çok uzun".to_string())),
+        "30" => Err(ApiError::Unauthorized("Netgsm: kullanıcı adı/şifre hatalı veya API yetkisi yok".to_string())),
+        "40" => Err(ApiError::FormValidation("Netgsm: gönderici başlığı tanımlı değil".to_string())),
+        "70" => Err(ApiError::FormValidation("Netgsm: hatalı parametre".to_string())),
+        _ => Err(ApiError::InternalServerError(format!("Netgsm beklenmeyen yanıt: {}", body.trim()))),
+    }
+}
+
+fn parse_iletimerkezi_response(body: &str) -> Result<String, ApiError> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| ApiError::ParseError(format!("İleti Merkezi yanıtı okunamadı: {}", e)))?;
+    let status = &value["response"]["status"];
+    let code = status["code"]
+        .as_i64()
+        .or_else(|| status["code"].as_str().and_then(|c| c.parse().ok()))
+        .unwrap_or_default();
+    if code != 200 {
+        let message = status["message"].as_str().unwrap_or("bilinmeyen hata");
+        return Err(ApiError::InternalServerError(format!("İleti Merkezi {}: {}", code, message)));
+    }
+
+    let order_id = &value["response"]["order"]["id"];
+    Ok(order_id
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| order_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::post;
+    use axum::Router;
+
+    /// Firma API'si yerine yerel sunucu: gelen gövdeyi döndürülen yanıtla eşler
+    async fn stand_in(handler: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, handler).await.unwrap();
+        });
+        format!("http://{}/send", addr)
+    }
+
+    #[test]
+    fn test_parse_netgsm_response() {
+        assert_eq!(parse_netgsm_response("00 1234567890").unwrap(), "1234567890");
+        assert!(matches!(parse_netgsm_response("30"), Err(ApiError::Unauthorized(_))));
+        assert!(matches!(parse_netgsm_response("40"), Err(ApiError::FormValidation(_))));
+        assert!(parse_netgsm_response("<html>").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_netgsm_gateway_posts_form() {
+        let url = stand_in(Router::new().route(
+            "/send",
+            post(|body: String| async move {
+                if body.contains("gsmno=5551234567") && body.contains("msgheader=EESSIGORTA") && body.contains("usercode=user") {
+                    "00 998877".to_string()
+                } else {
+                    "70".to_string()
+                }
+            }),
+        ))
+        .await;
+
+        let gateway = HttpSmsGateway::new(SmsVendor::Netgsm, &url, "user", "pass", "EESSIGORTA").unwrap();
+        assert_eq!(gateway.send("905551234567", "Teklifiniz hazır").await.unwrap(), "998877");
+    }
+
+    #[tokio::test]
+    async fn test_iletimerkezi_gateway_reads_order_id() {
+        let url = stand_in(Router::new().route(
+            "/send",
+            post(|axum::Json(body): axum::Json<Value>| async move {
+                let order = &body["request"]["order"];
+                let ok = order["message"]["receipents"]["number"][0] == "905551234567" && order["sender"] == "EESSIGORTA";
+                axum::Json(if ok {
+                    serde_json::json!({ "response": { "status": { "code": 200, "message": "İşlem başarılı" }, "order": { "id": "4321" } } })
+                } else {
+                    serde_json::json!({ "response": { "status": { "code": 452, "message": "Alıcı numarası hatalı" } } })
+                })
+            }),
+        ))
+        .await;
+
+        let gateway = HttpSmsGateway::new(SmsVendor::IletiMerkezi, &url, "user", "pass", "EESSIGORTA").unwrap();
+        assert_eq!(gateway.send("905551234567", "Teklifiniz hazır").await.unwrap(), "4321");
+        let err = gateway.send("905550000000", "Teklifiniz hazır").await.unwrap_err();
+        assert!(err.to_string().contains("452"));
+    }
+
+    #[tokio::test]
+    async fn test_verimor_gateway_http_error() {
+        let url = stand_in(Router::new().route(
+            "/send",
+            post(|| async { (axum::http::StatusCode::BAD_REQUEST, "INVALID_SOURCE_ADDRESS") }),
+        ))
+        .await;
+
+        let gateway = HttpSmsGateway::new(SmsVendor::Verimor, &url, "user", "pass", "EESSIGORTA").unwrap();
+        let err = gateway.send("905551234567", "Teklifiniz hazır").await.unwrap_err();
+        assert!(err.to_string().contains("INVALID_SOURCE_ADDRESS"));
+    }
+}
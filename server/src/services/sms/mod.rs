@@ -0,0 +1,318 @@
+pub mod gateway;
+pub mod http_gateway;
+
+pub use gateway::{LogSmsGateway, SmsGateway};
+pub use http_gateway::{HttpSmsGateway, SmsVendor};
+
+use crate::config::Config;
+use crate::db::models::{Policy, RenewalOffer, SmsMessage};
+use crate::db::sms::{self, NewSmsMessage};
+use crate::db::DbPool;
+use crate::http::{ApiError, QuoteRequest, QuoteResponse};
+use crate::services::email_templates::format_tl;
+use std::sync::Arc;
+
+/// "0555 123 45 67", "+90 555 123 4567", "5551234567" -> "905551234567".
+/// Yalnızca 5 ile başlayan cep numaraları kabul edilir.
+pub fn normalize_phone(value: &str) -> Option<String> {
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    let local = match digits.len() {
+        10 => digits.as_str(),
+        11 if digits.starts_with('0') => &digits[1..],
+        12 if digits.starts_with("90") => &digits[2..],
+        _ => return None,
+    };
+    local.starts_with('5').then(|| format!("90{}", local))
+}
+
+/// Araç plakasıyla, konut adresiyle anılır; adres yoksa yalnızca ürün adı kullanılır
+pub fn quote_ready_message(request: &QuoteRequest, quotes: &[QuoteResponse]) -> Option<String> {
+    let best = quotes.iter().min_by(|a, b| a.premium.gross.total_cmp(&b.premium.gross))?;
+    let subject = if request.coverage.product_type.is_vehicle() {
+        format!("{} plakalı aracınız için ", request.vehicle.plate)
+    } else {
+        request
+            .property
+            .as_ref()
+            .map(|p| p.address.trim())
+            .filter(|address| !address.is_empty())
+            .map(|address| format!("{} adresi için ", address))
+            .unwrap_or_default()
+    };
+    Some(format!(
+        "{}{} şirketten {} teklifi alındı. En uygun: {} {}. EES Sigorta",
+        subject,
+        quotes.len(),
+        best.product_type,
+        best.company,
+        format_tl(best.premium.gross)
+    ))
+}
+
+pub fn policy_issued_message(policy: &Policy) -> String {
+    format!(
+        "{} {} poliçeniz kesildi. Poliçe No: {}, prim: {}. EES Sigorta",
+        policy.provider,
+        policy.product_type,
+        policy.policy_number,
+        format_tl(policy.premium)
+    )
+}
+
+pub fn renewal_due_message(policy: &Policy, offers: &[RenewalOffer]) -> String {
+    let expires_at = policy
+        .expires_at
+        .as_deref()
+        .and_then(crate::utils::parse_portal_date)
+        .map(|d| d.format("%d.%m.%Y").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let best = offers.iter().min_by(|a, b| a.premium.total_cmp(&b.premium));
+    match best {
+        Some(offer) => format!(
+            "{} nolu poliçenizin vadesi {} tarihinde doluyor. Yenileme teklifi: {} {}. EES Sigorta",
+            policy.policy_number,
+            expires_at,
+            offer.provider,
+            format_tl(offer.premium)
+        ),
+        None => format!(
+            "{} nolu poliçenizin vadesi {} tarihinde doluyor. Yenileme için acentenizle iletişime geçin. EES Sigorta",
+            policy.policy_number, expires_at
+        ),
+    }
+}
+
+/// Sigortalıya SMS bildirimleri. Her gönderim (atlananlar dahil) sms_messages tablosuna yazılır;
+/// SMS red listesindeki numaralara gönderim yapılmaz.
+pub struct SmsService {
+    pool: DbPool,
+    gateway: Option<Arc<dyn SmsGateway>>,
+}
+
+impl SmsService {
+    pub fn new(config: &Config, pool: DbPool) -> Self {
+        let gateway: Option<Arc<dyn SmsGateway>> = match config.sms_gateway.as_str() {
+            "log" => Some(Arc::new(LogSmsGateway::new(&config.sms_log_file))),
+            "http" => {
+                let built = SmsVendor::parse(&config.sms_vendor)
+                    .ok_or_else(|| ApiError::FormValidation(format!("SMS_VENDOR geçersiz: {}", config.sms_vendor)))
+                    .and_then(|vendor| {
+                        HttpSmsGateway::new(
+                            vendor,
+                            config.sms_gateway_url.as_deref().unwrap_or_default(),
+                            &config.sms_username,
+                            &config.sms_password,
+                            &config.sms_sender,
+                        )
+                    });
+                match built {
+                    Ok(gateway) => Some(Arc::new(gateway)),
+                    Err(e) => {
+                        tracing::error!("❌ SMS gateway yapılandırması geçersiz: {}", e);
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        match &gateway {
+            Some(gateway) => tracing::info!("✅ SMS servisi hazır: {}", gateway.name()),
+            None => tracing::warn!("📱 SMS servisi yapılandırılmamış (SMS_GATEWAY boş)"),
+        }
+
+        Self::with_gateway(pool, gateway)
+    }
+
+    pub fn with_gateway(pool: DbPool, gateway: Option<Arc<dyn SmsGateway>>) -> Self {
+        Self { pool, gateway }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.gateway.is_some()
+    }
+
+    pub async fn send_quote_ready(
+        &self,
+        user_id: &str,
+        phone: &str,
+        request: &QuoteRequest,
+        quotes: &[QuoteResponse],
+    ) -> Result<Option<SmsMessage>, ApiError> {
+        match quote_ready_message(request, quotes) {
+            Some(message) => self.send(Some(user_id), phone, "quote_ready", &message).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn send_policy_issued(
+        &self,
+        user_id: &str,
+        phone: &str,
+        policy: &Policy,
+    ) -> Result<Option<SmsMessage>, ApiError> {
+        self.send(Some(user_id), phone, "policy_issued", &policy_issued_message(policy))
+            .await
+    }
+
+    pub async fn send_renewal_due(
+        &self,
+        user_id: &str,
+        phone: &str,
+        policy: &Policy,
+        offers: &[RenewalOffer],
+    ) -> Result<Option<SmsMessage>, ApiError> {
+        self.send(Some(user_id), phone, "renewal_due", &renewal_due_message(policy, offers))
+            .await
+    }
+
+    /// Gateway yoksa hiçbir şey yapmaz (None). Geçersiz ya da red listesindeki numaralar
+    /// gönderilmeden kaydedilir; gateway hatası `failed` olarak kaydedilir, tekrar denenmez.
+    pub async fn send(
+        &self,
+        user_id: Option<&str>,
+        phone: &str,
+        event: &str,
+        message: &str,
+    ) -> Result<Option<SmsMessage>, ApiError> {
+        let Some(gateway) = &self.gateway else {
+            tracing::debug!("SMS gönderimi atlandı (gateway yapılandırılmamış): {}", event);
+            return Ok(None);
+        };
+
+        let mut record = NewSmsMessage {
+            user_id,
+            phone,
+            event,
+            message,
+            status: "sent",
+            provider: Some(gateway.name()),
+            provider_message_id: None,
+            error: None,
+        };
+
+        let Some(normalized) = normalize_phone(phone) else {
+            tracing::warn!("⚠️ SMS gönderilmedi, geçersiz numara: {}", phone);
+            record.status = "invalid_phone";
+            return self.record(&record).await.map(Some);
+        };
+        record.phone = &normalized;
+
+        let opted_out = sms::is_opted_out(&self.pool, &normalized, user_id)
+            .await
+            .map_err(|e| ApiError::Unknown(e.to_string()))?;
+        if opted_out {
+            tracing::info!("🔕 SMS gönderilmedi, numara red listesinde: {} ({})", normalized, event);
+            record.status = "opted_out";
+            return self.record(&record).await.map(Some);
+        }
+
+        let result = gateway.send(&normalized, message).await;
+        let (provider_message_id, error) = match &result {
+            Ok(id) => {
+                tracing::info!("📱 SMS gönderildi: {} -> {}", event, normalized);
+                (Some(id.as_str()), None)
+            }
+            Err(e) => {
+                tracing::warn!("⚠️ SMS gönderilemedi: {} -> {} - {}", event, normalized, e);
+                (None, Some(e.to_string()))
+            }
+        };
+        record.provider_message_id = provider_message_id;
+        record.error = error.as_deref();
+        if error.is_some() {
+            record.status = "failed";
+        }
+
+        self.record(&record).await.map(Some)
+    }
+
+    async fn record(&self, record: &NewSmsMessage<'_>) -> Result<SmsMessage, ApiError> {
+        sms::record_sms(&self.pool, record)
+            .await
+            .map_err(|e| ApiError::Unknown(format!("SMS kaydı yazılamadı: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_pool;
+
+    #[test]
+    fn test_normalize_phone() {
+        assert_eq!(normalize_phone("0555 123 45 67").as_deref(), Some("905551234567"));
+        assert_eq!(normalize_phone("+90 (555) 123-4567").as_deref(), Some("905551234567"));
+        assert_eq!(normalize_phone("5551234567").as_deref(), Some("905551234567"));
+        // Sabit hat ve eksik numara
+        assert_eq!(normalize_phone("0212 123 45 67"), None);
+        assert_eq!(normalize_phone("555123"), None);
+    }
+
+    #[test]
+    fn test_quote_ready_message_by_product() {
+        let quote: QuoteResponse = serde_json::from_value(serde_json::json!({
+            "requestId": "req-1",
+            "company": "Sompo",
+            "productType": "konut",
+            "premium": { "net": 847.46, "gross": 1000.0, "taxes": 152.54, "currency": "TRY" },
+            "installments": [],
+            "coverages": [],
+            "warnings": [],
+        }))
+        .unwrap();
+        let quotes = [quote];
+        let request = |body: serde_json::Value| -> QuoteRequest { serde_json::from_value(body).unwrap() };
+        let insured = serde_json::json!({ "tckn": "12345678901", "name": "Ali Veli", "birthDate": "1990-01-01", "phone": "5551234567", "email": "ali@example.com" });
+
+        let vehicle = request(serde_json::json!({
+            "insured": insured,
+            "vehicle": { "plate": "34ABC123", "brand": "Fiat", "model": "Egea", "year": 2020, "usage": "hususi", "category": "otomobil" },
+            "coverage": { "productType": "trafik", "startDate": "2024-01-15" },
+        }));
+        assert!(quote_ready_message(&vehicle, &quotes).unwrap().starts_with("34ABC123 plakalı aracınız için 1 şirketten"));
+
+        let home = request(serde_json::json!({
+            "insured": insured,
+            "property": { "address": "Moda Cad. No:5", "city": "İstanbul", "district": "Kadıköy" },
+            "coverage": { "productType": "konut", "startDate": "2024-01-15" },
+        }));
+        let message = quote_ready_message(&home, &quotes).unwrap();
+        assert!(message.starts_with("Moda Cad. No:5 adresi için 1 şirketten konut teklifi"));
+        assert!(!message.contains("plakalı"));
+
+        let health = request(serde_json::json!({
+            "insured": insured,
+            "coverage": { "productType": "saglik", "startDate": "2024-01-15" },
+        }));
+        assert!(quote_ready_message(&health, &quotes).unwrap().starts_with("1 şirketten"));
+    }
+
+    #[tokio::test]
+    async fn test_send_respects_opt_out() {
+        let pool = test_pool().await;
+
+        let log_file = std::env::temp_dir().join(format!("sms_{}.log", uuid::Uuid::new_v4()));
+        let service = SmsService::with_gateway(pool.clone(), Some(Arc::new(LogSmsGateway::new(&log_file))));
+
+        let sent = service.send(None, "0555 123 45 67", "quote_ready", "Teklifiniz hazır").await.unwrap().unwrap();
+        assert_eq!(sent.status, "sent");
+        assert_eq!(sent.phone, "905551234567");
+        assert!(sent.provider_message_id.is_some());
+        let logged = std::fs::read_to_string(&log_file).unwrap();
+        assert!(logged.contains("Teklifiniz hazır"));
+
+        sms::add_opt_out(&pool, crate::db::agencies::DEFAULT_AGENCY_ID, "905551234567", None, None).await.unwrap();
+        let skipped = service.send(None, "5551234567", "quote_ready", "Teklifiniz hazır").await.unwrap().unwrap();
+        assert_eq!(skipped.status, "opted_out");
+        let invalid = service.send(None, "123", "quote_ready", "Teklifiniz hazır").await.unwrap().unwrap();
+        assert_eq!(invalid.status, "invalid_phone");
+
+        // Red listesindeki numaraya dosyaya yeni satır yazılmaz
+        assert_eq!(std::fs::read_to_string(&log_file).unwrap().lines().count(), 1);
+        let _ = std::fs::remove_file(&log_file);
+
+        let disabled = SmsService::with_gateway(pool, None);
+        assert!(disabled.send(None, "5551234567", "quote_ready", "x").await.unwrap().is_none());
+    }
+}
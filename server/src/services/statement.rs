@@ -0,0 +1,404 @@
+use crate::db::models::ProviderStatement;
+use crate::db::statements::{self, NewStatement, NewStatementItem, ReconciliationPolicy};
+use crate::db::DbPool;
+use crate::http::ApiError;
+use crate::providers::settings::StatementMapping;
+use crate::utils::{fold_turkish, parse_tl_price};
+use crate::utils::xlsx::{self, SheetCell};
+use std::collections::HashMap;
+
+/// Bu tutara kadar fark kuruş yuvarlaması sayılır
+const AMOUNT_TOLERANCE: f64 = 0.01;
+/// Başlık satırı belirtilmemişse aranacak satır sayısı
+const HEADER_SEARCH_ROWS: usize = 20;
+
+// Eşleme verilmeyen şirketler için başlık tahmini (normalize_header sonrası)
+const POLICY_HEADERS: &[&str] = &["policeno", "policenumarasi", "polno", "policyno", "policynumber", "police"];
+const COMMISSION_HEADERS: &[&str] = &["komisyon", "komisyontutari", "acentekomisyonu", "komisyontl", "commission"];
+const PREMIUM_HEADERS: &[&str] = &["prim", "brutprim", "primtutari", "netprim", "premium"];
+
+/// Ekstredeki bir satır (satır numarası dosyadaki gibi 1'den başlar)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    pub row: usize,
+    pub policy_number: String,
+    pub commission: f64,
+    pub premium: Option<f64>,
+}
+
+/// "Poliçe No" -> "policeno": Türkçe karakterler sadeleştirilir, harf/rakam dışı atılır
+fn normalize_header(value: &str) -> String {
+    fold_turkish(value).chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+}
+
+/// Eşleştirme anahtarı: boşluksuz, büyük harf; Excel'in sayıya çevirirken attığı baştaki sıfırlar yok sayılır
+pub fn policy_key(policy_number: &str) -> String {
+    let compact: String = policy_number
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    let trimmed = compact.trim_start_matches('0');
+    if trimmed.is_empty() { compact } else { trimmed.to_string() }
+}
+
+/// "1.234,50", "-120,00", "(120,00)", "120,00-" ve ham sayılar
+fn parse_amount(cell: &SheetCell) -> Option<f64> {
+    let text = match cell {
+        SheetCell::Number(n) => return Some(*n),
+        SheetCell::Text(text) => text.trim(),
+        SheetCell::Empty => return None,
+    };
+    let (negative, digits) = if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        (true, inner)
+    } else if let Some(rest) = text.strip_prefix('-') {
+        (true, rest)
+    } else if let Some(rest) = text.strip_suffix('-') {
+        (true, rest)
+    } else {
+        (false, text)
+    };
+    let value = parse_tl_price(digits.trim()).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Başlık adıyla (normalize edilmiş) veya sütun harfiyle ("C")
+fn find_column(header: &[SheetCell], wanted: &str) -> Option<usize> {
+    let key = normalize_header(wanted);
+    header
+        .iter()
+        .position(|cell| !key.is_empty() && normalize_header(&cell.as_text()) == key)
+        .or_else(|| {
+            let letters = wanted.trim();
+            (!letters.is_empty() && letters.len() <= 3 && letters.chars().all(|c| c.is_ascii_alphabetic())).then(|| {
+                letters
+                    .to_ascii_uppercase()
+                    .bytes()
+                    .fold(0usize, |acc, b| acc * 26 + (b - b'A' + 1) as usize)
+                    - 1
+            })
+        })
+}
+
+fn guess_column(header: &[SheetCell], candidates: &[&str]) -> Option<usize> {
+    candidates.iter().find_map(|candidate| {
+        header
+            .iter()
+            .position(|cell| normalize_header(&cell.as_text()) == *candidate)
+    })
+}
+
+#[derive(Debug)]
+struct Columns {
+    policy_number: usize,
+    commission: usize,
+    premium: Option<usize>,
+}
+
+fn resolve_columns(header: &[SheetCell], mapping: Option<&StatementMapping>) -> Option<Columns> {
+    match mapping {
+        Some(mapping) => Some(Columns {
+            policy_number: find_column(header, &mapping.policy_number)?,
+            commission: find_column(header, &mapping.commission)?,
+            premium: mapping.premium.as_deref().and_then(|p| find_column(header, p)),
+        }),
+        None => Some(Columns {
+            policy_number: guess_column(header, POLICY_HEADERS)?,
+            commission: guess_column(header, COMMISSION_HEADERS)?,
+            premium: guess_column(header, PREMIUM_HEADERS),
+        }),
+    }
+}
+
+/// CSV: UTF-8 (BOM'lu/BOM'suz) veya Windows-1254; ayraç verilmezse ilk satırdan tahmin edilir
+fn read_csv(bytes: &[u8], delimiter: Option<char>) -> Result<Vec<Vec<SheetCell>>, ApiError> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::WINDOWS_1254.decode(bytes).0.into_owned(),
+    };
+    let delimiter = delimiter.unwrap_or_else(|| {
+        let first_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or_default();
+        [';', '\t', ',']
+            .into_iter()
+            .max_by_key(|d| first_line.matches(*d).count())
+            .unwrap_or(';')
+    });
+    if !delimiter.is_ascii() {
+        return Err(ApiError::FormValidation(format!("CSV ayracı ASCII olmalı: {}", delimiter)));
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter as u8)
+        .from_reader(text.as_bytes());
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(|v| SheetCell::Text(v.to_string())).collect())
+                .map_err(|e| ApiError::ParseError(format!("CSV okunamadı: {}", e)))
+        })
+        .collect()
+}
+
+/// CSV veya XLSX ekstreyi satırlara ayırır. Poliçe numarasında rakam olmayan satırlar
+/// (ara başlıklar, "TOPLAM" satırları) atlanır; komisyonu okunamayan satır hata verir.
+pub fn parse_statement(bytes: &[u8], mapping: Option<&StatementMapping>) -> Result<Vec<StatementLine>, ApiError> {
+    let rows = if bytes.starts_with(b"PK\x03\x04") {
+        xlsx::read_sheet(bytes, mapping.and_then(|m| m.sheet.as_deref())).map_err(ApiError::ParseError)?
+    } else {
+        read_csv(bytes, mapping.and_then(|m| m.delimiter))?
+    };
+
+    let header_index = match mapping.and_then(|m| m.header_row) {
+        Some(row) => row.saturating_sub(1),
+        None => rows
+            .iter()
+            .take(HEADER_SEARCH_ROWS)
+            .position(|row| resolve_columns(row, mapping).is_some())
+            .ok_or_else(|| {
+                ApiError::FormValidation(
+                    "Ekstrede poliçe no ve komisyon sütunları bulunamadı; PROVIDERS_CONFIG'te statement eşlemesi tanımlayın"
+                        .to_string(),
+                )
+            })?,
+    };
+    let header = rows
+        .get(header_index)
+        .ok_or_else(|| ApiError::FormValidation(format!("Başlık satırı yok: {}", header_index + 1)))?;
+    let columns = resolve_columns(header, mapping).ok_or_else(|| {
+        ApiError::FormValidation(format!("{}. satırda eşlemedeki sütunlar bulunamadı", header_index + 1))
+    })?;
+
+    let mut lines = Vec::new();
+    let mut invalid_rows = Vec::new();
+    for (index, row) in rows.iter().enumerate().skip(header_index + 1) {
+        let cell = |column: usize| row.get(column).cloned().unwrap_or(SheetCell::Empty);
+        let policy_number = cell(columns.policy_number).as_text();
+        if !policy_number.chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        match parse_amount(&cell(columns.commission)) {
+            Some(commission) => lines.push(StatementLine {
+                row: index + 1,
+                policy_number,
+                commission,
+                premium: columns.premium.and_then(|column| parse_amount(&cell(column))),
+            }),
+            None => invalid_rows.push((index + 1).to_string()),
+        }
+    }
+
+    if !invalid_rows.is_empty() {
+        return Err(ApiError::FormValidation(format!(
+            "Komisyon tutarı okunamayan satırlar: {}",
+            invalid_rows.into_iter().take(10).collect::<Vec<_>>().join(", ")
+        )));
+    }
+    if lines.is_empty() {
+        return Err(ApiError::FormValidation("Ekstrede poliçe satırı bulunamadı".to_string()));
+    }
+    Ok(lines)
+}
+
+fn round(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Ekstre satırlarını poliçelerle poliçe numarasından eşleştirir. Aynı poliçenin satırları
+/// (ör. zeyil) toplanır. `period` (YYYY-MM) ayında kesilip ekstrede olmayan poliçeler missing_theirs olur.
+pub fn reconcile(lines: &[StatementLine], ours: &[ReconciliationPolicy], period: &str) -> Vec<NewStatementItem> {
+    let mut groups: Vec<(String, StatementLine, Vec<usize>)> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for line in lines {
+        let key = policy_key(&line.policy_number);
+        match group_index.get(&key) {
+            Some(&i) => {
+                let (_, total, rows) = &mut groups[i];
+                total.commission += line.commission;
+                total.premium = match (total.premium, line.premium) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+                rows.push(line.row);
+            }
+            None => {
+                group_index.insert(key.clone(), groups.len());
+                groups.push((key, line.clone(), vec![line.row]));
+            }
+        }
+    }
+
+    let mut by_key: HashMap<String, &ReconciliationPolicy> = HashMap::new();
+    for policy in ours {
+        by_key.entry(policy_key(&policy.policy_number)).or_insert(policy);
+    }
+
+    let mut items: Vec<NewStatementItem> = groups
+        .iter()
+        .map(|(key, line, rows)| {
+            let source_rows = Some(rows.iter().map(usize::to_string).collect::<Vec<_>>().join(","));
+            let statement_commission = Some(round(line.commission));
+            match by_key.get(key) {
+                Some(policy) => {
+                    let our_commission = round(policy.commission.unwrap_or_default());
+                    let difference = round(line.commission - our_commission);
+                    NewStatementItem {
+                        policy_id: Some(policy.id.clone()),
+                        policy_number: policy.policy_number.clone(),
+                        status: if difference.abs() <= AMOUNT_TOLERANCE { "matched" } else { "amount_mismatch" },
+                        statement_commission,
+                        our_commission: Some(our_commission),
+                        difference: Some(difference),
+                        statement_premium: line.premium.map(round),
+                        source_rows,
+                    }
+                }
+                None => NewStatementItem {
+                    policy_id: None,
+                    policy_number: line.policy_number.clone(),
+                    status: "missing_ours",
+                    statement_commission,
+                    our_commission: None,
+                    difference: statement_commission,
+                    statement_premium: line.premium.map(round),
+                    source_rows,
+                },
+            }
+        })
+        .collect();
+
+    for policy in ours {
+        let key = policy_key(&policy.policy_number);
+        if policy.created_at.starts_with(period) && !group_index.contains_key(&key) {
+            let our_commission = round(policy.commission.unwrap_or_default());
+            items.push(NewStatementItem {
+                policy_id: Some(policy.id.clone()),
+                policy_number: policy.policy_number.clone(),
+                status: "missing_theirs",
+                statement_commission: None,
+                our_commission: Some(our_commission),
+                difference: Some(-our_commission),
+                statement_premium: None,
+                source_rows: None,
+            });
+        }
+    }
+    items
+}
+
+/// Ekstreyi okur, şirketin poliçeleriyle eşleştirir ve sonucu kaydeder
+/// Yalnızca ekstrenin acentesine ait poliçeler dikkate alınır
+pub async fn import_statement(
+    pool: &DbPool,
+    upload: &NewStatement<'_>,
+    bytes: &[u8],
+    mapping: Option<&StatementMapping>,
+) -> Result<ProviderStatement, ApiError> {
+    let lines = parse_statement(bytes, mapping)?;
+    let ours = statements::list_policies_for_reconciliation(pool, upload.agency_id, upload.provider)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+    let items = reconcile(&lines, &ours, upload.period);
+
+    let statement = statements::create_statement(pool, upload, &items)
+        .await
+        .map_err(|e| ApiError::Unknown(format!("Ekstre kaydedilemedi: {}", e)))?;
+
+    tracing::info!(
+        "🧮 {} {} ekstresi: {} eşleşti, {} tutar farkı, {} bizde yok, {} ekstrede yok",
+        upload.provider,
+        upload.period,
+        statement.matched_count,
+        statement.mismatch_count,
+        statement.missing_ours_count,
+        statement.missing_theirs_count
+    );
+    Ok(statement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(id: &str, number: &str, commission: f64, created_at: &str) -> ReconciliationPolicy {
+        ReconciliationPolicy {
+            id: id.to_string(),
+            policy_number: number.to_string(),
+            commission: Some(commission),
+            status: "active".to_string(),
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_csv_statement_with_title_rows() {
+        // Windows-1254, ';' ayraçlı, başlıktan önce açıklama satırı ve sonda toplam satırı
+        let text = "SOMPO SİGORTA KOMİSYON EKSTRESİ;;\n\
+                    Poliçe No;Brüt Prim;Komisyon Tutarı\n\
+                    0012345;1.000,00;100,00\n\
+                    SMP 77;2.500,00;(25,50)\n\
+                    TOPLAM;3.500,00;74,50\n";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1254.encode(text);
+        let lines = parse_statement(&bytes, None).unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], StatementLine { row: 3, policy_number: "0012345".to_string(), commission: 100.0, premium: Some(1000.0) });
+        assert_eq!(lines[1].commission, -25.5);
+    }
+
+    #[test]
+    fn test_parse_statement_with_mapping() {
+        let mapping = StatementMapping {
+            policy_number: "B".to_string(),
+            commission: "Acente Payı".to_string(),
+            premium: None,
+            sheet: None,
+            header_row: Some(1),
+            delimiter: Some(','),
+        };
+        let csv = "Tarih,Police,Acente Payı\n01.05.2024,AX-1,\"1.250,75\"\n01.05.2024,AX-2,abc\n";
+        let err = parse_statement(csv.as_bytes(), Some(&mapping)).unwrap_err();
+        assert!(err.to_string().contains("3"));
+
+        let csv = "Tarih,Police,Acente Payı\n01.05.2024,AX-1,\"1.250,75\"\n";
+        let lines = parse_statement(csv.as_bytes(), Some(&mapping)).unwrap();
+        assert_eq!(lines[0].policy_number, "AX-1");
+        assert_eq!(lines[0].commission, 1250.75);
+    }
+
+    #[test]
+    fn test_reconcile_statuses() {
+        let ours = vec![
+            policy("p1", "12345", 100.0, "2024-05-03 10:00:00"),
+            policy("p2", "SMP-2", 200.0, "2024-05-10 10:00:00"),
+            policy("p3", "SMP-3", 300.0, "2024-05-20 10:00:00"),
+            // Önceki ay kesilmiş, ekstrede yok: bu ayın eksiği sayılmaz
+            policy("p4", "SMP-4", 400.0, "2024-04-20 10:00:00"),
+        ];
+        let line = |row: usize, number: &str, commission: f64| StatementLine {
+            row,
+            policy_number: number.to_string(),
+            commission,
+            premium: None,
+        };
+        let lines = vec![
+            line(2, "0012345", 100.004),
+            line(3, "smp-2", 150.0),
+            line(4, "SMP-2", 30.0),
+            line(5, "SMP-9", 45.0),
+        ];
+
+        let items = reconcile(&lines, &ours, "2024-05");
+        let status: Vec<(&str, &str)> = items.iter().map(|i| (i.policy_number.as_str(), i.status)).collect();
+        assert_eq!(
+            status,
+            vec![("12345", "matched"), ("SMP-2", "amount_mismatch"), ("SMP-9", "missing_ours"), ("SMP-3", "missing_theirs")]
+        );
+        assert_eq!(items[1].statement_commission, Some(180.0));
+        assert_eq!(items[1].difference, Some(-20.0));
+        assert_eq!(items[1].source_rows.as_deref(), Some("3,4"));
+        assert_eq!(items[3].difference, Some(-300.0));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::db::customers;
+use crate::db::models::{Customer, Vehicle};
+use crate::db::vehicles::{self, VehicleInput};
+use crate::db::DbPool;
+use crate::http::{ApiError, QuoteRequest, VehicleCategory, VehicleInfo, VehicleUsage};
+use crate::utils::parse_portal_date;
+
+/// "34 abc 123" -> "34ABC123"
+pub fn normalize_plate(plate: &str) -> String {
+    plate
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase()
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_uppercase)
+}
+
+/// Teklif isteğindeki aracı kayda çevirir; plaka yoksa (konut vb.) None
+pub fn vehicle_input(customer_id: &str, vehicle: &VehicleInfo) -> Option<VehicleInput> {
+    let plate = normalize_plate(&vehicle.plate);
+    if plate.is_empty() {
+        return None;
+    }
+    Some(VehicleInput {
+        customer_id: customer_id.to_string(),
+        plate,
+        vin: non_empty(&vehicle.vin),
+        brand: vehicle.brand.trim().to_string(),
+        model: vehicle.model.trim().to_string(),
+        year: i64::from(vehicle.year),
+        usage: vehicle.usage.as_str().to_string(),
+        category: vehicle.category.as_str().to_string(),
+        registration_serial: non_empty(&vehicle.registration_serial),
+        registration_no: non_empty(&vehicle.registration_no),
+        engine_no: non_empty(&vehicle.engine_no),
+        registration_date: vehicle
+            .registration_date
+            .as_deref()
+            .and_then(parse_portal_date)
+            .map(|d| d.format("%Y-%m-%d").to_string()),
+    })
+}
+
+/// Kayıttan teklif isteğindeki araç bilgisine
+pub fn vehicle_info(vehicle: &Vehicle) -> VehicleInfo {
+    VehicleInfo {
+        plate: vehicle.plate.clone(),
+        vin: vehicle.vin.clone(),
+        brand: vehicle.brand.clone(),
+        model: vehicle.model.clone(),
+        year: u16::try_from(vehicle.year).unwrap_or_default(),
+        usage: VehicleUsage::parse(&vehicle.usage).unwrap_or_default(),
+        category: VehicleCategory::parse(&vehicle.category).unwrap_or_default(),
+        registration_serial: vehicle.registration_serial.clone(),
+        registration_no: vehicle.registration_no.clone(),
+        engine_no: vehicle.engine_no.clone(),
+        registration_date: vehicle.registration_date.clone(),
+    }
+}
+
+/// Teklif alınırken aracı kaydeder veya günceller. Hata teklifi engellemez; None döner
+pub async fn record_vehicle(pool: &DbPool, customer: &Customer, vehicle: &VehicleInfo, user_id: &str) -> Option<Vehicle> {
+    let input = vehicle_input(&customer.id, vehicle)?;
+    match vehicles::upsert_vehicle(pool, &input, Some(user_id)).await {
+        Ok(vehicle) => Some(vehicle),
+        Err(e) => {
+            tracing::warn!("⚠️ Araç kaydedilemedi: {} - {}", input.plate, e);
+            None
+        }
+    }
+}
+
+/// `vehicleId` verilmişse isteğin araç bilgisini kayıttan doldurur. Araç teklifi alanın
+/// acentesinde olmalı (super-admin dahil); başka bir sigortalıya kayıtlıysa istek reddedilir.
+pub async fn apply_vehicle_id(
+    pool: &DbPool,
+    request: &mut QuoteRequest,
+    owner: Option<&str>,
+    agency_id: &str,
+) -> Result<(), ApiError> {
+    let Some(vehicle_id) = request.vehicle_id.as_deref() else {
+        return Ok(());
+    };
+    let vehicle = vehicles::get_vehicle(pool, vehicle_id, owner, Some(agency_id))
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?
+        .ok_or_else(|| ApiError::FormValidation(format!("Araç bulunamadı: {}", vehicle_id)))?;
+    let customer = customers::get_customer(pool, &vehicle.customer_id, None, None)
+        .await
+        .map_err(|e| ApiError::Unknown(e.to_string()))?;
+
+    let identity_no: String = request.insured.tckn.chars().filter(|c| !c.is_whitespace()).collect();
+    if customer.is_some_and(|c| c.identity_no != identity_no) {
+        return Err(ApiError::FormValidation(format!(
+            "{} plakalı araç başka bir sigortalıya kayıtlı",
+            vehicle.plate
+        )));
+    }
+
+    request.vehicle = vehicle_info(&vehicle);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vehicle_input_round_trip() {
+        let info = VehicleInfo {
+            plate: " 34 abc 123 ".to_string(),
+            vin: Some(" ".to_string()),
+            brand: "Fiat".to_string(),
+            model: "Egea".to_string(),
+            year: 2020,
+            usage: VehicleUsage::Ticari,
+            category: VehicleCategory::Kamyonet,
+            registration_serial: Some("ab".to_string()),
+            registration_no: Some("123456".to_string()),
+            engine_no: None,
+            registration_date: Some("15.03.2020".to_string()),
+        };
+        let input = vehicle_input("c1", &info).unwrap();
+        assert_eq!(input.plate, "34ABC123");
+        assert_eq!(input.vin, None);
+        assert_eq!(input.registration_serial.as_deref(), Some("AB"));
+        assert_eq!(input.registration_date.as_deref(), Some("2020-03-15"));
+        assert!(vehicle_input("c1", &VehicleInfo::default()).is_none());
+
+        let vehicle = Vehicle {
+            id: "v1".to_string(),
+            customer_id: "c1".to_string(),
+            plate: input.plate,
+            vin: None,
+            brand: input.brand,
+            model: input.model,
+            year: input.year,
+            usage: input.usage,
+            category: input.category,
+            registration_serial: input.registration_serial,
+            registration_no: input.registration_no,
+            engine_no: None,
+            registration_date: input.registration_date,
+            created_by: None,
+            created_at: "2024-01-01 00:00:00".to_string(),
+            updated_at: "2024-01-01 00:00:00".to_string(),
+            last_quote_at: None,
+        };
+        let restored = vehicle_info(&vehicle);
+        assert_eq!(restored.plate, "34ABC123");
+        assert_eq!(restored.usage, VehicleUsage::Ticari);
+        assert_eq!(restored.category, VehicleCategory::Kamyonet);
+        assert_eq!(restored.year, 2020);
+    }
+}
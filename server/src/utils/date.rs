@@ -0,0 +1,39 @@
+use chrono::{Months, NaiveDate};
+
+/// Portal tarih formatlarını parse eder
+/// Örnekler: "2024-06-01", "2024-06-01T00:00:00", "01.06.2024", "01/06/2024"
+pub fn parse_portal_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    // ISO datetime ise sadece tarih kısmı
+    let date_part = text.split(['T', ' ']).next().unwrap_or(text);
+
+    ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%d-%m-%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date_part, format).ok())
+}
+
+/// Yıllık poliçe bitiş tarihi (29 Şubat -> 28 Şubat)
+pub fn one_year_after(date: NaiveDate) -> NaiveDate {
+    date.checked_add_months(Months::new(12)).unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_portal_date() {
+        let expected = NaiveDate::from_ymd_opt(2024, 6, 1);
+        assert_eq!(parse_portal_date("2024-06-01"), expected);
+        assert_eq!(parse_portal_date("2024-06-01T00:00:00+03:00"), expected);
+        assert_eq!(parse_portal_date(" 01.06.2024 "), expected);
+        assert_eq!(parse_portal_date("01/06/2024"), expected);
+        assert!(parse_portal_date("yarın").is_none());
+    }
+
+    #[test]
+    fn test_one_year_after() {
+        let leap = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(one_year_after(leap), NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
+    }
+}
@@ -0,0 +1,55 @@
+use scraper::{ElementRef, Html, Selector};
+
+/// Element'in görünen metni (whitespace normalize edilmiş)
+pub fn element_text(element: &ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Verilen CSS selector'lara (sırasıyla) uyan elementlerin metinleri.
+/// Geçersiz selector'lar (ör. Playwright'a özel `:has-text`) atlanır.
+pub fn texts_by_selectors(document: &Html, selectors: &[&str]) -> Vec<String> {
+    selectors
+        .iter()
+        .filter_map(|sel| Selector::parse(sel).ok())
+        .flat_map(|selector| {
+            document
+                .select(&selector)
+                .map(|el| element_text(&el))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Çocuk elementi olmayan (leaf) elementlerin metinleri, doküman sırasıyla
+pub fn leaf_texts(document: &Html) -> Vec<String> {
+    let all = Selector::parse("body *").expect("static selector");
+    document
+        .select(&all)
+        .filter(|el| el.children().all(|c| !c.value().is_element()))
+        .filter(|el| !matches!(el.value().name(), "script" | "style"))
+        .map(|el| element_text(&el))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_texts_and_leaves() {
+        let doc = Html::parse_document(
+            r#"<html><body><div class="price"> 4.350,00
+                <span>TL</span></div><script>var x = 1;</script><p>Not</p></body></html>"#,
+        );
+
+        assert_eq!(texts_by_selectors(&doc, &[".price", "span:has-text('TL')"]), vec!["4.350,00 TL"]);
+        assert_eq!(leaf_texts(&doc), vec!["TL", "Not"]);
+    }
+}
@@ -1,6 +1,12 @@
+pub mod date;
+pub mod html;
 pub mod mask;
 pub mod parser;
+pub mod text;
+pub mod xlsx;
 
+pub use date::{one_year_after, parse_portal_date};
 pub use mask::mask_sensitive;
 pub use parser::parse_tl_price;
+pub use text::fold_turkish;
 
@@ -0,0 +1,27 @@
+/// Türkçe harfleri ASCII karşılığına indirip küçük harfe çevirir ("ŞÜKRÜ Işık" -> "sukru isik").
+/// Arama ve başlık eşleştirmede büyük/küçük harf ve İ/ı farkını yok saymak için.
+pub fn fold_turkish(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            'ç' | 'Ç' => 'c',
+            'ğ' | 'Ğ' => 'g',
+            'ı' | 'İ' | 'I' => 'i',
+            'ö' | 'Ö' => 'o',
+            'ş' | 'Ş' => 's',
+            'ü' | 'Ü' => 'u',
+            other => other.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_turkish() {
+        assert_eq!(fold_turkish("ŞÜKRÜ Işık"), "sukru isik");
+        assert_eq!(fold_turkish("Poliçe No"), "police no");
+    }
+}
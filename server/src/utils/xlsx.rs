@@ -0,0 +1,383 @@
+//! Şirket ekstreleri için minimal XLSX okuyucu: tek sayfanın hücre değerleri.
+//! Biçim, formül ve tarih stilleri okunmaz; formüllü hücrelerde son hesaplanan değer gelir.
+
+use std::io::{Cursor, Read};
+
+/// Excel sınırları; dosyadaki `r=` değerleri bunları aşamaz
+const MAX_ROWS: usize = 1_048_576;
+const MAX_COLUMNS: usize = 16_384;
+/// Açılmış tek bir XML dosyasının azami boyutu (zip bombası koruması)
+const MAX_ENTRY_BYTES: u64 = 50 * 1024 * 1024;
+/// Boşluklar dahil ayrılan toplam hücre sayısı (seyrek ama uzak hücrelerle bellek tüketimine karşı)
+const MAX_CELLS: usize = 2_000_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SheetCell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+impl SheetCell {
+    /// Hücrenin metin hali (sayılar Excel'deki ham değeriyle: 1234.5)
+    pub fn as_text(&self) -> String {
+        match self {
+            SheetCell::Text(text) => text.trim().to_string(),
+            SheetCell::Number(n) => n.to_string(),
+            SheetCell::Empty => String::new(),
+        }
+    }
+}
+
+/// `sheet` verilirse o isimdeki sayfa, yoksa ilk sayfa okunur. Boş satırlar korunur
+/// (satır indeksi = Excel satır numarası - 1).
+pub fn read_sheet(bytes: &[u8], sheet: Option<&str>) -> Result<Vec<Vec<SheetCell>>, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("XLSX açılamadı: {}", e))?;
+
+    let shared_strings = match read_entry(&mut archive, "xl/sharedStrings.xml") {
+        Ok(xml) => parse_shared_strings(&xml),
+        Err(_) => Vec::new(),
+    };
+    let path = sheet_path(&mut archive, sheet)?;
+    let xml = read_entry(&mut archive, &path)?;
+    parse_sheet(&xml, &shared_strings)
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String, String> {
+    let entry = archive.by_name(name).map_err(|e| format!("XLSX içinde {} yok: {}", name, e))?;
+    // Başlıktaki boyuta güvenilmez; okuma sınırda kesilir
+    let mut content = String::new();
+    entry
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_string(&mut content)
+        .map_err(|e| format!("{} okunamadı: {}", name, e))?;
+    if content.len() as u64 > MAX_ENTRY_BYTES {
+        return Err(format!("{} çok büyük (en fazla {} MB)", name, MAX_ENTRY_BYTES / 1024 / 1024));
+    }
+    Ok(content)
+}
+
+/// workbook.xml'deki sayfa adını ilişki dosyası üzerinden sayfa XML yoluna çevirir
+fn sheet_path(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, sheet: Option<&str>) -> Result<String, String> {
+    let fallback = "xl/worksheets/sheet1.xml".to_string();
+    let Ok(workbook) = read_entry(archive, "xl/workbook.xml") else {
+        return Ok(fallback);
+    };
+
+    let sheets: Vec<(String, String)> = tokens(&workbook)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Open { name: "sheet", attrs, .. } => Some((attr(attrs, "name")?, attr(attrs, "r:id")?)),
+            _ => None,
+        })
+        .collect();
+    let rel_id = match sheet {
+        Some(wanted) => sheets
+            .iter()
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(wanted.trim()))
+            .map(|(_, id)| id.clone())
+            .ok_or_else(|| {
+                let names: Vec<&str> = sheets.iter().map(|(name, _)| name.as_str()).collect();
+                format!("'{}' sayfası bulunamadı (sayfalar: {})", wanted, names.join(", "))
+            })?,
+        None => match sheets.first() {
+            Some((_, id)) => id.clone(),
+            None => return Ok(fallback),
+        },
+    };
+
+    let Ok(rels) = read_entry(archive, "xl/_rels/workbook.xml.rels") else {
+        return Ok(fallback);
+    };
+    let target = tokens(&rels).into_iter().find_map(|token| match token {
+        Token::Open { name: "Relationship", attrs, .. } if attr(attrs, "Id").as_deref() == Some(rel_id.as_str()) => {
+            attr(attrs, "Target")
+        }
+        _ => None,
+    });
+    Ok(match target {
+        Some(target) if target.starts_with('/') => target.trim_start_matches('/').to_string(),
+        Some(target) => format!("xl/{}", target),
+        None => fallback,
+    })
+}
+
+fn parse_shared_strings(xml: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let (mut in_text, mut in_phonetic) = (false, false);
+    for token in tokens(xml) {
+        match token {
+            Token::Open { name: "si", .. } => current.clear(),
+            Token::Close("si") => strings.push(std::mem::take(&mut current)),
+            Token::Open { name: "t", empty: false, .. } => in_text = true,
+            Token::Close("t") => in_text = false,
+            // Japonca okunuş ipuçları metne dahil değildir
+            Token::Open { name: "rPh", empty: false, .. } => in_phonetic = true,
+            Token::Close("rPh") => in_phonetic = false,
+            Token::Text(text) if in_text && !in_phonetic => current.push_str(&decode_entities(text)),
+            _ => {}
+        }
+    }
+    strings
+}
+
+fn parse_sheet(xml: &str, shared_strings: &[String]) -> Result<Vec<Vec<SheetCell>>, String> {
+    let mut rows: Vec<Vec<SheetCell>> = Vec::new();
+    let mut cell: Option<(usize, Option<String>)> = None;
+    let mut value = String::new();
+    let mut in_value = false;
+    let mut allocated_cells = 0usize;
+
+    for token in tokens(xml) {
+        match token {
+            Token::Open { name: "row", attrs, .. } => {
+                let index = match attr(attrs, "r") {
+                    Some(r) => r
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|r| (1..=MAX_ROWS).contains(r))
+                        .ok_or_else(|| format!("Geçersiz satır numarası: {}", r))?
+                        - 1,
+                    None => rows.len(),
+                };
+                if index >= MAX_ROWS {
+                    return Err(format!("Sayfa en fazla {} satır olabilir", MAX_ROWS));
+                }
+                rows.resize_with(index.max(rows.len()), Vec::new);
+                rows.push(Vec::new());
+            }
+            Token::Open { name: "c", attrs, empty } => {
+                let row = rows.last().map(Vec::len).unwrap_or_default();
+                let column = match attr(attrs, "r") {
+                    Some(r) => column_index(&r).ok_or_else(|| format!("Geçersiz hücre adresi: {}", r))?,
+                    None => row,
+                };
+                if column >= MAX_COLUMNS {
+                    return Err(format!("Sayfa en fazla {} sütun olabilir", MAX_COLUMNS));
+                }
+                if empty {
+                    continue;
+                }
+                cell = Some((column, attr(attrs, "t")));
+                value.clear();
+            }
+            Token::Open { name: "v" | "t", empty: false, .. } if cell.is_some() => in_value = true,
+            Token::Close("v" | "t") => in_value = false,
+            Token::Text(text) if in_value => value.push_str(&decode_entities(text)),
+            Token::Close("c") => {
+                let Some((column, kind)) = cell.take() else { continue };
+                let parsed = match kind.as_deref() {
+                    Some("s") => value
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| shared_strings.get(i))
+                        .map(|s| SheetCell::Text(s.clone()))
+                        .unwrap_or(SheetCell::Empty),
+                    Some("b") => SheetCell::Text(if value.trim() == "1" { "TRUE" } else { "FALSE" }.to_string()),
+                    Some("str" | "inlineStr" | "e") => SheetCell::Text(value.clone()),
+                    _ => value.trim().parse::<f64>().map(SheetCell::Number).unwrap_or(SheetCell::Empty),
+                };
+                if let Some(row) = rows.last_mut() {
+                    if row.len() <= column {
+                        allocated_cells += column + 1 - row.len();
+                        if allocated_cells > MAX_CELLS {
+                            return Err(format!("Sayfa en fazla {} hücre olabilir", MAX_CELLS));
+                        }
+                        row.resize(column + 1, SheetCell::Empty);
+                    }
+                    row[column] = parsed;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+/// "C12" -> 2; Excel'in son sütunu (XFD) aşılırsa None
+fn column_index(reference: &str) -> Option<usize> {
+    let letters = reference.bytes().take_while(u8::is_ascii_alphabetic);
+    let mut index = 0usize;
+    for (i, b) in letters.enumerate() {
+        // XFD üç harftir; daha uzun adresler taşmadan reddedilir
+        if i >= 3 {
+            return None;
+        }
+        index = index * 26 + (b.to_ascii_uppercase() - b'A' + 1) as usize;
+    }
+    index.checked_sub(1).filter(|i| *i < MAX_COLUMNS)
+}
+
+#[derive(Debug)]
+enum Token<'a> {
+    /// Namespace öneki atılmış etiket adı
+    Open { name: &'a str, attrs: &'a str, empty: bool },
+    Close(&'a str),
+    Text(&'a str),
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn tokens(xml: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = xml;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            tokens.push(Token::Text(&cdata[..end]));
+            rest = cdata.get(end + 3..).unwrap_or_default();
+            continue;
+        }
+        let close = if rest.starts_with("<!--") { rest.find("-->").map(|i| i + 2) } else { rest.find('>') };
+        let Some(end) = close else { break };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(local_name(name.trim())));
+            continue;
+        }
+        let empty = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        tokens.push(Token::Open {
+            name: local_name(name),
+            attrs,
+            empty,
+        });
+    }
+    tokens
+}
+
+/// `key="değer"` veya `key='değer'` (anahtar tam adıyla: "r:id")
+fn attr(attrs: &str, key: &str) -> Option<String> {
+    let mut rest = attrs;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let end = after[1..].find(quote)? + 1;
+        if name == key {
+            return Some(decode_entities(&after[1..end]));
+        }
+        rest = &after[end + 1..];
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else { break };
+        let entity = &rest[1..end];
+        let ch = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match ch {
+            Some(ch) => {
+                decoded.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_xlsxwriter::Workbook;
+
+    #[test]
+    fn test_read_sheet_from_generated_workbook() {
+        let mut workbook = Workbook::new();
+        workbook.add_worksheet().set_name("Özet").unwrap().write_string(0, 0, "boş").unwrap();
+        let sheet = workbook.add_worksheet().set_name("Komisyon").unwrap();
+        sheet.write_string(2, 0, "Poliçe No").unwrap();
+        sheet.write_string(2, 2, "Komisyon & Prim").unwrap();
+        sheet.write_string(3, 0, "SMP-001").unwrap();
+        sheet.write_number(3, 2, 1234.5).unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let rows = read_sheet(&bytes, Some("komisyon")).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert!(rows[0].is_empty());
+        assert_eq!(rows[2][0], SheetCell::Text("Poliçe No".to_string()));
+        assert_eq!(rows[2][1], SheetCell::Empty);
+        assert_eq!(rows[2][2], SheetCell::Text("Komisyon & Prim".to_string()));
+        assert_eq!(rows[3][2], SheetCell::Number(1234.5));
+
+        let first = read_sheet(&bytes, None).unwrap();
+        assert_eq!(first[0][0], SheetCell::Text("boş".to_string()));
+        assert!(read_sheet(&bytes, Some("Yok")).is_err());
+    }
+
+    #[test]
+    fn test_inline_strings_and_entities() {
+        let xml = r#"<?xml version="1.0"?><x:worksheet><x:sheetData>
+            <x:row r="1"><x:c r="B1" t="inlineStr"><x:is><x:t>A &amp; B &#x130;</x:t></x:is></x:c><x:c r="C1"/></x:row>
+            </x:sheetData></x:worksheet>"#;
+        let rows = parse_sheet(xml, &[]).unwrap();
+        assert_eq!(rows[0], vec![SheetCell::Empty, SheetCell::Text("A & B İ".to_string())]);
+        assert_eq!(column_index("AA10"), Some(26));
+        assert_eq!(column_index("XFD1"), Some(16_383));
+    }
+
+    #[test]
+    fn test_out_of_range_addresses_rejected() {
+        assert_eq!(column_index("XFE1"), None);
+        assert_eq!(column_index("ZZZZZZZZZZZZZZZZ1"), None);
+        assert_eq!(column_index("12"), None);
+
+        let sheet = |body: &str| format!("<worksheet><sheetData>{}</sheetData></worksheet>", body);
+        assert!(parse_sheet(&sheet(r#"<row r="99999999999"><c r="A1"><v>1</v></c></row>"#), &[]).is_err());
+        assert!(parse_sheet(&sheet(r#"<row r="1"><c r="AAAAAAAAAAAAAAAAAAAAAAAA1"><v>1</v></c></row>"#), &[]).is_err());
+        assert!(parse_sheet(&sheet(r#"<row r="1048577"><c r="A1048577"><v>1</v></c></row>"#), &[]).is_err());
+
+        // Her satırın son sütununa yazılan az sayıda hücre bile hücre bütçesini aşar
+        let far: String = (1..=200).map(|r| format!(r#"<row r="{r}"><c r="XFD{r}"><v>1</v></c></row>"#)).collect();
+        assert!(parse_sheet(&sheet(&far), &[]).is_err());
+        let near = parse_sheet(&sheet(r#"<row r="1048576"><c r="XFD1048576"><v>1</v></c></row>"#), &[]).unwrap();
+        assert_eq!(near.len(), MAX_ROWS);
+    }
+}
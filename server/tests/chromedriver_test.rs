@@ -1,4 +1,5 @@
 use sigorta_server::browser::driver::create_webdriver_client;
+use sigorta_server::config::Config;
 
 #[tokio::test]
 async fn test_chromedriver_connection() {
@@ -7,7 +8,10 @@ async fn test_chromedriver_connection() {
     
     println!("ChromeDriver'a bağlanılıyor: {}", webdriver_url);
     
-    let client_result = create_webdriver_client(webdriver_url).await;
+    let mut config = Config::from_env().expect("Config yüklenemedi");
+    config.webdriver_url = webdriver_url.to_string();
+    
+    let client_result = create_webdriver_client(&config).await;
     
     match client_result {
         Ok(_client) => {
@@ -30,7 +34,10 @@ async fn test_chromedriver_navigation() {
     use fantoccini::Locator;
     
     let webdriver_url = "http://localhost:9515";
-    let mut client = create_webdriver_client(webdriver_url)
+    let mut config = Config::from_env().expect("Config yüklenemedi");
+    config.webdriver_url = webdriver_url.to_string();
+    
+    let client = create_webdriver_client(&config)
         .await
         .expect("ChromeDriver'a bağlanılamadı");
     
@@ -0,0 +1,94 @@
+// Scraper E2E testleri: sahte portal (mock-portal) + headless Chrome
+//
+// Çalıştırmak için (mock-portal ve chromedriver'ı da başlatır):
+//   scripts/portal_e2e.sh
+// Portal adresi ve kullanıcı bilgileri Config ile verilir; testler paralel çalışabilir.
+use sigorta_server::browser::create_webdriver_client;
+use sigorta_server::browser::SessionManager;
+use sigorta_server::config::Config;
+use sigorta_server::http::QuoteRequest;
+use sigorta_server::providers::quick::quote::fetch_quick_quote;
+use sigorta_server::providers::sompo::login::login_to_sompo;
+use sigorta_server::providers::sompo::quote_cdp::fetch_sompo_quote_cdp;
+use std::sync::Arc;
+
+fn portal_url() -> String {
+    std::env::var("MOCK_PORTAL_URL").unwrap_or_else(|_| "http://127.0.0.1:9300".to_string())
+}
+
+fn portal_config() -> Config {
+    let mut config = Config::from_env().expect("Config yüklenemedi");
+    config.sompo_base_url = format!("{}/dashboard/login", portal_url());
+    config.sompo_username = "demo".to_string();
+    config.sompo_password = "demo123".to_string();
+    config.sompo_secret_key = "JBSWY3DPEHPK3PXP".to_string();
+    config.session_dir = std::env::temp_dir()
+        .join(format!("portal_e2e_{}", uuid::Uuid::new_v4()))
+        .to_string_lossy()
+        .to_string();
+    config.quick_url = format!("{}/quick/agent/login", portal_url());
+    config.quick_username = "demo".to_string();
+    config.quick_password = "demo123".to_string();
+    config.headless = true;
+    config
+}
+
+fn trafik_request() -> QuoteRequest {
+    serde_json::from_value(serde_json::json!({
+        "insured": {
+            "tckn": "12345678901",
+            "name": "Test Kullanıcı",
+            "birthDate": "1990-01-01",
+            "phone": "5551234567",
+            "email": "test@example.com"
+        },
+        "vehicle": {
+            "plate": "34ABC123",
+            "brand": "Renault",
+            "model": "Clio",
+            "year": 2020,
+            "usage": "hususi"
+        },
+        "coverage": { "productType": "trafik", "startDate": "2025-01-01" }
+    }))
+    .expect("Geçersiz test isteği")
+}
+
+#[tokio::test]
+#[ignore] // mock-portal + chromedriver gerekli
+async fn test_sompo_login_against_mock_portal() {
+    let config = portal_config();
+    let session_manager = SessionManager::new(&config.session_dir);
+
+    let client = create_webdriver_client(&config)
+        .await
+        .expect("ChromeDriver'a bağlanılamadı");
+
+    let result = login_to_sompo(&client, Arc::new(config), &session_manager).await;
+    client.close().await.ok();
+
+    result.expect("Mock portal login başarısız");
+    assert!(session_manager.load_session("sompo").is_some());
+}
+
+#[tokio::test]
+#[ignore] // mock-portal + chromedriver gerekli
+async fn test_quick_quote_against_mock_portal() {
+    let quote = fetch_quick_quote(Arc::new(portal_config()), trafik_request())
+        .await
+        .expect("Quick teklif alınamadı");
+
+    assert_eq!(quote.company, "Quick");
+    assert!(quote.premium.gross >= 3500.0 && quote.premium.gross <= 6000.0);
+}
+
+#[tokio::test]
+#[ignore] // mock-portal + Chrome gerekli (CDP)
+async fn test_sompo_cdp_quote_against_mock_portal() {
+    let quote = fetch_sompo_quote_cdp(Arc::new(portal_config()), trafik_request())
+        .await
+        .expect("CDP teklif alınamadı");
+
+    assert_eq!(quote.company, "Sompo");
+    assert!(quote.premium.gross >= 3500.0 && quote.premium.gross <= 6000.0);
+}